@@ -21,4 +21,8 @@ pub fn routes() -> Router<Arc<AppState>> {
       post(services::create_appointment_for_new_customer_api),
     )
     .route("/appointments/{id}/payment", post(services::payment_appointment))
+    .route(
+      "/appointments/{id}/services/{service_id}",
+      patch(services::update_appointment_service),
+    )
 }
@@ -6,7 +6,7 @@ use core_app::{errors::AppError, AppResult, AppState};
 use domain::{
   entities::{
     appointment::{
-      AppointmentExtra, AppointmentFilter, AppointmentWithServices, CreateAppointmentForNewCustomerRequest, CreateAppointmentRequest, PaymentAppointmentRequest, UpdateAppointmentRequest
+      AppointmentExtra, AppointmentFilter, AppointmentWithServices, CreateAppointmentForNewCustomerRequest, CreateAppointmentRequest, PaymentAppointmentRequest, UpdateAppointmentRequest, UpdateAppointmentServiceRequest
     },
     common::{GetPaginationList, PaginationOptions},
     user::UserWithPassword,
@@ -15,6 +15,7 @@ use domain::{
 };
 use infra::repositories::{
   appointment::SqlxAppointmentRepository,
+  referral::SqlxReferralRepository,
   user::SqlxUserRepository,
 };
 use modql::filter::{ListOptions, OrderBys};
@@ -69,6 +70,39 @@ pub async fn update_appointment(
   Ok(Json(appointment))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/api/v1/appointments/{id}/services/{service_id}",
+    params(
+        ("id" = i64, Path, description = "Appointment id"),
+        ("service_id" = i64, Path, description = "Appointment service line id")
+    ),
+    tag="Appointment Service",
+    request_body = UpdateAppointmentServiceRequest,
+    responses(
+        (status = 200, description = "Update successfully", body = AppointmentWithServices),
+        (status = 400, description = "Bad request", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn update_appointment_service(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path((id, service_id)): Path<(i64, i64)>,
+  Json(req): Json<UpdateAppointmentServiceRequest>,
+) -> AppResult<Json<AppointmentWithServices>> {
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" && user.role != "TECHNICIAN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+  let appointment_repo = SqlxAppointmentRepository { db: state.db.clone() };
+
+  let appointment =
+    AppointmentUseCase::update_appointment_service(&appointment_repo, user, id, service_id, req)
+      .await?;
+
+  Ok(Json(appointment))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/appointment/{id}",
@@ -260,10 +294,12 @@ pub async fn create_appointment_for_new_customer_api(
 ) -> AppResult<Json<AppointmentWithServices>> {
   let appointment_repo = SqlxAppointmentRepository { db: state.db.clone() };
   let user_repo = SqlxUserRepository { db: state.db.clone() }; // Need UserRepo here too
+  let referral_repo = SqlxReferralRepository { db: state.db.clone() };
 
   let created_appointment = AppointmentUseCase::create_appointment_for_new_customer(
     &appointment_repo,
     &user_repo,
+    &referral_repo,
     user,
     payload,
   ).await?;
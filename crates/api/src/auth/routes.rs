@@ -17,10 +17,14 @@ pub fn routes() -> Router<Arc<AppState>> {
     .route("/auth/forgot-password", post(services::forgot_password_service))
     .route("/auth/resend-code", post(services::resend_code_service))
     .route("/auth/verify-code-firebase", post(services::verify_code_firebase_service))
+    .route("/auth/forgot-password-email", post(services::forgot_password_email_service))
+    .route("/auth/verify-email-code", post(services::verify_email_code_service))
 }
 
 pub fn routes_auth() -> Router<Arc<AppState>> {
   Router::new()
     .route("/auth/get-current-user", get(services::get_current_user_service))
     .route("/auth/logout", post(services::logout_user_service))
+    .route("/auth/email/send-verification", post(services::send_email_verification_service))
+    .route("/auth/email/verify", post(services::verify_email_service))
 }
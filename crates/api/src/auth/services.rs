@@ -5,14 +5,17 @@ use axum::extract::Request;
 use axum::{Json, extract::State};
 use core_app::{AppResult, AppState};
 use domain::entities::auth::{
-  CheckPhoneReponse, CheckPhoneRequest, ForgotPasswordRequest, LogoutRequest, RefreshTokenRequest,
-  ResendCodeRequest, SetPasswordRequest, SigninRequest, SigninRequestByPhone, SigninResponse,
+  CheckPhoneReponse, CheckPhoneRequest, ForgotPasswordEmailRequest, ForgotPasswordRequest,
+  LogoutRequest, RefreshTokenRequest, ResendCodeRequest, SetPasswordRequest, SigninRequest,
+  SigninRequestByPhone, SigninResponse, VerifyEmailCodeRequest, VerifyEmailRequest,
   VerifyFireCodeRequest, VerifyPhoneCodeRequest, VerifyPhoneCodeResponse,
 };
 use domain::entities::user::{User, UserWithPassword};
 use infra::repositories::auth::{
-  check_phone, forgot_password, get_current_user, login_with_phone, login_with_user_name,
-  logout_user, refresh_token, resend_code, set_password, verify_code_firebase, verify_phone,
+  check_phone,
+  email::{forgot_password_email, send_email_verification, verify_email, verify_email_code},
+  forgot_password, get_current_user, login_with_phone, login_with_user_name, logout_user,
+  refresh_token, resend_code, set_password, verify_code_firebase, verify_phone,
 };
 
 use infra::database::schema::UserDmc;
@@ -223,3 +226,79 @@ pub async fn logout_user_service(
   let data = logout_user(state, user, req).await?;
   Ok(Json(data))
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/forgot-password-email",
+    tag="Auth Service",
+    request_body = ForgotPasswordEmailRequest,
+    responses(
+        (status = 200, description = "Reset code sent if the email is verified", body = bool),
+        (status = 400, description = "Bad request", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn forgot_password_email_service(
+  State(state): State<Arc<AppState>>,
+  Json(req): Json<ForgotPasswordEmailRequest>,
+) -> AppResult<Json<bool>> {
+  let data = forgot_password_email(state, req).await?;
+  Ok(Json(data))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email-code",
+    tag="Auth Service",
+    request_body = VerifyEmailCodeRequest,
+    responses(
+        (status = 200, description = "Verify successfully, use the token with /auth/set-password", body = VerifyPhoneCodeResponse),
+        (status = 400, description = "Bad request", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn verify_email_code_service(
+  State(state): State<Arc<AppState>>,
+  Json(req): Json<VerifyEmailCodeRequest>,
+) -> AppResult<Json<VerifyPhoneCodeResponse>> {
+  let data = verify_email_code(state, req).await?;
+  Ok(Json(data))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/email/send-verification",
+    tag="Auth Service",
+    responses(
+        (status = 200, description = "Verification code sent, false if one was sent less than a minute ago", body = bool),
+        (status = 400, description = "Bad request", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn send_email_verification_service(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<bool>> {
+  let data = send_email_verification(state, user).await?;
+  Ok(Json(data))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/email/verify",
+    tag="Auth Service",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 200, description = "Email verified", body = User),
+        (status = 400, description = "Bad request", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn verify_email_service(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(req): Json<VerifyEmailRequest>,
+) -> AppResult<Json<User>> {
+  let data = verify_email(state, user, req).await?;
+  Ok(Json(data))
+}
@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/campaigns", get(services::get_campaigns).post(services::create_campaign))
+    .route("/campaigns/audience/preview", post(services::preview_audience))
+    .route("/campaigns/{id}", get(services::get_campaign_by_id).patch(services::update_campaign))
+    .route("/campaigns/{id}/schedule", post(services::schedule_campaign))
+    .route("/campaigns/{id}/cancel", post(services::cancel_campaign))
+    .route("/campaigns/{id}/open", post(services::open_campaign))
+}
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    campaign::{
+      Campaign, CampaignAudience, CampaignAudiencePreview, CampaignDetail, CampaignFilter,
+      CreateCampaignRequest, ScheduleCampaignRequest, UpdateCampaignRequest,
+    },
+    user::UserWithPassword,
+  },
+  services::campaign::CampaignUseCase,
+};
+use infra::repositories::campaign::SqlxCampaignRepository;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/campaigns",
+    tag = "Campaign Service",
+    params(CampaignFilter),
+    responses(
+        (status = 200, description = "Get campaigns successfully", body = Vec<Campaign>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_campaigns(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<CampaignFilter>,
+) -> AppResult<Json<Vec<Campaign>>> {
+  let repo = SqlxCampaignRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let campaigns = CampaignUseCase::list(&repo, filter).await?;
+
+  Ok(Json(campaigns))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/campaigns",
+    tag = "Campaign Service",
+    request_body = CreateCampaignRequest,
+    responses(
+        (status = 200, description = "Create campaign successfully", body = Campaign),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_campaign(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateCampaignRequest>,
+) -> AppResult<Json<Campaign>> {
+  let repo = SqlxCampaignRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let campaign = CampaignUseCase::create(&repo, user.pk_user_id, payload).await?;
+
+  Ok(Json(campaign))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/campaigns/{id}",
+    tag = "Campaign Service",
+    params(
+        ("id" = i64, Path, description = "Campaign ID")
+    ),
+    responses(
+        (status = 200, description = "Get campaign with delivery stats successfully", body = CampaignDetail),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Campaign not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_campaign_by_id(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<CampaignDetail>> {
+  let repo = SqlxCampaignRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let campaign = CampaignUseCase::get_by_id(&repo, id).await?;
+
+  Ok(Json(campaign))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/campaigns/{id}",
+    tag = "Campaign Service",
+    params(
+        ("id" = i64, Path, description = "Campaign ID")
+    ),
+    request_body = UpdateCampaignRequest,
+    responses(
+        (status = 200, description = "Update campaign successfully", body = Campaign),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Campaign not found"),
+        (status = 409, description = "Campaign status changed concurrently", body = String),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_campaign(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<UpdateCampaignRequest>,
+) -> AppResult<Json<Campaign>> {
+  let repo = SqlxCampaignRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let campaign = CampaignUseCase::update(&repo, id, payload).await?;
+
+  Ok(Json(campaign))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/campaigns/{id}/schedule",
+    tag = "Campaign Service",
+    params(
+        ("id" = i64, Path, description = "Campaign ID")
+    ),
+    request_body = ScheduleCampaignRequest,
+    responses(
+        (status = 200, description = "Schedule campaign successfully", body = Campaign),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Campaign not found"),
+        (status = 409, description = "Campaign status changed concurrently", body = String),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn schedule_campaign(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<ScheduleCampaignRequest>,
+) -> AppResult<Json<Campaign>> {
+  let repo = SqlxCampaignRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let campaign = CampaignUseCase::schedule(&repo, id, payload).await?;
+
+  Ok(Json(campaign))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/campaigns/{id}/cancel",
+    tag = "Campaign Service",
+    params(
+        ("id" = i64, Path, description = "Campaign ID")
+    ),
+    responses(
+        (status = 200, description = "Cancel campaign successfully", body = Campaign),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Campaign not found"),
+        (status = 409, description = "Campaign status changed concurrently", body = String),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn cancel_campaign(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<Campaign>> {
+  let repo = SqlxCampaignRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let campaign = CampaignUseCase::cancel(&repo, id).await?;
+
+  Ok(Json(campaign))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/campaigns/audience/preview",
+    tag = "Campaign Service",
+    request_body = CampaignAudience,
+    responses(
+        (status = 200, description = "Count matching customers successfully", body = CampaignAudiencePreview),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn preview_audience(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CampaignAudience>,
+) -> AppResult<Json<CampaignAudiencePreview>> {
+  let repo = SqlxCampaignRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let preview = CampaignUseCase::preview_audience(&repo, payload).await?;
+
+  Ok(Json(preview))
+}
+
+// Khách mở thông báo chiến dịch, app gọi khi người dùng bấm vào thông báo
+#[utoipa::path(
+    post,
+    path = "/api/v1/campaigns/{id}/open",
+    tag = "Campaign Service",
+    params(
+        ("id" = i64, Path, description = "Campaign ID")
+    ),
+    responses(
+        (status = 200, description = "Record campaign open successfully", body = bool),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Campaign was not sent to this user"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn open_campaign(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<bool>> {
+  let repo = SqlxCampaignRepository { db: state.db.clone() };
+
+  CampaignUseCase::mark_opened(&repo, id, user.pk_user_id).await?;
+
+  Ok(Json(true))
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, patch, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/consent-templates", get(services::get_consent_templates))
+    .route("/consent-templates", post(services::create_consent_template))
+    .route("/consent-templates/{id}", get(services::get_consent_template))
+    .route("/consent-templates/{id}", patch(services::update_consent_template))
+    .route("/consent-forms/pending", get(services::get_pending_consent_forms))
+    .route("/appointments/{id}/consent-forms", get(services::get_appointment_consent_forms))
+    .route("/consent-submissions", get(services::get_consent_submissions))
+    .route("/consent-submissions", post(services::create_consent_submission))
+    .route("/consent-submissions/{id}", get(services::get_consent_submission))
+    .route("/consent-submissions/{id}/pdf", get(services::get_consent_submission_pdf))
+    .route("/consent-submissions/{id}/signature", get(services::get_consent_submission_signature))
+}
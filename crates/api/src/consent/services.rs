@@ -0,0 +1,474 @@
+use std::{
+  net::{IpAddr, SocketAddr},
+  sync::Arc,
+};
+
+use axum::{
+  Json,
+  extract::{ConnectInfo, Extension, Multipart, Path, Query, State},
+  http::{HeaderMap, header},
+  response::{IntoResponse, Response},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    consent::{
+      AppointmentConsentForm, ConsentSubmission, ConsentSubmissionFilter, ConsentTemplate,
+      ConsentTemplateFilter, CreateConsentSubmissionRequest, CreateConsentTemplateRequest,
+      SignatureCapture, UpdateConsentTemplateRequest,
+    },
+    user::UserWithPassword,
+  },
+  services::consent::ConsentUseCase,
+};
+use infra::repositories::{consent::SqlxConsentRepository, image::LocalImageService};
+use tracing::error;
+
+// IP thật của client; header của reverse proxy chỉ được tin khi kết nối đến từ proxy đã cấu hình,
+// nếu không lấy địa chỉ kết nối (client có thể tự gửi X-Forwarded-For giả)
+fn client_ip(
+  headers: &HeaderMap,
+  addr: SocketAddr,
+  trusted_proxies: &[IpAddr],
+) -> String {
+  if !trusted_proxies.contains(&addr.ip()) {
+    return addr.ip().to_string();
+  }
+
+  headers
+    .get("x-forwarded-for")
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.split(',').next())
+    .or_else(|| headers.get("x-real-ip").and_then(|value| value.to_str().ok()))
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| addr.ip().to_string())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consent-templates",
+    tag = "Consent Service",
+    params(ConsentTemplateFilter),
+    responses(
+        (status = 200, description = "Get consent templates successfully", body = Vec<ConsentTemplate>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_consent_templates(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<ConsentTemplateFilter>,
+) -> AppResult<Json<Vec<ConsentTemplate>>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  if user.role != "TECHNICIAN" && user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let templates = ConsentUseCase::get_templates(&repo, filter.service_id).await?;
+
+  Ok(Json(templates))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/consent-templates",
+    tag = "Consent Service",
+    request_body = CreateConsentTemplateRequest,
+    responses(
+        (status = 200, description = "Consent template created successfully", body = ConsentTemplate),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_consent_template(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateConsentTemplateRequest>,
+) -> AppResult<Json<ConsentTemplate>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let template = ConsentUseCase::create_template(&repo, payload, user.pk_user_id).await?;
+
+  Ok(Json(template))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consent-templates/{id}",
+    tag = "Consent Service",
+    params(
+        ("id" = i64, Path, description = "Consent template ID")
+    ),
+    responses(
+        (status = 200, description = "Get consent template successfully", body = ConsentTemplate),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Consent template not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_consent_template(
+  State(state): State<Arc<AppState>>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<ConsentTemplate>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  let template = ConsentUseCase::get_template(&repo, id).await?;
+
+  Ok(Json(template))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/consent-templates/{id}",
+    tag = "Consent Service",
+    params(
+        ("id" = i64, Path, description = "Consent template ID")
+    ),
+    request_body = UpdateConsentTemplateRequest,
+    responses(
+        (status = 200, description = "Consent template updated successfully", body = ConsentTemplate),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Consent template not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_consent_template(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<UpdateConsentTemplateRequest>,
+) -> AppResult<Json<ConsentTemplate>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let template = ConsentUseCase::update_template(&repo, id, payload).await?;
+
+  Ok(Json(template))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consent-forms/pending",
+    tag = "Consent Service",
+    responses(
+        (status = 200, description = "Get forms to sign before upcoming appointments", body = Vec<AppointmentConsentForm>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_pending_consent_forms(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<Vec<AppointmentConsentForm>>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let forms = ConsentUseCase::get_pending_forms(&repo, user).await?;
+
+  Ok(Json(forms))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/appointments/{id}/consent-forms",
+    tag = "Consent Service",
+    params(
+        ("id" = i64, Path, description = "Appointment ID")
+    ),
+    responses(
+        (status = 200, description = "Get consent forms of the appointment successfully", body = Vec<AppointmentConsentForm>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Appointment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_appointment_consent_forms(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<Vec<AppointmentConsentForm>>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  let forms = ConsentUseCase::get_appointment_forms(&repo, user, id).await?;
+
+  Ok(Json(forms))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consent-submissions",
+    tag = "Consent Service",
+    params(ConsentSubmissionFilter),
+    responses(
+        (status = 200, description = "Get signed consent forms successfully", body = Vec<ConsentSubmission>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_consent_submissions(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<ConsentSubmissionFilter>,
+) -> AppResult<Json<Vec<ConsentSubmission>>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  if user.role != "TECHNICIAN" && user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let submissions = ConsentUseCase::get_submissions(&repo, filter).await?;
+
+  Ok(Json(submissions))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/consent-submissions",
+    tag = "Consent Service",
+    request_body(
+        content_type = "multipart/form-data",
+        content = CreateConsentSubmissionRequest,
+        description = "Signed form (answers as JSON string, field name 'signature': PNG, JPG or WEBP)"
+    ),
+    responses(
+        (status = 200, description = "Consent form signed successfully", body = ConsentSubmission),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Appointment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_consent_submission(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  ConnectInfo(addr): ConnectInfo<SocketAddr>,
+  headers: HeaderMap,
+  mut multipart: Multipart,
+) -> AppResult<Json<ConsentSubmission>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+  let image_repo = Arc::new(LocalImageService);
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let mut payload = CreateConsentSubmissionRequest {
+    template_id: 0,
+    appointment_id: 0,
+    answers: String::new(),
+    signature: None,
+  };
+  let mut signature_data = None;
+  let mut content_type = None;
+
+  while let Some(field) = multipart.next_field().await.map_err(|err| {
+    error!("Failed to read multipart field: {}", err);
+    AppError::BadRequest(format!("Failed to process form data: {}", err))
+  })? {
+    let field_name = field
+      .name()
+      .ok_or_else(|| AppError::BadRequest("Missing field name in form data".to_string()))?
+      .to_string();
+
+    if field_name == "signature" {
+      let ct = field.content_type().map(|ct| ct.to_string());
+      let data = field.bytes().await.map_err(|err| {
+        error!("Failed to read signature data: {}", err);
+        AppError::BadRequest(format!("Failed to read signature data: {}", err))
+      })?;
+
+      signature_data = Some(data.to_vec());
+      content_type = ct;
+      continue;
+    }
+
+    let value = field.text().await.map_err(|err| {
+      error!("Failed to read field {}: {}", field_name, err);
+      AppError::BadRequest(format!("Failed to read field {}: {}", field_name, err))
+    })?;
+    let value = value.trim().to_string();
+
+    match field_name.as_str() {
+      "template_id" => {
+        payload.template_id = value
+          .parse::<i64>()
+          .map_err(|err| AppError::BadRequest(format!("Invalid template_id format: {}", err)))?;
+      },
+      "appointment_id" => {
+        payload.appointment_id = value
+          .parse::<i64>()
+          .map_err(|err| AppError::BadRequest(format!("Invalid appointment_id format: {}", err)))?;
+      },
+      "answers" => payload.answers = value,
+      _ => {},
+    }
+  }
+
+  let signature = SignatureCapture {
+    data: signature_data.unwrap_or_default(),
+    content_type: content_type.unwrap_or_default(),
+    ip_address: Some(client_ip(&headers, addr, &state.config.web.trusted_proxies)),
+    user_agent: headers
+      .get(header::USER_AGENT)
+      .and_then(|value| value.to_str().ok())
+      .map(|value| value.to_string()),
+  };
+
+  let submission =
+    ConsentUseCase::submit(&repo, image_repo, &state.config.spa, user, payload, signature).await?;
+
+  Ok(Json(submission))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consent-submissions/{id}",
+    tag = "Consent Service",
+    params(
+        ("id" = i64, Path, description = "Consent submission ID")
+    ),
+    responses(
+        (status = 200, description = "Get signed consent form successfully", body = ConsentSubmission),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Consent submission not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_consent_submission(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<ConsentSubmission>> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  let submission = ConsentUseCase::get_submission(&repo, user, id).await?;
+
+  Ok(Json(submission))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consent-submissions/{id}/pdf",
+    tag = "Consent Service",
+    params(
+        ("id" = i64, Path, description = "Consent submission ID")
+    ),
+    responses(
+        (status = 200, description = "Signed consent form as PDF", content_type = "application/pdf"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Consent submission not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_consent_submission_pdf(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Response> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+
+  let pdf = ConsentUseCase::get_submission_pdf(&repo, user, id).await?;
+
+  Ok(
+    (
+      [
+        (header::CONTENT_TYPE, "application/pdf".to_string()),
+        (header::CONTENT_DISPOSITION, format!("inline; filename=\"consent-{}.pdf\"", id)),
+      ],
+      pdf,
+    )
+      .into_response(),
+  )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consent-submissions/{id}/signature",
+    tag = "Consent Service",
+    params(
+        ("id" = i64, Path, description = "Consent submission ID")
+    ),
+    responses(
+        (status = 200, description = "Signature image of the consent form", content_type = "image/png"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Consent submission not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_consent_submission_signature(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Response> {
+  let repo = SqlxConsentRepository { db: state.db.clone() };
+  let image_repo = Arc::new(LocalImageService);
+
+  let (image, content_type) =
+    ConsentUseCase::get_signature_image(&repo, image_repo, user, id).await?;
+
+  Ok(
+    (
+      [
+        (header::CONTENT_TYPE, content_type),
+        (header::CONTENT_DISPOSITION, "inline".to_string()),
+        (header::CACHE_CONTROL, "private, no-store".to_string()),
+      ],
+      image,
+    )
+      .into_response(),
+  )
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use super::services;
 use axum::{
   Router,
-  routing::{get, patch, post},
+  routing::{get, patch, post, put},
 };
 use core_app::AppState;
 
@@ -11,6 +11,10 @@ pub fn routes() -> Router<Arc<AppState>> {
   Router::new()
     .route("/deposits", post(services::create_deposit))
     .route("/deposits", get(services::get_deposits))
+    .route("/deposits/top-up", post(services::create_top_up))
+    .route("/deposits/withdraw", post(services::create_withdrawal))
+    .route("/deposits/bank-account", get(services::get_bank_account))
+    .route("/deposits/bank-account", put(services::upsert_bank_account))
     .route("/deposits/{id}", get(services::get_deposit_by_id))
     .route("/deposits/{id}/status", patch(services::update_deposit_status))
     .route("/deposits/user", get(services::get_deposits_by_user_id))
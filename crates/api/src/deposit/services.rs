@@ -1,21 +1,26 @@
 use axum::{
   Json,
-  extract::{Extension, Path, Query, State},
+  extract::{Extension, Multipart, Path, Query, State},
 };
 use core_app::{AppResult, AppState, errors::AppError};
 use domain::{
   entities::{
     common::PaginationOptions,
     deposit::{
-      CreateDepositRequest, Deposit, DepositDetail, DepositFilter, UpdateDepositStatusRequest,
+      BankAccount, CreateDepositRequest, CreateTopUpRequest, CreateWithdrawalRequest, Deposit,
+      DepositDetail, DepositFilter, UpdateDepositStatusRequest, UpsertBankAccountRequest,
     },
     user::UserWithPassword,
   },
   repositories::deposit_repository::DepositRepository,
+  services::deposit::DepositUseCase,
+};
+use infra::repositories::{
+  base::generate_listoption, deposit::SqlxDepositRepository, image::LocalImageService,
 };
-use infra::repositories::{base::generate_listoption, deposit::SqlxDepositRepository};
 use serde_json::{Value, json};
 use std::sync::Arc;
+use tracing::error;
 
 #[utoipa::path(
     post,
@@ -49,6 +54,192 @@ pub async fn create_deposit(
   Ok(Json(deposit))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/deposits/top-up",
+    tag = "Deposit Service",
+    request_body(
+        content_type = "multipart/form-data",
+        content = CreateTopUpRequest,
+        description = "Top-up request with proof of transfer (field name: 'image', supported formats: JPG, PNG, WEBP)"
+    ),
+    responses(
+        (status = 200, description = "Top-up request created successfully", body = Deposit),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_top_up(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  mut multipart: Multipart,
+) -> AppResult<Json<Deposit>> {
+  let repo = SqlxDepositRepository { db: state.db.clone() };
+  let image_repo = Arc::new(LocalImageService);
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let mut payload =
+    CreateTopUpRequest { amount: 0, payment_method: None, notes: None, image: None };
+  let mut image_data = None;
+  let mut content_type = None;
+
+  while let Some(field) = multipart.next_field().await.map_err(|err| {
+    error!("Failed to read multipart field: {}", err);
+    AppError::BadRequest(format!("Failed to process form data: {}", err))
+  })? {
+    let field_name = field
+      .name()
+      .ok_or_else(|| AppError::BadRequest("Missing field name in form data".to_string()))?
+      .to_string();
+
+    if field_name == "image" {
+      let ct = field.content_type().map(|ct| ct.to_string());
+      let data = field.bytes().await.map_err(|err| {
+        error!("Failed to read image data: {}", err);
+        AppError::BadRequest(format!("Failed to read image data: {}", err))
+      })?;
+
+      image_data = Some(data.to_vec());
+      content_type = ct;
+      continue;
+    }
+
+    let value = field.text().await.map_err(|err| {
+      error!("Failed to read field {}: {}", field_name, err);
+      AppError::BadRequest(format!("Failed to read field {}: {}", field_name, err))
+    })?;
+    let value = value.trim().to_string();
+    if value.is_empty() {
+      continue;
+    }
+
+    match field_name.as_str() {
+      "amount" => {
+        payload.amount = value
+          .parse::<i64>()
+          .map_err(|err| AppError::BadRequest(format!("Invalid amount format: {}", err)))?;
+      },
+      "payment_method" => payload.payment_method = Some(value.to_uppercase()),
+      "notes" => payload.notes = Some(value),
+      _ => {},
+    }
+  }
+
+  let image_data = image_data.unwrap_or_default();
+  let content_type = content_type.unwrap_or_default();
+
+  let deposit = DepositUseCase::create_top_up(
+    &repo,
+    image_repo,
+    user.pk_user_id,
+    &image_data,
+    &content_type,
+    payload,
+  )
+  .await?;
+
+  Ok(Json(deposit))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/deposits/withdraw",
+    tag = "Deposit Service",
+    request_body = CreateWithdrawalRequest,
+    responses(
+        (status = 200, description = "Withdrawal request created successfully", body = Deposit),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_withdrawal(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateWithdrawalRequest>,
+) -> AppResult<Json<Deposit>> {
+  let repo = SqlxDepositRepository { db: state.db.clone() };
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let deposit =
+    DepositUseCase::create_withdrawal(&repo, &state.config.withdrawal, user.pk_user_id, payload)
+      .await?;
+
+  Ok(Json(deposit))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/deposits/bank-account",
+    tag = "Deposit Service",
+    responses(
+        (status = 200, description = "Get bank account successfully", body = BankAccount),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Bank account not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_bank_account(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<BankAccount>> {
+  let repo = SqlxDepositRepository { db: state.db.clone() };
+
+  let bank_account = DepositUseCase::get_bank_account(&repo, user.pk_user_id).await?;
+
+  Ok(Json(bank_account))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/deposits/bank-account",
+    tag = "Deposit Service",
+    request_body = UpsertBankAccountRequest,
+    responses(
+        (status = 200, description = "Bank account saved successfully", body = BankAccount),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn upsert_bank_account(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<UpsertBankAccountRequest>,
+) -> AppResult<Json<BankAccount>> {
+  let repo = SqlxDepositRepository { db: state.db.clone() };
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let bank_account = DepositUseCase::upsert_bank_account(&repo, user.pk_user_id, payload).await?;
+
+  Ok(Json(bank_account))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/deposits",
@@ -126,6 +317,10 @@ pub async fn get_deposit_by_id(
 
   let deposit = repo.get_deposit_by_id(id).await?;
   match deposit {
+    // Khách hàng chỉ xem được giao dịch của mình
+    Some(deposit) if user.role == "CUSTOMER" && deposit.user_id != user.pk_user_id => {
+      Err(AppError::NotFound)
+    },
     Some(deposit) => Ok(Json(deposit)),
     None => Err(AppError::NotFound),
   }
@@ -160,7 +355,7 @@ pub async fn update_deposit_status(
     return Err(AppError::Forbidden("You don't have permission".to_string()));
   }
 
-  let deposit = repo.update_deposit_status(id, request).await?;
+  let deposit = DepositUseCase::update_deposit_status(&repo, id, request, user.pk_user_id).await?;
 
   Ok(Json(deposit))
 }
@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, patch},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/emails/templates", get(services::get_templates))
+    .route("/emails/templates/{id}", patch(services::update_template))
+    .route("/emails/messages", get(services::get_messages))
+}
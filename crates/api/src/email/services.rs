@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    email::{
+      EmailMessage, EmailMessageFilter, EmailTemplate, EmailTemplateFilter,
+      UpdateEmailTemplateRequest,
+    },
+    user::UserWithPassword,
+  },
+  services::email::EmailUseCase,
+};
+use infra::repositories::email::SqlxEmailRepository;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/emails/templates",
+    tag = "Email Service",
+    params(EmailTemplateFilter),
+    responses(
+        (status = 200, description = "Get email templates successfully", body = Vec<EmailTemplate>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_templates(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<EmailTemplateFilter>,
+) -> AppResult<Json<Vec<EmailTemplate>>> {
+  let repo = SqlxEmailRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let templates = EmailUseCase::list_templates(&repo, filter).await?;
+
+  Ok(Json(templates))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/emails/templates/{id}",
+    tag = "Email Service",
+    params(
+        ("id" = i64, Path, description = "Email template ID")
+    ),
+    request_body = UpdateEmailTemplateRequest,
+    responses(
+        (status = 200, description = "Update email template successfully", body = EmailTemplate),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Email template not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_template(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<UpdateEmailTemplateRequest>,
+) -> AppResult<Json<EmailTemplate>> {
+  let repo = SqlxEmailRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let template = EmailUseCase::update_template(&repo, id, payload).await?;
+
+  Ok(Json(template))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/emails/messages",
+    tag = "Email Service",
+    params(EmailMessageFilter),
+    responses(
+        (status = 200, description = "Get email messages successfully", body = Vec<EmailMessage>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_messages(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<EmailMessageFilter>,
+) -> AppResult<Json<Vec<EmailMessage>>> {
+  let repo = SqlxEmailRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let messages = EmailUseCase::list_messages(&repo, filter).await?;
+
+  Ok(Json(messages))
+}
@@ -0,0 +1,9 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{Router, routing::get};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new().route("/events", get(services::stream_events))
+}
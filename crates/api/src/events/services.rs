@@ -0,0 +1,117 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+  extract::{Extension, State},
+  response::sse::{Event, KeepAlive, Sse},
+};
+use core_app::AppState;
+use domain::{
+  entities::{
+    notification::NotificationFilter, realtime_event::RealtimeEvent, user::UserWithPassword,
+  },
+  services::notification::NotificationUseCase,
+};
+use futures::{Stream, StreamExt, stream};
+use infra::repositories::notification::SqlxNotificationRepository;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Luồng sự kiện realtime của người dùng hiện tại. Các sự kiện gửi xuống:
+/// - `unread_count`: `{"count": n}`, gửi ngay khi kết nối và mỗi khi số chưa đọc thay đổi
+/// - `notification`: thông báo in-app mới
+/// - `appointment_status`: `{"appointment_id": id, "status": "..."}`
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    tag = "Event Service",
+    responses(
+        (status = 200, description = "Server-Sent Events stream", content_type = "text/event-stream", body = String),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn stream_events(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+  // Đăng ký trước khi đọc số chưa đọc để không lỡ sự kiện phát ra giữa hai bước
+  let receiver = state.events.subscribe();
+  let initial = unread_count_event(&state, &user).await;
+
+  let events = stream::unfold((state, user, receiver), |(state, user, mut receiver)| async move {
+    loop {
+      let events = match receiver.recv().await {
+        Ok(payload) => match serde_json::from_str::<RealtimeEvent>(&payload) {
+          Ok(event) if event.is_visible_to(user.pk_user_id, &user.role) => {
+            to_sse_events(&state, &user, event).await
+          },
+          Ok(_) => continue,
+          Err(e) => {
+            tracing::warn!("Invalid realtime event {}: {}", payload, e);
+            continue;
+          },
+        },
+        // Client đọc chậm đã bị bỏ qua một số sự kiện, gửi lại số chưa đọc để đồng bộ
+        Err(RecvError::Lagged(_)) => unread_count_event(&state, &user).await.into_iter().collect(),
+        Err(RecvError::Closed) => return None,
+      };
+
+      return Some((stream::iter(events), (state, user, receiver)));
+    }
+  })
+  .flatten();
+
+  let stream = stream::iter(initial).chain(events).map(Ok);
+
+  Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn to_sse_events(
+  state: &AppState,
+  user: &UserWithPassword,
+  event: RealtimeEvent,
+) -> Vec<Event> {
+  let mut events = vec![];
+
+  match event {
+    RealtimeEvent::NotificationCreated { notification_id, .. } => {
+      let repo = SqlxNotificationRepository { db: state.db.clone() };
+      if let Ok(notification) = NotificationUseCase::get_by_id(&repo, notification_id).await {
+        events.extend(Event::default().event("notification").json_data(notification).ok());
+      }
+      events.extend(unread_count_event(state, user).await);
+    },
+    RealtimeEvent::NotificationChanged { .. } => {
+      events.extend(unread_count_event(state, user).await);
+    },
+    RealtimeEvent::AppointmentStatus { appointment_id, status, .. } => {
+      let data = json!({ "appointment_id": appointment_id, "status": status });
+      events.extend(Event::default().event("appointment_status").json_data(data).ok());
+    },
+  }
+
+  events
+}
+
+async fn unread_count_event(
+  state: &AppState,
+  user: &UserWithPassword,
+) -> Option<Event> {
+  let repo = SqlxNotificationRepository { db: state.db.clone() };
+  let filter = NotificationFilter {
+    user_id: Some(user.pk_user_id),
+    is_read: Some(false),
+    receiver: None,
+    notification_type: None,
+  };
+
+  match NotificationUseCase::un_read(&repo, user.clone(), filter).await {
+    Ok(count) => Event::default().event("unread_count").json_data(json!({ "count": count })).ok(),
+    Err(e) => {
+      tracing::error!("Failed to count unread notifications: {:?}", e);
+      None
+    },
+  }
+}
@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/invoices", get(services::get_invoices).post(services::create_invoice))
+    .route("/invoices/{id}", get(services::get_invoice))
+    .route("/invoices/{id}/xml", get(services::get_invoice_xml))
+    .route("/invoices/{id}/replacement", post(services::create_replacement_invoice))
+    .route("/invoices/{id}/adjustments", post(services::create_adjustment_invoice))
+    .route("/invoices/{id}/submit", post(services::submit_invoice))
+}
@@ -0,0 +1,301 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+  http::header,
+  response::{IntoResponse, Response},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    invoice::{
+      CreateAdjustmentInvoiceRequest, CreateInvoiceRequest, CreateReplacementInvoiceRequest,
+      Invoice, InvoiceDetail, InvoiceFilter,
+    },
+    user::UserWithPassword,
+  },
+  services::invoice::InvoiceUseCase,
+};
+use infra::repositories::invoice::{SqlxInvoiceRepository, file_drop::FileDropInvoiceProvider};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices",
+    tag = "Invoice Service",
+    request_body = CreateInvoiceRequest,
+    responses(
+        (status = 200, description = "Invoice issued successfully", body = InvoiceDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_invoice(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateInvoiceRequest>,
+) -> AppResult<Json<InvoiceDetail>> {
+  let repo = SqlxInvoiceRepository { db: state.db.clone() };
+  let provider = FileDropInvoiceProvider { dir: state.config.invoice.file_drop_dir.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let invoice =
+    InvoiceUseCase::create_invoice(&repo, &provider, &state.config.spa, payload, user.pk_user_id)
+      .await?;
+
+  Ok(Json(invoice))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices",
+    tag = "Invoice Service",
+    params(InvoiceFilter),
+    responses(
+        (status = 200, description = "Get invoices successfully", body = Vec<Invoice>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_invoices(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<InvoiceFilter>,
+) -> AppResult<Json<Vec<Invoice>>> {
+  let repo = SqlxInvoiceRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let invoices = InvoiceUseCase::get_invoices(&repo, filter).await?;
+
+  Ok(Json(invoices))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}",
+    tag = "Invoice Service",
+    params(
+        ("id" = i64, Path, description = "Invoice ID")
+    ),
+    responses(
+        (status = 200, description = "Get invoice successfully", body = InvoiceDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Invoice not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_invoice(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<InvoiceDetail>> {
+  let repo = SqlxInvoiceRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let invoice = InvoiceUseCase::get_invoice(&repo, id).await?;
+
+  Ok(Json(invoice))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/xml",
+    tag = "Invoice Service",
+    params(
+        ("id" = i64, Path, description = "Invoice ID")
+    ),
+    responses(
+        (status = 200, description = "Invoice XML", body = String),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Invoice not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_invoice_xml(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Response> {
+  let repo = SqlxInvoiceRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let detail = InvoiceUseCase::get_invoice(&repo, id).await?;
+  let invoice = detail.invoice;
+
+  Ok(
+    (
+      [
+        (header::CONTENT_TYPE, "application/xml; charset=utf-8".to_string()),
+        (
+          header::CONTENT_DISPOSITION,
+          format!(
+            "attachment; filename=\"{}{}-{:08}.xml\"",
+            invoice.template_code, invoice.symbol, invoice.invoice_number
+          ),
+        ),
+      ],
+      invoice.xml_content,
+    )
+      .into_response(),
+  )
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/replacement",
+    tag = "Invoice Service",
+    request_body = CreateReplacementInvoiceRequest,
+    params(
+        ("id" = i64, Path, description = "Invoice ID")
+    ),
+    responses(
+        (status = 200, description = "Replacement invoice issued successfully", body = InvoiceDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Invoice not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_replacement_invoice(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<CreateReplacementInvoiceRequest>,
+) -> AppResult<Json<InvoiceDetail>> {
+  let repo = SqlxInvoiceRepository { db: state.db.clone() };
+  let provider = FileDropInvoiceProvider { dir: state.config.invoice.file_drop_dir.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let invoice = InvoiceUseCase::create_replacement(
+    &repo,
+    &provider,
+    &state.config.spa,
+    id,
+    payload,
+    user.pk_user_id,
+  )
+  .await?;
+
+  Ok(Json(invoice))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/adjustments",
+    tag = "Invoice Service",
+    request_body = CreateAdjustmentInvoiceRequest,
+    params(
+        ("id" = i64, Path, description = "Invoice ID")
+    ),
+    responses(
+        (status = 200, description = "Adjustment invoice issued successfully", body = InvoiceDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Invoice not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_adjustment_invoice(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<CreateAdjustmentInvoiceRequest>,
+) -> AppResult<Json<InvoiceDetail>> {
+  let repo = SqlxInvoiceRepository { db: state.db.clone() };
+  let provider = FileDropInvoiceProvider { dir: state.config.invoice.file_drop_dir.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let invoice = InvoiceUseCase::create_adjustment(
+    &repo,
+    &provider,
+    &state.config.spa,
+    id,
+    payload,
+    user.pk_user_id,
+  )
+  .await?;
+
+  Ok(Json(invoice))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/submit",
+    tag = "Invoice Service",
+    params(
+        ("id" = i64, Path, description = "Invoice ID")
+    ),
+    responses(
+        (status = 200, description = "Invoice submitted", body = InvoiceDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Invoice not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn submit_invoice(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<InvoiceDetail>> {
+  let repo = SqlxInvoiceRepository { db: state.db.clone() };
+  let provider = FileDropInvoiceProvider { dir: state.config.invoice.file_drop_dir.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let invoice = InvoiceUseCase::resubmit(&repo, &provider, id).await?;
+
+  Ok(Json(invoice))
+}
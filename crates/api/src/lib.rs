@@ -5,15 +5,31 @@ use core_app::AppState;
 
 pub mod appointment;
 pub mod auth;
+pub mod campaign;
 pub mod chat;
+pub mod consent;
 pub mod deposit;
+pub mod email;
+pub mod events;
+pub mod invoice;
 pub mod macro_service;
 pub mod notification;
+pub mod notification_outbox;
+pub mod notification_template;
 pub mod notification_token;
+pub mod payment;
+pub mod payroll;
 pub mod profile;
+pub mod receipt;
+pub mod referral;
+pub mod review;
 pub mod service;
+pub mod shift;
 pub mod statistics;
+pub mod treatment;
 pub mod user;
+pub mod webhook;
+pub mod zalo;
 pub use macro_service::*;
 
 pub fn router_v1() -> Router<Arc<AppState>> {
@@ -26,8 +42,15 @@ pub fn router_v1() -> Router<Arc<AppState>> {
 }
 
 pub fn router_v1_public() -> Router<Arc<AppState>> {
-  Router::new()
-    .nest("/api/v1", Router::new().merge(auth::routes()).merge(service::routes_service_pub()))
+  Router::new().nest(
+    "/api/v1",
+    Router::new()
+      .merge(auth::routes())
+      .merge(service::routes_service_pub())
+      .merge(payment::routes::routes_pub())
+      .merge(receipt::routes::routes_pub())
+      .merge(zalo::routes::routes_pub()),
+  )
 }
 
 pub fn router_v0_private() -> Router<Arc<AppState>> {
@@ -48,6 +71,22 @@ pub fn router_v1_private() -> Router<Arc<AppState>> {
       .merge(notification::routes())
       .merge(statistics::routes::routes())
       .merge(deposit::routes::routes())
+      .merge(payroll::routes::routes())
+      .merge(payment::routes::routes())
+      .merge(receipt::routes::routes())
+      .merge(invoice::routes::routes())
+      .merge(shift::routes::routes())
+      .merge(treatment::routes::routes())
+      .merge(consent::routes::routes())
+      .merge(review::routes::routes())
+      .merge(referral::routes::routes())
+      .merge(notification_outbox::routes::routes())
+      .merge(notification_template::routes::routes())
+      .merge(campaign::routes::routes())
+      .merge(events::routes::routes())
+      .merge(zalo::routes::routes())
+      .merge(webhook::routes::routes())
+      .merge(email::routes::routes())
       .layer(DefaultBodyLimit::max(5 * 1024 * 1024)), // 10MB
   )
 }
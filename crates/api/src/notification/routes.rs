@@ -16,4 +16,6 @@ pub fn routes() -> Router<Arc<AppState>> {
     .route("/notifications/{id}", delete(services::delete))
     .route("/notifications/{id}", patch(services::update))
     .route("/notifications/unread/count", get(services::get_unread_count))
+    .route("/notifications/bulk/read", post(services::bulk_update_read))
+    .route("/notifications/bulk/delete", post(services::bulk_delete))
 }
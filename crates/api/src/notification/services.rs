@@ -5,6 +5,7 @@ use core_app::errors::AppError;
 use core_app::{AppResult, AppState};
 use domain::entities::common::PaginationOptions;
 use domain::entities::notification::{
+  BulkDeleteNotificationsRequest, BulkNotificationResult, BulkReadNotificationsRequest,
   CreateNotification, Notification, NotificationFilter, UpdateNotification,
 };
 use domain::entities::notification_token::NotificationToken;
@@ -218,3 +219,45 @@ pub async fn get_unread_count(
     "count": count
   })))
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/bulk/read",
+    tag="Notification Service",
+    request_body = BulkReadNotificationsRequest,
+    responses(
+        (status = 200, description = "Notifications updated successfully", body = BulkNotificationResult),
+        (status = 400, description = "Bad request", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn bulk_update_read(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(req): Json<BulkReadNotificationsRequest>,
+) -> AppResult<Json<BulkNotificationResult>> {
+  let repo = SqlxNotificationRepository { db: state.db.clone() };
+  let result = NotificationUseCase::bulk_update_read(&repo, user, req).await?;
+  Ok(Json(result))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/bulk/delete",
+    tag="Notification Service",
+    request_body = BulkDeleteNotificationsRequest,
+    responses(
+        (status = 200, description = "Notifications deleted successfully", body = BulkNotificationResult),
+        (status = 400, description = "Bad request", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn bulk_delete(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(req): Json<BulkDeleteNotificationsRequest>,
+) -> AppResult<Json<BulkNotificationResult>> {
+  let repo = SqlxNotificationRepository { db: state.db.clone() };
+  let result = NotificationUseCase::bulk_delete(&repo, user, req).await?;
+  Ok(Json(result))
+}
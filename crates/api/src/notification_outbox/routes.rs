@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/notifications/outbox", get(services::get_outbox))
+    .route("/notifications/outbox/stats", get(services::get_outbox_stats))
+    .route("/notifications/outbox/{id}", get(services::get_outbox_by_id))
+    .route("/notifications/outbox/{id}/retry", post(services::retry_outbox))
+}
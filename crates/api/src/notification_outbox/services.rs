@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    notification_outbox::{
+      NotificationOutbox, NotificationOutboxDetail, NotificationOutboxFilter,
+      NotificationOutboxStats,
+    },
+    user::UserWithPassword,
+  },
+  services::notification_outbox::NotificationOutboxUseCase,
+};
+use infra::repositories::notification_outbox::SqlxNotificationOutboxRepository;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/outbox",
+    tag = "Notification Outbox Service",
+    params(NotificationOutboxFilter),
+    responses(
+        (status = 200, description = "Get queued notifications successfully", body = Vec<NotificationOutbox>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_outbox(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<NotificationOutboxFilter>,
+) -> AppResult<Json<Vec<NotificationOutbox>>> {
+  let repo = SqlxNotificationOutboxRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let outbox = NotificationOutboxUseCase::list(&repo, filter).await?;
+
+  Ok(Json(outbox))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/outbox/stats",
+    tag = "Notification Outbox Service",
+    responses(
+        (status = 200, description = "Get outbox counters successfully", body = NotificationOutboxStats),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_outbox_stats(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<NotificationOutboxStats>> {
+  let repo = SqlxNotificationOutboxRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let stats = NotificationOutboxUseCase::get_stats(&repo).await?;
+
+  Ok(Json(stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/outbox/{id}",
+    tag = "Notification Outbox Service",
+    params(
+        ("id" = i64, Path, description = "Outbox id")
+    ),
+    responses(
+        (status = 200, description = "Get queued notification with per-token results", body = NotificationOutboxDetail),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Notification not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_outbox_by_id(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<NotificationOutboxDetail>> {
+  let repo = SqlxNotificationOutboxRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let detail = NotificationOutboxUseCase::get_by_id(&repo, id).await?;
+
+  Ok(Json(detail))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/outbox/{id}/retry",
+    tag = "Notification Outbox Service",
+    params(
+        ("id" = i64, Path, description = "Outbox id")
+    ),
+    responses(
+        (status = 200, description = "Dead notification queued again", body = NotificationOutboxDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Notification not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn retry_outbox(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<NotificationOutboxDetail>> {
+  let repo = SqlxNotificationOutboxRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let detail = NotificationOutboxUseCase::retry(&repo, id).await?;
+
+  Ok(Json(detail))
+}
@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, patch},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/notification-templates", get(services::get_templates))
+    .route("/notification-templates/{id}", get(services::get_template_by_id))
+    .route("/notification-templates/{id}", patch(services::update_template))
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    notification_template::{
+      NotificationTemplate, NotificationTemplateFilter, UpdateNotificationTemplateRequest,
+    },
+    user::UserWithPassword,
+  },
+  services::notification_template::NotificationTemplateUseCase,
+};
+use infra::repositories::notification_template::SqlxNotificationTemplateRepository;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/notification-templates",
+    tag = "Notification Template Service",
+    params(NotificationTemplateFilter),
+    responses(
+        (status = 200, description = "Get notification templates successfully", body = Vec<NotificationTemplate>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_templates(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<NotificationTemplateFilter>,
+) -> AppResult<Json<Vec<NotificationTemplate>>> {
+  let repo = SqlxNotificationTemplateRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let templates = NotificationTemplateUseCase::list(&repo, filter).await?;
+
+  Ok(Json(templates))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/notification-templates/{id}",
+    tag = "Notification Template Service",
+    params(
+        ("id" = i64, Path, description = "Notification template ID")
+    ),
+    responses(
+        (status = 200, description = "Get notification template successfully", body = NotificationTemplate),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Notification template not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_template_by_id(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<NotificationTemplate>> {
+  let repo = SqlxNotificationTemplateRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let template = NotificationTemplateUseCase::get_by_id(&repo, id).await?;
+
+  Ok(Json(template))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/notification-templates/{id}",
+    tag = "Notification Template Service",
+    params(
+        ("id" = i64, Path, description = "Notification template ID")
+    ),
+    request_body = UpdateNotificationTemplateRequest,
+    responses(
+        (status = 200, description = "Update notification template successfully", body = NotificationTemplate),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Notification template not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_template(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<UpdateNotificationTemplateRequest>,
+) -> AppResult<Json<NotificationTemplate>> {
+  let repo = SqlxNotificationTemplateRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let template = NotificationTemplateUseCase::update(&repo, id, payload).await?;
+
+  Ok(Json(template))
+}
@@ -6,7 +6,7 @@ use domain::entities::notification::Notification;
 use domain::entities::notification_token::{NotificationToken, PayloadNotificationToken};
 use domain::services::notification_token::NotificationTokenUseCase;
 use infra::events::zalo::ZaloService;
-use infra::firebase::NotificationService;
+use infra::firebase::{FcmSendResult, NotificationService};
 use infra::repositories::notification_token::SqlxNotiTokenRepository;
 use modql::filter::{ListOptions, OrderBys};
 use serde_json::{Value, json};
@@ -171,7 +171,7 @@ pub async fn get_list_tokens(
 
 pub async fn test(State(state): State<Arc<AppState>>) -> AppResult<Json<Vec<String>>> {
   let noti_service =
-    NotificationService::new().await.map_err(|err| AppError::BadRequest(err.to_string()))?;
+    NotificationService::shared().await.map_err(|err| AppError::BadRequest(err.to_string()))?;
   let noti_token_repo = SqlxNotiTokenRepository { db: state.db.clone() };
   let tokens = NotificationTokenUseCase::get_token_by_user_id(&noti_token_repo, 2).await?;
 
@@ -182,7 +182,7 @@ pub async fn test(State(state): State<Arc<AppState>>) -> AppResult<Json<Vec<Stri
 
   tracing::info!("all_tokens: {:#?}", all_tokens);
 
-  let _ = noti_service
+  let results = noti_service
     .send_notification(
       Notification {
         id: 0,
@@ -202,6 +202,13 @@ pub async fn test(State(state): State<Arc<AppState>>) -> AppResult<Json<Vec<Stri
     .await
     .map_err(|err| AppError::BadRequest(err.to_string()))?;
 
+  let invalid_tokens: Vec<String> = results
+    .into_iter()
+    .filter(|(_, result)| matches!(result, FcmSendResult::InvalidToken(_)))
+    .map(|(token, _)| token)
+    .collect();
+  NotificationTokenUseCase::delete_invalid_tokens(&noti_token_repo, invalid_tokens).await?;
+
   Ok(Json(all_tokens))
 }
 
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/payments/intents", post(services::create_payment_intent))
+    .route("/payments/intents/{id}", get(services::get_payment_intent))
+    .route("/payments/bank-transactions", get(services::get_bank_transactions))
+    .route("/payments/webhook/stub", post(services::bank_transfer_webhook_stub))
+}
+
+// Webhook do cổng thanh toán gọi, xác thực bằng chữ ký HMAC thay cho token đăng nhập
+pub fn routes_pub() -> Router<Arc<AppState>> {
+  Router::new().route("/payments/webhook", post(services::bank_transfer_webhook))
+}
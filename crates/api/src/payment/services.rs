@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  body::Bytes,
+  extract::{Extension, Path, Query, State},
+  http::HeaderMap,
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    payment::{
+      BankTransaction, BankTransactionFilter, BankTransferWebhook, CreatePaymentIntentRequest,
+      PaymentIntentResponse,
+    },
+    user::UserWithPassword,
+  },
+  services::payment::PaymentUseCase,
+};
+use infra::repositories::payment::SqlxPaymentRepository;
+use utils::helper::verify_hmac_sha256;
+
+const SIGNATURE_HEADER: &str = "x-signature";
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/intents",
+    tag = "Payment Service",
+    request_body = CreatePaymentIntentRequest,
+    responses(
+        (status = 200, description = "Payment intent created successfully", body = PaymentIntentResponse),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Appointment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_payment_intent(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreatePaymentIntentRequest>,
+) -> AppResult<Json<PaymentIntentResponse>> {
+  let repo = SqlxPaymentRepository { db: state.db.clone() };
+
+  let intent = PaymentUseCase::create_intent(&repo, &state.config.bank, user, payload).await?;
+
+  Ok(Json(intent))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments/intents/{id}",
+    tag = "Payment Service",
+    params(
+        ("id" = i64, Path, description = "Payment intent id")
+    ),
+    responses(
+        (status = 200, description = "Get payment intent successfully", body = PaymentIntentResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Payment intent not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_payment_intent(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<PaymentIntentResponse>> {
+  let repo = SqlxPaymentRepository { db: state.db.clone() };
+
+  let intent = PaymentUseCase::get_intent(&repo, &state.config.bank, user, id).await?;
+
+  Ok(Json(intent))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments/bank-transactions",
+    tag = "Payment Service",
+    params(BankTransactionFilter),
+    responses(
+        (status = 200, description = "Get bank transactions successfully", body = Vec<BankTransaction>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_bank_transactions(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<BankTransactionFilter>,
+) -> AppResult<Json<Vec<BankTransaction>>> {
+  let repo = SqlxPaymentRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let transactions = PaymentUseCase::get_bank_transactions(&repo, filter).await?;
+
+  Ok(Json(transactions))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/webhook",
+    tag = "Payment Service",
+    request_body = BankTransferWebhook,
+    params(
+        ("X-Signature" = String, Header, description = "Hex HMAC-SHA256 of the raw request body")
+    ),
+    responses(
+        (status = 200, description = "Bank transfer processed", body = BankTransaction),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Invalid signature"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn bank_transfer_webhook(
+  State(state): State<Arc<AppState>>,
+  headers: HeaderMap,
+  body: Bytes,
+) -> AppResult<Json<BankTransaction>> {
+  let secret = &state.config.bank.webhook_secret;
+  let signature = headers.get(SIGNATURE_HEADER).and_then(|value| value.to_str().ok());
+
+  let verified = match signature {
+    Some(signature) if !secret.is_empty() => verify_hmac_sha256(secret, &body, signature),
+    _ => false,
+  };
+
+  if !verified {
+    tracing::warn!("Rejected bank transfer webhook with invalid signature");
+    return Err(AppError::Unauthorized("Invalid signature".to_string()));
+  }
+
+  let raw_payload: serde_json::Value = serde_json::from_slice(&body)?;
+  let payload: BankTransferWebhook = serde_json::from_value(raw_payload.clone())?;
+
+  let repo = SqlxPaymentRepository { db: state.db.clone() };
+
+  let transaction =
+    PaymentUseCase::handle_bank_transfer(&repo, &state.config.bank, payload, raw_payload).await?;
+
+  Ok(Json(transaction))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/webhook/stub",
+    tag = "Payment Service",
+    request_body = BankTransferWebhook,
+    responses(
+        (status = 200, description = "Simulated bank transfer processed", body = BankTransaction),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not available in production"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+// Giả lập ngân hàng gửi webhook để test luồng đối soát khi chưa có cổng thanh toán thật
+pub async fn bank_transfer_webhook_stub(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<BankTransferWebhook>,
+) -> AppResult<Json<BankTransaction>> {
+  if std::env::var("ENV").unwrap_or_default() == "production" {
+    return Err(AppError::NotFound);
+  }
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let mut payload = payload;
+  payload.provider = Some(payload.provider.unwrap_or_else(|| "STUB".to_string()));
+  payload.account_number =
+    Some(payload.account_number.unwrap_or_else(|| state.config.bank.account_number.clone()));
+  let raw_payload = serde_json::to_value(&payload)?;
+
+  let repo = SqlxPaymentRepository { db: state.db.clone() };
+
+  let transaction =
+    PaymentUseCase::handle_bank_transfer(&repo, &state.config.bank, payload, raw_payload).await?;
+
+  Ok(Json(transaction))
+}
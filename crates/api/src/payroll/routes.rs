@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{delete, get, patch, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/payroll", get(services::get_payroll))
+    .route("/payroll/{period}/lock", post(services::lock_payroll_period))
+    .route("/payroll/adjustments", post(services::create_adjustment))
+    .route("/payroll/commission-rules", get(services::get_commission_rules))
+    .route("/payroll/commission-rules", post(services::create_commission_rule))
+    .route("/payroll/commission-rules/{id}", patch(services::update_commission_rule))
+    .route("/payroll/commission-rules/{id}", delete(services::delete_commission_rule))
+}
@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    payroll::{
+      Commission, CommissionRule, CreateCommissionRuleRequest, CreatePayrollAdjustmentRequest,
+      PayrollFilter, PayrollPeriod, PayrollReport, UpdateCommissionRuleRequest,
+    },
+    user::UserWithPassword,
+  },
+  services::payroll::PayrollUseCase,
+};
+use infra::repositories::payroll::SqlxPayrollRepository;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payroll",
+    tag = "Payroll Service",
+    params(PayrollFilter),
+    responses(
+        (status = 200, description = "Get payroll successfully", body = PayrollReport),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_payroll(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<PayrollFilter>,
+) -> AppResult<Json<PayrollReport>> {
+  let repo = SqlxPayrollRepository { db: state.db.clone() };
+
+  // Kỹ thuật viên chỉ xem được bảng lương của mình
+  let technician_id = match user.role.as_str() {
+    "ADMIN" => filter.technician_id,
+    "TECHNICIAN" => Some(user.pk_user_id),
+    _ => return Err(AppError::Forbidden("You don't have permission".to_string())),
+  };
+
+  let report = PayrollUseCase::get_payroll(&repo, filter.period, technician_id).await?;
+
+  Ok(Json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payroll/{period}/lock",
+    tag = "Payroll Service",
+    params(
+        ("period" = String, Path, description = "Payroll period (YYYY-MM)")
+    ),
+    responses(
+        (status = 200, description = "Payroll period locked successfully", body = PayrollPeriod),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn lock_payroll_period(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(period): Path<String>,
+) -> AppResult<Json<PayrollPeriod>> {
+  let repo = SqlxPayrollRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let payroll_period = PayrollUseCase::lock_payroll_period(&repo, period, user.pk_user_id).await?;
+
+  Ok(Json(payroll_period))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payroll/adjustments",
+    tag = "Payroll Service",
+    request_body = CreatePayrollAdjustmentRequest,
+    responses(
+        (status = 200, description = "Payroll adjustment created successfully", body = Commission),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_adjustment(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreatePayrollAdjustmentRequest>,
+) -> AppResult<Json<Commission>> {
+  let repo = SqlxPayrollRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let adjustment = PayrollUseCase::create_adjustment(&repo, payload, user.pk_user_id).await?;
+
+  Ok(Json(adjustment))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payroll/commission-rules",
+    tag = "Payroll Service",
+    responses(
+        (status = 200, description = "Get commission rules successfully", body = Vec<CommissionRule>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_commission_rules(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<Vec<CommissionRule>>> {
+  let repo = SqlxPayrollRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let rules = PayrollUseCase::get_commission_rules(&repo).await?;
+
+  Ok(Json(rules))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payroll/commission-rules",
+    tag = "Payroll Service",
+    request_body = CreateCommissionRuleRequest,
+    responses(
+        (status = 200, description = "Commission rule created successfully", body = CommissionRule),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_commission_rule(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateCommissionRuleRequest>,
+) -> AppResult<Json<CommissionRule>> {
+  let repo = SqlxPayrollRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let rule = PayrollUseCase::create_commission_rule(&repo, payload, user.pk_user_id).await?;
+
+  Ok(Json(rule))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/payroll/commission-rules/{id}",
+    tag = "Payroll Service",
+    request_body = UpdateCommissionRuleRequest,
+    responses(
+        (status = 200, description = "Commission rule updated successfully", body = CommissionRule),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Commission rule not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_commission_rule(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<UpdateCommissionRuleRequest>,
+) -> AppResult<Json<CommissionRule>> {
+  let repo = SqlxPayrollRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let rule = PayrollUseCase::update_commission_rule(&repo, id, payload).await?;
+
+  Ok(Json(rule))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/payroll/commission-rules/{id}",
+    tag = "Payroll Service",
+    responses(
+        (status = 200, description = "Commission rule deleted successfully", body = bool),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Commission rule not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_commission_rule(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<bool>> {
+  let repo = SqlxPayrollRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  PayrollUseCase::delete_commission_rule(&repo, id).await?;
+
+  Ok(Json(true))
+}
@@ -4,7 +4,7 @@ use super::services;
 use axum::{
   Router,
   extract::DefaultBodyLimit,
-  routing::{delete, get, patch, post},
+  routing::{delete, get, patch, post, put},
 };
 use core_app::AppState;
 
@@ -16,5 +16,7 @@ pub fn routes() -> Router<Arc<AppState>> {
     .route("/profile/update", patch(services::update_profile_service))
     .route("/profile/change-avatar", patch(services::change_avatar_service))
     .route("/profile/delete-account", delete(services::delete_account))
+    .route("/profile/notification-preferences", get(services::get_notification_preferences))
+    .route("/profile/notification-preferences", put(services::update_notification_preferences))
     .layer(DefaultBodyLimit::max(5 * 1024 * 1024)) // 10MB
 }
@@ -6,12 +6,16 @@ use core_app::{AppResult, AppState, errors::AppError};
 use domain::{
   entities::{
     auth::LogoutRequest,
+    notification_preference::{NotificationPreferences, UpdateNotificationPreferencesRequest},
     profile::{ChangeAvatarRequest, ChangePasswordRequest, UpdateProfileRequest},
     user::{User, UserWithPassword},
   },
-  services::profile::ProfileUseCase,
+  services::{notification_preference::NotificationPreferenceUseCase, profile::ProfileUseCase},
+};
+use infra::repositories::{
+  image::LocalImageService, notification_preference::SqlxNotificationPreferenceRepository,
+  profile::SqlxProfileRepository,
 };
-use infra::repositories::{image::LocalImageService, profile::SqlxProfileRepository};
 use std::sync::Arc;
 
 #[utoipa::path(
@@ -192,3 +196,47 @@ pub async fn delete_account(
 
   Ok(Json(is_success))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/profile/notification-preferences",
+    tag="Profile Service",
+    responses(
+        (status = 200, description = "Get notification preferences successfully", body = NotificationPreferences),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn get_notification_preferences(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<NotificationPreferences>> {
+  let repo = SqlxNotificationPreferenceRepository { db: state.db.clone() };
+
+  let preferences = NotificationPreferenceUseCase::get_preferences(&repo, user.pk_user_id).await?;
+
+  Ok(Json(preferences))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/profile/notification-preferences",
+    tag="Profile Service",
+    request_body = UpdateNotificationPreferencesRequest,
+    responses(
+        (status = 200, description = "Update notification preferences successfully", body = NotificationPreferences),
+        (status = 400, description = "Bad request", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    )
+)]
+pub async fn update_notification_preferences(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(req): Json<UpdateNotificationPreferencesRequest>,
+) -> AppResult<Json<NotificationPreferences>> {
+  let repo = SqlxNotificationPreferenceRepository { db: state.db.clone() };
+
+  let preferences =
+    NotificationPreferenceUseCase::update_preferences(&repo, user.pk_user_id, req).await?;
+
+  Ok(Json(preferences))
+}
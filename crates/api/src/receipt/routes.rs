@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{Router, routing::get};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new().route("/appointments/{id}/receipt", get(services::get_appointment_receipt))
+}
+
+// Link chia sẻ hoá đơn, xác thực bằng token ký kèm theo số hoá đơn
+pub fn routes_pub() -> Router<Arc<AppState>> {
+  Router::new().route("/receipts/{receipt_no}", get(services::get_shared_receipt))
+}
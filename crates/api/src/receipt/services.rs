@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+  http::header,
+  response::{IntoResponse, Response},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    receipt::{ReceiptDocument, ReceiptQuery, SharedReceiptQuery},
+    user::UserWithPassword,
+  },
+  services::receipt::ReceiptUseCase,
+};
+use infra::{
+  documents::{
+    escpos::{ESCPOS_COLUMNS, render_escpos},
+    pdf::{PaperSize, render_pdf},
+    receipt::layout_receipt,
+  },
+  repositories::receipt::SqlxReceiptRepository,
+};
+
+// Trả hoá đơn theo định dạng yêu cầu: JSON, PDF hoặc lệnh ESC/POS cho máy in nhiệt 80mm
+fn render_receipt(
+  document: ReceiptDocument,
+  format: Option<String>,
+  paper: Option<String>,
+) -> AppResult<Response> {
+  let format = format.unwrap_or_else(|| "JSON".to_string()).to_uppercase();
+
+  match format.as_str() {
+    "JSON" => Ok(Json(document).into_response()),
+    "PDF" => {
+      let paper = PaperSize::from_name(paper.as_deref())
+        .ok_or_else(|| AppError::BadRequest("Paper must be A4 or A5".to_string()))?;
+      let bytes = render_pdf(&layout_receipt(&document, paper.columns()), paper);
+
+      Ok(
+        (
+          [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+              header::CONTENT_DISPOSITION,
+              format!("inline; filename=\"{}.pdf\"", document.receipt_no),
+            ),
+          ],
+          bytes,
+        )
+          .into_response(),
+      )
+    },
+    "ESCPOS" => {
+      let bytes = render_escpos(&layout_receipt(&document, ESCPOS_COLUMNS));
+
+      Ok(
+        (
+          [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (
+              header::CONTENT_DISPOSITION,
+              format!("attachment; filename=\"{}.bin\"", document.receipt_no),
+            ),
+          ],
+          bytes,
+        )
+          .into_response(),
+      )
+    },
+    _ => Err(AppError::BadRequest("Format must be JSON, PDF or ESCPOS".to_string())),
+  }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/appointments/{id}/receipt",
+    tag = "Receipt Service",
+    params(
+        ("id" = i64, Path, description = "Appointment ID"),
+        ReceiptQuery
+    ),
+    responses(
+        (status = 200, description = "Get receipt successfully", body = ReceiptDocument),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Appointment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_appointment_receipt(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Query(query): Query<ReceiptQuery>,
+) -> AppResult<Response> {
+  let repo = SqlxReceiptRepository { db: state.db.clone() };
+
+  let document = ReceiptUseCase::get_receipt(
+    &repo,
+    &state.config.spa,
+    &state.config.token.receipt_share_secret,
+    user,
+    id,
+  )
+  .await?;
+
+  render_receipt(document, query.format, query.paper)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/receipts/{receipt_no}",
+    tag = "Receipt Service",
+    params(
+        ("receipt_no" = String, Path, description = "Receipt number"),
+        SharedReceiptQuery
+    ),
+    responses(
+        (status = 200, description = "Get receipt successfully", body = ReceiptDocument),
+        (status = 400, description = "Bad request", body = String),
+        (status = 404, description = "Receipt not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_shared_receipt(
+  State(state): State<Arc<AppState>>,
+  Path(receipt_no): Path<String>,
+  Query(query): Query<SharedReceiptQuery>,
+) -> AppResult<Response> {
+  let repo = SqlxReceiptRepository { db: state.db.clone() };
+
+  let document = ReceiptUseCase::get_shared_receipt(
+    &repo,
+    &state.config.spa,
+    &state.config.token.receipt_share_secret,
+    receipt_no,
+    &query.token,
+  )
+  .await?;
+
+  render_receipt(document, query.format, query.paper)
+}
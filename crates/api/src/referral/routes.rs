@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{Router, routing::get};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/referrals", get(services::get_referrals))
+    .route("/referrals/me", get(services::get_my_referrals))
+    .route("/referrals/report", get(services::get_referral_report))
+}
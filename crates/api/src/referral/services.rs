@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    referral::{
+      Referral, ReferralFilter, ReferralReportFilter, ReferralReportRow, ReferralSummary,
+    },
+    user::UserWithPassword,
+  },
+  services::referral::ReferralUseCase,
+};
+use infra::repositories::referral::SqlxReferralRepository;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/referrals/me",
+    tag = "Referral Service",
+    responses(
+        (status = 200, description = "Get referral code and referrals successfully", body = ReferralSummary),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_my_referrals(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<ReferralSummary>> {
+  let repo = SqlxReferralRepository { db: state.db.clone() };
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let summary = ReferralUseCase::get_my_summary(&repo, user).await?;
+
+  Ok(Json(summary))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/referrals",
+    tag = "Referral Service",
+    params(ReferralFilter),
+    responses(
+        (status = 200, description = "Get referrals successfully", body = Vec<Referral>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_referrals(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<ReferralFilter>,
+) -> AppResult<Json<Vec<Referral>>> {
+  let repo = SqlxReferralRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" && user.role != "RECEPTIONIST" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let referrals = ReferralUseCase::get_referrals(&repo, filter).await?;
+
+  Ok(Json(referrals))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/referrals/report",
+    tag = "Referral Service",
+    params(ReferralReportFilter),
+    responses(
+        (status = 200, description = "Get referral report successfully", body = Vec<ReferralReportRow>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_referral_report(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<ReferralReportFilter>,
+) -> AppResult<Json<Vec<ReferralReportRow>>> {
+  let repo = SqlxReferralRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let report = ReferralUseCase::get_report(&repo, filter).await?;
+
+  Ok(Json(report))
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{delete, get, patch, post, put},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/reviews", get(services::get_reviews))
+    .route("/reviews", post(services::create_review))
+    .route("/reviews/me", get(services::get_my_reviews))
+    .route("/reviews/{id}", get(services::get_review))
+    .route("/reviews/{id}/moderation", patch(services::moderate_review))
+    .route("/reviews/{id}/reply", put(services::reply_review))
+    .route("/reviews/{id}/photos", post(services::add_review_photo))
+    .route("/reviews/{id}/photos/{photo_id}", delete(services::delete_review_photo))
+    .route("/appointments/{id}/review", get(services::get_appointment_review))
+}
@@ -0,0 +1,342 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Multipart, Path, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    review::{
+      CreateReviewPhotoRequest, CreateReviewRequest, ModerateReviewRequest, ReplyReviewRequest,
+      ReviewDetail, ReviewFilter, ReviewPhoto,
+    },
+    user::UserWithPassword,
+  },
+  services::review::ReviewUseCase,
+};
+use infra::repositories::{image::LocalImageService, review::SqlxReviewRepository};
+use tracing::error;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/reviews",
+    tag = "Review Service",
+    request_body = CreateReviewRequest,
+    responses(
+        (status = 200, description = "Review created successfully", body = ReviewDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Appointment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_review(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateReviewRequest>,
+) -> AppResult<Json<ReviewDetail>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let review = ReviewUseCase::create(&repo, &state.config.review, user, payload).await?;
+
+  Ok(Json(review))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reviews",
+    tag = "Review Service",
+    params(ReviewFilter),
+    responses(
+        (status = 200, description = "Get reviews successfully", body = Vec<ReviewDetail>),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_reviews(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<ReviewFilter>,
+) -> AppResult<Json<Vec<ReviewDetail>>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+
+  let reviews = ReviewUseCase::get_reviews(&repo, user, filter).await?;
+
+  Ok(Json(reviews))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reviews/me",
+    tag = "Review Service",
+    responses(
+        (status = 200, description = "Get my reviews successfully", body = Vec<ReviewDetail>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_my_reviews(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<Vec<ReviewDetail>>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let reviews = ReviewUseCase::get_my_reviews(&repo, user).await?;
+
+  Ok(Json(reviews))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reviews/{id}",
+    tag = "Review Service",
+    params(
+        ("id" = i64, Path, description = "Review ID")
+    ),
+    responses(
+        (status = 200, description = "Get review successfully", body = ReviewDetail),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Review not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_review(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<ReviewDetail>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+
+  let review = ReviewUseCase::get_review(&repo, user, id).await?;
+
+  Ok(Json(review))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/appointments/{id}/review",
+    tag = "Review Service",
+    params(
+        ("id" = i64, Path, description = "Appointment ID")
+    ),
+    responses(
+        (status = 200, description = "Get review of the appointment, null if not reviewed yet", body = Option<ReviewDetail>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Appointment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_appointment_review(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<Option<ReviewDetail>>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+
+  let review = ReviewUseCase::get_appointment_review(&repo, user, id).await?;
+
+  Ok(Json(review))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/reviews/{id}/moderation",
+    tag = "Review Service",
+    params(
+        ("id" = i64, Path, description = "Review ID")
+    ),
+    request_body = ModerateReviewRequest,
+    responses(
+        (status = 200, description = "Review hidden or shown successfully", body = ReviewDetail),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Review not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn moderate_review(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<ModerateReviewRequest>,
+) -> AppResult<Json<ReviewDetail>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let review = ReviewUseCase::moderate(&repo, user, id, payload).await?;
+
+  Ok(Json(review))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/reviews/{id}/reply",
+    tag = "Review Service",
+    params(
+        ("id" = i64, Path, description = "Review ID")
+    ),
+    request_body = ReplyReviewRequest,
+    responses(
+        (status = 200, description = "Review replied successfully", body = ReviewDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Review not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn reply_review(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<ReplyReviewRequest>,
+) -> AppResult<Json<ReviewDetail>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let review = ReviewUseCase::reply(&repo, user, id, payload).await?;
+
+  Ok(Json(review))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/reviews/{id}/photos",
+    tag = "Review Service",
+    params(
+        ("id" = i64, Path, description = "Review ID")
+    ),
+    request_body(
+        content_type = "multipart/form-data",
+        content = CreateReviewPhotoRequest,
+        description = "Review photo (field name 'image': PNG, JPG or WEBP)"
+    ),
+    responses(
+        (status = 200, description = "Photo uploaded successfully", body = ReviewPhoto),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Review not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn add_review_photo(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  mut multipart: Multipart,
+) -> AppResult<Json<ReviewPhoto>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+  let image_repo = Arc::new(LocalImageService);
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let mut image_data = None;
+  let mut content_type = None;
+
+  while let Some(field) = multipart.next_field().await.map_err(|err| {
+    error!("Failed to read multipart field: {}", err);
+    AppError::BadRequest(format!("Failed to process form data: {}", err))
+  })? {
+    if field.name() == Some("image") {
+      let ct = field.content_type().map(|ct| ct.to_string());
+      let data = field.bytes().await.map_err(|err| {
+        error!("Failed to read image data: {}", err);
+        AppError::BadRequest(format!("Failed to read image data: {}", err))
+      })?;
+
+      image_data = Some(data.to_vec());
+      content_type = ct;
+    }
+  }
+
+  let image_data = image_data.unwrap_or_default();
+  let content_type = content_type.unwrap_or_default();
+
+  let photo = ReviewUseCase::add_photo(
+    &repo,
+    image_repo,
+    &state.config.review,
+    user,
+    id,
+    &image_data,
+    &content_type,
+  )
+  .await?;
+
+  Ok(Json(photo))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/reviews/{id}/photos/{photo_id}",
+    tag = "Review Service",
+    params(
+        ("id" = i64, Path, description = "Review ID"),
+        ("photo_id" = i64, Path, description = "Photo ID")
+    ),
+    responses(
+        (status = 200, description = "Photo deleted successfully", body = bool),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Photo not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_review_photo(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path((id, photo_id)): Path<(i64, i64)>,
+) -> AppResult<Json<bool>> {
+  let repo = SqlxReviewRepository { db: state.db.clone() };
+  let image_repo = Arc::new(LocalImageService);
+
+  ReviewUseCase::delete_photo(&repo, image_repo, user, id, photo_id).await?;
+
+  Ok(Json(true))
+}
@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/shifts", get(services::get_shifts))
+    .route("/shifts/open", post(services::open_shift))
+    .route("/shifts/current", get(services::get_current_shift))
+    .route("/shifts/current/close", post(services::close_current_shift))
+    .route("/shifts/variance-report", get(services::get_variance_report))
+    .route("/shifts/{id}", get(services::get_shift))
+}
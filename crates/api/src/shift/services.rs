@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    shift::{
+      CloseShiftRequest, OpenShiftRequest, Shift, ShiftFilter, ShiftSummary, ShiftVarianceReport,
+    },
+    user::UserWithPassword,
+  },
+  services::shift::ShiftUseCase,
+};
+use infra::repositories::shift::SqlxShiftRepository;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/shifts/open",
+    tag = "Shift Service",
+    request_body = OpenShiftRequest,
+    responses(
+        (status = 200, description = "Shift opened successfully", body = Shift),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn open_shift(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<OpenShiftRequest>,
+) -> AppResult<Json<Shift>> {
+  let repo = SqlxShiftRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let shift = ShiftUseCase::open_shift(&repo, user, payload).await?;
+
+  Ok(Json(shift))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/shifts/current",
+    tag = "Shift Service",
+    responses(
+        (status = 200, description = "Get current shift successfully", body = ShiftSummary),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "No open shift"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_current_shift(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<ShiftSummary>> {
+  let repo = SqlxShiftRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let summary = ShiftUseCase::get_current_shift(&repo, user).await?;
+
+  Ok(Json(summary))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/shifts/current/close",
+    tag = "Shift Service",
+    request_body = CloseShiftRequest,
+    responses(
+        (status = 200, description = "Shift closed successfully", body = ShiftSummary),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn close_current_shift(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CloseShiftRequest>,
+) -> AppResult<Json<ShiftSummary>> {
+  let repo = SqlxShiftRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let summary = ShiftUseCase::close_current_shift(&repo, user, payload).await?;
+
+  Ok(Json(summary))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/shifts",
+    tag = "Shift Service",
+    params(ShiftFilter),
+    responses(
+        (status = 200, description = "Get shifts successfully", body = Vec<Shift>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_shifts(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(mut filter): Query<ShiftFilter>,
+) -> AppResult<Json<Vec<Shift>>> {
+  let repo = SqlxShiftRepository { db: state.db.clone() };
+
+  // Lễ tân chỉ xem được lịch sử ca của mình
+  match user.role.as_str() {
+    "ADMIN" => {},
+    "RECEPTIONIST" => filter.receptionist_id = Some(user.pk_user_id),
+    _ => return Err(AppError::Forbidden("You don't have permission".to_string())),
+  }
+
+  let shifts = ShiftUseCase::get_shifts(&repo, filter).await?;
+
+  Ok(Json(shifts))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/shifts/variance-report",
+    tag = "Shift Service",
+    params(ShiftFilter),
+    responses(
+        (status = 200, description = "Get variance report successfully", body = ShiftVarianceReport),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_variance_report(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<ShiftFilter>,
+) -> AppResult<Json<ShiftVarianceReport>> {
+  let repo = SqlxShiftRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let report = ShiftUseCase::get_variance_report(&repo, filter).await?;
+
+  Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/shifts/{id}",
+    tag = "Shift Service",
+    params(
+        ("id" = i64, Path, description = "Shift ID")
+    ),
+    responses(
+        (status = 200, description = "Get shift successfully", body = ShiftSummary),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Shift not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_shift(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<ShiftSummary>> {
+  let repo = SqlxShiftRepository { db: state.db.clone() };
+
+  if user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let summary = ShiftUseCase::get_shift(&repo, user, id).await?;
+
+  Ok(Json(summary))
+}
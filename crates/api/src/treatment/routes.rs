@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{delete, get, patch, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/treatment-fields", get(services::get_treatment_fields))
+    .route("/treatment-fields", post(services::create_treatment_field))
+    .route("/treatment-fields/{id}", patch(services::update_treatment_field))
+    .route("/treatment-records", get(services::get_treatment_records))
+    .route("/treatment-records", post(services::create_treatment_record))
+    .route("/treatment-records/me", get(services::get_my_treatment_records))
+    .route("/treatment-records/{id}", get(services::get_treatment_record))
+    .route("/treatment-records/{id}", patch(services::update_treatment_record))
+    .route("/treatment-records/{id}/history", get(services::get_treatment_record_history))
+    .route("/treatment-records/{id}/photos", post(services::add_treatment_photo))
+    .route("/treatment-records/{id}/photos/{photo_id}", delete(services::delete_treatment_photo))
+    .route(
+      "/treatment-records/{id}/photos/{photo_id}/image",
+      get(services::get_treatment_photo_image),
+    )
+}
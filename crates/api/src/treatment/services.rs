@@ -0,0 +1,483 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Multipart, Path, Query, State},
+  http::header,
+  response::{IntoResponse, Response},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    treatment::{
+      CreateTreatmentFieldRequest, CreateTreatmentPhotoRequest, CreateTreatmentRecordRequest,
+      CustomerTreatmentRecord, TreatmentField, TreatmentFieldFilter, TreatmentPhoto,
+      TreatmentRecord, TreatmentRecordDetail, TreatmentRecordFilter, TreatmentRecordHistory,
+      UpdateTreatmentFieldRequest, UpdateTreatmentRecordRequest,
+    },
+    user::UserWithPassword,
+  },
+  services::treatment::TreatmentUseCase,
+};
+use infra::repositories::{image::LocalImageService, treatment::SqlxTreatmentRepository};
+use tracing::error;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/treatment-fields",
+    tag = "Treatment Service",
+    params(TreatmentFieldFilter),
+    responses(
+        (status = 200, description = "Get treatment fields successfully", body = Vec<TreatmentField>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_treatment_fields(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<TreatmentFieldFilter>,
+) -> AppResult<Json<Vec<TreatmentField>>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "TECHNICIAN" && user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let fields = TreatmentUseCase::get_fields(&repo, filter.service_id).await?;
+
+  Ok(Json(fields))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/treatment-fields",
+    tag = "Treatment Service",
+    request_body = CreateTreatmentFieldRequest,
+    responses(
+        (status = 200, description = "Treatment field created successfully", body = TreatmentField),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_treatment_field(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateTreatmentFieldRequest>,
+) -> AppResult<Json<TreatmentField>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let field = TreatmentUseCase::create_field(&repo, payload, user.pk_user_id).await?;
+
+  Ok(Json(field))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/treatment-fields/{id}",
+    tag = "Treatment Service",
+    params(
+        ("id" = i64, Path, description = "Treatment field ID")
+    ),
+    request_body = UpdateTreatmentFieldRequest,
+    responses(
+        (status = 200, description = "Treatment field updated successfully", body = TreatmentField),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Treatment field not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_treatment_field(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<UpdateTreatmentFieldRequest>,
+) -> AppResult<Json<TreatmentField>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let field = TreatmentUseCase::update_field(&repo, id, payload).await?;
+
+  Ok(Json(field))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/treatment-records",
+    tag = "Treatment Service",
+    params(TreatmentRecordFilter),
+    responses(
+        (status = 200, description = "Get treatment records successfully", body = Vec<TreatmentRecord>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_treatment_records(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<TreatmentRecordFilter>,
+) -> AppResult<Json<Vec<TreatmentRecord>>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "TECHNICIAN" && user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let records = TreatmentUseCase::get_records(&repo, filter).await?;
+
+  Ok(Json(records))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/treatment-records",
+    tag = "Treatment Service",
+    request_body = CreateTreatmentRecordRequest,
+    responses(
+        (status = 200, description = "Treatment record created successfully", body = TreatmentRecordDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_treatment_record(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateTreatmentRecordRequest>,
+) -> AppResult<Json<TreatmentRecordDetail>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "TECHNICIAN" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let record = TreatmentUseCase::create_record(&repo, user, payload).await?;
+
+  Ok(Json(record))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/treatment-records/me",
+    tag = "Treatment Service",
+    responses(
+        (status = 200, description = "Get my treatment records successfully", body = Vec<CustomerTreatmentRecord>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_my_treatment_records(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<Vec<CustomerTreatmentRecord>>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "CUSTOMER" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let records = TreatmentUseCase::get_my_records(&repo, user).await?;
+
+  Ok(Json(records))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/treatment-records/{id}",
+    tag = "Treatment Service",
+    params(
+        ("id" = i64, Path, description = "Treatment record ID")
+    ),
+    responses(
+        (status = 200, description = "Get treatment record successfully", body = TreatmentRecordDetail),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Treatment record not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_treatment_record(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<TreatmentRecordDetail>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "TECHNICIAN" && user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let record = TreatmentUseCase::get_record(&repo, id).await?;
+
+  Ok(Json(record))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/treatment-records/{id}",
+    tag = "Treatment Service",
+    params(
+        ("id" = i64, Path, description = "Treatment record ID")
+    ),
+    request_body = UpdateTreatmentRecordRequest,
+    responses(
+        (status = 200, description = "Treatment record updated successfully", body = TreatmentRecordDetail),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Treatment record not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_treatment_record(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<UpdateTreatmentRecordRequest>,
+) -> AppResult<Json<TreatmentRecordDetail>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "TECHNICIAN" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let record = TreatmentUseCase::update_record(&repo, user, id, payload).await?;
+
+  Ok(Json(record))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/treatment-records/{id}/history",
+    tag = "Treatment Service",
+    params(
+        ("id" = i64, Path, description = "Treatment record ID")
+    ),
+    responses(
+        (status = 200, description = "Get treatment record history successfully", body = Vec<TreatmentRecordHistory>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Treatment record not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_treatment_record_history(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<Vec<TreatmentRecordHistory>>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+
+  if user.role != "TECHNICIAN" && user.role != "RECEPTIONIST" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let history = TreatmentUseCase::get_record_history(&repo, id).await?;
+
+  Ok(Json(history))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/treatment-records/{id}/photos",
+    tag = "Treatment Service",
+    params(
+        ("id" = i64, Path, description = "Treatment record ID")
+    ),
+    request_body(
+        content_type = "multipart/form-data",
+        content = CreateTreatmentPhotoRequest,
+        description = "Before/after photo (field name: 'image', supported formats: JPG, PNG, WEBP)"
+    ),
+    responses(
+        (status = 200, description = "Photo uploaded successfully", body = TreatmentPhoto),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Treatment record not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn add_treatment_photo(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  mut multipart: Multipart,
+) -> AppResult<Json<TreatmentPhoto>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+  let image_repo = Arc::new(LocalImageService);
+
+  if user.role != "TECHNICIAN" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let mut payload =
+    CreateTreatmentPhotoRequest { photo_type: String::new(), caption: None, image: None };
+  let mut image_data = None;
+  let mut content_type = None;
+
+  while let Some(field) = multipart.next_field().await.map_err(|err| {
+    error!("Failed to read multipart field: {}", err);
+    AppError::BadRequest(format!("Failed to process form data: {}", err))
+  })? {
+    let field_name = field
+      .name()
+      .ok_or_else(|| AppError::BadRequest("Missing field name in form data".to_string()))?
+      .to_string();
+
+    if field_name == "image" {
+      let ct = field.content_type().map(|ct| ct.to_string());
+      let data = field.bytes().await.map_err(|err| {
+        error!("Failed to read image data: {}", err);
+        AppError::BadRequest(format!("Failed to read image data: {}", err))
+      })?;
+
+      image_data = Some(data.to_vec());
+      content_type = ct;
+      continue;
+    }
+
+    let value = field.text().await.map_err(|err| {
+      error!("Failed to read field {}: {}", field_name, err);
+      AppError::BadRequest(format!("Failed to read field {}: {}", field_name, err))
+    })?;
+
+    match field_name.as_str() {
+      "photo_type" => payload.photo_type = value,
+      "caption" => payload.caption = Some(value),
+      _ => {},
+    }
+  }
+
+  let image_data = image_data.unwrap_or_default();
+  let content_type = content_type.unwrap_or_default();
+
+  let photo =
+    TreatmentUseCase::add_photo(&repo, image_repo, user, id, &image_data, &content_type, payload)
+      .await?;
+
+  Ok(Json(photo))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/treatment-records/{id}/photos/{photo_id}",
+    tag = "Treatment Service",
+    params(
+        ("id" = i64, Path, description = "Treatment record ID"),
+        ("photo_id" = i64, Path, description = "Photo ID")
+    ),
+    responses(
+        (status = 200, description = "Photo deleted successfully", body = bool),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Photo not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_treatment_photo(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path((id, photo_id)): Path<(i64, i64)>,
+) -> AppResult<Json<bool>> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+  let image_repo = Arc::new(LocalImageService);
+
+  if user.role != "TECHNICIAN" && user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  TreatmentUseCase::delete_photo(&repo, image_repo, user, id, photo_id).await?;
+
+  Ok(Json(true))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/treatment-records/{id}/photos/{photo_id}/image",
+    tag = "Treatment Service",
+    params(
+        ("id" = i64, Path, description = "Treatment record ID"),
+        ("photo_id" = i64, Path, description = "Photo ID")
+    ),
+    responses(
+        (status = 200, description = "Treatment photo image", content_type = "image/jpeg"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Photo not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_treatment_photo_image(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path((id, photo_id)): Path<(i64, i64)>,
+) -> AppResult<Response> {
+  let repo = SqlxTreatmentRepository { db: state.db.clone() };
+  let image_repo = Arc::new(LocalImageService);
+
+  let (image, content_type) =
+    TreatmentUseCase::get_photo_image(&repo, image_repo, user, id, photo_id).await?;
+
+  Ok(
+    (
+      [
+        (header::CONTENT_TYPE, content_type),
+        (header::CONTENT_DISPOSITION, "inline".to_string()),
+        (header::CACHE_CONTROL, "private, no-store".to_string()),
+      ],
+      image,
+    )
+      .into_response(),
+  )
+}
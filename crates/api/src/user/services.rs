@@ -8,8 +8,8 @@ use domain::{
   entities::{
     common::PaginationOptions,
     user::{
-      PhoneFilterConvert, RequestCreateUser, RequestGetUser, RequestUpdateUser, User, UserFilter,
-      UserFilterConvert, UserWithPassword,
+      PhoneFilterConvert, RequestCreateUser, RequestGetUser, RequestUpdateUser,
+      TechnicianWithRating, User, UserFilter, UserFilterConvert, UserWithPassword,
     },
   },
   services::user::UserUseCase,
@@ -111,7 +111,7 @@ impl UserService {
     path = "/api/v1/users/technicians",
     tag="User Service",
     responses(
-        (status = 200, description = "successfully", body = Vec<User>),
+        (status = 200, description = "successfully", body = Vec<TechnicianWithRating>),
         (status = 400, description = "Bad request", body = String),
         (status = 500, description = "Internal server error", body = String)
     )
@@ -119,7 +119,7 @@ impl UserService {
 pub async fn get_all_technician(
   State(state): State<Arc<AppState>>,
   Extension(user): Extension<UserWithPassword>,
-) -> AppResult<Json<Vec<User>>> {
+) -> AppResult<Json<Vec<TechnicianWithRating>>> {
   let user_repo = SqlxUserRepository { db: state.db.clone() };
   let users = UserUseCase::get_all_technician(&user_repo, user).await?;
 
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{delete, get, patch, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/webhooks", get(services::get_webhooks))
+    .route("/webhooks", post(services::create_webhook))
+    .route("/webhooks/{id}", get(services::get_webhook_by_id))
+    .route("/webhooks/{id}", patch(services::update_webhook))
+    .route("/webhooks/{id}", delete(services::delete_webhook))
+    .route("/webhooks/{id}/deliveries", get(services::get_deliveries))
+    .route("/webhooks/deliveries/{delivery_id}/redeliver", post(services::redeliver))
+}
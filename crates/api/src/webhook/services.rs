@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  extract::{Extension, Path, Query, State},
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    user::UserWithPassword,
+    webhook::{
+      CreateWebhookSubscriptionRequest, UpdateWebhookSubscriptionRequest, WebhookDelivery,
+      WebhookDeliveryFilter, WebhookSubscription,
+    },
+  },
+  services::webhook::WebhookUseCase,
+};
+use infra::repositories::webhook::SqlxWebhookRepository;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks",
+    tag = "Webhook Service",
+    responses(
+        (status = 200, description = "Get webhook subscriptions successfully", body = Vec<WebhookSubscription>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_webhooks(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<Vec<WebhookSubscription>>> {
+  let repo = SqlxWebhookRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let subscriptions = WebhookUseCase::list(&repo).await?;
+
+  Ok(Json(subscriptions))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks",
+    tag = "Webhook Service",
+    request_body = CreateWebhookSubscriptionRequest,
+    responses(
+        (status = 200, description = "Create webhook subscription successfully", body = WebhookSubscription),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_webhook(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Json(payload): Json<CreateWebhookSubscriptionRequest>,
+) -> AppResult<Json<WebhookSubscription>> {
+  let repo = SqlxWebhookRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let subscription =
+    WebhookUseCase::create(&repo, &state.config.webhook, payload, user.pk_user_id).await?;
+
+  Ok(Json(subscription))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{id}",
+    tag = "Webhook Service",
+    params(
+        ("id" = i64, Path, description = "Webhook subscription ID")
+    ),
+    responses(
+        (status = 200, description = "Get webhook subscription successfully", body = WebhookSubscription),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Webhook subscription not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_webhook_by_id(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<WebhookSubscription>> {
+  let repo = SqlxWebhookRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let subscription = WebhookUseCase::get_by_id(&repo, id).await?;
+
+  Ok(Json(subscription))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/webhooks/{id}",
+    tag = "Webhook Service",
+    params(
+        ("id" = i64, Path, description = "Webhook subscription ID")
+    ),
+    request_body = UpdateWebhookSubscriptionRequest,
+    responses(
+        (status = 200, description = "Update webhook subscription successfully", body = WebhookSubscription),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Webhook subscription not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_webhook(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Json(payload): Json<UpdateWebhookSubscriptionRequest>,
+) -> AppResult<Json<WebhookSubscription>> {
+  let repo = SqlxWebhookRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let subscription = WebhookUseCase::update(&repo, &state.config.webhook, id, payload).await?;
+
+  Ok(Json(subscription))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhooks/{id}",
+    tag = "Webhook Service",
+    params(
+        ("id" = i64, Path, description = "Webhook subscription ID")
+    ),
+    responses(
+        (status = 200, description = "Delete webhook subscription successfully", body = bool),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Webhook subscription not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_webhook(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+) -> AppResult<Json<bool>> {
+  let repo = SqlxWebhookRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  WebhookUseCase::delete(&repo, id).await?;
+
+  Ok(Json(true))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{id}/deliveries",
+    tag = "Webhook Service",
+    params(
+        ("id" = i64, Path, description = "Webhook subscription ID"),
+        WebhookDeliveryFilter
+    ),
+    responses(
+        (status = 200, description = "Get webhook deliveries successfully", body = Vec<WebhookDelivery>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Webhook subscription not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_deliveries(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(id): Path<i64>,
+  Query(filter): Query<WebhookDeliveryFilter>,
+) -> AppResult<Json<Vec<WebhookDelivery>>> {
+  let repo = SqlxWebhookRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let deliveries = WebhookUseCase::list_deliveries(&repo, id, filter).await?;
+
+  Ok(Json(deliveries))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/deliveries/{delivery_id}/redeliver",
+    tag = "Webhook Service",
+    params(
+        ("delivery_id" = i64, Path, description = "Webhook delivery ID")
+    ),
+    responses(
+        (status = 200, description = "Webhook delivery queued again", body = WebhookDelivery),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Webhook delivery not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn redeliver(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(delivery_id): Path<i64>,
+) -> AppResult<Json<WebhookDelivery>> {
+  let repo = SqlxWebhookRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let delivery = WebhookUseCase::redeliver(&repo, delivery_id).await?;
+
+  Ok(Json(delivery))
+}
@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use super::services;
+use axum::{
+  Router,
+  routing::{get, patch, post},
+};
+use core_app::AppState;
+
+pub fn routes() -> Router<Arc<AppState>> {
+  Router::new()
+    .route("/zalo/templates", get(services::get_templates))
+    .route("/zalo/templates/{event_type}", patch(services::update_template))
+    .route("/zalo/messages", get(services::get_messages))
+}
+
+// Callback trạng thái gửi tin của Zalo, xác thực bằng chữ ký thay cho token đăng nhập
+pub fn routes_pub() -> Router<Arc<AppState>> {
+  Router::new().route("/zalo/webhook", post(services::zalo_webhook))
+}
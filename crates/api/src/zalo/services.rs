@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use axum::{
+  Json,
+  body::Bytes,
+  extract::{Extension, Path, Query, State},
+  http::HeaderMap,
+};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    user::UserWithPassword,
+    zalo::{
+      UpdateZaloEventTemplateRequest, ZaloEventTemplate, ZaloMessage, ZaloMessageFilter,
+      ZaloWebhookEvent,
+    },
+  },
+  services::zalo::ZaloUseCase,
+};
+use infra::repositories::zalo::SqlxZaloRepository;
+use utils::helper::verify_sha256;
+
+const SIGNATURE_HEADER: &str = "x-zevent-signature";
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/zalo/templates",
+    tag = "Zalo Service",
+    responses(
+        (status = 200, description = "Get Zalo templates successfully", body = Vec<ZaloEventTemplate>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_templates(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+) -> AppResult<Json<Vec<ZaloEventTemplate>>> {
+  let repo = SqlxZaloRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let templates = ZaloUseCase::list_templates(&repo).await?;
+
+  Ok(Json(templates))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/zalo/templates/{event_type}",
+    tag = "Zalo Service",
+    params(
+        ("event_type" = String, Path, description = "Event type, e.g. APPOINTMENT_CONFIRMED")
+    ),
+    request_body = UpdateZaloEventTemplateRequest,
+    responses(
+        (status = 200, description = "Update Zalo template successfully", body = ZaloEventTemplate),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Zalo template not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_template(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Path(event_type): Path<String>,
+  Json(payload): Json<UpdateZaloEventTemplateRequest>,
+) -> AppResult<Json<ZaloEventTemplate>> {
+  let repo = SqlxZaloRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let template = ZaloUseCase::update_template(&repo, &event_type, payload).await?;
+
+  Ok(Json(template))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/zalo/messages",
+    tag = "Zalo Service",
+    params(ZaloMessageFilter),
+    responses(
+        (status = 200, description = "Get Zalo messages successfully", body = Vec<ZaloMessage>),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_messages(
+  State(state): State<Arc<AppState>>,
+  Extension(user): Extension<UserWithPassword>,
+  Query(filter): Query<ZaloMessageFilter>,
+) -> AppResult<Json<Vec<ZaloMessage>>> {
+  let repo = SqlxZaloRepository { db: state.db.clone() };
+
+  if user.role != "ADMIN" {
+    return Err(AppError::Forbidden("You don't have permission".to_string()));
+  }
+
+  let messages = ZaloUseCase::list_messages(&repo, filter).await?;
+
+  Ok(Json(messages))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/zalo/webhook",
+    tag = "Zalo Service",
+    request_body = ZaloWebhookEvent,
+    params(
+        ("X-ZEvent-Signature" = String, Header, description = "mac=sha256(app_id + body + timestamp + OA secret key)")
+    ),
+    responses(
+        (status = 200, description = "Callback recorded", body = bool),
+        (status = 400, description = "Bad request", body = String),
+        (status = 401, description = "Invalid signature"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn zalo_webhook(
+  State(state): State<Arc<AppState>>,
+  headers: HeaderMap,
+  body: Bytes,
+) -> AppResult<Json<bool>> {
+  let raw_payload: serde_json::Value = serde_json::from_slice(&body)?;
+
+  // Zalo ký bằng app_id + nội dung gốc + timestamp trong payload + OA secret key
+  let config = &state.config.zalo;
+  let timestamp = match raw_payload.get("timestamp") {
+    Some(serde_json::Value::String(timestamp)) => timestamp.clone(),
+    Some(timestamp) => timestamp.to_string(),
+    None => String::new(),
+  };
+  let signature = headers
+    .get(SIGNATURE_HEADER)
+    .and_then(|value| value.to_str().ok())
+    .map(|value| value.trim_start_matches("mac="));
+
+  let verified = match signature {
+    Some(signature) if !config.webhook_secret.is_empty() => {
+      let mut data = config.app_id.as_bytes().to_vec();
+      data.extend_from_slice(&body);
+      data.extend_from_slice(timestamp.as_bytes());
+      data.extend_from_slice(config.webhook_secret.as_bytes());
+      verify_sha256(&data, signature)
+    },
+    _ => false,
+  };
+
+  if !verified {
+    tracing::warn!("Rejected Zalo webhook with invalid signature");
+    return Err(AppError::Unauthorized("Invalid signature".to_string()));
+  }
+
+  let event: ZaloWebhookEvent = serde_json::from_value(raw_payload.clone())?;
+  let repo = SqlxZaloRepository { db: state.db.clone() };
+
+  ZaloUseCase::record_callback(&repo, event, raw_payload).await?;
+
+  Ok(Json(true))
+}
@@ -23,12 +23,20 @@ use utoipa_swagger_ui::SwaggerUi;
     api::auth::services::verify_code_firebase_service,
     api::auth::services::get_current_user_service,
     api::auth::services::logout_user_service,
+    api::auth::services::forgot_password_email_service,
+    api::auth::services::verify_email_code_service,
+    api::auth::services::send_email_verification_service,
+    api::auth::services::verify_email_service,
 
     //deposit
     api::deposit::services::create_deposit,
     api::deposit::services::get_deposits,
     api::deposit::services::get_deposit_by_id,
     api::deposit::services::update_deposit_status,
+    api::deposit::services::create_top_up,
+    api::deposit::services::create_withdrawal,
+    api::deposit::services::get_bank_account,
+    api::deposit::services::upsert_bank_account,
     api::deposit::services::get_deposits_by_user_id,
 
     //profile
@@ -38,6 +46,8 @@ use utoipa_swagger_ui::SwaggerUi;
     api::profile::services::update_profile_service,
     api::profile::services::change_avatar_service,
     api::profile::services::delete_account,
+    api::profile::services::get_notification_preferences,
+    api::profile::services::update_notification_preferences,
 
     //services
     api::service::services::get_all_services,
@@ -59,6 +69,7 @@ use utoipa_swagger_ui::SwaggerUi;
     api::appointment::services::get_appointments,
     api::appointment::services::create_appointment,
     api::appointment::services::update_appointment,
+    api::appointment::services::update_appointment_service,
     api::appointment::services::get_appointment,
     api::appointment::services::get_appointment_by_user_id,
     api::appointment::services::delete_appointment,
@@ -88,10 +99,121 @@ use utoipa_swagger_ui::SwaggerUi;
     api::notification::services::update,
     api::notification::services::get_by_id,
     api::notification::services::get_list,
+    api::notification::services::bulk_update_read,
+    api::notification::services::bulk_delete,
 
     // statistics
     api::statistics::services::get_admin_statistics,
     api::statistics::services::get_receptionist_statistics,
+
+    // payroll
+    api::payroll::services::get_payroll,
+    api::payroll::services::lock_payroll_period,
+    api::payroll::services::create_adjustment,
+    api::payroll::services::get_commission_rules,
+    api::payroll::services::create_commission_rule,
+    api::payroll::services::update_commission_rule,
+    api::payroll::services::delete_commission_rule,
+    // payment
+    api::payment::services::create_payment_intent,
+    api::payment::services::get_payment_intent,
+    api::payment::services::get_bank_transactions,
+    api::payment::services::bank_transfer_webhook,
+    api::payment::services::bank_transfer_webhook_stub,
+    // receipt
+    api::receipt::services::get_appointment_receipt,
+    api::receipt::services::get_shared_receipt,
+    // invoice
+    api::invoice::services::create_invoice,
+    api::invoice::services::get_invoices,
+    api::invoice::services::get_invoice,
+    api::invoice::services::get_invoice_xml,
+    api::invoice::services::create_replacement_invoice,
+    api::invoice::services::create_adjustment_invoice,
+    api::invoice::services::submit_invoice,
+    // shift
+    api::shift::services::open_shift,
+    api::shift::services::get_current_shift,
+    api::shift::services::close_current_shift,
+    api::shift::services::get_shifts,
+    api::shift::services::get_variance_report,
+    api::shift::services::get_shift,
+    // treatment
+    api::treatment::services::get_treatment_fields,
+    api::treatment::services::create_treatment_field,
+    api::treatment::services::update_treatment_field,
+    api::treatment::services::get_treatment_records,
+    api::treatment::services::create_treatment_record,
+    api::treatment::services::get_my_treatment_records,
+    api::treatment::services::get_treatment_record,
+    api::treatment::services::update_treatment_record,
+    api::treatment::services::get_treatment_record_history,
+    api::treatment::services::add_treatment_photo,
+    api::treatment::services::delete_treatment_photo,
+    api::treatment::services::get_treatment_photo_image,
+    // consent
+    api::consent::services::get_consent_templates,
+    api::consent::services::create_consent_template,
+    api::consent::services::get_consent_template,
+    api::consent::services::update_consent_template,
+    api::consent::services::get_pending_consent_forms,
+    api::consent::services::get_appointment_consent_forms,
+    api::consent::services::get_consent_submissions,
+    api::consent::services::create_consent_submission,
+    api::consent::services::get_consent_submission,
+    api::consent::services::get_consent_submission_pdf,
+    api::consent::services::get_consent_submission_signature,
+    // review
+    api::review::services::create_review,
+    api::review::services::get_reviews,
+    api::review::services::get_my_reviews,
+    api::review::services::get_review,
+    api::review::services::get_appointment_review,
+    api::review::services::moderate_review,
+    api::review::services::reply_review,
+    api::review::services::add_review_photo,
+    api::review::services::delete_review_photo,
+    // referral
+    api::referral::services::get_my_referrals,
+    api::referral::services::get_referrals,
+    api::referral::services::get_referral_report,
+    // notification outbox
+    api::notification_outbox::services::get_outbox,
+    api::notification_outbox::services::get_outbox_stats,
+    api::notification_outbox::services::get_outbox_by_id,
+    api::notification_outbox::services::retry_outbox,
+    // notification template
+    api::notification_template::services::get_templates,
+    api::notification_template::services::get_template_by_id,
+    api::notification_template::services::update_template,
+    // campaign
+    api::campaign::services::get_campaigns,
+    api::campaign::services::create_campaign,
+    api::campaign::services::get_campaign_by_id,
+    api::campaign::services::update_campaign,
+    api::campaign::services::schedule_campaign,
+    api::campaign::services::cancel_campaign,
+    api::campaign::services::preview_audience,
+    api::campaign::services::open_campaign,
+    // events
+    api::events::services::stream_events,
+    // zalo
+    api::zalo::services::get_templates,
+    api::zalo::services::update_template,
+    api::zalo::services::get_messages,
+    api::zalo::services::zalo_webhook,
+    // webhook
+    api::webhook::services::get_webhooks,
+    api::webhook::services::create_webhook,
+    api::webhook::services::get_webhook_by_id,
+    api::webhook::services::update_webhook,
+    api::webhook::services::delete_webhook,
+    api::webhook::services::get_deliveries,
+    api::webhook::services::redeliver,
+    // email
+    api::email::services::get_templates,
+    api::email::services::update_template,
+    api::email::services::get_messages,
   ),
   tags(
     (name = "Auth Service", description = "Auth service endpoints"),
@@ -104,6 +226,22 @@ use utoipa_swagger_ui::SwaggerUi;
     (name = "Notification token Service", description = "Notification token Service endpoints"),
     (name = "Chat Service", description = "Chat service endpoints"),
     (name = "Statistics Service", description = "Statistics service endpoints"),
+    (name = "Payroll Service", description = "Payroll service endpoints"),
+    (name = "Payment Service", description = "VietQR payment intents and bank transfer webhook"),
+    (name = "Receipt Service", description = "Printable receipts for paid appointments"),
+    (name = "Invoice Service", description = "VAT e-invoices for corporate customers"),
+    (name = "Shift Service", description = "Receptionist shifts and end-of-day cash drawer closing"),
+    (name = "Treatment Service", description = "Customer treatment records, clinical fields and before/after photos"),
+    (name = "Consent Service", description = "Intake and consent form templates and signed submissions"),
+    (name = "Review Service", description = "Appointment and technician reviews with moderation"),
+    (name = "Referral Service", description = "Customer referral codes, rewards and report"),
+    (name = "Notification Outbox Service", description = "Push notification delivery queue and dead-letter view"),
+    (name = "Notification Template Service", description = "Localized notification templates"),
+    (name = "Campaign Service", description = "Marketing broadcast campaigns"),
+    (name = "Event Service", description = "Realtime Server-Sent Events"),
+    (name = "Zalo Service", description = "Zalo ZNS templates, message queue and delivery callbacks"),
+    (name = "Webhook Service", description = "Outgoing webhook subscriptions and delivery log"),
+    (name = "Email Service", description = "Email templates and SMTP delivery queue"),
   ),
   security(
     ("BearerAuth" = [])
@@ -1,8 +1,201 @@
 use chrono::{DateTime, Duration, Local, Timelike, Utc};
+use core_app::configs::{
+  CampaignConfig, EmailConfig, NotificationConfig, ReferralConfig, ReviewConfig, SpaConfig,
+  WebhookConfig, ZaloConfig,
+};
+use infra::repositories::{
+  campaign::process_campaigns,
+  email::dispatch_emails,
+  notification::archive_old_notifications,
+  notification_outbox::dispatch_notifications,
+  notification_token::expire_stale_tokens,
+  referral::process_referral_rewards,
+  review::send_review_prompts,
+  webhook::dispatch_webhooks,
+  zalo::{dispatch_zalo_messages, enqueue_zalo_reminders},
+};
+use sqlx::PgPool;
 use std::fs;
 use std::path::Path;
 use tokio::time::{Duration as TokioDuration, sleep};
-use tracing::info;
+use tracing::{error, info};
+
+// Worker gửi thông báo từ outbox, còn việc thì lấy lô tiếp theo ngay, hết việc thì chờ poll_interval
+pub async fn start_notification_dispatcher(
+  db: PgPool,
+  config: NotificationConfig,
+) {
+  loop {
+    let processed = match dispatch_notifications(&db, &config).await {
+      Ok(count) => count,
+      Err(e) => {
+        error!("Failed to dispatch notifications: {:?}", e);
+        0
+      },
+    };
+
+    if (processed as i64) < config.batch_size {
+      sleep(TokioDuration::from_millis(config.poll_interval_ms)).await;
+    }
+  }
+}
+
+// Xoá token thiết bị không còn được app đăng ký lại sau token_expiry_days ngày
+pub async fn start_notification_token_expiry_job(
+  db: PgPool,
+  config: NotificationConfig,
+) {
+  loop {
+    match expire_stale_tokens(&db, config.token_expiry_days).await {
+      Ok(0) => {},
+      Ok(count) => info!("Expired {} stale notification tokens", count),
+      Err(e) => error!("Failed to expire notification tokens: {:?}", e),
+    }
+
+    sleep(TokioDuration::from_secs(24 * 60 * 60)).await;
+  }
+}
+
+// Gửi chiến dịch marketing theo từng lô batch_size người nhận mỗi interval_seconds
+pub async fn start_campaign_job(
+  db: PgPool,
+  config: CampaignConfig,
+) {
+  loop {
+    match process_campaigns(&db, &config).await {
+      Ok(0) => {},
+      Ok(count) => info!("Queued {} campaign notifications", count),
+      Err(e) => error!("Failed to process campaigns: {:?}", e),
+    }
+
+    sleep(TokioDuration::from_secs(config.interval_seconds.max(1))).await;
+  }
+}
+
+// Chuyển thông báo in-app quá retention_days ngày sang bảng lưu trữ
+pub async fn start_notification_retention_job(
+  db: PgPool,
+  config: NotificationConfig,
+) {
+  loop {
+    match archive_old_notifications(&db, config.retention_days).await {
+      Ok(0) => {},
+      Ok(count) => info!("Archived {} old notifications", count),
+      Err(e) => error!("Failed to archive notifications: {:?}", e),
+    }
+
+    sleep(TokioDuration::from_secs(24 * 60 * 60)).await;
+  }
+}
+
+// Quét định kỳ các lịch hẹn đã thanh toán để nhắc khách đánh giá
+pub async fn start_review_prompt_job(
+  db: PgPool,
+  config: ReviewConfig,
+) {
+  loop {
+    match send_review_prompts(&db, &config).await {
+      Ok(0) => {},
+      Ok(count) => info!("Sent {} review prompts", count),
+      Err(e) => error!("Failed to send review prompts: {:?}", e),
+    }
+
+    sleep(TokioDuration::from_secs(10 * 60)).await;
+  }
+}
+
+// Thưởng giới thiệu khi lịch hẹn đầu tiên của khách được giới thiệu đã thanh toán
+pub async fn start_referral_reward_job(
+  db: PgPool,
+  config: ReferralConfig,
+) {
+  loop {
+    match process_referral_rewards(&db, &config).await {
+      Ok(0) => {},
+      Ok(count) => info!("Processed {} referral rewards", count),
+      Err(e) => error!("Failed to process referral rewards: {:?}", e),
+    }
+
+    sleep(TokioDuration::from_secs(5 * 60)).await;
+  }
+}
+
+// Worker gửi tin ZNS trong hàng đợi, còn việc thì lấy lô tiếp theo ngay
+pub async fn start_zalo_dispatcher(
+  db: PgPool,
+  config: ZaloConfig,
+) {
+  loop {
+    let processed = match dispatch_zalo_messages(&db, &config).await {
+      Ok(count) => count,
+      Err(e) => {
+        error!("Failed to dispatch Zalo messages: {:?}", e);
+        0
+      },
+    };
+
+    if (processed as i64) < config.batch_size {
+      sleep(TokioDuration::from_secs(config.poll_interval_seconds.max(1))).await;
+    }
+  }
+}
+
+// Nhắc lịch qua Zalo cho các lịch hẹn diễn ra trong reminder_hours giờ tới
+pub async fn start_zalo_reminder_job(
+  db: PgPool,
+  config: ZaloConfig,
+) {
+  loop {
+    match enqueue_zalo_reminders(&db, &config).await {
+      Ok(0) => {},
+      Ok(count) => info!("Queued {} Zalo appointment reminders", count),
+      Err(e) => error!("Failed to queue Zalo reminders: {:?}", e),
+    }
+
+    sleep(TokioDuration::from_secs(10 * 60)).await;
+  }
+}
+
+// Worker gửi webhook ra hệ thống bên ngoài, còn việc thì lấy lô tiếp theo ngay
+pub async fn start_webhook_dispatcher(
+  db: PgPool,
+  config: WebhookConfig,
+) {
+  loop {
+    let processed = match dispatch_webhooks(&db, &config).await {
+      Ok(count) => count,
+      Err(e) => {
+        error!("Failed to dispatch webhooks: {:?}", e);
+        0
+      },
+    };
+
+    if (processed as i64) < config.batch_size {
+      sleep(TokioDuration::from_secs(config.poll_interval_seconds.max(1))).await;
+    }
+  }
+}
+
+// Worker gửi email qua SMTP, còn việc thì lấy lô tiếp theo ngay
+pub async fn start_email_dispatcher(
+  db: PgPool,
+  config: EmailConfig,
+  spa: SpaConfig,
+) {
+  loop {
+    let processed = match dispatch_emails(&db, &config, &spa).await {
+      Ok(count) => count,
+      Err(e) => {
+        error!("Failed to dispatch emails: {:?}", e);
+        0
+      },
+    };
+
+    if (processed as i64) < config.batch_size {
+      sleep(TokioDuration::from_secs(config.poll_interval_seconds.max(1))).await;
+    }
+  }
+}
 
 pub async fn start_log_cleanup_job() {
   loop {
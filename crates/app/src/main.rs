@@ -13,12 +13,13 @@ use core_app::{AppState, configs::AppConfig};
 use dotenv::dotenv;
 use infra::{
   database::Database,
+  events::realtime::start_event_listener,
   middleware::{
     mw_auth,
     mw_response_v1::{self, handler_404},
   },
 };
-use std::{sync::Arc, time::Duration};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tower::ServiceBuilder;
 use tower_http::{
   LatencyUnit, ServiceBuilderExt,
@@ -70,6 +71,44 @@ async fn main() {
   let pool = Database::initialize_db(&configs.postgres.dsn, configs.postgres.max_conns).await;
   let state = AppState::new(pool.clone(), configs.clone());
 
+  // Nhận sự kiện realtime từ Postgres cho các kết nối SSE
+  tokio::spawn(start_event_listener(pool.clone(), state.events.clone()));
+
+  // Gửi thông báo đẩy từ outbox
+  tokio::spawn(cron::start_notification_dispatcher(pool.clone(), configs.notification.clone()));
+
+  // Dọn token thiết bị lâu không dùng
+  tokio::spawn(cron::start_notification_token_expiry_job(
+    pool.clone(),
+    configs.notification.clone(),
+  ));
+
+  // Lưu trữ thông báo in-app cũ
+  tokio::spawn(cron::start_notification_retention_job(pool.clone(), configs.notification.clone()));
+
+  // Nhắc khách đánh giá sau khi thanh toán
+  tokio::spawn(cron::start_review_prompt_job(pool.clone(), configs.review.clone()));
+
+  // Xét thưởng cho các lượt giới thiệu khách hàng
+  tokio::spawn(cron::start_referral_reward_job(pool.clone(), configs.referral.clone()));
+
+  // Gửi chiến dịch marketing theo lô
+  tokio::spawn(cron::start_campaign_job(pool.clone(), configs.campaign.clone()));
+
+  // Gửi tin Zalo ZNS theo lịch hẹn và nhắc lịch
+  tokio::spawn(cron::start_zalo_dispatcher(pool.clone(), configs.zalo.clone()));
+  tokio::spawn(cron::start_zalo_reminder_job(pool.clone(), configs.zalo.clone()));
+
+  // Gửi webhook sự kiện nghiệp vụ ra hệ thống bên ngoài
+  tokio::spawn(cron::start_webhook_dispatcher(pool.clone(), configs.webhook.clone()));
+
+  // Gửi email xác minh, đặt lại mật khẩu và biên nhận
+  tokio::spawn(cron::start_email_dispatcher(
+    pool.clone(),
+    configs.email.clone(),
+    configs.spa.clone(),
+  ));
+
   let cors = CorsLayer::new()
     .allow_origin(Any) // Adjust in production!
     .allow_methods(Any)
@@ -97,5 +136,6 @@ async fn main() {
   // run our app with hyper, listening globally on port 3000
   let listener = tokio::net::TcpListener::bind(&configs.web.addr).await.unwrap();
   info!("listening on http://{} with {} cpu", configs.web.addr, num_cpus::get());
-  axum::serve(listener, app).await.unwrap();
+  // Giữ địa chỉ client để ghi nhận IP khi khách ký phiếu đồng ý
+  axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
 }
@@ -1,12 +1,16 @@
 use crate::errors::AppError;
 use dotenv::var;
 use serde::Deserialize;
+use std::net::IpAddr;
 
 #[derive(Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct WebConfig {
   #[serde(default)]
   pub addr: String,
+  // Địa chỉ reverse proxy được tin cậy; chỉ đọc X-Forwarded-For / X-Real-IP khi kết nối đến từ đây
+  #[serde(default)]
+  pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl Default for WebConfig {
@@ -17,6 +21,7 @@ impl Default for WebConfig {
       } else {
         "127.0.0.1:3000".to_string()
       },
+      trusted_proxies: Vec::new(),
     }
   }
 }
@@ -69,6 +74,9 @@ pub struct TokenConfig {
   pub phone_code_ttl_minutes: i64,
   #[serde(default)]
   pub access_token_set_password_minutes: i64,
+  // Ký link chia sẻ hoá đơn, để trống thì tắt chia sẻ
+  #[serde(default)]
+  pub receipt_share_secret: String,
 }
 
 impl Default for TokenConfig {
@@ -83,6 +91,312 @@ impl Default for TokenConfig {
       refresh_token_duration_days: 30,
       phone_code_ttl_minutes: 2,
       access_token_set_password_minutes: 30,
+      receipt_share_secret: if var("ENV").unwrap_or_default() == "production" {
+        String::new()
+      } else {
+        "dev_receipt_share_secret".to_string()
+      },
+    }
+  }
+}
+
+// Tài khoản nhận chuyển khoản VietQR và secret ký webhook của cổng thanh toán
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct BankConfig {
+  #[serde(default)]
+  pub bin: String,
+  #[serde(default)]
+  pub account_number: String,
+  #[serde(default)]
+  pub account_name: String,
+  #[serde(default)]
+  pub webhook_secret: String,
+}
+
+// Hạn mức rút tiền từ ví về tài khoản ngân hàng (VND), daily_limit tính theo ngày giờ Việt Nam
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct WithdrawalConfig {
+  #[serde(default)]
+  pub min_amount: i64,
+  #[serde(default)]
+  pub max_amount: i64,
+  #[serde(default)]
+  pub daily_limit: i64,
+}
+
+impl Default for WithdrawalConfig {
+  fn default() -> Self {
+    Self { min_amount: 50_000, max_amount: 10_000_000, daily_limit: 20_000_000 }
+  }
+}
+
+// Thông tin cửa hàng in trên hoá đơn
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct SpaConfig {
+  #[serde(default)]
+  pub name: String,
+  #[serde(default)]
+  pub address: String,
+  #[serde(default)]
+  pub phone: String,
+  #[serde(default)]
+  pub tax_code: String,
+}
+
+impl Default for SpaConfig {
+  fn default() -> Self {
+    Self {
+      name: "NaSpa".to_string(),
+      address: String::new(),
+      phone: String::new(),
+      tax_code: String::new(),
+    }
+  }
+}
+
+// Hoá đơn điện tử: thư mục adapter file-drop ghi XML cho phần mềm của nhà cung cấp đọc
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct InvoiceConfig {
+  #[serde(default)]
+  pub file_drop_dir: String,
+}
+
+impl Default for InvoiceConfig {
+  fn default() -> Self {
+    Self { file_drop_dir: "einvoices".to_string() }
+  }
+}
+
+// Đánh giá sau lịch hẹn: số ngày được phép đánh giá và số giờ sau thanh toán thì nhắc khách
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ReviewConfig {
+  #[serde(default = "default_review_window_days")]
+  pub window_days: i64,
+  #[serde(default = "default_review_prompt_delay_hours")]
+  pub prompt_delay_hours: i64,
+}
+
+fn default_review_window_days() -> i64 {
+  7
+}
+
+fn default_review_prompt_delay_hours() -> i64 {
+  3
+}
+
+impl Default for ReviewConfig {
+  fn default() -> Self {
+    Self {
+      window_days: default_review_window_days(),
+      prompt_delay_hours: default_review_prompt_delay_hours(),
+    }
+  }
+}
+
+// Giới thiệu khách hàng: thưởng bằng tiền ví (WALLET) hoặc điểm tích luỹ (POINTS) cho cả hai bên,
+// max_rewards_per_referrer = 0 thì không giới hạn số lần người giới thiệu được thưởng
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ReferralConfig {
+  #[serde(default)]
+  pub reward_type: String,
+  #[serde(default)]
+  pub referrer_reward: i64,
+  #[serde(default)]
+  pub referred_reward: i64,
+  #[serde(default)]
+  pub max_rewards_per_referrer: i64,
+}
+
+impl Default for ReferralConfig {
+  fn default() -> Self {
+    Self {
+      reward_type: "WALLET".to_string(),
+      referrer_reward: 50_000,
+      referred_reward: 50_000,
+      max_rewards_per_referrer: 0,
+    }
+  }
+}
+
+// Hàng đợi gửi thông báo (outbox): số lần thử tối đa trước khi chuyển sang dead-letter,
+// thời gian chờ lần thử lại đầu tiên (tăng gấp đôi sau mỗi lần, tối đa retry_max_seconds)
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct NotificationConfig {
+  #[serde(default)]
+  pub max_attempts: i32,
+  #[serde(default)]
+  pub retry_base_seconds: i64,
+  #[serde(default)]
+  pub retry_max_seconds: i64,
+  #[serde(default)]
+  pub batch_size: i64,
+  #[serde(default)]
+  pub poll_interval_ms: u64,
+  #[serde(default)]
+  pub token_expiry_days: i64,
+  // Thông báo in-app cũ hơn số ngày này được chuyển sang bảng lưu trữ
+  #[serde(default)]
+  pub retention_days: i64,
+}
+
+impl Default for NotificationConfig {
+  fn default() -> Self {
+    Self {
+      max_attempts: 8,
+      retry_base_seconds: 30,
+      retry_max_seconds: 3600,
+      batch_size: 50,
+      poll_interval_ms: 2000,
+      token_expiry_days: 60,
+      retention_days: 90,
+    }
+  }
+}
+
+// Chiến dịch marketing: mỗi lượt chỉ đưa batch_size người nhận vào hàng đợi gửi để tránh dồn FCM
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct CampaignConfig {
+  #[serde(default)]
+  pub batch_size: i64,
+  #[serde(default)]
+  pub interval_seconds: u64,
+}
+
+impl Default for CampaignConfig {
+  fn default() -> Self {
+    Self { batch_size: 200, interval_seconds: 60 }
+  }
+}
+
+// Tin ZNS theo lịch hẹn: worker gửi batch_size tin mỗi poll_interval_seconds, thử lại tối đa max_attempts,
+// nhắc lịch trước reminder_hours giờ; webhook_secret là OA secret key dùng kiểm tra chữ ký callback
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ZaloConfig {
+  #[serde(default)]
+  pub app_id: String,
+  #[serde(default)]
+  pub webhook_secret: String,
+  #[serde(default)]
+  pub batch_size: i64,
+  #[serde(default)]
+  pub poll_interval_seconds: u64,
+  #[serde(default)]
+  pub max_attempts: i32,
+  #[serde(default)]
+  pub retry_base_seconds: i64,
+  #[serde(default)]
+  pub reminder_hours: i64,
+}
+
+impl Default for ZaloConfig {
+  fn default() -> Self {
+    Self {
+      app_id: String::new(),
+      webhook_secret: String::new(),
+      batch_size: 20,
+      poll_interval_seconds: 30,
+      max_attempts: 5,
+      retry_base_seconds: 60,
+      reminder_hours: 24,
+    }
+  }
+}
+
+// Webhook gửi ra ngoài: thử lại tối đa max_attempts lần, chờ tăng gấp đôi từ retry_base_seconds
+// (tối đa retry_max_seconds), mỗi request chờ phản hồi tối đa timeout_seconds
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct WebhookConfig {
+  #[serde(default)]
+  pub batch_size: i64,
+  #[serde(default)]
+  pub poll_interval_seconds: u64,
+  #[serde(default)]
+  pub max_attempts: i32,
+  #[serde(default)]
+  pub retry_base_seconds: i64,
+  #[serde(default)]
+  pub retry_max_seconds: i64,
+  #[serde(default)]
+  pub timeout_seconds: u64,
+  // Bắt buộc URL https (mặc định bật ở production, dev cho phép http)
+  #[serde(default)]
+  pub require_https: bool,
+}
+
+impl Default for WebhookConfig {
+  fn default() -> Self {
+    Self {
+      batch_size: 50,
+      poll_interval_seconds: 5,
+      max_attempts: 8,
+      retry_base_seconds: 30,
+      retry_max_seconds: 6 * 3600,
+      timeout_seconds: 10,
+      require_https: var("ENV").unwrap_or_default() == "production",
+    }
+  }
+}
+
+// Email qua SMTP (smtp_tls: none, starttls hoặc tls; smtp_host trống là chưa cấu hình): worker gửi
+// batch_size email mỗi poll_interval_seconds, thử lại tối đa max_attempts; mã xác minh email và
+// mã đặt lại mật khẩu hết hạn sau code_ttl_minutes phút
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct EmailConfig {
+  #[serde(default)]
+  pub smtp_host: String,
+  #[serde(default)]
+  pub smtp_port: u16,
+  #[serde(default)]
+  pub smtp_username: String,
+  #[serde(default)]
+  pub smtp_password: String,
+  #[serde(default)]
+  pub smtp_tls: String,
+  #[serde(default)]
+  pub from_address: String,
+  #[serde(default)]
+  pub from_name: String,
+  #[serde(default)]
+  pub batch_size: i64,
+  #[serde(default)]
+  pub poll_interval_seconds: u64,
+  #[serde(default)]
+  pub max_attempts: i32,
+  #[serde(default)]
+  pub retry_base_seconds: i64,
+  #[serde(default)]
+  pub timeout_seconds: u64,
+  #[serde(default)]
+  pub code_ttl_minutes: i64,
+}
+
+impl Default for EmailConfig {
+  fn default() -> Self {
+    Self {
+      smtp_host: String::new(),
+      smtp_port: 587,
+      smtp_username: String::new(),
+      smtp_password: String::new(),
+      smtp_tls: "starttls".to_string(),
+      from_address: "no-reply@naspa.vn".to_string(),
+      from_name: "NaSpa".to_string(),
+      batch_size: 20,
+      poll_interval_seconds: 10,
+      max_attempts: 5,
+      retry_base_seconds: 60,
+      timeout_seconds: 10,
+      code_ttl_minutes: 15,
     }
   }
 }
@@ -98,6 +412,28 @@ pub struct AppConfig {
   pub token: TokenConfig,
   #[serde(default)]
   pub twilio: TwilioConfig,
+  #[serde(default)]
+  pub bank: BankConfig,
+  #[serde(default)]
+  pub withdrawal: WithdrawalConfig,
+  #[serde(default)]
+  pub spa: SpaConfig,
+  #[serde(default)]
+  pub invoice: InvoiceConfig,
+  #[serde(default)]
+  pub review: ReviewConfig,
+  #[serde(default)]
+  pub referral: ReferralConfig,
+  #[serde(default)]
+  pub notification: NotificationConfig,
+  #[serde(default)]
+  pub campaign: CampaignConfig,
+  #[serde(default)]
+  pub zalo: ZaloConfig,
+  #[serde(default)]
+  pub webhook: WebhookConfig,
+  #[serde(default)]
+  pub email: EmailConfig,
 }
 
 impl AppConfig {
@@ -114,6 +450,10 @@ impl AppConfig {
     if let Ok(addr) = var("APP_WEB_ADDR") {
       app_config.web.addr = addr;
     }
+    if let Ok(proxies) = var("APP_WEB_TRUSTED_PROXIES") {
+      app_config.web.trusted_proxies =
+        proxies.split(',').filter_map(|proxy| proxy.trim().parse().ok()).collect();
+    }
 
     // Try to get postgres config
     if let Ok(dsn) = var("APP_POSTGRES_DSN") {
@@ -139,6 +479,9 @@ impl AppConfig {
     if let Ok(set_pass_minutes) = var("APP_TOKEN_ACCESS_TOKEN_SET_PASSWORD_MINUTES") {
       app_config.token.access_token_set_password_minutes = set_pass_minutes.parse().unwrap_or(30);
     }
+    if let Ok(share_secret) = var("APP_TOKEN_RECEIPT_SHARE_SECRET") {
+      app_config.token.receipt_share_secret = share_secret;
+    }
 
     // Try to get twilio config
     if let Ok(account_sid) = var("APP_TWILIO_ACCOUNT_SID") {
@@ -150,6 +493,194 @@ impl AppConfig {
     if let Ok(from_number) = var("APP_TWILIO_FROM_NUMBER") {
       app_config.twilio.from_number = from_number;
     }
+
+    // Try to get bank config
+    if let Ok(bin) = var("APP_BANK_BIN") {
+      app_config.bank.bin = bin;
+    }
+    if let Ok(account_number) = var("APP_BANK_ACCOUNT_NUMBER") {
+      app_config.bank.account_number = account_number;
+    }
+    if let Ok(account_name) = var("APP_BANK_ACCOUNT_NAME") {
+      app_config.bank.account_name = account_name;
+    }
+    if let Ok(webhook_secret) = var("APP_BANK_WEBHOOK_SECRET") {
+      app_config.bank.webhook_secret = webhook_secret;
+    }
+
+    // Try to get withdrawal config
+    if let Ok(min_amount) = var("APP_WITHDRAWAL_MIN_AMOUNT") {
+      app_config.withdrawal.min_amount = min_amount.parse().unwrap_or(50_000);
+    }
+    if let Ok(max_amount) = var("APP_WITHDRAWAL_MAX_AMOUNT") {
+      app_config.withdrawal.max_amount = max_amount.parse().unwrap_or(10_000_000);
+    }
+    if let Ok(daily_limit) = var("APP_WITHDRAWAL_DAILY_LIMIT") {
+      app_config.withdrawal.daily_limit = daily_limit.parse().unwrap_or(20_000_000);
+    }
+
+    // Try to get spa config
+    if let Some(name) = var("APP_SPA_NAME").ok().filter(|name| !name.trim().is_empty()) {
+      app_config.spa.name = name;
+    }
+    if let Ok(address) = var("APP_SPA_ADDRESS") {
+      app_config.spa.address = address;
+    }
+    if let Ok(phone) = var("APP_SPA_PHONE") {
+      app_config.spa.phone = phone;
+    }
+    if let Ok(tax_code) = var("APP_SPA_TAX_CODE") {
+      app_config.spa.tax_code = tax_code;
+    }
+
+    // Try to get e-invoice config
+    if let Some(dir) = var("APP_INVOICE_FILE_DROP_DIR").ok().filter(|dir| !dir.trim().is_empty()) {
+      app_config.invoice.file_drop_dir = dir;
+    }
+
+    // Try to get review config
+    if let Ok(window_days) = var("APP_REVIEW_WINDOW_DAYS") {
+      app_config.review.window_days = window_days.parse().unwrap_or(default_review_window_days());
+    }
+    if let Ok(delay_hours) = var("APP_REVIEW_PROMPT_DELAY_HOURS") {
+      app_config.review.prompt_delay_hours =
+        delay_hours.parse().unwrap_or(default_review_prompt_delay_hours());
+    }
+
+    // Try to get referral config
+    if let Ok(reward_type) = var("APP_REFERRAL_REWARD_TYPE") {
+      if reward_type == "WALLET" || reward_type == "POINTS" {
+        app_config.referral.reward_type = reward_type;
+      }
+    }
+    if let Ok(reward) = var("APP_REFERRAL_REFERRER_REWARD") {
+      app_config.referral.referrer_reward = reward.parse().unwrap_or(50_000);
+    }
+    if let Ok(reward) = var("APP_REFERRAL_REFERRED_REWARD") {
+      app_config.referral.referred_reward = reward.parse().unwrap_or(50_000);
+    }
+    if let Ok(max_rewards) = var("APP_REFERRAL_MAX_REWARDS_PER_REFERRER") {
+      app_config.referral.max_rewards_per_referrer = max_rewards.parse().unwrap_or(0);
+    }
+
+    // Try to get notification outbox config
+    if let Ok(max_attempts) = var("APP_NOTIFICATION_MAX_ATTEMPTS") {
+      app_config.notification.max_attempts = max_attempts.parse().unwrap_or(8);
+    }
+    if let Ok(seconds) = var("APP_NOTIFICATION_RETRY_BASE_SECONDS") {
+      app_config.notification.retry_base_seconds = seconds.parse().unwrap_or(30);
+    }
+    if let Ok(seconds) = var("APP_NOTIFICATION_RETRY_MAX_SECONDS") {
+      app_config.notification.retry_max_seconds = seconds.parse().unwrap_or(3600);
+    }
+    if let Ok(batch_size) = var("APP_NOTIFICATION_BATCH_SIZE") {
+      app_config.notification.batch_size = batch_size.parse().unwrap_or(50);
+    }
+    if let Ok(interval) = var("APP_NOTIFICATION_POLL_INTERVAL_MS") {
+      app_config.notification.poll_interval_ms = interval.parse().unwrap_or(2000);
+    }
+    if let Ok(days) = var("APP_NOTIFICATION_TOKEN_EXPIRY_DAYS") {
+      app_config.notification.token_expiry_days = days.parse().unwrap_or(60);
+    }
+    if let Ok(days) = var("APP_NOTIFICATION_RETENTION_DAYS") {
+      app_config.notification.retention_days = days.parse().unwrap_or(90);
+    }
+
+    // Try to get campaign config
+    if let Ok(batch_size) = var("APP_CAMPAIGN_BATCH_SIZE") {
+      app_config.campaign.batch_size = batch_size.parse().unwrap_or(200);
+    }
+    if let Ok(interval) = var("APP_CAMPAIGN_INTERVAL_SECONDS") {
+      app_config.campaign.interval_seconds = interval.parse().unwrap_or(60);
+    }
+
+    // Try to get zalo config
+    if let Ok(app_id) = var("ZALO_APP_ID") {
+      app_config.zalo.app_id = app_id;
+    }
+    if let Ok(secret) = var("APP_ZALO_WEBHOOK_SECRET") {
+      app_config.zalo.webhook_secret = secret;
+    }
+    if let Ok(batch_size) = var("APP_ZALO_BATCH_SIZE") {
+      app_config.zalo.batch_size = batch_size.parse().unwrap_or(20);
+    }
+    if let Ok(interval) = var("APP_ZALO_POLL_INTERVAL_SECONDS") {
+      app_config.zalo.poll_interval_seconds = interval.parse().unwrap_or(30);
+    }
+    if let Ok(max_attempts) = var("APP_ZALO_MAX_ATTEMPTS") {
+      app_config.zalo.max_attempts = max_attempts.parse().unwrap_or(5);
+    }
+    if let Ok(seconds) = var("APP_ZALO_RETRY_BASE_SECONDS") {
+      app_config.zalo.retry_base_seconds = seconds.parse().unwrap_or(60);
+    }
+    if let Ok(hours) = var("APP_ZALO_REMINDER_HOURS") {
+      app_config.zalo.reminder_hours = hours.parse().unwrap_or(24);
+    }
+
+    // Try to get webhook config
+    if let Ok(batch_size) = var("APP_WEBHOOK_BATCH_SIZE") {
+      app_config.webhook.batch_size = batch_size.parse().unwrap_or(50);
+    }
+    if let Ok(interval) = var("APP_WEBHOOK_POLL_INTERVAL_SECONDS") {
+      app_config.webhook.poll_interval_seconds = interval.parse().unwrap_or(5);
+    }
+    if let Ok(max_attempts) = var("APP_WEBHOOK_MAX_ATTEMPTS") {
+      app_config.webhook.max_attempts = max_attempts.parse().unwrap_or(8);
+    }
+    if let Ok(seconds) = var("APP_WEBHOOK_RETRY_BASE_SECONDS") {
+      app_config.webhook.retry_base_seconds = seconds.parse().unwrap_or(30);
+    }
+    if let Ok(seconds) = var("APP_WEBHOOK_RETRY_MAX_SECONDS") {
+      app_config.webhook.retry_max_seconds = seconds.parse().unwrap_or(6 * 3600);
+    }
+    if let Ok(seconds) = var("APP_WEBHOOK_TIMEOUT_SECONDS") {
+      app_config.webhook.timeout_seconds = seconds.parse().unwrap_or(10);
+    }
+    if let Ok(require_https) = var("APP_WEBHOOK_REQUIRE_HTTPS") {
+      app_config.webhook.require_https = require_https.parse().unwrap_or(true);
+    }
+
+    // Try to get email config
+    if let Ok(host) = var("APP_SMTP_HOST") {
+      app_config.email.smtp_host = host;
+    }
+    if let Ok(port) = var("APP_SMTP_PORT") {
+      app_config.email.smtp_port = port.parse().unwrap_or(587);
+    }
+    if let Ok(username) = var("APP_SMTP_USERNAME") {
+      app_config.email.smtp_username = username;
+    }
+    if let Ok(password) = var("APP_SMTP_PASSWORD") {
+      app_config.email.smtp_password = password;
+    }
+    if let Ok(tls) = var("APP_SMTP_TLS") {
+      app_config.email.smtp_tls = tls.to_lowercase();
+    }
+    if let Ok(from_address) = var("APP_EMAIL_FROM_ADDRESS") {
+      app_config.email.from_address = from_address;
+    }
+    if let Ok(from_name) = var("APP_EMAIL_FROM_NAME") {
+      app_config.email.from_name = from_name;
+    }
+    if let Ok(batch_size) = var("APP_EMAIL_BATCH_SIZE") {
+      app_config.email.batch_size = batch_size.parse().unwrap_or(20);
+    }
+    if let Ok(interval) = var("APP_EMAIL_POLL_INTERVAL_SECONDS") {
+      app_config.email.poll_interval_seconds = interval.parse().unwrap_or(10);
+    }
+    if let Ok(max_attempts) = var("APP_EMAIL_MAX_ATTEMPTS") {
+      app_config.email.max_attempts = max_attempts.parse().unwrap_or(5);
+    }
+    if let Ok(seconds) = var("APP_EMAIL_RETRY_BASE_SECONDS") {
+      app_config.email.retry_base_seconds = seconds.parse().unwrap_or(60);
+    }
+    if let Ok(seconds) = var("APP_EMAIL_TIMEOUT_SECONDS") {
+      app_config.email.timeout_seconds = seconds.parse().unwrap_or(10);
+    }
+    if let Ok(minutes) = var("APP_EMAIL_CODE_TTL_MINUTES") {
+      app_config.email.code_ttl_minutes = minutes.parse().unwrap_or(15);
+    }
+
     Ok(app_config)
   }
 }
@@ -162,6 +693,7 @@ impl Default for AppConfig {
     Self {
       web: WebConfig {
         addr: if is_prod { "0.0.0.0:8080".to_string() } else { "127.0.0.1:3000".to_string() },
+        trusted_proxies: Vec::new(),
       },
       postgres: PostgresConfig {
         dsn: if is_prod {
@@ -181,12 +713,28 @@ impl Default for AppConfig {
         refresh_token_duration_days: 30,
         phone_code_ttl_minutes: 2,
         access_token_set_password_minutes: 30,
+        receipt_share_secret: if is_prod {
+          String::new()
+        } else {
+          "dev_receipt_share_secret".to_string()
+        },
       },
       twilio: TwilioConfig {
         account_sid: String::new(),
         auth_token: String::new(),
         from_number: String::new(),
       },
+      bank: BankConfig::default(),
+      withdrawal: WithdrawalConfig::default(),
+      spa: SpaConfig::default(),
+      invoice: InvoiceConfig::default(),
+      review: ReviewConfig::default(),
+      referral: ReferralConfig::default(),
+      notification: NotificationConfig::default(),
+      campaign: CampaignConfig::default(),
+      zalo: ZaloConfig::default(),
+      webhook: WebhookConfig::default(),
+      email: EmailConfig::default(),
     }
   }
 }
@@ -31,6 +31,7 @@ pub enum ErrorCode {
   Unauthorized,
   Forbidden,
   DuplicateEntry,
+  Conflict,
 
   // Specific Server Errors (5xx)
   DatabaseError,
@@ -95,6 +96,10 @@ pub enum AppError {
   #[error("Permission denied: {0}")]
   Forbidden(String),
 
+  // Trạng thái đã bị thay đổi bởi tiến trình khác (cập nhật có điều kiện không khớp dòng nào)
+  #[error("Conflict: {0}")]
+  Conflict(String),
+
   #[error("Invalid Refresh Token")]
   InvalidRefreshToken,
 
@@ -214,6 +219,13 @@ impl AppError {
         None,
         LogLevel::Warn,
       ),
+      AppError::Conflict(msg) => (
+        StatusCode::CONFLICT, // 409
+        ErrorCode::Conflict,
+        msg.clone(),
+        None,
+        LogLevel::Warn,
+      ),
       // --- Lỗi Server (Log ở ERROR, thông điệp client chung chung) ---
       AppError::Config(_) => (
         StatusCode::INTERNAL_SERVER_ERROR,
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use configs::AppConfig;
 use sqlx::PgPool;
+use tokio::sync::broadcast;
 
 pub mod configs;
 pub mod errors;
@@ -9,10 +10,15 @@ pub mod response;
 
 pub type AppResult<T> = Result<T, errors::AppError>;
 
+// Số sự kiện realtime tối đa được giữ cho client đọc chậm trước khi bị bỏ qua
+const EVENT_BUFFER_SIZE: usize = 1024;
+
 #[derive(Clone)]
 pub struct AppState {
   pub db: PgPool,
   pub config: AppConfig,
+  // Payload NOTIFY nhận từ Postgres, phát lại cho các kết nối SSE của instance này
+  pub events: broadcast::Sender<String>,
 }
 
 impl AppState {
@@ -20,6 +26,7 @@ impl AppState {
     db: PgPool,
     config: AppConfig,
   ) -> Arc<AppState> {
-    Arc::new(Self { db, config })
+    let (events, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+    Arc::new(Self { db, config, events })
   }
 }
@@ -72,7 +72,7 @@ pub struct AppointmentExtra {
 
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema, Serialize)]
 pub struct CreateAppointmentRequest {
-  pub services: Vec<i64>,
+  pub services: Vec<AppointmentServiceRequest>,
   pub user_id: i64,
   pub receptionist_id: Option<i64>,
   pub technician_id: Option<i64>,
@@ -87,7 +87,7 @@ pub struct CreateAppointmentRequest {
 
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema, Serialize)]
 pub struct UpdateAppointmentRequest {
-  pub services: Option<Vec<i64>>,
+  pub services: Option<Vec<AppointmentServiceRequest>>,
   pub receptionist_id: Option<i64>,
   pub technician_id: Option<i64>,
   pub start_time: Option<String>,
@@ -101,12 +101,46 @@ pub struct UpdateAppointmentRequest {
   pub started_at: Option<DateTime<Utc>>,
 }
 
+// Các hình thức thanh toán được chấp nhận. VOUCHER và PACKAGE_SESSION chưa có nguồn dữ liệu
+// để đối chiếu mã/số dư nên tạm thời không nhận
+pub const TENDER_TYPES: [&str; 5] = ["WALLET", "CASH", "BANK_TRANSFER", "CARD", "POINTS"];
+
+#[derive(Deserialize, Debug, Clone, ToSchema, Serialize)]
+pub struct PaymentTender {
+  pub tender_type: String,
+  pub amount: i64,
+  // Mã giao dịch chuyển khoản / thẻ
+  pub reference: Option<String>,
+}
+
+#[derive(Deserialize, FromRow, Debug, Clone, ToSchema, Serialize)]
+pub struct AppointmentPayment {
+  pub id: i64,
+  pub appointment_id: i64,
+  pub tender_type: String,
+  pub amount: i64,
+  pub reference: Option<String>,
+  pub deposit_id: Option<i64>,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema, Serialize)]
 pub struct PaymentAppointmentRequest {
   pub status: Option<String>,
+  #[serde(default)]
   pub user_balance: i64,
   pub full_name: String,
   pub payment_method: Option<String>,
+  // Thanh toán nhiều hình thức, tổng phải bằng total_price. Bỏ trống thì dùng user_balance + payment_method
+  #[sqlx(skip)]
+  pub tenders: Option<Vec<PaymentTender>>,
+  // Tiền tip cho kỹ thuật viên: nhập số tiền hoặc % trên tổng hoá đơn
+  pub tip_amount: Option<i64>,
+  pub tip_percent: Option<i64>,
+  // WALLET (trừ vào số dư ví) hoặc CASH, mặc định CASH
+  pub tip_payment_method: Option<String>,
 }
 
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema, Serialize)]
@@ -134,15 +168,86 @@ pub struct AppointmentWithServices {
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema, Serialize)]
 pub struct AppointmentService {
   pub id: i64,
+  pub appointment_id: i64,
   pub service_id: i64,
   pub technician_id: Option<i64>,
-  pub quantity: Option<i32>,
-  pub sequence: Option<i32>,
+  pub quantity: i32,
+  pub sequence: i32,
+  pub start_time: Option<String>,
+  pub end_time: Option<String>,
+  pub status: String,
+  pub service_name: Option<String>,
+  pub service_name_en: Option<String>,
+  pub service_name_ko: Option<String>,
+  pub unit_price: i64,
+  pub discount: i64,
   pub created_at: DateTime<Utc>,
   pub updated_at: DateTime<Utc>,
   pub updated_by: Option<i64>,
 }
 
+/// Một dòng dịch vụ khi đặt lịch. Vẫn nhận dạng cũ là id dịch vụ (`[1, 2]`).
+#[derive(Deserialize, Debug, Clone, ToSchema, Serialize)]
+#[serde(from = "AppointmentServiceInput")]
+pub struct AppointmentServiceRequest {
+  pub service_id: i64,
+  pub technician_id: Option<i64>,
+  pub quantity: Option<i32>,
+  pub sequence: Option<i32>,
+  pub start_time: Option<String>,
+  pub end_time: Option<String>,
+  // Chiết khấu của dòng (VND), lưu cùng đơn giá lúc đặt lịch
+  pub discount: Option<i64>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AppointmentServiceInput {
+  Id(i64),
+  Detail {
+    service_id: i64,
+    technician_id: Option<i64>,
+    quantity: Option<i32>,
+    sequence: Option<i32>,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    discount: Option<i64>,
+  },
+}
+
+impl From<AppointmentServiceInput> for AppointmentServiceRequest {
+  fn from(input: AppointmentServiceInput) -> Self {
+    match input {
+      AppointmentServiceInput::Id(service_id) => Self {
+        service_id,
+        technician_id: None,
+        quantity: None,
+        sequence: None,
+        start_time: None,
+        end_time: None,
+        discount: None,
+      },
+      AppointmentServiceInput::Detail {
+        service_id,
+        technician_id,
+        quantity,
+        sequence,
+        start_time,
+        end_time,
+        discount,
+      } => Self { service_id, technician_id, quantity, sequence, start_time, end_time, discount },
+    }
+  }
+}
+
+#[derive(Deserialize, Debug, Clone, ToSchema, Serialize)]
+pub struct UpdateAppointmentServiceRequest {
+  pub technician_id: Option<i64>,
+  pub start_time: Option<String>,
+  pub end_time: Option<String>,
+  pub status: Option<String>,
+}
+
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema, Serialize, IntoParams)]
 pub struct AppointmentFilter {
   pub user_id: Option<i64>,
@@ -196,12 +301,14 @@ pub struct CreateAppointmentForNewCustomerRequest {
   pub date_of_birth: Option<String>,
 
   // Appointment fields
-  pub services: Vec<i64>,
+  pub services: Vec<AppointmentServiceRequest>,
   pub technician_id: Option<i64>,
   pub start_time: String,
   pub end_time: Option<String>,
   pub notes: Option<String>,
   pub surcharge: Option<i64>,
   pub promotion: Option<i64>,
+  // Mã giới thiệu khách cung cấp tại quầy
+  pub referral_code: Option<String>,
   // status and price will be set by the service
 }
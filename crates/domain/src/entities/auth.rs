@@ -108,6 +108,12 @@ pub struct SetPasswordRequest {
   pub user_id: i64,
   pub token: String,
   pub full_name: Option<String>,
+  // Mã giới thiệu khi khách đăng ký mới bằng số điện thoại
+  #[serde(default)]
+  pub referral_code: Option<String>,
+  // FCM token của thiết bị đăng ký, dùng để chống gian lận giới thiệu
+  #[serde(default)]
+  pub device_token: Option<String>,
 }
 
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema)]
@@ -115,6 +121,37 @@ pub struct ForgotPasswordRequest {
   pub phone: String,
 }
 
+// Đặt lại mật khẩu qua email đã xác minh, thay cho mã OTP điện thoại
+#[derive(Deserialize, FromRow, Debug, Clone, ToSchema)]
+pub struct ForgotPasswordEmailRequest {
+  pub email: String,
+}
+
+#[derive(Deserialize, FromRow, Debug, Clone, ToSchema)]
+pub struct VerifyEmailCodeRequest {
+  pub email: String,
+  pub code: String,
+}
+
+// Xác minh email của tài khoản đang đăng nhập
+#[derive(Deserialize, FromRow, Debug, Clone, ToSchema)]
+pub struct VerifyEmailRequest {
+  pub code: String,
+}
+
+#[derive(Serialize, FromRow, Debug, Clone)]
+pub struct EmailCode {
+  pub id: i64,
+  pub user_id: i64,
+  pub email: String,
+  pub code: String,
+  pub purpose: String,
+  pub attempts: i32,
+  pub used_at: Option<chrono::DateTime<Utc>>,
+  pub expires_at: chrono::DateTime<Utc>,
+  pub created_at: chrono::DateTime<Utc>,
+}
+
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema)]
 pub struct ResendCodeRequest {
   pub phone: String,
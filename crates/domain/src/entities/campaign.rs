@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+pub const CAMPAIGN_STATUSES: [&str; 5] =
+  ["DRAFT", "SCHEDULED", "SENDING", "COMPLETED", "CANCELLED"];
+
+// Bộ lọc khách hàng nhận chiến dịch, các điều kiện được kết hợp bằng AND, bỏ trống là không lọc.
+// Lần ghé gần nhất và tổng chi tiêu tính trên các lịch hẹn đã thanh toán.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CampaignAudience {
+  pub membership_levels: Option<Vec<String>>,
+  // Đã ghé trong N ngày gần đây
+  pub visited_within_days: Option<i32>,
+  // Không ghé trong N ngày gần đây (kể cả khách chưa ghé lần nào)
+  pub not_visited_within_days: Option<i32>,
+  pub min_total_spent: Option<i64>,
+  pub max_total_spent: Option<i64>,
+  pub birthday_month: Option<i32>,
+  // Đã từng sử dụng ít nhất một trong các dịch vụ này
+  pub service_ids: Option<Vec<i64>>,
+}
+
+// Nội dung theo ngôn ngữ, bắt buộc có bản tiếng Việt làm mặc định
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignMessage {
+  pub locale: String,
+  pub title: String,
+  pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Campaign {
+  pub id: i64,
+  pub name: String,
+  pub status: String,
+  #[sqlx(json)]
+  pub audience: CampaignAudience,
+  #[sqlx(json)]
+  pub messages: Vec<CampaignMessage>,
+  pub deep_link: Option<String>,
+  pub scheduled_at: Option<DateTime<Utc>>,
+  pub started_at: Option<DateTime<Utc>>,
+  pub completed_at: Option<DateTime<Utc>>,
+  pub created_by: Option<i64>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+// delivered/failed tính theo kết quả gửi đẩy trong outbox, opened là số khách mở thông báo
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CampaignStats {
+  pub recipients: i64,
+  pub pending: i64,
+  pub queued: i64,
+  pub delivered: i64,
+  pub failed: i64,
+  pub opened: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignDetail {
+  #[serde(flatten)]
+  pub campaign: Campaign,
+  pub stats: CampaignStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateCampaignRequest {
+  pub name: String,
+  #[serde(default)]
+  pub audience: CampaignAudience,
+  pub messages: Vec<CampaignMessage>,
+  pub deep_link: Option<String>,
+}
+
+// Chỉ sửa được khi chiến dịch còn DRAFT hoặc SCHEDULED
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateCampaignRequest {
+  pub name: Option<String>,
+  pub audience: Option<CampaignAudience>,
+  pub messages: Option<Vec<CampaignMessage>>,
+  pub deep_link: Option<String>,
+}
+
+// Bỏ trống scheduled_at là gửi ngay ở lượt quét tiếp theo
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleCampaignRequest {
+  pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignAudiencePreview {
+  pub total: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct CampaignFilter {
+  pub status: Option<String>,
+}
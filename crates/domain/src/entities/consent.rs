@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+pub const CONSENT_QUESTION_TYPES: [&str; 5] = ["TEXT", "NUMBER", "BOOLEAN", "SELECT", "DATE"];
+
+// Câu hỏi trong mẫu phiếu. BOOLEAN bắt buộc nghĩa là khách phải đánh dấu đồng ý (true)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConsentQuestion {
+  pub key: String,
+  pub label: String,
+  pub question_type: String,
+  #[serde(default)]
+  pub required: bool,
+  pub options: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ConsentTemplate {
+  pub id: i64,
+  pub service_id: Option<i64>,
+  #[sqlx(default)]
+  pub service_name: Option<String>,
+  pub title: String,
+  pub description: Option<String>,
+  #[sqlx(json)]
+  pub questions: Vec<ConsentQuestion>,
+  pub is_active: bool,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateConsentTemplateRequest {
+  pub service_id: Option<i64>,
+  pub title: String,
+  pub description: Option<String>,
+  pub questions: Vec<ConsentQuestion>,
+}
+
+// Phiếu đã ký giữ nguyên nội dung mẫu tại thời điểm ký nên có thể sửa câu hỏi
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateConsentTemplateRequest {
+  pub title: Option<String>,
+  pub description: Option<String>,
+  pub questions: Option<Vec<ConsentQuestion>>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct ConsentTemplateFilter {
+  pub service_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ConsentSubmission {
+  pub id: i64,
+  pub template_id: i64,
+  pub customer_id: i64,
+  #[sqlx(default)]
+  pub customer_name: Option<String>,
+  pub appointment_id: Option<i64>,
+  pub template_snapshot: serde_json::Value,
+  pub answers: serde_json::Value,
+  pub signature_image: String,
+  pub signed_at: DateTime<Utc>,
+  pub ip_address: Option<String>,
+  pub user_agent: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+// Multipart: template_id, appointment_id, answers (chuỗi JSON), signature (ảnh chữ ký)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateConsentSubmissionRequest {
+  pub template_id: i64,
+  pub appointment_id: i64,
+  pub answers: String,
+  #[schema(value_type = String, format = Binary)]
+  pub signature: Option<String>,
+}
+
+// Chữ ký khách vẽ trong app cùng thông tin thiết bị lúc ký
+#[derive(Debug, Clone)]
+pub struct SignatureCapture {
+  pub data: Vec<u8>,
+  pub content_type: String,
+  pub ip_address: Option<String>,
+  pub user_agent: Option<String>,
+}
+
+// Dữ liệu đã kiểm tra, dùng để lưu phiếu
+#[derive(Debug, Clone)]
+pub struct NewConsentSubmission {
+  pub template: ConsentTemplate,
+  pub customer_id: i64,
+  pub appointment_id: i64,
+  pub answers: serde_json::Value,
+  pub signature_image: String,
+  pub ip_address: Option<String>,
+  pub user_agent: Option<String>,
+}
+
+// Phiếu cần ký cho một lịch hẹn, submission_id None là khách chưa ký
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AppointmentConsentForm {
+  pub appointment_id: i64,
+  #[sqlx(flatten)]
+  pub template: ConsentTemplate,
+  pub submission_id: Option<i64>,
+  pub signed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct ConsentSubmissionFilter {
+  pub customer_id: Option<i64>,
+  pub appointment_id: Option<i64>,
+  pub template_id: Option<i64>,
+}
@@ -21,6 +21,13 @@ pub struct Deposit {
   pub transaction_id: Option<String>,
   pub notes: Option<String>,
   pub deposit_type: String,
+  pub proof_image: Option<String>,
+  pub rejection_reason: Option<String>,
+  pub reviewed_by: Option<i64>,
+  pub reviewed_at: Option<DateTime<Utc>>,
+  pub bank_name: Option<String>,
+  pub bank_account_number: Option<String>,
+  pub bank_account_name: Option<String>,
   pub created_by: i64,
   pub created_at: DateTime<Utc>,
   pub updated_at: DateTime<Utc>,
@@ -38,6 +45,13 @@ pub struct DepositDetail {
   pub transaction_id: Option<String>,
   pub notes: Option<String>,
   pub deposit_type: String,
+  pub proof_image: Option<String>,
+  pub rejection_reason: Option<String>,
+  pub reviewed_by: Option<i64>,
+  pub reviewed_at: Option<DateTime<Utc>>,
+  pub bank_name: Option<String>,
+  pub bank_account_number: Option<String>,
+  pub bank_account_name: Option<String>,
   pub created_by: i64,
   #[sqlx(json)]
   pub created_by_user: Option<User>,
@@ -60,6 +74,43 @@ pub struct UpdateDepositStatusRequest {
   pub status: Option<String>,
   pub transaction_id: Option<String>,
   pub notes: Option<String>,
+  // Bắt buộc khi từ chối (CANCELLED) yêu cầu nạp tiền
+  pub rejection_reason: Option<String>,
+}
+
+// Yêu cầu nạp tiền do khách hàng tự tạo (multipart: amount, payment_method, notes, image)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateTopUpRequest {
+  pub amount: i64,
+  pub payment_method: Option<String>,
+  pub notes: Option<String>,
+  #[schema(value_type = String, format = Binary)]
+  pub image: Option<String>,
+}
+
+// Tài khoản ngân hàng nhận tiền khi khách hàng rút tiền từ ví
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct BankAccount {
+  pub id: i64,
+  pub user_id: i64,
+  pub bank_name: String,
+  pub account_number: String,
+  pub account_name: String,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpsertBankAccountRequest {
+  pub bank_name: String,
+  pub account_number: String,
+  pub account_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateWithdrawalRequest {
+  pub amount: i64,
+  pub notes: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
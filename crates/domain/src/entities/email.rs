@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+// Sự kiện có mẫu email
+pub const EMAIL_EVENT_TYPES: [&str; 5] = [
+  "EMAIL_VERIFICATION",
+  "PASSWORD_RESET",
+  "APPOINTMENT_CONFIRMED",
+  "DEPOSIT_RECEIPT",
+  "PAYMENT_RECEIPT",
+];
+pub const EMAIL_MESSAGE_STATUSES: [&str; 4] = ["PENDING", "PROCESSING", "SENT", "FAILED"];
+
+// Mẫu email theo sự kiện và ngôn ngữ, body là HTML chứa biến dạng {ten_bien}
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct EmailTemplate {
+  pub id: i64,
+  pub event_type: String,
+  pub locale: String,
+  pub subject: String,
+  pub body: String,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct EmailTemplateFilter {
+  pub event_type: Option<String>,
+  pub locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateEmailTemplateRequest {
+  pub subject: String,
+  pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct EmailMessage {
+  pub id: i64,
+  pub event_type: String,
+  pub user_id: Option<i64>,
+  pub to_address: String,
+  pub locale: String,
+  // Biến có thể chứa mã xác minh nên không trả ra API
+  #[serde(skip_serializing)]
+  pub variables: serde_json::Value,
+  pub subject: Option<String>,
+  pub status: String,
+  pub attempts: i32,
+  pub next_attempt_at: DateTime<Utc>,
+  pub locked_at: Option<DateTime<Utc>>,
+  pub last_error: Option<String>,
+  pub sent_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct EmailMessageFilter {
+  pub status: Option<String>,
+  pub event_type: Option<String>,
+  pub user_id: Option<i64>,
+}
@@ -0,0 +1,137 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+// Thuế suất GTGT hợp lệ (%)
+pub const VAT_RATES: [i32; 4] = [0, 5, 8, 10];
+pub const DEFAULT_VAT_RATE: i32 = 8;
+
+// Hoá đơn GTGT: ORIGINAL xuất từ lịch hẹn đã thanh toán, REPLACEMENT thay thế và
+// ADJUSTMENT điều chỉnh một hoá đơn đã xuất
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Invoice {
+  pub id: i64,
+  pub invoice_type: String,
+  pub original_invoice_id: Option<i64>,
+  pub template_code: String,
+  pub symbol: String,
+  pub invoice_number: i64,
+  pub issued_date: NaiveDate,
+  pub status: String,
+  pub buyer_tax_code: String,
+  pub buyer_company_name: String,
+  pub buyer_address: String,
+  pub buyer_name: Option<String>,
+  pub buyer_email: Option<String>,
+  pub payment_method: String,
+  pub total_before_tax: i64,
+  pub total_vat: i64,
+  pub total_amount: i64,
+  pub amount_in_words: String,
+  pub reason: Option<String>,
+  // Tải riêng qua /invoices/{id}/xml
+  #[serde(skip)]
+  pub xml_content: String,
+  pub submission_status: String,
+  pub provider: Option<String>,
+  pub provider_reference: Option<String>,
+  pub submission_error: Option<String>,
+  pub submitted_at: Option<DateTime<Utc>>,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+// Số tiền trước thuế; dòng DISCOUNT và dòng điều chỉnh giảm mang giá trị âm
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct InvoiceLine {
+  pub id: i64,
+  pub invoice_id: i64,
+  pub line_no: i32,
+  pub line_type: String,
+  pub appointment_id: Option<i64>,
+  pub appointment_service_id: Option<i64>,
+  pub description: String,
+  pub unit: String,
+  pub quantity: i32,
+  pub unit_price: i64,
+  pub amount: i64,
+  pub vat_rate: i32,
+  pub vat_amount: i64,
+  pub total: i64,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceDetail {
+  #[serde(flatten)]
+  pub invoice: Invoice,
+  pub lines: Vec<InvoiceLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceBuyer {
+  pub buyer_tax_code: String,
+  pub buyer_company_name: String,
+  pub buyer_address: String,
+  pub buyer_name: Option<String>,
+  pub buyer_email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceLineVatRate {
+  pub appointment_service_id: i64,
+  pub vat_rate: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateInvoiceRequest {
+  pub appointment_ids: Vec<i64>,
+  #[serde(flatten)]
+  pub buyer: InvoiceBuyer,
+  // Thuế suất mặc định cho mọi dòng, không truyền thì dùng 8%
+  pub vat_rate: Option<i32>,
+  // Thuế suất riêng cho từng dịch vụ của lịch hẹn
+  pub line_vat_rates: Option<Vec<InvoiceLineVatRate>>,
+}
+
+// Hoá đơn thay thế lập lại từ các lịch hẹn của hoá đơn gốc với thông tin đã sửa
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateReplacementInvoiceRequest {
+  pub reason: String,
+  #[serde(flatten)]
+  pub buyer: InvoiceBuyer,
+  pub vat_rate: Option<i32>,
+  pub line_vat_rates: Option<Vec<InvoiceLineVatRate>>,
+}
+
+// unit_price là đơn giá chưa thuế, âm nếu điều chỉnh giảm
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AdjustmentLineRequest {
+  pub description: String,
+  pub quantity: i32,
+  pub unit_price: i64,
+  pub vat_rate: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateAdjustmentInvoiceRequest {
+  pub reason: String,
+  pub lines: Vec<AdjustmentLineRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct InvoiceFilter {
+  pub status: Option<String>,
+  pub invoice_type: Option<String>,
+  pub buyer_tax_code: Option<String>,
+  pub appointment_id: Option<i64>,
+}
+
+// Kết quả gửi hoá đơn sang nhà cung cấp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceSubmission {
+  pub provider: String,
+  pub reference: String,
+}
@@ -1,13 +1,29 @@
 pub mod appointment;
 pub mod auth;
+pub mod campaign;
 pub mod chat;
 pub mod common;
+pub mod consent;
 pub mod deposit;
+pub mod email;
+pub mod invoice;
 pub mod notification;
+pub mod notification_outbox;
+pub mod notification_preference;
+pub mod notification_template;
 pub mod notification_token;
+pub mod payment;
+pub mod payroll;
 pub mod profile;
+pub mod realtime_event;
+pub mod receipt;
+pub mod referral;
+pub mod review;
 pub mod service;
 pub mod service_child;
+pub mod shift;
 pub mod statistics;
+pub mod treatment;
 pub mod user;
+pub mod webhook;
 pub mod zalo;
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::{IntoParams, ToSchema};
 
+use super::notification_template::NotificationMessage;
+
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone, ToSchema)]
 pub struct Notification {
   pub id: i64,
@@ -28,6 +30,9 @@ pub struct CreateNotification {
   pub notification_type: String,
   pub data: Option<serde_json::Value>,
   pub appointment_id: Option<i64>,
+  // Có mẫu thì tiêu đề/nội dung được dựng từ notification_templates theo ngôn ngữ người nhận
+  #[serde(default)]
+  pub template: Option<NotificationMessage>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -35,6 +40,29 @@ pub struct UpdateNotification {
   pub is_read: bool,
 }
 
+// Đánh dấu đọc/chưa đọc hàng loạt theo ids, theo loại hoặc tất cả (all = true),
+// phải có ít nhất một điều kiện để tránh cập nhật toàn bộ khi client quên truyền
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkReadNotificationsRequest {
+  pub is_read: bool,
+  pub ids: Option<Vec<i64>>,
+  pub notification_type: Option<String>,
+  #[serde(default)]
+  pub all: bool,
+}
+
+// Xoá hàng loạt theo ids và/hoặc các thông báo tạo trước thời điểm before
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkDeleteNotificationsRequest {
+  pub ids: Option<Vec<i64>>,
+  pub before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkNotificationResult {
+  pub affected: u64,
+}
+
 #[derive(FilterNodes, Deserialize, Default, Debug, Clone, IntoParams, ToSchema)]
 pub struct NotificationFilter {
   pub user_id: Option<i64>,
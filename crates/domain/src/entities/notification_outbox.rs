@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+pub const OUTBOX_STATUSES: [&str; 4] = ["PENDING", "PROCESSING", "SENT", "DEAD"];
+
+// Thông báo đẩy chờ gửi, DEAD là các thông báo đã hết số lần thử (dead-letter)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct NotificationOutbox {
+  pub id: i64,
+  pub notification_id: Option<i64>,
+  pub user_id: Option<i64>,
+  pub receiver: String,
+  pub title: String,
+  pub body: String,
+  pub notification_type: String,
+  pub data: Option<serde_json::Value>,
+  pub event_type: Option<String>,
+  pub variables: Option<serde_json::Value>,
+  pub status: String,
+  pub attempts: i32,
+  pub next_attempt_at: DateTime<Utc>,
+  pub locked_at: Option<DateTime<Utc>>,
+  pub last_error: Option<String>,
+  pub sent_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+// Kết quả gửi tới từng FCM token, INVALID là token bị FCM từ chối vĩnh viễn
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct NotificationDelivery {
+  pub id: i64,
+  pub outbox_id: i64,
+  pub token: String,
+  pub status: String,
+  pub attempts: i32,
+  pub last_error: Option<String>,
+  pub sent_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationOutboxDetail {
+  #[serde(flatten)]
+  pub outbox: NotificationOutbox,
+  pub deliveries: Vec<NotificationDelivery>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct NotificationOutboxFilter {
+  pub status: Option<String>,
+  pub user_id: Option<i64>,
+  pub notification_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct NotificationOutboxStats {
+  pub pending: i64,
+  pub processing: i64,
+  pub sent: i64,
+  pub dead: i64,
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+pub const NOTIFICATION_TYPES: [&str; 7] =
+  ["APPOINTMENT", "PROMOTION", "DEPOSIT", "PAYMENT", "REVIEW", "REFERRAL", "SYSTEM"];
+pub const NOTIFICATION_CHANNELS: [&str; 5] = ["PUSH", "IN_APP", "ZALO", "SMS", "EMAIL"];
+// Thông báo giao dịch vẫn được gửi trong khung giờ yên lặng
+pub const TRANSACTIONAL_NOTIFICATION_TYPES: [&str; 4] =
+  ["APPOINTMENT", "DEPOSIT", "PAYMENT", "SYSTEM"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct NotificationPreference {
+  pub notification_type: String,
+  pub channel: String,
+  pub enabled: bool,
+}
+
+// Khung giờ yên lặng theo múi giờ của người dùng, giờ dạng HH:MM, có thể qua nửa đêm (22:00 - 07:00)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct QuietHours {
+  pub enabled: bool,
+  pub start_time: String,
+  pub end_time: String,
+  pub timezone: String,
+}
+
+impl Default for QuietHours {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      start_time: "22:00".to_string(),
+      end_time: "07:00".to_string(),
+      timezone: "Asia/Ho_Chi_Minh".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPreferences {
+  pub quiet_hours: QuietHours,
+  // Đủ mọi cặp loại thông báo/kênh, cặp chưa cấu hình là đang bật
+  pub preferences: Vec<NotificationPreference>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateNotificationPreferencesRequest {
+  pub quiet_hours: Option<QuietHours>,
+  #[serde(default)]
+  pub preferences: Vec<NotificationPreference>,
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+pub const NOTIFICATION_LOCALES: [&str; 3] = ["vi", "en", "ko"];
+pub const DEFAULT_LOCALE: &str = "vi";
+
+// Mẫu thông báo theo loại sự kiện và ngôn ngữ, biến được viết dạng {ten_bien}
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct NotificationTemplate {
+  pub id: i64,
+  pub event_type: String,
+  pub locale: String,
+  pub title: String,
+  pub body: String,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct NotificationTemplateFilter {
+  pub event_type: Option<String>,
+  pub locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateNotificationTemplateRequest {
+  pub title: String,
+  pub body: String,
+}
+
+// Thông báo dựng từ mẫu: nội dung được dịch theo ngôn ngữ của người nhận khi gửi
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationMessage {
+  pub event_type: String,
+  pub variables: serde_json::Value,
+}
+
+impl NotificationMessage {
+  pub fn new(
+    event_type: &str,
+    variables: serde_json::Value,
+  ) -> Self {
+    Self { event_type: event_type.to_string(), variables }
+  }
+}
@@ -9,6 +9,7 @@ pub struct NotificationToken {
   pub platform: String,
   pub user_id: i64,
   pub token: String,
+  pub last_seen_at: DateTime<Utc>,
   pub created_at: DateTime<Utc>,
   pub updated_at: DateTime<Utc>,
 }
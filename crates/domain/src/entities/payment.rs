@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+pub const PAYMENT_INTENT_TYPES: [&str; 2] = ["TOP_UP", "APPOINTMENT"];
+
+// Yêu cầu thanh toán chuyển khoản: TOP_UP gắn với một deposit PENDING, APPOINTMENT gắn với lịch hẹn
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PaymentIntent {
+  pub id: i64,
+  pub user_id: i64,
+  pub intent_type: String,
+  pub appointment_id: Option<i64>,
+  pub deposit_id: Option<i64>,
+  pub amount: i64,
+  pub memo: String,
+  pub status: String,
+  pub expires_at: DateTime<Utc>,
+  pub paid_at: Option<DateTime<Utc>>,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PaymentIntentResponse {
+  #[serde(flatten)]
+  pub intent: PaymentIntent,
+  // Chuỗi VietQR (EMVCo) để client tự render mã QR
+  pub qr_payload: String,
+  pub bank_bin: String,
+  pub bank_account_number: String,
+  pub bank_account_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatePaymentIntentRequest {
+  pub intent_type: String,
+  // Bắt buộc với APPOINTMENT, số tiền lấy theo tổng hoá đơn
+  pub appointment_id: Option<i64>,
+  // Bắt buộc với TOP_UP
+  pub amount: Option<i64>,
+  // Nhân viên tạo hộ khách hàng; khách hàng luôn tạo cho chính mình
+  pub user_id: Option<i64>,
+}
+
+// Dữ liệu chuẩn hoá từ webhook của cổng thanh toán / ngân hàng
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BankTransferWebhook {
+  pub provider: Option<String>,
+  pub transaction_id: String,
+  pub amount: i64,
+  pub description: String,
+  pub account_number: Option<String>,
+  pub transaction_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct BankTransaction {
+  pub id: i64,
+  pub provider: String,
+  pub provider_transaction_id: String,
+  pub amount: i64,
+  pub description: String,
+  pub account_number: Option<String>,
+  pub transaction_time: Option<DateTime<Utc>>,
+  pub payment_intent_id: Option<i64>,
+  pub match_status: String,
+  pub match_note: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct BankTransactionFilter {
+  pub match_status: Option<String>,
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+// Quy tắc hoa hồng: PERCENTAGE tính value % trên tiền dịch vụ, FIXED tính value VND cho mỗi lượt
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CommissionRule {
+  pub id: i64,
+  pub service_id: Option<i64>,
+  pub technician_level: Option<String>,
+  pub commission_type: String,
+  pub value: i64,
+  pub include_tips: bool,
+  pub is_active: bool,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateCommissionRuleRequest {
+  pub service_id: Option<i64>,
+  pub technician_level: Option<String>,
+  pub commission_type: String,
+  pub value: i64,
+  pub include_tips: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateCommissionRuleRequest {
+  pub commission_type: Option<String>,
+  pub value: Option<i64>,
+  pub include_tips: Option<bool>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Commission {
+  pub id: i64,
+  pub technician_id: i64,
+  pub appointment_id: Option<i64>,
+  pub appointment_service_id: Option<i64>,
+  pub rule_id: Option<i64>,
+  pub period: String,
+  pub entry_type: String,
+  pub service_name: Option<String>,
+  pub base_amount: i64,
+  pub tip_amount: i64,
+  pub commission_amount: i64,
+  pub notes: Option<String>,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PayrollItem {
+  pub id: i64,
+  pub technician_id: i64,
+  pub technician_name: Option<String>,
+  pub technician_level: Option<String>,
+  pub appointment_id: Option<i64>,
+  pub appointment_service_id: Option<i64>,
+  pub appointment_start_time: Option<String>,
+  pub rule_id: Option<i64>,
+  pub entry_type: String,
+  pub service_name: Option<String>,
+  pub base_amount: i64,
+  pub tip_amount: i64,
+  pub commission_amount: i64,
+  pub notes: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TechnicianPayroll {
+  pub technician_id: i64,
+  pub technician_name: Option<String>,
+  pub technician_level: Option<String>,
+  pub total_base: i64,
+  pub total_tips: i64,
+  pub total_commission: i64,
+  pub total_adjustment: i64,
+  pub total_payout: i64,
+  pub items: Vec<PayrollItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PayrollPeriod {
+  pub id: i64,
+  pub period: String,
+  pub status: String,
+  pub locked_by: Option<i64>,
+  pub locked_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayrollReport {
+  pub period: String,
+  pub status: String,
+  pub total_payout: i64,
+  pub technicians: Vec<TechnicianPayroll>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct PayrollFilter {
+  pub period: Option<String>,
+  pub technician_id: Option<i64>,
+}
+
+// Bút toán điều chỉnh, amount có thể âm; period bỏ trống thì ghi vào kỳ đang mở gần nhất
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatePayrollAdjustmentRequest {
+  pub technician_id: i64,
+  pub period: Option<String>,
+  pub appointment_id: Option<i64>,
+  pub amount: i64,
+  pub notes: String,
+}
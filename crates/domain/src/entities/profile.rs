@@ -26,4 +26,7 @@ pub struct UpdateProfileRequest {
   #[serde(deserialize_with = "trim_option_string")]
   pub address: Option<String>,
   pub date_of_birth: Option<String>,
+  #[serde(default)]
+  #[serde(deserialize_with = "trim_option_string")]
+  pub preferred_language: Option<String>,
 }
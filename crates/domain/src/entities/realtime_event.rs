@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+// Kênh LISTEN/NOTIFY dùng chung cho mọi instance server
+pub const REALTIME_CHANNEL: &str = "app_events";
+
+/// Sự kiện realtime phát qua NOTIFY. Payload NOTIFY giới hạn 8000 byte nên chỉ mang id,
+/// nội dung đầy đủ được đọc lại khi đẩy xuống client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RealtimeEvent {
+  NotificationCreated {
+    notification_id: i64,
+    user_id: Option<i64>,
+    receiver: String,
+  },
+  // Thông báo được đánh dấu đọc hoặc bị xoá, client chỉ cần cập nhật lại số chưa đọc
+  NotificationChanged {
+    user_id: Option<i64>,
+    receiver: String,
+  },
+  // Phát từ trigger trên bảng appointments
+  AppointmentStatus {
+    appointment_id: i64,
+    user_id: Option<i64>,
+    #[serde(default)]
+    technician_ids: Vec<i64>,
+    status: String,
+  },
+}
+
+impl RealtimeEvent {
+  // Cùng quy tắc hiển thị với danh sách thông báo của người dùng
+  pub fn is_visible_to(
+    &self,
+    user_id: i64,
+    role: &str,
+  ) -> bool {
+    match self {
+      RealtimeEvent::NotificationCreated { user_id: owner, receiver, .. }
+      | RealtimeEvent::NotificationChanged { user_id: owner, receiver } => {
+        let is_owner = *owner == Some(user_id);
+        match role {
+          "CUSTOMER" => receiver == "CUSTOMER" && is_owner,
+          "RECEPTIONIST" => {
+            receiver == "ALLRECEPTIONIST" || (receiver == "RECEPTIONIST" && is_owner)
+          },
+          "TECHNICIAN" => receiver == "ALLTECHNICIAN" || (receiver == "TECHNICIAN" && is_owner),
+          "ADMIN" => true,
+          _ => false,
+        }
+      },
+      RealtimeEvent::AppointmentStatus { user_id: customer_id, technician_ids, .. } => match role {
+        "CUSTOMER" => *customer_id == Some(user_id),
+        "TECHNICIAN" => technician_ids.contains(&user_id),
+        "RECEPTIONIST" | "ADMIN" => true,
+        _ => false,
+      },
+    }
+  }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Receipt {
+  pub id: i64,
+  pub appointment_id: Option<i64>,
+  pub receipt_no: String,
+  pub snapshot: serde_json::Value,
+  pub issued_at: DateTime<Utc>,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ReceiptSpaInfo {
+  pub name: String,
+  pub address: String,
+  pub phone: String,
+  pub tax_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReceiptLine {
+  pub service_name: String,
+  pub quantity: i32,
+  pub unit_price: i64,
+  pub discount: i64,
+  pub amount: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReceiptTender {
+  pub tender_type: String,
+  pub amount: i64,
+  pub reference: Option<String>,
+}
+
+// Nội dung hoá đơn, được lưu snapshot lúc xuất để in lại luôn giống bản gốc
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReceiptDocument {
+  pub receipt_no: String,
+  pub issued_at: DateTime<Utc>,
+  pub appointment_id: i64,
+  #[serde(default)]
+  pub spa: ReceiptSpaInfo,
+  pub customer_id: Option<i64>,
+  pub customer_name: Option<String>,
+  pub customer_phone: Option<String>,
+  pub cashier_name: Option<String>,
+  pub lines: Vec<ReceiptLine>,
+  pub subtotal: i64,
+  pub surcharge: i64,
+  pub promotion: i64,
+  pub total: i64,
+  pub tenders: Vec<ReceiptTender>,
+  pub wallet_used: i64,
+  pub points_used: i64,
+  pub points_earned: i64,
+  pub tip_total: i64,
+  // Token để chia sẻ hoá đơn qua link công khai, không lưu trong snapshot
+  #[serde(default, skip_deserializing)]
+  pub share_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct ReceiptQuery {
+  // JSON (mặc định), PDF hoặc ESCPOS
+  pub format: Option<String>,
+  // A4 (mặc định) hoặc A5, chỉ dùng cho PDF
+  pub paper: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct SharedReceiptQuery {
+  pub token: String,
+  pub format: Option<String>,
+  pub paper: Option<String>,
+}
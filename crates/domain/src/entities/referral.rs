@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+pub const REFERRAL_STATUSES: [&str; 3] = ["PENDING", "REWARDED", "REJECTED"];
+
+// source: SIGNUP khi khách tự đăng ký bằng số điện thoại, RECEPTION khi lễ tân tạo lịch cho khách mới
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Referral {
+  pub id: i64,
+  pub referrer_id: i64,
+  #[sqlx(default)]
+  pub referrer_name: Option<String>,
+  pub referred_id: i64,
+  #[sqlx(default)]
+  pub referred_name: Option<String>,
+  #[sqlx(default)]
+  pub referred_phone: Option<String>,
+  pub referral_code: String,
+  pub source: String,
+  pub status: String,
+  pub reject_reason: Option<String>,
+  pub appointment_id: Option<i64>,
+  pub reward_type: Option<String>,
+  pub referrer_reward: i64,
+  pub referred_reward: i64,
+  pub rewarded_at: Option<DateTime<Utc>>,
+  pub created_by: Option<i64>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+// Mã giới thiệu của khách và kết quả giới thiệu
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReferralSummary {
+  pub referral_code: Option<String>,
+  pub total_referrals: i64,
+  pub pending: i64,
+  pub rewarded: i64,
+  pub rejected: i64,
+  pub total_reward: i64,
+  pub referred_by: Option<Referral>,
+  pub referrals: Vec<Referral>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct ReferralFilter {
+  pub referrer_id: Option<i64>,
+  pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct ReferralReportFilter {
+  pub start_date: Option<DateTime<Utc>>,
+  pub end_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReferralReportRow {
+  pub referrer_id: i64,
+  pub referrer_name: Option<String>,
+  pub referrer_phone: Option<String>,
+  pub referral_code: Option<String>,
+  pub total_referrals: i64,
+  pub pending: i64,
+  pub rewarded: i64,
+  pub rejected: i64,
+  pub total_referrer_reward: i64,
+  pub total_referred_reward: i64,
+}
+
+// Khách được giới thiệu: user_id None khi chưa có tài khoản
+#[derive(Debug, Clone)]
+pub struct ReferralCandidate {
+  pub user_id: Option<i64>,
+  pub phone: String,
+  pub device_token: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewReferral {
+  pub referrer_id: i64,
+  pub referred_id: i64,
+  pub referral_code: String,
+  pub source: String,
+  pub device_token: Option<String>,
+  pub created_by: Option<i64>,
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+// Đánh giá tổng thể của khách cho một lịch hẹn đã hoàn thành
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Review {
+  pub id: i64,
+  pub appointment_id: i64,
+  pub customer_id: i64,
+  #[sqlx(default)]
+  pub customer_name: Option<String>,
+  pub rating: i32,
+  pub comment: Option<String>,
+  pub is_hidden: bool,
+  pub hidden_reason: Option<String>,
+  pub hidden_by: Option<i64>,
+  pub hidden_at: Option<DateTime<Utc>>,
+  pub reply: Option<String>,
+  pub replied_by: Option<i64>,
+  pub replied_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TechnicianReview {
+  pub id: i64,
+  pub review_id: i64,
+  pub technician_id: i64,
+  #[sqlx(default)]
+  pub technician_name: Option<String>,
+  pub rating: i32,
+  pub comment: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReviewPhoto {
+  pub id: i64,
+  pub review_id: i64,
+  pub image: String,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReviewDetail {
+  #[serde(flatten)]
+  pub review: Review,
+  pub technicians: Vec<TechnicianReview>,
+  pub photos: Vec<ReviewPhoto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateTechnicianReviewRequest {
+  pub technician_id: i64,
+  pub rating: i32,
+  pub comment: Option<String>,
+}
+
+// technicians: chỉ gồm kỹ thuật viên đã phục vụ trong lịch hẹn
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateReviewRequest {
+  pub appointment_id: i64,
+  pub rating: i32,
+  pub comment: Option<String>,
+  #[serde(default)]
+  pub technicians: Vec<CreateTechnicianReviewRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateReviewPhotoRequest {
+  #[schema(value_type = String, format = Binary)]
+  pub image: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModerateReviewRequest {
+  pub is_hidden: bool,
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReplyReviewRequest {
+  pub reply: String,
+}
+
+// include_hidden chỉ có tác dụng với nhân viên
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct ReviewFilter {
+  pub appointment_id: Option<i64>,
+  pub customer_id: Option<i64>,
+  pub technician_id: Option<i64>,
+  pub rating: Option<i32>,
+  pub include_hidden: Option<bool>,
+}
+
+// Thông tin lịch hẹn cần để kiểm tra quyền đánh giá
+#[derive(Debug, Clone)]
+pub struct ReviewContext {
+  pub appointment_id: i64,
+  pub customer_id: Option<i64>,
+  pub status: String,
+  // Thời điểm thanh toán, nếu chưa thanh toán thì thời điểm hoàn thành
+  pub finished_at: Option<DateTime<Utc>>,
+  pub technician_ids: Vec<i64>,
+}
@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+// Hình thức thanh toán có thể kiểm đếm / đối soát khi đóng ca
+pub const COUNTABLE_TENDERS: [&str; 3] = ["CASH", "CARD", "BANK_TRANSFER"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Shift {
+  pub id: i64,
+  pub receptionist_id: i64,
+  #[sqlx(default)]
+  pub receptionist_name: Option<String>,
+  pub status: String,
+  pub opening_float: i64,
+  pub opened_at: DateTime<Utc>,
+  pub closed_at: Option<DateTime<Utc>>,
+  pub closed_by: Option<i64>,
+  pub total_expected: Option<i64>,
+  pub total_counted: Option<i64>,
+  pub total_variance: Option<i64>,
+  pub notes: Option<String>,
+  pub closing_notes: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+// expected = thanh toán lịch hẹn + nạp/rút ví tại quầy + tip (tiền mặt cộng thêm tiền đầu ca)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ShiftTender {
+  pub tender_type: String,
+  pub payment_amount: i64,
+  pub deposit_amount: i64,
+  pub tip_amount: i64,
+  pub expected_amount: i64,
+  pub counted_amount: Option<i64>,
+  pub variance: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ShiftSummary {
+  #[serde(flatten)]
+  pub shift: Shift,
+  pub appointment_count: i64,
+  pub tenders: Vec<ShiftTender>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OpenShiftRequest {
+  pub opening_float: i64,
+  pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CountedTender {
+  pub tender_type: String,
+  pub amount: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CloseShiftRequest {
+  // Bắt buộc có CASH và mọi hình thức có phát sinh trong ca;
+  // CARD / BANK_TRANSFER nhập theo chốt máy POS, sao kê
+  pub counted: Vec<CountedTender>,
+  pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct ShiftFilter {
+  pub receptionist_id: Option<i64>,
+  pub status: Option<String>,
+  pub start_date: Option<DateTime<Utc>>,
+  pub end_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReceptionistVariance {
+  pub receptionist_id: i64,
+  pub receptionist_name: Option<String>,
+  pub shift_count: i64,
+  pub total_expected: i64,
+  pub total_counted: i64,
+  pub total_variance: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TenderVariance {
+  pub tender_type: String,
+  pub total_expected: i64,
+  pub total_counted: i64,
+  pub total_variance: i64,
+}
+
+// Báo cáo chênh lệch các ca đã đóng
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ShiftVarianceReport {
+  pub shift_count: i64,
+  pub total_expected: i64,
+  pub total_counted: i64,
+  pub total_variance: i64,
+  pub receptionists: Vec<ReceptionistVariance>,
+  pub tenders: Vec<TenderVariance>,
+}
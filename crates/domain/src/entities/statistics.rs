@@ -5,6 +5,8 @@ use utoipa::ToSchema;
 #[derive(Debug, Serialize, ToSchema)]
 pub struct AdminStatistics {
   pub total_revenue: i64,
+  pub total_tips: i64,
+  pub tender_statistics: Vec<TenderStatistics>,
   pub total_appointments: i64,
   pub completed_appointments: i64,
   pub cancelled_appointments: i64,
@@ -28,6 +30,7 @@ pub struct ReceptionistStatistics {
   pub completed_appointments: i64,
   pub cancelled_appointments: i64,
   pub total_revenue: i64,
+  pub tender_statistics: Vec<TenderStatistics>,
   pub daily_statistics: Vec<DailyStatistics>,
   pub appointment_status_counts: Vec<(String, i64)>,
   pub parent_service_statistics: Vec<(i64, String, i64)>,
@@ -51,6 +54,10 @@ pub struct TechnicianStatistics {
   pub completed_appointments: i64,
   pub confirmed_appointments: i64,
   pub total_revenue: i64,
+  pub total_tips: i64,
+  // Điểm đánh giá trung bình, None khi chưa có đánh giá
+  pub average_rating: Option<f64>,
+  pub review_count: i64,
   pub service_statistics: Vec<ServiceStatistics>,
   pub daily_statistics: Vec<DailyStatistics>,
 }
@@ -69,6 +76,7 @@ pub struct TechnicianStats {
   pub technician_name: String,
   pub total_appointments: i64,
   pub total_revenue: i64,
+  pub total_tips: i64,
 }
 
 #[derive(Debug, Serialize, ToSchema, FromRow)]
@@ -77,3 +85,10 @@ pub struct DailyStatistics {
   pub total_appointments: i64,
   pub total_revenue: i64,
 }
+
+#[derive(Debug, Serialize, ToSchema, FromRow)]
+pub struct TenderStatistics {
+  pub tender_type: String,
+  pub total_count: i64,
+  pub total_amount: i64,
+}
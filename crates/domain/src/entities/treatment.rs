@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+pub const TREATMENT_FIELD_TYPES: [&str; 4] = ["TEXT", "NUMBER", "BOOLEAN", "SELECT"];
+pub const TREATMENT_PHOTO_TYPES: [&str; 2] = ["BEFORE", "AFTER"];
+
+// Trường thông tin lâm sàng theo nhóm dịch vụ, service_id None áp dụng cho mọi nhóm.
+// is_internal: chỉ nhân viên xem được, ẩn khỏi hồ sơ khách hàng
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TreatmentField {
+  pub id: i64,
+  pub service_id: Option<i64>,
+  pub field_key: String,
+  pub label: String,
+  pub field_type: String,
+  pub options: Option<serde_json::Value>,
+  pub is_required: bool,
+  pub is_internal: bool,
+  pub sort_order: i32,
+  pub is_active: bool,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateTreatmentFieldRequest {
+  pub service_id: Option<i64>,
+  pub field_key: String,
+  pub label: String,
+  pub field_type: String,
+  // Danh sách lựa chọn, bắt buộc với SELECT
+  pub options: Option<Vec<String>>,
+  pub is_required: Option<bool>,
+  pub is_internal: Option<bool>,
+  pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateTreatmentFieldRequest {
+  pub label: Option<String>,
+  pub options: Option<Vec<String>>,
+  pub is_required: Option<bool>,
+  pub is_internal: Option<bool>,
+  pub sort_order: Option<i32>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct TreatmentFieldFilter {
+  pub service_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TreatmentRecord {
+  pub id: i64,
+  pub customer_id: i64,
+  #[sqlx(default)]
+  pub customer_name: Option<String>,
+  pub appointment_id: Option<i64>,
+  pub appointment_service_id: Option<i64>,
+  pub service_id: Option<i64>,
+  #[sqlx(default)]
+  pub service_name: Option<String>,
+  pub technician_id: i64,
+  #[sqlx(default)]
+  pub technician_name: Option<String>,
+  pub skin_type: Option<String>,
+  pub allergies: Option<String>,
+  pub machine_settings: Option<String>,
+  pub reactions: Option<String>,
+  pub notes: Option<String>,
+  pub internal_notes: Option<String>,
+  pub fields: serde_json::Value,
+  pub version: i32,
+  pub updated_by: Option<i64>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TreatmentPhoto {
+  pub id: i64,
+  pub record_id: i64,
+  pub photo_type: String,
+  pub image: String,
+  pub caption: Option<String>,
+  pub uploaded_by: i64,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TreatmentRecordDetail {
+  #[serde(flatten)]
+  pub record: TreatmentRecord,
+  pub photos: Vec<TreatmentPhoto>,
+}
+
+// Hồ sơ khách hàng tự xem: bỏ thông số máy, ghi chú nội bộ và các trường is_internal
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CustomerTreatmentRecord {
+  pub id: i64,
+  pub appointment_id: Option<i64>,
+  pub service_id: Option<i64>,
+  pub service_name: Option<String>,
+  pub technician_name: Option<String>,
+  pub skin_type: Option<String>,
+  pub allergies: Option<String>,
+  pub reactions: Option<String>,
+  pub notes: Option<String>,
+  pub fields: serde_json::Value,
+  pub photos: Vec<TreatmentPhoto>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TreatmentRecordHistory {
+  pub id: i64,
+  pub record_id: i64,
+  pub version: i32,
+  pub snapshot: serde_json::Value,
+  pub changed_by: i64,
+  #[sqlx(default)]
+  pub changed_by_name: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+// Có appointment_service_id thì khách hàng, lịch hẹn và nhóm dịch vụ lấy theo dòng dịch vụ đó
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateTreatmentRecordRequest {
+  pub customer_id: Option<i64>,
+  pub appointment_id: Option<i64>,
+  pub appointment_service_id: Option<i64>,
+  pub service_id: Option<i64>,
+  pub skin_type: Option<String>,
+  pub allergies: Option<String>,
+  pub machine_settings: Option<String>,
+  pub reactions: Option<String>,
+  pub notes: Option<String>,
+  pub internal_notes: Option<String>,
+  pub fields: Option<serde_json::Value>,
+}
+
+// fields gộp với giá trị hiện tại, key có giá trị null thì bị xoá
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateTreatmentRecordRequest {
+  pub skin_type: Option<String>,
+  pub allergies: Option<String>,
+  pub machine_settings: Option<String>,
+  pub reactions: Option<String>,
+  pub notes: Option<String>,
+  pub internal_notes: Option<String>,
+  pub fields: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateTreatmentPhotoRequest {
+  pub photo_type: String,
+  pub caption: Option<String>,
+  #[schema(value_type = String, format = Binary)]
+  pub image: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+pub struct TreatmentRecordFilter {
+  pub customer_id: Option<i64>,
+  pub appointment_id: Option<i64>,
+  pub technician_id: Option<i64>,
+  pub service_id: Option<i64>,
+}
+
+// Thông tin lấy từ lịch hẹn khi tạo hồ sơ
+#[derive(Debug, Clone, FromRow)]
+pub struct TreatmentContext {
+  pub appointment_id: i64,
+  pub customer_id: Option<i64>,
+  pub service_id: Option<i64>,
+}
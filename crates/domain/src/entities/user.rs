@@ -31,6 +31,19 @@ pub struct User {
   pub membership_level: String,
   pub balance: i64,
   pub loyalty_points: i64,
+  pub technician_level: Option<String>,
+  pub preferred_language: String,
+  pub is_email_verified: bool,
+}
+
+// Kỹ thuật viên kèm điểm đánh giá trung bình (không tính đánh giá đã ẩn)
+#[derive(Serialize, FromRow, Debug, Clone, ToSchema)]
+pub struct TechnicianWithRating {
+  #[serde(flatten)]
+  #[sqlx(flatten)]
+  pub user: User,
+  pub average_rating: Option<f64>,
+  pub review_count: i64,
 }
 
 #[derive(Serialize, FromRow, Fields, Debug, Clone, ToSchema)] // chuyển đổi Struct về Json
@@ -59,6 +72,9 @@ pub struct UserWithPassword {
   pub membership_level: String,
   pub balance: i64,
   pub loyalty_points: i64,
+  pub technician_level: Option<String>,
+  pub preferred_language: String,
+  pub is_email_verified: bool,
 }
 
 // Chuyển từ UserWithPassword sang User (loại bỏ password_hash)
@@ -79,6 +95,9 @@ impl From<UserWithPassword> for User {
       membership_level: user_with_pw.membership_level,
       balance: user_with_pw.balance,
       loyalty_points: user_with_pw.loyalty_points,
+      technician_level: user_with_pw.technician_level,
+      preferred_language: user_with_pw.preferred_language,
+      is_email_verified: user_with_pw.is_email_verified,
     }
   }
 }
@@ -107,6 +126,8 @@ pub struct RequestUpdateUser {
   pub membership_level: Option<String>,
   pub balance: Option<i64>,
   pub loyalty_points: Option<i64>,
+  pub technician_level: Option<String>,
+  pub preferred_language: String,
 }
 
 #[derive(Deserialize, FromRow, Fields, Serialize, ToSchema)]
@@ -223,3 +244,5 @@ pub enum Point {
   DIAMOND = 20000,
   VIP = 50000,
 }
+
+pub const MEMBERSHIP_LEVELS: [&str; 4] = ["BRONZE", "GOLD", "DIAMOND", "VIP"];
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+// Các sự kiện nghiệp vụ có thể đăng ký nhận qua webhook
+pub const WEBHOOK_EVENT_TYPES: [&str; 5] = [
+  "appointment.created",
+  "appointment.status_changed",
+  "appointment.paid",
+  "deposit.completed",
+  "user.created",
+];
+pub const WEBHOOK_DELIVERY_STATUSES: [&str; 4] = ["PENDING", "PROCESSING", "SUCCEEDED", "FAILED"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookSubscription {
+  pub id: i64,
+  pub url: String,
+  pub secret: String,
+  pub event_types: Vec<String>,
+  pub description: Option<String>,
+  pub is_active: bool,
+  pub created_by: Option<i64>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+// Bỏ trống secret thì hệ thống tự sinh
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookSubscriptionRequest {
+  pub url: String,
+  pub secret: Option<String>,
+  pub event_types: Vec<String>,
+  pub description: Option<String>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateWebhookSubscriptionRequest {
+  pub url: Option<String>,
+  pub secret: Option<String>,
+  pub event_types: Option<Vec<String>>,
+  pub description: Option<String>,
+  pub is_active: Option<bool>,
+}
+
+// Một lượt gửi sự kiện tới subscription, kèm kết quả phản hồi lần gửi gần nhất
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookDelivery {
+  pub id: i64,
+  pub subscription_id: i64,
+  pub event_type: String,
+  pub event_id: String,
+  pub payload: serde_json::Value,
+  pub status: String,
+  pub attempts: i32,
+  pub next_attempt_at: DateTime<Utc>,
+  pub locked_at: Option<DateTime<Utc>>,
+  pub response_status: Option<i32>,
+  pub response_body: Option<String>,
+  pub last_error: Option<String>,
+  pub delivered_at: Option<DateTime<Utc>>,
+  pub redelivery_of: Option<i64>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct WebhookDeliveryFilter {
+  pub status: Option<String>,
+  pub event_type: Option<String>,
+}
@@ -1,7 +1,19 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+
+// Sự kiện có thể gắn mẫu ZNS
+pub const ZALO_EVENT_TYPES: [&str; 6] = [
+  "OTP",
+  "APPOINTMENT_CONFIRMED",
+  "APPOINTMENT_REMINDER",
+  "APPOINTMENT_RESCHEDULED",
+  "APPOINTMENT_CANCELLED",
+  "PAYMENT_RECEIPT",
+];
+pub const ZALO_MESSAGE_STATUSES: [&str; 5] =
+  ["PENDING", "PROCESSING", "SENT", "DELIVERED", "FAILED"];
 
 #[derive(Deserialize, FromRow, Debug, Clone, ToSchema, Serialize)]
 pub struct ZaloToken {
@@ -29,15 +41,10 @@ pub struct RefreshTokenData {
 pub struct SendMessagePayload {
   pub phone: String,
   pub template_id: String,
-  pub template_data: TemplateData,
+  pub template_data: serde_json::Value,
   pub tracking_id: String,
 }
 
-#[derive(Deserialize, FromRow, Debug, ToSchema, Clone, Serialize)]
-pub struct TemplateData {
-  pub otp: String,
-}
-
 #[derive(Deserialize, FromRow, Debug, ToSchema, Clone, Serialize)]
 pub struct ZaloTemplate {
   #[serde(rename = "templateId")]
@@ -89,3 +96,64 @@ pub struct ZaloTemplateResponse {
 pub struct ZaloTemplateMetadata {
   pub total: i32,
 }
+
+// Mẫu ZNS gắn với sự kiện: params là {tên tham số của mẫu: nội dung}, nội dung chứa biến dạng {ten_bien}
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ZaloEventTemplate {
+  pub id: i64,
+  pub event_type: String,
+  pub template_id: Option<String>,
+  pub params: serde_json::Value,
+  pub enabled: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateZaloEventTemplateRequest {
+  pub template_id: Option<String>,
+  pub params: Option<serde_json::Value>,
+  pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ZaloMessage {
+  pub id: i64,
+  pub event_type: String,
+  pub appointment_id: Option<i64>,
+  pub user_id: Option<i64>,
+  pub phone: String,
+  pub template_id: Option<String>,
+  pub template_data: Option<serde_json::Value>,
+  pub status: String,
+  pub attempts: i32,
+  pub next_attempt_at: DateTime<Utc>,
+  pub locked_at: Option<DateTime<Utc>>,
+  pub last_error: Option<String>,
+  pub msg_id: Option<String>,
+  pub sent_at: Option<DateTime<Utc>>,
+  pub delivered_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct ZaloMessageFilter {
+  pub status: Option<String>,
+  pub event_type: Option<String>,
+  pub appointment_id: Option<i64>,
+}
+
+// Callback Zalo gửi về, chỉ lấy các trường cần để đối soát, phần còn lại lưu nguyên payload
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ZaloWebhookEvent {
+  pub event_name: String,
+  pub app_id: Option<String>,
+  pub message: Option<ZaloWebhookMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ZaloWebhookMessage {
+  pub msg_id: Option<String>,
+  pub tracking_id: Option<String>,
+}
@@ -5,19 +5,22 @@ use modql::filter::ListOptions;
 use crate::entities::{
   appointment::{
     AppointmentExtra, AppointmentFilter, AppointmentWithServices, CreateAppointmentRequest,
-    PaymentAppointmentRequest, UpdateAppointmentRequest,
+    PaymentAppointmentRequest, UpdateAppointmentRequest, UpdateAppointmentServiceRequest,
   },
   common::PaginationMetadata,
+  referral::NewReferral,
   user::UserWithPassword,
 };
 
 #[async_trait]
 pub trait AppointmentRepository: Send + Sync {
+  // Mã giới thiệu (nếu có) được ghi cùng transaction với lịch hẹn
   async fn create_appointment(
     &self,
     user: UserWithPassword,
     appointment: CreateAppointmentRequest,
     create_by_role: String,
+    referral: Option<NewReferral>,
   ) -> AppResult<AppointmentWithServices>;
 
   async fn update_appointment(
@@ -27,6 +30,14 @@ pub trait AppointmentRepository: Send + Sync {
     payload: UpdateAppointmentRequest,
   ) -> AppResult<AppointmentWithServices>;
 
+  async fn update_appointment_service(
+    &self,
+    user: UserWithPassword,
+    appointment_id: i64,
+    id: i64,
+    payload: UpdateAppointmentServiceRequest,
+  ) -> AppResult<AppointmentWithServices>;
+
   async fn payment_appointment(
     &self,
     user: UserWithPassword,
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use core_app::AppResult;
+
+use crate::entities::campaign::{
+  Campaign, CampaignAudience, CampaignFilter, CampaignStats, CreateCampaignRequest,
+  UpdateCampaignRequest,
+};
+
+#[async_trait]
+pub trait CampaignRepository: Send + Sync {
+  async fn list(
+    &self,
+    filter: CampaignFilter,
+  ) -> AppResult<Vec<Campaign>>;
+  async fn get_by_id(
+    &self,
+    id: i64,
+  ) -> AppResult<Campaign>;
+  async fn get_stats(
+    &self,
+    id: i64,
+  ) -> AppResult<CampaignStats>;
+  async fn create(
+    &self,
+    created_by: i64,
+    payload: CreateCampaignRequest,
+  ) -> AppResult<Campaign>;
+  async fn update(
+    &self,
+    id: i64,
+    payload: UpdateCampaignRequest,
+  ) -> AppResult<Campaign>;
+  // Chỉ đổi trạng thái khi chiến dịch còn ở một trong from_statuses, nếu không trả về Conflict
+  async fn update_status(
+    &self,
+    id: i64,
+    from_statuses: &[&str],
+    status: &str,
+    scheduled_at: Option<DateTime<Utc>>,
+  ) -> AppResult<Campaign>;
+  async fn count_audience(
+    &self,
+    audience: CampaignAudience,
+  ) -> AppResult<i64>;
+  async fn mark_opened(
+    &self,
+    id: i64,
+    user_id: i64,
+  ) -> AppResult<()>;
+}
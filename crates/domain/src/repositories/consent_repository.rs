@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use core_app::{AppResult, configs::SpaConfig};
+
+use crate::entities::consent::{
+  AppointmentConsentForm, ConsentSubmission, ConsentSubmissionFilter, ConsentTemplate,
+  CreateConsentTemplateRequest, NewConsentSubmission, UpdateConsentTemplateRequest,
+};
+
+#[async_trait]
+pub trait ConsentRepository: Send + Sync {
+  // service_id Some: mẫu của dịch vụ đó và mẫu dùng chung; None: toàn bộ
+  async fn get_templates(
+    &self,
+    service_id: Option<i64>,
+    include_inactive: bool,
+  ) -> AppResult<Vec<ConsentTemplate>>;
+  async fn get_template(
+    &self,
+    id: i64,
+  ) -> AppResult<ConsentTemplate>;
+  async fn create_template(
+    &self,
+    payload: CreateConsentTemplateRequest,
+    created_by: i64,
+  ) -> AppResult<ConsentTemplate>;
+  async fn update_template(
+    &self,
+    id: i64,
+    payload: UpdateConsentTemplateRequest,
+  ) -> AppResult<ConsentTemplate>;
+  // Khách hàng của lịch hẹn, NotFound nếu lịch hẹn không tồn tại
+  async fn get_appointment_customer(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<Option<i64>>;
+  // Mẫu đang hoạt động áp dụng cho các dịch vụ trong lịch hẹn, kèm trạng thái đã ký của khách
+  async fn get_appointment_forms(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<Vec<AppointmentConsentForm>>;
+  // Phiếu khách chưa ký cho các lịch hẹn sắp tới (PENDING, CONFIRMED)
+  async fn get_pending_forms(
+    &self,
+    customer_id: i64,
+  ) -> AppResult<Vec<AppointmentConsentForm>>;
+  // Lưu phiếu kèm bản PDF có chữ ký
+  async fn create_submission(
+    &self,
+    submission: NewConsentSubmission,
+    signature: &[u8],
+    spa: &SpaConfig,
+  ) -> AppResult<ConsentSubmission>;
+  async fn get_submission(
+    &self,
+    id: i64,
+  ) -> AppResult<ConsentSubmission>;
+  async fn get_submission_pdf(
+    &self,
+    id: i64,
+  ) -> AppResult<Vec<u8>>;
+  async fn get_submissions(
+    &self,
+    filter: ConsentSubmissionFilter,
+  ) -> AppResult<Vec<ConsentSubmission>>;
+}
@@ -6,7 +6,8 @@ use modql::filter::ListOptions;
 use crate::entities::{
   common::PaginationMetadata,
   deposit::{
-    CreateDepositRequest, Deposit, DepositDetail, DepositFilter, UpdateDepositStatusRequest,
+    BankAccount, CreateDepositRequest, CreateTopUpRequest, CreateWithdrawalRequest, Deposit,
+    DepositDetail, DepositFilter, UpdateDepositStatusRequest, UpsertBankAccountRequest,
   },
 };
 
@@ -17,10 +18,32 @@ pub trait DepositRepository: Send + Sync {
     request: CreateDepositRequest,
     created_by: i64,
   ) -> AppResult<Deposit>;
+  async fn create_top_up(
+    &self,
+    user_id: i64,
+    request: CreateTopUpRequest,
+  ) -> AppResult<Deposit>;
+  // Trừ ngay số tiền rút khỏi ví (giữ lại) để không bị dùng trong lúc chờ duyệt
+  async fn create_withdrawal(
+    &self,
+    user_id: i64,
+    request: CreateWithdrawalRequest,
+    daily_limit: i64,
+  ) -> AppResult<Deposit>;
+  async fn get_bank_account(
+    &self,
+    user_id: i64,
+  ) -> AppResult<Option<BankAccount>>;
+  async fn upsert_bank_account(
+    &self,
+    user_id: i64,
+    request: UpsertBankAccountRequest,
+  ) -> AppResult<BankAccount>;
   async fn update_deposit_status(
     &self,
     deposit_id: i64,
     request: UpdateDepositStatusRequest,
+    reviewed_by: i64,
   ) -> AppResult<Deposit>;
   async fn get_deposit_by_id(
     &self,
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::email::{
+  EmailMessage, EmailMessageFilter, EmailTemplate, EmailTemplateFilter, UpdateEmailTemplateRequest,
+};
+
+#[async_trait]
+pub trait EmailRepository: Send + Sync {
+  async fn list_templates(
+    &self,
+    filter: EmailTemplateFilter,
+  ) -> AppResult<Vec<EmailTemplate>>;
+  async fn update_template(
+    &self,
+    id: i64,
+    payload: UpdateEmailTemplateRequest,
+  ) -> AppResult<EmailTemplate>;
+  async fn list_messages(
+    &self,
+    filter: EmailMessageFilter,
+  ) -> AppResult<Vec<EmailMessage>>;
+}
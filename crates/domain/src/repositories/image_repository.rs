@@ -1,5 +1,9 @@
 use core_app::AppResult;
 
+// Ảnh nhạy cảm (ảnh trước / sau điều trị, chữ ký phiếu đồng ý) được lưu trong private_uploads/
+// thay vì uploads/ công khai, chỉ đọc qua API có xác thực
+pub const PRIVATE_IMAGE_DIRS: [&str; 2] = ["treatments", "signatures"];
+
 #[async_trait::async_trait]
 pub trait ImageRepository: Send + Sync {
   /// Upload và resize ảnh, trả về đường dẫn file đã lưu
@@ -11,7 +15,7 @@ pub trait ImageRepository: Send + Sync {
     max_file_size: usize, // Kích thước tối đa (bytes)
     max_width: u32,       // Chiều rộng tối đa để resize
     quality: u8,          // Chất lượng ảnh (0-100)
-    sub_dir: &str,        // Thư mục con trong uploads/ (ví dụ: "avatar", "service")
+    sub_dir: &str, // Thư mục con trong uploads/ (hoặc private_uploads/, xem PRIVATE_IMAGE_DIRS)
   ) -> AppResult<String>;
 
   /// Xóa file ảnh cũ nếu tồn tại
@@ -19,4 +23,10 @@ pub trait ImageRepository: Send + Sync {
     &self,
     image_path: &str,
   ) -> AppResult<()>;
+
+  /// Đọc ảnh trong private_uploads/, trả về dữ liệu và content type
+  async fn read_private_image(
+    &self,
+    image_path: &str,
+  ) -> AppResult<(Vec<u8>, String)>;
 }
@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use core_app::{AppResult, configs::SpaConfig};
+
+use crate::entities::invoice::{
+  CreateAdjustmentInvoiceRequest, CreateInvoiceRequest, CreateReplacementInvoiceRequest, Invoice,
+  InvoiceDetail, InvoiceFilter, InvoiceSubmission,
+};
+
+#[async_trait]
+pub trait InvoiceRepository: Send + Sync {
+  // Cấp số, lưu hoá đơn và XML trong cùng một transaction
+  async fn create_invoice(
+    &self,
+    payload: CreateInvoiceRequest,
+    seller: &SpaConfig,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail>;
+  // Hoá đơn gốc chuyển sang REPLACED
+  async fn create_replacement(
+    &self,
+    original_id: i64,
+    payload: CreateReplacementInvoiceRequest,
+    seller: &SpaConfig,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail>;
+  async fn create_adjustment(
+    &self,
+    original_id: i64,
+    payload: CreateAdjustmentInvoiceRequest,
+    seller: &SpaConfig,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail>;
+  async fn get_invoice(
+    &self,
+    id: i64,
+  ) -> AppResult<InvoiceDetail>;
+  async fn get_invoices(
+    &self,
+    filter: InvoiceFilter,
+  ) -> AppResult<Vec<Invoice>>;
+  async fn update_submission(
+    &self,
+    id: i64,
+    submission: Result<InvoiceSubmission, String>,
+  ) -> AppResult<Invoice>;
+}
+
+// Adapter gửi XML hoá đơn sang nhà cung cấp hoá đơn điện tử
+#[async_trait]
+pub trait InvoiceProviderRepository: Send + Sync {
+  async fn submit(
+    &self,
+    invoice: &Invoice,
+  ) -> AppResult<InvoiceSubmission>;
+}
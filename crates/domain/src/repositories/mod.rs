@@ -1,12 +1,28 @@
 pub mod appointment_repository;
 pub mod auth_repository;
+pub mod campaign_repository;
 pub mod chat_repository;
+pub mod consent_repository;
 pub mod deposit_repository;
+pub mod email_repository;
 pub mod image_repository;
+pub mod invoice_repository;
 pub mod noti_token_repository;
+pub mod notification_outbox_repository;
+pub mod notification_preference_repository;
+pub mod notification_template_repository;
 pub mod notification_repository;
+pub mod payment_repository;
+pub mod payroll_repository;
 pub mod profile_repository;
+pub mod receipt_repository;
+pub mod referral_repository;
+pub mod review_repository;
 pub mod service_child_repository;
 pub mod service_repository;
+pub mod shift_repository;
 pub mod statistics_repository;
+pub mod treatment_repository;
 pub mod user_repository;
+pub mod webhook_repository;
+pub mod zalo_repository;
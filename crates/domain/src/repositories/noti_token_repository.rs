@@ -24,6 +24,11 @@ pub trait NotificationTokenRepository: Send + Sync {
     id: i64,
   ) -> AppResult<bool>;
 
+  async fn delete_invalid_tokens(
+    &self,
+    tokens: Vec<String>,
+  ) -> AppResult<u64>;
+
   async fn get_token_by_user_id(
     &self,
     user_id: i64,
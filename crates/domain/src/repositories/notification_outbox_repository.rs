@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::notification_outbox::{
+  NotificationOutbox, NotificationOutboxDetail, NotificationOutboxFilter, NotificationOutboxStats,
+};
+
+#[async_trait]
+pub trait NotificationOutboxRepository: Send + Sync {
+  async fn list(
+    &self,
+    filter: NotificationOutboxFilter,
+  ) -> AppResult<Vec<NotificationOutbox>>;
+  async fn get_by_id(
+    &self,
+    id: i64,
+  ) -> AppResult<NotificationOutboxDetail>;
+  async fn get_stats(&self) -> AppResult<NotificationOutboxStats>;
+  // Đưa thông báo dead-letter trở lại hàng đợi
+  async fn retry(
+    &self,
+    id: i64,
+  ) -> AppResult<NotificationOutboxDetail>;
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::notification_preference::{
+  NotificationPreferences, UpdateNotificationPreferencesRequest,
+};
+
+#[async_trait]
+pub trait NotificationPreferenceRepository: Send + Sync {
+  async fn get_preferences(
+    &self,
+    user_id: i64,
+  ) -> AppResult<NotificationPreferences>;
+  async fn is_valid_timezone(
+    &self,
+    timezone: &str,
+  ) -> AppResult<bool>;
+  async fn update_preferences(
+    &self,
+    user_id: i64,
+    payload: UpdateNotificationPreferencesRequest,
+  ) -> AppResult<NotificationPreferences>;
+}
@@ -4,7 +4,10 @@ use modql::filter::ListOptions;
 
 use crate::entities::{
   common::PaginationMetadata,
-  notification::{CreateNotification, Notification, NotificationFilter, UpdateNotification},
+  notification::{
+    BulkDeleteNotificationsRequest, BulkReadNotificationsRequest, CreateNotification, Notification,
+    NotificationFilter, UpdateNotification,
+  },
   user::UserWithPassword,
 };
 
@@ -46,4 +49,16 @@ pub trait NotificationRepository: Send + Sync {
     user: UserWithPassword,
     filter: NotificationFilter,
   ) -> AppResult<i64>;
+
+  async fn bulk_update_read(
+    &self,
+    user: UserWithPassword,
+    payload: BulkReadNotificationsRequest,
+  ) -> AppResult<u64>;
+
+  async fn bulk_delete(
+    &self,
+    user: UserWithPassword,
+    payload: BulkDeleteNotificationsRequest,
+  ) -> AppResult<u64>;
 }
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::notification_template::{
+  NotificationTemplate, NotificationTemplateFilter, UpdateNotificationTemplateRequest,
+};
+
+#[async_trait]
+pub trait NotificationTemplateRepository: Send + Sync {
+  async fn list(
+    &self,
+    filter: NotificationTemplateFilter,
+  ) -> AppResult<Vec<NotificationTemplate>>;
+  async fn get_by_id(
+    &self,
+    id: i64,
+  ) -> AppResult<NotificationTemplate>;
+  async fn update(
+    &self,
+    id: i64,
+    payload: UpdateNotificationTemplateRequest,
+  ) -> AppResult<NotificationTemplate>;
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::payment::{
+  BankTransaction, BankTransactionFilter, BankTransferWebhook, CreatePaymentIntentRequest,
+  PaymentIntent,
+};
+
+#[async_trait]
+pub trait PaymentRepository: Send + Sync {
+  async fn create_intent(
+    &self,
+    request: CreatePaymentIntentRequest,
+    memo: String,
+    created_by: i64,
+  ) -> AppResult<PaymentIntent>;
+  async fn get_intent(
+    &self,
+    id: i64,
+  ) -> AppResult<Option<PaymentIntent>>;
+  // Webhook gửi lại giao dịch đã có thì trả về bản ghi cũ
+  async fn record_bank_transaction(
+    &self,
+    payload: BankTransferWebhook,
+    raw_payload: serde_json::Value,
+  ) -> AppResult<BankTransaction>;
+  // Khớp giao dịch PENDING theo memo + số tiền. TOP_UP cộng số dư, APPOINTMENT thanh toán lịch hẹn,
+  // cả hai hoàn tất yêu cầu thanh toán trong cùng transaction. Giao dịch đã xử lý thì trả về nguyên trạng
+  async fn match_bank_transaction(
+    &self,
+    transaction_id: i64,
+  ) -> AppResult<BankTransaction>;
+  async fn flag_mismatch(
+    &self,
+    transaction_id: i64,
+    intent_id: Option<i64>,
+    note: String,
+  ) -> AppResult<BankTransaction>;
+  async fn get_bank_transactions(
+    &self,
+    filter: BankTransactionFilter,
+  ) -> AppResult<Vec<BankTransaction>>;
+}
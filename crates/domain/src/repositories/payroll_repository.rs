@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::payroll::{
+  Commission, CommissionRule, CreateCommissionRuleRequest, CreatePayrollAdjustmentRequest,
+  PayrollPeriod, PayrollReport, UpdateCommissionRuleRequest,
+};
+
+#[async_trait]
+pub trait PayrollRepository: Send + Sync {
+  async fn get_commission_rules(&self) -> AppResult<Vec<CommissionRule>>;
+  async fn create_commission_rule(
+    &self,
+    payload: CreateCommissionRuleRequest,
+    created_by: i64,
+  ) -> AppResult<CommissionRule>;
+  async fn update_commission_rule(
+    &self,
+    id: i64,
+    payload: UpdateCommissionRuleRequest,
+  ) -> AppResult<CommissionRule>;
+  async fn delete_commission_rule(
+    &self,
+    id: i64,
+  ) -> AppResult<()>;
+  async fn get_payroll(
+    &self,
+    period: String,
+    technician_id: Option<i64>,
+  ) -> AppResult<PayrollReport>;
+  async fn lock_payroll_period(
+    &self,
+    period: String,
+    locked_by: i64,
+  ) -> AppResult<PayrollPeriod>;
+  async fn create_adjustment(
+    &self,
+    payload: CreatePayrollAdjustmentRequest,
+    created_by: i64,
+  ) -> AppResult<Commission>;
+}
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::receipt::ReceiptDocument;
+
+#[async_trait]
+pub trait ReceiptRepository: Send + Sync {
+  async fn get_appointment_customer(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<Option<i64>>;
+  // issued_by = Some: xuất hoá đơn nếu lịch hẹn đã thanh toán nhưng chưa có số hoá đơn
+  async fn get_receipt_by_appointment(
+    &self,
+    appointment_id: i64,
+    issued_by: Option<i64>,
+  ) -> AppResult<ReceiptDocument>;
+  async fn get_receipt_by_no(
+    &self,
+    receipt_no: String,
+  ) -> AppResult<ReceiptDocument>;
+}
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::referral::{
+  Referral, ReferralCandidate, ReferralFilter, ReferralReportFilter, ReferralReportRow,
+  ReferralSummary,
+};
+
+#[async_trait]
+pub trait ReferralRepository: Send + Sync {
+  // Kiểm tra mã và các quy tắc chống gian lận, trả về người giới thiệu
+  async fn check_referral(
+    &self,
+    referral_code: &str,
+    candidate: &ReferralCandidate,
+  ) -> AppResult<i64>;
+  async fn get_summary(
+    &self,
+    user_id: i64,
+  ) -> AppResult<ReferralSummary>;
+  async fn get_referrals(
+    &self,
+    filter: ReferralFilter,
+  ) -> AppResult<Vec<Referral>>;
+  async fn get_report(
+    &self,
+    filter: ReferralReportFilter,
+  ) -> AppResult<Vec<ReferralReportRow>>;
+}
@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::review::{
+  CreateReviewRequest, ModerateReviewRequest, ReviewContext, ReviewDetail, ReviewFilter,
+  ReviewPhoto,
+};
+
+#[async_trait]
+pub trait ReviewRepository: Send + Sync {
+  async fn get_context(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<ReviewContext>;
+  // Tạo đánh giá cùng đánh giá kỹ thuật viên, báo lỗi nếu lịch hẹn đã được đánh giá
+  async fn create_review(
+    &self,
+    customer_id: i64,
+    request: CreateReviewRequest,
+  ) -> AppResult<ReviewDetail>;
+  async fn get_review(
+    &self,
+    id: i64,
+  ) -> AppResult<ReviewDetail>;
+  async fn get_appointment_review(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<Option<ReviewDetail>>;
+  async fn get_reviews(
+    &self,
+    filter: ReviewFilter,
+  ) -> AppResult<Vec<ReviewDetail>>;
+  async fn moderate(
+    &self,
+    id: i64,
+    staff_id: i64,
+    request: ModerateReviewRequest,
+  ) -> AppResult<ReviewDetail>;
+  async fn reply(
+    &self,
+    id: i64,
+    staff_id: i64,
+    reply: String,
+  ) -> AppResult<ReviewDetail>;
+  async fn add_photo(
+    &self,
+    review_id: i64,
+    image: String,
+  ) -> AppResult<ReviewPhoto>;
+  async fn delete_photo(
+    &self,
+    review_id: i64,
+    photo_id: i64,
+  ) -> AppResult<ReviewPhoto>;
+}
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::shift::{
+  CloseShiftRequest, OpenShiftRequest, Shift, ShiftFilter, ShiftSummary, ShiftVarianceReport,
+};
+
+#[async_trait]
+pub trait ShiftRepository: Send + Sync {
+  async fn open_shift(
+    &self,
+    receptionist_id: i64,
+    request: OpenShiftRequest,
+  ) -> AppResult<Shift>;
+  async fn get_open_shift(
+    &self,
+    receptionist_id: i64,
+  ) -> AppResult<Option<Shift>>;
+  // Ca đang mở được tính trực tiếp, ca đã đóng lấy số liệu đã chốt
+  async fn get_shift_summary(
+    &self,
+    shift_id: i64,
+  ) -> AppResult<ShiftSummary>;
+  async fn close_shift(
+    &self,
+    shift_id: i64,
+    closed_by: i64,
+    request: CloseShiftRequest,
+  ) -> AppResult<ShiftSummary>;
+  async fn get_shifts(
+    &self,
+    filter: ShiftFilter,
+  ) -> AppResult<Vec<Shift>>;
+  async fn get_variance_report(
+    &self,
+    filter: ShiftFilter,
+  ) -> AppResult<ShiftVarianceReport>;
+}
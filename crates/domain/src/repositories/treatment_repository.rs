@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::treatment::{
+  CreateTreatmentFieldRequest, CreateTreatmentRecordRequest, TreatmentContext, TreatmentField,
+  TreatmentPhoto, TreatmentRecord, TreatmentRecordDetail, TreatmentRecordFilter,
+  TreatmentRecordHistory, UpdateTreatmentFieldRequest, UpdateTreatmentRecordRequest,
+};
+
+#[async_trait]
+pub trait TreatmentRepository: Send + Sync {
+  // service_id Some: trường của nhóm đó và trường dùng chung; None: toàn bộ
+  async fn get_fields(
+    &self,
+    service_id: Option<i64>,
+    include_inactive: bool,
+  ) -> AppResult<Vec<TreatmentField>>;
+  async fn create_field(
+    &self,
+    request: CreateTreatmentFieldRequest,
+    created_by: i64,
+  ) -> AppResult<TreatmentField>;
+  async fn update_field(
+    &self,
+    id: i64,
+    request: UpdateTreatmentFieldRequest,
+  ) -> AppResult<TreatmentField>;
+  async fn get_context(
+    &self,
+    appointment_id: Option<i64>,
+    appointment_service_id: Option<i64>,
+  ) -> AppResult<TreatmentContext>;
+  async fn is_customer(
+    &self,
+    customer_id: i64,
+  ) -> AppResult<bool>;
+  // Tạo hồ sơ và ghi phiên bản đầu tiên vào lịch sử
+  async fn create_record(
+    &self,
+    customer_id: i64,
+    technician_id: i64,
+    request: CreateTreatmentRecordRequest,
+  ) -> AppResult<TreatmentRecordDetail>;
+  async fn get_record(
+    &self,
+    id: i64,
+  ) -> AppResult<TreatmentRecordDetail>;
+  async fn get_records(
+    &self,
+    filter: TreatmentRecordFilter,
+  ) -> AppResult<Vec<TreatmentRecord>>;
+  async fn get_customer_records(
+    &self,
+    customer_id: i64,
+  ) -> AppResult<Vec<TreatmentRecordDetail>>;
+  // Tăng version và ghi phiên bản mới vào lịch sử
+  async fn update_record(
+    &self,
+    id: i64,
+    updated_by: i64,
+    request: UpdateTreatmentRecordRequest,
+  ) -> AppResult<TreatmentRecordDetail>;
+  async fn get_record_history(
+    &self,
+    id: i64,
+  ) -> AppResult<Vec<TreatmentRecordHistory>>;
+  async fn add_photo(
+    &self,
+    record_id: i64,
+    photo_type: String,
+    image: String,
+    caption: Option<String>,
+    uploaded_by: i64,
+  ) -> AppResult<TreatmentPhoto>;
+  async fn delete_photo(
+    &self,
+    record_id: i64,
+    photo_id: i64,
+  ) -> AppResult<TreatmentPhoto>;
+}
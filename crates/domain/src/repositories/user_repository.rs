@@ -1,6 +1,6 @@
 use crate::entities::user::{
-  PhoneFilterConvert, RequestCreateUser, RequestUpdateUser, User, UserFilter, UserFilterConvert,
-  UserWithPassword,
+  PhoneFilterConvert, RequestCreateUser, RequestUpdateUser, TechnicianWithRating, User, UserFilter,
+  UserFilterConvert, UserWithPassword,
 };
 use async_trait::async_trait;
 use core_app::{AppResult, errors::AppError};
@@ -131,7 +131,7 @@ pub trait UserRepository: Send + Sync {
   async fn get_all_technician(
     &self,
     user: UserWithPassword,
-  ) -> AppResult<Vec<User>>;
+  ) -> AppResult<Vec<TechnicianWithRating>>;
 
   async fn create(
     &self,
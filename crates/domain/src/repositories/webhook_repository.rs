@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::webhook::{
+  CreateWebhookSubscriptionRequest, UpdateWebhookSubscriptionRequest, WebhookDelivery,
+  WebhookDeliveryFilter, WebhookSubscription,
+};
+
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+  async fn list(&self) -> AppResult<Vec<WebhookSubscription>>;
+  async fn get_by_id(
+    &self,
+    id: i64,
+  ) -> AppResult<WebhookSubscription>;
+  async fn create(
+    &self,
+    payload: CreateWebhookSubscriptionRequest,
+    created_by: i64,
+  ) -> AppResult<WebhookSubscription>;
+  async fn update(
+    &self,
+    id: i64,
+    payload: UpdateWebhookSubscriptionRequest,
+  ) -> AppResult<WebhookSubscription>;
+  async fn delete(
+    &self,
+    id: i64,
+  ) -> AppResult<()>;
+  async fn list_deliveries(
+    &self,
+    subscription_id: i64,
+    filter: WebhookDeliveryFilter,
+  ) -> AppResult<Vec<WebhookDelivery>>;
+  async fn redeliver(
+    &self,
+    delivery_id: i64,
+  ) -> AppResult<WebhookDelivery>;
+  /// Host của URL phải phân giải ra địa chỉ công khai
+  async fn check_public_url(
+    &self,
+    url: &str,
+  ) -> AppResult<()>;
+}
@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use core_app::AppResult;
+
+use crate::entities::zalo::{
+  UpdateZaloEventTemplateRequest, ZaloEventTemplate, ZaloMessage, ZaloMessageFilter,
+  ZaloWebhookEvent,
+};
+
+#[async_trait]
+pub trait ZaloRepository: Send + Sync {
+  async fn list_templates(&self) -> AppResult<Vec<ZaloEventTemplate>>;
+  async fn update_template(
+    &self,
+    event_type: &str,
+    payload: UpdateZaloEventTemplateRequest,
+  ) -> AppResult<ZaloEventTemplate>;
+  async fn list_messages(
+    &self,
+    filter: ZaloMessageFilter,
+  ) -> AppResult<Vec<ZaloMessage>>;
+  async fn record_callback(
+    &self,
+    event: ZaloWebhookEvent,
+    payload: serde_json::Value,
+  ) -> AppResult<()>;
+}
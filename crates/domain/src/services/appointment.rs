@@ -5,14 +5,19 @@ use modql::filter::{ListOptions, OpValsString};
 use crate::{
   entities::{
     appointment::{
-      AppointmentExtra, AppointmentFilter, AppointmentWithServices,
+      AppointmentExtra, AppointmentFilter, AppointmentServiceRequest, AppointmentWithServices,
       CreateAppointmentForNewCustomerRequest, CreateAppointmentRequest, PaymentAppointmentRequest,
-      Status, UpdateAppointmentRequest,
+      Status, TENDER_TYPES, UpdateAppointmentRequest, UpdateAppointmentServiceRequest,
     },
     common::PaginationMetadata,
+    referral::{NewReferral, ReferralCandidate},
     user::{PhoneFilterConvert, RequestCreateUser, Role, UserWithPassword},
   },
-  repositories::{appointment_repository::AppointmentRepository, user_repository::UserRepository},
+  repositories::{
+    appointment_repository::AppointmentRepository, referral_repository::ReferralRepository,
+    user_repository::UserRepository,
+  },
+  services::referral::normalize_referral_code,
 };
 
 fn validate_appointment_time(start_time_str: &str) -> Result<(), AppError> {
@@ -38,6 +43,24 @@ fn validate_appointment_time(start_time_str: &str) -> Result<(), AppError> {
 
   Ok(())
 }
+
+fn validate_services(services: &[AppointmentServiceRequest]) -> Result<(), AppError> {
+  for service in services {
+    if service.quantity.is_some_and(|quantity| quantity <= 0) {
+      return Err(AppError::BadRequest("Quantity must be greater than 0".to_string()));
+    }
+    if service.discount.is_some_and(|discount| discount < 0) {
+      return Err(AppError::BadRequest("Discount cannot be negative".to_string()));
+    }
+
+    if let Some(start_time) = service.start_time.as_deref() {
+      validate_appointment_time(start_time)?;
+    }
+  }
+
+  Ok(())
+}
+
 pub struct AppointmentUseCase;
 
 impl AppointmentUseCase {
@@ -59,10 +82,11 @@ impl AppointmentUseCase {
     }
 
     validate_appointment_time(&appointment.start_time)?;
+    validate_services(&appointment.services)?;
 
     // Create appointment
     let created_appointment =
-      appointment_repo.create_appointment(user.clone(), appointment, user.role).await?;
+      appointment_repo.create_appointment(user.clone(), appointment, user.role, None).await?;
 
     Ok(created_appointment)
   }
@@ -95,6 +119,10 @@ impl AppointmentUseCase {
       validate_appointment_time(&appointment.start_time.as_ref().unwrap())?;
     }
 
+    if let Some(services) = appointment.services.as_ref() {
+      validate_services(services)?;
+    }
+
     // Update appointment
     let updated_appointment =
       appointment_repo.update_appointment(user.clone(), id, appointment.clone()).await?;
@@ -102,6 +130,30 @@ impl AppointmentUseCase {
     Ok(updated_appointment)
   }
 
+  pub async fn update_appointment_service(
+    appointment_repo: &dyn AppointmentRepository,
+    user: UserWithPassword,
+    appointment_id: i64,
+    id: i64,
+    mut payload: UpdateAppointmentServiceRequest,
+  ) -> AppResult<AppointmentWithServices> {
+    if payload.status.as_deref() == Some("") {
+      payload.status = None;
+    }
+
+    if let Some(status) = payload.status.as_deref() {
+      if !["PENDING", "IN_PROGRESS", "COMPLETED", "CANCELLED"].contains(&status) {
+        return Err(AppError::BadRequest("Invalid status".to_string()));
+      }
+    }
+
+    if let Some(start_time) = payload.start_time.as_deref() {
+      validate_appointment_time(start_time)?;
+    }
+
+    appointment_repo.update_appointment_service(user, appointment_id, id, payload).await
+  }
+
   pub async fn get_appointments(
     appointment_repo: &dyn AppointmentRepository,
     user: UserWithPassword,
@@ -155,17 +207,63 @@ impl AppointmentUseCase {
     appointment_repo: &dyn AppointmentRepository,
     user: UserWithPassword,
     id: i64,
-    payload: PaymentAppointmentRequest,
+    mut payload: PaymentAppointmentRequest,
   ) -> AppResult<AppointmentWithServices> {
+    if let Some(tenders) = payload.tenders.as_mut() {
+      if tenders.is_empty() {
+        return Err(AppError::BadRequest("Tenders must not be empty".to_string()));
+      }
+
+      if payload.user_balance > 0 {
+        return Err(AppError::BadRequest(
+          "Use a WALLET tender instead of user_balance when paying with tenders".to_string(),
+        ));
+      }
+
+      for tender in tenders.iter_mut() {
+        tender.tender_type = tender.tender_type.trim().to_uppercase();
+        if !TENDER_TYPES.contains(&tender.tender_type.as_str()) {
+          return Err(AppError::BadRequest(format!("Invalid tender type: {}", tender.tender_type)));
+        }
+
+        if tender.amount <= 0 {
+          return Err(AppError::BadRequest("Tender amount must be greater than 0".to_string()));
+        }
+      }
+    }
+
+    if payload.tip_amount.is_some() && payload.tip_percent.is_some() {
+      return Err(AppError::BadRequest(
+        "Only one of tip_amount or tip_percent is allowed".to_string(),
+      ));
+    }
+
+    if payload.tip_amount.is_some_and(|amount| amount < 0) {
+      return Err(AppError::BadRequest("Tip amount must not be negative".to_string()));
+    }
+
+    if payload.tip_percent.is_some_and(|percent| !(0..=100).contains(&percent)) {
+      return Err(AppError::BadRequest("Tip percent must be between 0 and 100".to_string()));
+    }
+
+    if let Some(method) = payload.tip_payment_method.as_deref() {
+      if method != "WALLET" && method != "CASH" {
+        return Err(AppError::BadRequest("Invalid tip payment method".to_string()));
+      }
+    }
+
     appointment_repo.payment_appointment(user, id, payload).await
   }
 
   pub async fn create_appointment_for_new_customer(
     appointment_repo: &dyn AppointmentRepository,
     user_repo: &dyn UserRepository,
+    referral_repo: &dyn ReferralRepository,
     user: UserWithPassword,
     payload: CreateAppointmentForNewCustomerRequest,
   ) -> AppResult<AppointmentWithServices> {
+    validate_services(&payload.services)?;
+
     let user_payload = RequestCreateUser {
       user_name: None,
       password_hash: None,
@@ -203,10 +301,27 @@ impl AppointmentUseCase {
         membership_level: "BRONZE".to_string(),
         balance: 0,
         loyalty_points: 0,
+        technician_level: None,
+        preferred_language: "vi".to_string(),
+        is_email_verified: false,
       });
 
     tracing::info!("exist_user: {:#?}", exist_user);
 
+    // Kiểm tra mã giới thiệu trước khi tạo khách và lịch hẹn
+    let referral = match normalize_referral_code(payload.referral_code) {
+      Some(referral_code) => {
+        let candidate = ReferralCandidate {
+          user_id: (exist_user.pk_user_id > 0).then_some(exist_user.pk_user_id),
+          phone: payload.phone.clone(),
+          device_token: None,
+        };
+        let referrer_id = referral_repo.check_referral(&referral_code, &candidate).await?;
+        Some((referrer_id, referral_code))
+      },
+      None => None,
+    };
+
     let new_user;
 
     if exist_user.pk_user_id > 0 {
@@ -228,8 +343,17 @@ impl AppointmentUseCase {
       promotion: payload.promotion,
       price: None,
     };
-    let created_appointment =
-      appointment_repo.create_appointment(new_user, appointment_payload, user.role).await?;
+    let referral = referral.map(|(referrer_id, referral_code)| NewReferral {
+      referrer_id,
+      referred_id: new_user.pk_user_id,
+      referral_code,
+      source: "RECEPTION".to_string(),
+      device_token: None,
+      created_by: Some(user.pk_user_id),
+    });
+    let created_appointment = appointment_repo
+      .create_appointment(new_user, appointment_payload, user.role, referral)
+      .await?;
 
     Ok(created_appointment)
   }
@@ -0,0 +1,220 @@
+use chrono::Utc;
+use core_app::{AppResult, errors::AppError};
+
+use crate::{
+  entities::{
+    campaign::{
+      CAMPAIGN_STATUSES, Campaign, CampaignAudience, CampaignAudiencePreview, CampaignDetail,
+      CampaignFilter, CampaignMessage, CreateCampaignRequest, ScheduleCampaignRequest,
+      UpdateCampaignRequest,
+    },
+    notification_template::{DEFAULT_LOCALE, NOTIFICATION_LOCALES},
+    user::MEMBERSHIP_LEVELS,
+  },
+  repositories::campaign_repository::CampaignRepository,
+};
+
+// Trạng thái còn được sửa / lên lịch, và trạng thái còn huỷ được
+const EDITABLE_STATUSES: [&str; 2] = ["DRAFT", "SCHEDULED"];
+const CANCELLABLE_STATUSES: [&str; 3] = ["DRAFT", "SCHEDULED", "SENDING"];
+
+pub struct CampaignUseCase;
+
+impl CampaignUseCase {
+  pub async fn list(
+    repo: &dyn CampaignRepository,
+    mut filter: CampaignFilter,
+  ) -> AppResult<Vec<Campaign>> {
+    if let Some(status) = filter.status.as_mut() {
+      *status = status.trim().to_uppercase();
+      if !CAMPAIGN_STATUSES.contains(&status.as_str()) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid status, expected one of {}",
+          CAMPAIGN_STATUSES.join(", ")
+        )));
+      }
+    }
+    repo.list(filter).await
+  }
+
+  pub async fn get_by_id(
+    repo: &dyn CampaignRepository,
+    id: i64,
+  ) -> AppResult<CampaignDetail> {
+    let campaign = repo.get_by_id(id).await?;
+    let stats = repo.get_stats(id).await?;
+    Ok(CampaignDetail { campaign, stats })
+  }
+
+  pub async fn create(
+    repo: &dyn CampaignRepository,
+    created_by: i64,
+    mut payload: CreateCampaignRequest,
+  ) -> AppResult<Campaign> {
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+      return Err(AppError::BadRequest("Campaign name is required".to_string()));
+    }
+    validate_audience(&mut payload.audience)?;
+    validate_messages(&mut payload.messages)?;
+    payload.deep_link = normalize_deep_link(payload.deep_link);
+
+    repo.create(created_by, payload).await
+  }
+
+  pub async fn update(
+    repo: &dyn CampaignRepository,
+    id: i64,
+    mut payload: UpdateCampaignRequest,
+  ) -> AppResult<Campaign> {
+    let campaign = repo.get_by_id(id).await?;
+    ensure_editable(&campaign)?;
+
+    if let Some(name) = payload.name.as_mut() {
+      *name = name.trim().to_string();
+      if name.is_empty() {
+        return Err(AppError::BadRequest("Campaign name is required".to_string()));
+      }
+    }
+    if let Some(audience) = payload.audience.as_mut() {
+      validate_audience(audience)?;
+    }
+    if let Some(messages) = payload.messages.as_mut() {
+      validate_messages(messages)?;
+    }
+    payload.deep_link = normalize_deep_link(payload.deep_link);
+
+    repo.update(id, payload).await
+  }
+
+  // Người nhận được chốt khi đến giờ gửi nên khách phát sinh sau khi lên lịch vẫn được tính
+  pub async fn schedule(
+    repo: &dyn CampaignRepository,
+    id: i64,
+    payload: ScheduleCampaignRequest,
+  ) -> AppResult<Campaign> {
+    let campaign = repo.get_by_id(id).await?;
+    ensure_editable(&campaign)?;
+
+    let scheduled_at = payload.scheduled_at.unwrap_or_else(Utc::now);
+    repo.update_status(id, &EDITABLE_STATUSES, "SCHEDULED", Some(scheduled_at)).await
+  }
+
+  // Huỷ khi đang gửi chỉ dừng các lượt chưa vào hàng đợi, thông báo đã xếp hàng vẫn được gửi
+  pub async fn cancel(
+    repo: &dyn CampaignRepository,
+    id: i64,
+  ) -> AppResult<Campaign> {
+    let campaign = repo.get_by_id(id).await?;
+    if !CANCELLABLE_STATUSES.contains(&campaign.status.as_str()) {
+      return Err(AppError::BadRequest(format!("Campaign is already {}", campaign.status)));
+    }
+    repo.update_status(id, &CANCELLABLE_STATUSES, "CANCELLED", campaign.scheduled_at).await
+  }
+
+  pub async fn preview_audience(
+    repo: &dyn CampaignRepository,
+    mut audience: CampaignAudience,
+  ) -> AppResult<CampaignAudiencePreview> {
+    validate_audience(&mut audience)?;
+    let total = repo.count_audience(audience).await?;
+    Ok(CampaignAudiencePreview { total })
+  }
+
+  pub async fn mark_opened(
+    repo: &dyn CampaignRepository,
+    id: i64,
+    user_id: i64,
+  ) -> AppResult<()> {
+    repo.mark_opened(id, user_id).await
+  }
+}
+
+fn ensure_editable(campaign: &Campaign) -> AppResult<()> {
+  if !EDITABLE_STATUSES.contains(&campaign.status.as_str()) {
+    return Err(AppError::BadRequest(format!(
+      "Campaign is {} and can no longer be changed",
+      campaign.status
+    )));
+  }
+  Ok(())
+}
+
+fn validate_audience(audience: &mut CampaignAudience) -> AppResult<()> {
+  if let Some(levels) = audience.membership_levels.as_mut() {
+    for level in levels.iter_mut() {
+      *level = level.trim().to_uppercase();
+      if !MEMBERSHIP_LEVELS.contains(&level.as_str()) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid membership level, expected one of {}",
+          MEMBERSHIP_LEVELS.join(", ")
+        )));
+      }
+    }
+  }
+
+  for days in [audience.visited_within_days, audience.not_visited_within_days].into_iter().flatten()
+  {
+    if days <= 0 {
+      return Err(AppError::BadRequest("Visit days must be greater than 0".to_string()));
+    }
+  }
+
+  for amount in [audience.min_total_spent, audience.max_total_spent].into_iter().flatten() {
+    if amount < 0 {
+      return Err(AppError::BadRequest("Total spent must not be negative".to_string()));
+    }
+  }
+  if let (Some(min), Some(max)) = (audience.min_total_spent, audience.max_total_spent) {
+    if min > max {
+      return Err(AppError::BadRequest(
+        "min_total_spent must not be greater than max_total_spent".to_string(),
+      ));
+    }
+  }
+
+  if let Some(month) = audience.birthday_month {
+    if !(1..=12).contains(&month) {
+      return Err(AppError::BadRequest("Birthday month must be between 1 and 12".to_string()));
+    }
+  }
+
+  Ok(())
+}
+
+fn validate_messages(messages: &mut [CampaignMessage]) -> AppResult<()> {
+  for message in messages.iter_mut() {
+    message.locale = message.locale.trim().to_lowercase();
+    message.title = message.title.trim().to_string();
+    message.body = message.body.trim().to_string();
+
+    if !NOTIFICATION_LOCALES.contains(&message.locale.as_str()) {
+      return Err(AppError::BadRequest(format!(
+        "Invalid locale, expected one of {}",
+        NOTIFICATION_LOCALES.join(", ")
+      )));
+    }
+    if message.title.is_empty() || message.body.is_empty() {
+      return Err(AppError::BadRequest("Message title and body are required".to_string()));
+    }
+  }
+
+  for (index, message) in messages.iter().enumerate() {
+    if messages[..index].iter().any(|m| m.locale == message.locale) {
+      return Err(AppError::BadRequest(format!("Duplicate message for locale {}", message.locale)));
+    }
+  }
+
+  if !messages.iter().any(|m| m.locale == DEFAULT_LOCALE) {
+    return Err(AppError::BadRequest(format!(
+      "A message in the default locale ({}) is required",
+      DEFAULT_LOCALE
+    )));
+  }
+
+  Ok(())
+}
+
+fn normalize_deep_link(deep_link: Option<String>) -> Option<String> {
+  deep_link.map(|link| link.trim().to_string()).filter(|link| !link.is_empty())
+}
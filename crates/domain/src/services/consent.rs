@@ -0,0 +1,290 @@
+use std::{collections::HashSet, sync::Arc};
+
+use chrono::NaiveDate;
+use core_app::{AppResult, configs::SpaConfig, errors::AppError};
+use serde_json::{Map, Value};
+
+use crate::{
+  entities::{
+    consent::{
+      AppointmentConsentForm, CONSENT_QUESTION_TYPES, ConsentQuestion, ConsentSubmission,
+      ConsentSubmissionFilter, ConsentTemplate, CreateConsentSubmissionRequest,
+      CreateConsentTemplateRequest, NewConsentSubmission, SignatureCapture,
+      UpdateConsentTemplateRequest,
+    },
+    user::UserWithPassword,
+  },
+  repositories::{consent_repository::ConsentRepository, image_repository::ImageRepository},
+};
+
+fn is_staff(user: &UserWithPassword) -> bool {
+  matches!(user.role.as_str(), "ADMIN" | "RECEPTIONIST" | "TECHNICIAN")
+}
+
+fn validate_questions(questions: &mut [ConsentQuestion]) -> Result<(), AppError> {
+  if questions.is_empty() {
+    return Err(AppError::BadRequest("Template must have at least one question".to_string()));
+  }
+
+  let mut keys = HashSet::new();
+  for question in questions.iter_mut() {
+    question.key = question.key.trim().to_lowercase();
+    question.label = question.label.trim().to_string();
+    question.question_type = question.question_type.trim().to_uppercase();
+
+    if question.key.is_empty()
+      || !question.key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+      return Err(AppError::BadRequest(
+        "Question key must contain only letters, digits and underscores".to_string(),
+      ));
+    }
+
+    if !keys.insert(question.key.clone()) {
+      return Err(AppError::BadRequest(format!("Duplicate question key {}", question.key)));
+    }
+
+    if question.label.is_empty() {
+      return Err(AppError::BadRequest(format!("Question {} has no label", question.key)));
+    }
+
+    if !CONSENT_QUESTION_TYPES.contains(&question.question_type.as_str()) {
+      return Err(AppError::BadRequest(format!(
+        "Invalid question type, expected one of {}",
+        CONSENT_QUESTION_TYPES.join(", ")
+      )));
+    }
+
+    if question.question_type == "SELECT"
+      && question.options.as_ref().is_none_or(|options| options.is_empty())
+    {
+      return Err(AppError::BadRequest(format!("Question {} requires options", question.key)));
+    }
+  }
+
+  Ok(())
+}
+
+// Chỉ giữ câu trả lời của các câu hỏi trong mẫu
+fn validate_answers(
+  questions: &[ConsentQuestion],
+  answers: &str,
+) -> Result<Value, AppError> {
+  let answers: Map<String, Value> = serde_json::from_str(answers)
+    .map_err(|_| AppError::BadRequest("answers must be a JSON object".to_string()))?;
+
+  if let Some(key) = answers.keys().find(|key| !questions.iter().any(|q| &q.key == *key)) {
+    return Err(AppError::BadRequest(format!("Unknown question {}", key)));
+  }
+
+  let mut result = Map::new();
+  for question in questions {
+    let value = answers.get(&question.key).filter(|value| !value.is_null());
+
+    let Some(value) = value else {
+      if question.required {
+        return Err(AppError::BadRequest(format!("{} is required", question.label)));
+      }
+      continue;
+    };
+
+    let valid = match question.question_type.as_str() {
+      "TEXT" => value.as_str().is_some_and(|text| !question.required || !text.trim().is_empty()),
+      "NUMBER" => value.is_number(),
+      "BOOLEAN" => value.as_bool().is_some_and(|checked| !question.required || checked),
+      "SELECT" => value.as_str().is_some_and(|value| {
+        question.options.as_ref().is_some_and(|options| options.iter().any(|o| o == value))
+      }),
+      "DATE" => {
+        value.as_str().is_some_and(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok())
+      },
+      _ => false,
+    };
+
+    if !valid {
+      return Err(AppError::BadRequest(format!("Invalid answer for {}", question.label)));
+    }
+
+    result.insert(question.key.clone(), value.clone());
+  }
+
+  Ok(Value::Object(result))
+}
+
+pub struct ConsentUseCase;
+
+impl ConsentUseCase {
+  pub async fn get_templates(
+    repo: &dyn ConsentRepository,
+    service_id: Option<i64>,
+  ) -> AppResult<Vec<ConsentTemplate>> {
+    repo.get_templates(service_id, false).await
+  }
+
+  pub async fn get_template(
+    repo: &dyn ConsentRepository,
+    id: i64,
+  ) -> AppResult<ConsentTemplate> {
+    repo.get_template(id).await
+  }
+
+  pub async fn create_template(
+    repo: &dyn ConsentRepository,
+    mut payload: CreateConsentTemplateRequest,
+    created_by: i64,
+  ) -> AppResult<ConsentTemplate> {
+    payload.title = payload.title.trim().to_string();
+    if payload.title.is_empty() {
+      return Err(AppError::BadRequest("Title is required".to_string()));
+    }
+
+    validate_questions(&mut payload.questions)?;
+
+    repo.create_template(payload, created_by).await
+  }
+
+  pub async fn update_template(
+    repo: &dyn ConsentRepository,
+    id: i64,
+    mut payload: UpdateConsentTemplateRequest,
+  ) -> AppResult<ConsentTemplate> {
+    payload.title =
+      payload.title.map(|title| title.trim().to_string()).filter(|title| !title.is_empty());
+
+    if let Some(questions) = payload.questions.as_mut() {
+      validate_questions(questions)?;
+    }
+
+    repo.update_template(id, payload).await
+  }
+
+  // Khách hàng chỉ xem được lịch hẹn của mình
+  pub async fn get_appointment_forms(
+    repo: &dyn ConsentRepository,
+    user: UserWithPassword,
+    appointment_id: i64,
+  ) -> AppResult<Vec<AppointmentConsentForm>> {
+    let customer_id = repo.get_appointment_customer(appointment_id).await?;
+
+    if !is_staff(&user) && customer_id != Some(user.pk_user_id) {
+      return Err(AppError::NotFound);
+    }
+
+    repo.get_appointment_forms(appointment_id).await
+  }
+
+  pub async fn get_pending_forms(
+    repo: &dyn ConsentRepository,
+    user: UserWithPassword,
+  ) -> AppResult<Vec<AppointmentConsentForm>> {
+    repo.get_pending_forms(user.pk_user_id).await
+  }
+
+  pub async fn submit(
+    repo: &dyn ConsentRepository,
+    image_service: Arc<dyn ImageRepository>,
+    spa: &SpaConfig,
+    user: UserWithPassword,
+    payload: CreateConsentSubmissionRequest,
+    signature: SignatureCapture,
+  ) -> AppResult<ConsentSubmission> {
+    if repo.get_appointment_customer(payload.appointment_id).await? != Some(user.pk_user_id) {
+      return Err(AppError::NotFound);
+    }
+
+    let form = repo
+      .get_appointment_forms(payload.appointment_id)
+      .await?
+      .into_iter()
+      .find(|form| form.template.id == payload.template_id)
+      .ok_or_else(|| {
+        AppError::BadRequest("This form is not required for the appointment".to_string())
+      })?;
+
+    if form.submission_id.is_some() {
+      return Err(AppError::BadRequest("You have already signed this form".to_string()));
+    }
+
+    let answers = validate_answers(&form.template.questions, &payload.answers)?;
+
+    if signature.data.is_empty() {
+      return Err(AppError::BadRequest("Signature is required".to_string()));
+    }
+
+    const MAX_FILE_SIZE: usize = 2 * 1024 * 1024; // 2MB
+    const MAX_WIDTH: u32 = 800;
+    const QUALITY: u8 = 90;
+
+    let signature_image = image_service
+      .upload_and_resize(
+        &signature.data,
+        &signature.content_type,
+        user.pk_user_id,
+        MAX_FILE_SIZE,
+        MAX_WIDTH,
+        QUALITY,
+        "signatures",
+      )
+      .await?;
+
+    let submission = NewConsentSubmission {
+      template: form.template,
+      customer_id: user.pk_user_id,
+      appointment_id: payload.appointment_id,
+      answers,
+      signature_image: signature_image.clone(),
+      ip_address: signature.ip_address,
+      user_agent: signature.user_agent,
+    };
+
+    match repo.create_submission(submission, &signature.data, spa).await {
+      Ok(submission) => Ok(submission),
+      Err(err) => {
+        let _ = image_service.remove_old_image(&signature_image).await;
+        Err(err)
+      },
+    }
+  }
+
+  pub async fn get_submission(
+    repo: &dyn ConsentRepository,
+    user: UserWithPassword,
+    id: i64,
+  ) -> AppResult<ConsentSubmission> {
+    let submission = repo.get_submission(id).await?;
+
+    if !is_staff(&user) && submission.customer_id != user.pk_user_id {
+      return Err(AppError::NotFound);
+    }
+
+    Ok(submission)
+  }
+
+  pub async fn get_submission_pdf(
+    repo: &dyn ConsentRepository,
+    user: UserWithPassword,
+    id: i64,
+  ) -> AppResult<Vec<u8>> {
+    Self::get_submission(repo, user, id).await?;
+
+    repo.get_submission_pdf(id).await
+  }
+
+  pub async fn get_signature_image(
+    repo: &dyn ConsentRepository,
+    image_service: Arc<dyn ImageRepository>,
+    user: UserWithPassword,
+    id: i64,
+  ) -> AppResult<(Vec<u8>, String)> {
+    let submission = Self::get_submission(repo, user, id).await?;
+
+    image_service.read_private_image(&submission.signature_image).await
+  }
+
+  pub async fn get_submissions(
+    repo: &dyn ConsentRepository,
+    filter: ConsentSubmissionFilter,
+  ) -> AppResult<Vec<ConsentSubmission>> {
+    repo.get_submissions(filter).await
+  }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use core_app::{AppResult, configs::WithdrawalConfig, errors::AppError};
+
+use crate::{
+  entities::deposit::{
+    BankAccount, CreateTopUpRequest, CreateWithdrawalRequest, Deposit, UpdateDepositStatusRequest,
+    UpsertBankAccountRequest,
+  },
+  repositories::{deposit_repository::DepositRepository, image_repository::ImageRepository},
+};
+
+pub struct DepositUseCase;
+
+impl DepositUseCase {
+  // Khách hàng tự tạo yêu cầu nạp tiền kèm ảnh chuyển khoản, số dư chỉ cộng khi nhân viên duyệt
+  pub async fn create_top_up(
+    repo: &dyn DepositRepository,
+    image_service: Arc<dyn ImageRepository>,
+    user_id: i64,
+    data: &[u8],
+    content_type: &str,
+    mut payload: CreateTopUpRequest,
+  ) -> AppResult<Deposit> {
+    if payload.amount <= 0 {
+      return Err(AppError::BadRequest("Amount must be greater than 0".to_string()));
+    }
+
+    if data.is_empty() {
+      return Err(AppError::BadRequest("Proof of transfer image is required".to_string()));
+    }
+
+    const MAX_FILE_SIZE: usize = 5 * 1024 * 1024; // 5MB
+    const MAX_WIDTH: u32 = 1200; // Giữ đủ nét để đọc nội dung chuyển khoản
+    const QUALITY: u8 = 80;
+
+    let image_path = image_service
+      .upload_and_resize(data, content_type, user_id, MAX_FILE_SIZE, MAX_WIDTH, QUALITY, "deposits")
+      .await?;
+
+    payload.image = Some(image_path.clone());
+
+    match repo.create_top_up(user_id, payload).await {
+      Ok(deposit) => Ok(deposit),
+      Err(err) => {
+        let _ = image_service.remove_old_image(&image_path).await;
+        Err(err)
+      },
+    }
+  }
+
+  pub async fn create_withdrawal(
+    repo: &dyn DepositRepository,
+    config: &WithdrawalConfig,
+    user_id: i64,
+    payload: CreateWithdrawalRequest,
+  ) -> AppResult<Deposit> {
+    if payload.amount < config.min_amount || payload.amount > config.max_amount {
+      return Err(AppError::BadRequest(format!(
+        "Withdrawal amount must be between {} and {}",
+        config.min_amount, config.max_amount
+      )));
+    }
+
+    repo.create_withdrawal(user_id, payload, config.daily_limit).await
+  }
+
+  pub async fn get_bank_account(
+    repo: &dyn DepositRepository,
+    user_id: i64,
+  ) -> AppResult<BankAccount> {
+    repo.get_bank_account(user_id).await?.ok_or(AppError::NotFound)
+  }
+
+  pub async fn upsert_bank_account(
+    repo: &dyn DepositRepository,
+    user_id: i64,
+    mut payload: UpsertBankAccountRequest,
+  ) -> AppResult<BankAccount> {
+    payload.bank_name = payload.bank_name.trim().to_string();
+    payload.account_name = payload.account_name.trim().to_uppercase();
+    payload.account_number =
+      payload.account_number.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if payload.bank_name.is_empty() || payload.account_name.is_empty() {
+      return Err(AppError::BadRequest("Bank name and account name are required".to_string()));
+    }
+
+    if payload.account_number.is_empty()
+      || payload.account_number.len() > 30
+      || !payload.account_number.chars().all(|c| c.is_ascii_digit())
+    {
+      return Err(AppError::BadRequest("Invalid bank account number".to_string()));
+    }
+
+    repo.upsert_bank_account(user_id, payload).await
+  }
+
+  // Duyệt (COMPLETED) hoặc từ chối (CANCELLED) giao dịch đang PENDING
+  pub async fn update_deposit_status(
+    repo: &dyn DepositRepository,
+    deposit_id: i64,
+    mut payload: UpdateDepositStatusRequest,
+    reviewed_by: i64,
+  ) -> AppResult<Deposit> {
+    let status = payload.status.as_deref().map(|status| status.trim().to_uppercase());
+
+    match status.as_deref() {
+      Some("COMPLETED") => {
+        payload.rejection_reason = None;
+      },
+      Some("CANCELLED") => {
+        let reason = payload
+          .rejection_reason
+          .as_deref()
+          .map(str::trim)
+          .filter(|reason| !reason.is_empty())
+          .ok_or(AppError::BadRequest("Rejection reason is required".to_string()))?;
+        payload.rejection_reason = Some(reason.to_string());
+      },
+      _ => {
+        return Err(AppError::BadRequest("Status must be COMPLETED or CANCELLED".to_string()));
+      },
+    }
+
+    payload.status = status;
+
+    repo.update_deposit_status(deposit_id, payload, reviewed_by).await
+  }
+}
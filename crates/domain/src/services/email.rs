@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use core_app::{AppResult, errors::AppError};
+use regex::Regex;
+
+use crate::{
+  entities::email::{
+    EMAIL_EVENT_TYPES, EMAIL_MESSAGE_STATUSES, EmailMessage, EmailMessageFilter, EmailTemplate,
+    EmailTemplateFilter, UpdateEmailTemplateRequest,
+  },
+  repositories::email_repository::EmailRepository,
+  services::notification_template::{render_template, validate_locale},
+};
+
+pub fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&#39;")
+}
+
+/// Dựng tiêu đề và nội dung HTML từ mẫu, biến trong nội dung được escape để tên khách,
+/// ghi chú... không chèn được HTML vào email
+pub fn render_email(
+  template: &EmailTemplate,
+  variables: &serde_json::Value,
+) -> (String, String) {
+  let escaped = match variables.as_object() {
+    Some(variables) => serde_json::Value::Object(
+      variables
+        .iter()
+        .map(|(key, value)| {
+          let value = match value {
+            serde_json::Value::String(value) => value.clone(),
+            serde_json::Value::Null => String::new(),
+            value => value.to_string(),
+          };
+          (key.clone(), serde_json::Value::String(escape_html(&value)))
+        })
+        .collect(),
+    ),
+    None => variables.clone(),
+  };
+
+  (render_template(&template.subject, variables), render_template(&template.body, &escaped))
+}
+
+/// Kiểm tra địa chỉ email (cùng quy tắc với cập nhật hồ sơ), trả về địa chỉ đã bỏ khoảng trắng
+pub fn validate_email(email: &str) -> AppResult<String> {
+  let email = email.trim();
+  let email_regex =
+    Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").expect("Invalid regex pattern");
+  if email.len() > 150 || !email_regex.is_match(email) {
+    return Err(AppError::BadRequest("Invalid email address format".to_string()));
+  }
+
+  Ok(email.to_string())
+}
+
+// Escape nội dung text theo RFC 5545
+fn escape_ics(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace(';', "\\;")
+    .replace(',', "\\,")
+    .replace("\r\n", "\\n")
+    .replace('\n', "\\n")
+}
+
+// Dòng dài hơn 75 byte phải gập lại, dòng tiếp theo bắt đầu bằng khoảng trắng
+fn fold_ics_line(line: &str) -> String {
+  let mut folded = String::new();
+  let mut width = 0;
+  for ch in line.chars() {
+    if width + ch.len_utf8() > 75 {
+      folded.push_str("\r\n ");
+      width = 1;
+    }
+    folded.push(ch);
+    width += ch.len_utf8();
+  }
+
+  folded
+}
+
+pub struct AppointmentEvent<'a> {
+  pub uid: String,
+  pub start: DateTime<Utc>,
+  pub end: DateTime<Utc>,
+  pub summary: &'a str,
+  pub description: &'a str,
+  pub location: &'a str,
+  pub organizer_name: &'a str,
+  pub organizer_email: &'a str,
+}
+
+/// File lịch .ics (METHOD:REQUEST) để khách thêm lịch hẹn vào Google Calendar, Outlook...
+pub fn appointment_ics(event: &AppointmentEvent) -> String {
+  let format = "%Y%m%dT%H%M%SZ";
+  let lines = [
+    "BEGIN:VCALENDAR".to_string(),
+    "VERSION:2.0".to_string(),
+    "PRODID:-//NaSpa//Appointments//VI".to_string(),
+    "CALSCALE:GREGORIAN".to_string(),
+    "METHOD:REQUEST".to_string(),
+    "BEGIN:VEVENT".to_string(),
+    format!("UID:{}", event.uid),
+    format!("DTSTAMP:{}", Utc::now().format(format)),
+    format!("DTSTART:{}", event.start.format(format)),
+    format!("DTEND:{}", event.end.format(format)),
+    format!("SUMMARY:{}", escape_ics(event.summary)),
+    format!("DESCRIPTION:{}", escape_ics(event.description)),
+    format!("LOCATION:{}", escape_ics(event.location)),
+    format!("ORGANIZER;CN={}:mailto:{}", escape_ics(event.organizer_name), event.organizer_email),
+    "STATUS:CONFIRMED".to_string(),
+    "END:VEVENT".to_string(),
+    "END:VCALENDAR".to_string(),
+  ];
+
+  lines.iter().map(|line| fold_ics_line(line)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+}
+
+pub struct EmailUseCase;
+
+impl EmailUseCase {
+  pub async fn list_templates(
+    repo: &dyn EmailRepository,
+    filter: EmailTemplateFilter,
+  ) -> AppResult<Vec<EmailTemplate>> {
+    if let Some(event_type) = filter.event_type.as_deref() {
+      if !EMAIL_EVENT_TYPES.contains(&event_type) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid event type, expected one of {}",
+          EMAIL_EVENT_TYPES.join(", ")
+        )));
+      }
+    }
+    if let Some(locale) = filter.locale.as_deref() {
+      validate_locale(locale)?;
+    }
+
+    repo.list_templates(filter).await
+  }
+
+  pub async fn update_template(
+    repo: &dyn EmailRepository,
+    id: i64,
+    mut payload: UpdateEmailTemplateRequest,
+  ) -> AppResult<EmailTemplate> {
+    payload.subject = payload.subject.trim().to_string();
+    payload.body = payload.body.trim().to_string();
+
+    if payload.subject.is_empty() || payload.body.is_empty() {
+      return Err(AppError::BadRequest("Subject and body cannot be empty".to_string()));
+    }
+    if payload.subject.len() > 255 {
+      return Err(AppError::BadRequest("Subject cannot exceed 255 characters".to_string()));
+    }
+
+    repo.update_template(id, payload).await
+  }
+
+  pub async fn list_messages(
+    repo: &dyn EmailRepository,
+    filter: EmailMessageFilter,
+  ) -> AppResult<Vec<EmailMessage>> {
+    if let Some(status) = filter.status.as_deref() {
+      if !EMAIL_MESSAGE_STATUSES.contains(&status) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid status, expected one of {}",
+          EMAIL_MESSAGE_STATUSES.join(", ")
+        )));
+      }
+    }
+
+    repo.list_messages(filter).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::TimeZone;
+  use serde_json::json;
+
+  use super::*;
+
+  fn template(
+    subject: &str,
+    body: &str,
+  ) -> EmailTemplate {
+    EmailTemplate {
+      id: 1,
+      event_type: "APPOINTMENT_CONFIRMED".to_string(),
+      locale: "vi".to_string(),
+      subject: subject.to_string(),
+      body: body.to_string(),
+      created_at: Utc::now(),
+      updated_at: Utc::now(),
+    }
+  }
+
+  #[test]
+  fn escapes_html_special_characters() {
+    assert_eq!(
+      escape_html(r#"<a href="x">Tom & 'Jerry'</a>"#),
+      "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+    );
+    // & được thay trước nên thực thể có sẵn không bị escape thiếu
+    assert_eq!(escape_html("&lt;"), "&amp;lt;");
+    assert_eq!(escape_html("Chăm sóc da"), "Chăm sóc da");
+  }
+
+  #[test]
+  fn renders_email_with_escaped_body_variables() {
+    let template =
+      template("Lịch hẹn của {name}", "<p>Xin chào {name}, {count} dịch vụ{missing}</p>");
+    let variables = json!({ "name": "<b>An</b>", "count": 2, "missing": null });
+
+    let (subject, body) = render_email(&template, &variables);
+
+    assert_eq!(subject, "Lịch hẹn của <b>An</b>");
+    assert_eq!(body, "<p>Xin chào &lt;b&gt;An&lt;/b&gt;, 2 dịch vụ</p>");
+  }
+
+  #[test]
+  fn escapes_ics_text() {
+    assert_eq!(escape_ics("a\\b;c,d\r\ne\nf"), "a\\\\b\\;c\\,d\\ne\\nf");
+  }
+
+  #[test]
+  fn folds_ics_lines_at_75_octets() {
+    assert_eq!(fold_ics_line("SUMMARY:Massage"), "SUMMARY:Massage");
+
+    let ascii = format!("DESCRIPTION:{}", "a".repeat(100));
+    let folded = fold_ics_line(&ascii);
+    let lines: Vec<&str> = folded.split("\r\n").collect();
+    assert_eq!(lines[0].len(), 75);
+    assert!(lines[1..].iter().all(|line| line.starts_with(' ')));
+    assert_eq!(folded.replace("\r\n ", ""), ascii);
+
+    // Ký tự tiếng Việt 2-3 byte không bị cắt giữa chừng khi gập dòng
+    let vietnamese = format!("DESCRIPTION:{}", "Chăm sóc da mặt chuyên sâu, đắp mặt nạ ".repeat(5));
+    let folded = fold_ics_line(&vietnamese);
+    assert!(folded.split("\r\n").count() > 1);
+    assert!(folded.split("\r\n").all(|line| line.len() <= 75));
+    assert_eq!(folded.replace("\r\n ", ""), vietnamese);
+  }
+
+  #[test]
+  fn builds_appointment_ics() {
+    let event = AppointmentEvent {
+      uid: "appointment-42@naspa.vn".to_string(),
+      start: Utc.with_ymd_and_hms(2026, 10, 20, 3, 0, 0).unwrap(),
+      end: Utc.with_ymd_and_hms(2026, 10, 20, 4, 30, 0).unwrap(),
+      summary: "Lịch hẹn: Massage; Gội đầu",
+      description: "Dịch vụ:\nMassage toàn thân thư giãn với tinh dầu thiên nhiên, chăm sóc da mặt chuyên sâu",
+      location: "12 Nguyễn Huệ, Quận 1",
+      organizer_name: "NaSpa, Quận 1",
+      organizer_email: "booking@naspa.vn",
+    };
+
+    let ics = appointment_ics(&event);
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(!ics.replace("\r\n", "").contains('\n'));
+    assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains("\r\nUID:appointment-42@naspa.vn\r\n"));
+    assert!(unfolded.contains("\r\nDTSTART:20261020T030000Z\r\n"));
+    assert!(unfolded.contains("\r\nDTEND:20261020T043000Z\r\n"));
+    assert!(unfolded.contains("\r\nSUMMARY:Lịch hẹn: Massage\\; Gội đầu\r\n"));
+    assert!(unfolded.contains(
+      "\r\nDESCRIPTION:Dịch vụ:\\nMassage toàn thân thư giãn với tinh dầu thiên nhiên\\, chăm sóc da mặt chuyên sâu\r\n"
+    ));
+    assert!(unfolded.contains("\r\nLOCATION:12 Nguyễn Huệ\\, Quận 1\r\n"));
+    assert!(unfolded.contains("\r\nORGANIZER;CN=NaSpa\\, Quận 1:mailto:booking@naspa.vn\r\n"));
+  }
+}
@@ -0,0 +1,194 @@
+use core_app::{AppResult, configs::SpaConfig, errors::AppError};
+
+use crate::{
+  entities::invoice::{
+    CreateAdjustmentInvoiceRequest, CreateInvoiceRequest, CreateReplacementInvoiceRequest, Invoice,
+    InvoiceBuyer, InvoiceDetail, InvoiceFilter, InvoiceLineVatRate, VAT_RATES,
+  },
+  repositories::invoice_repository::{InvoiceProviderRepository, InvoiceRepository},
+};
+
+fn validate_vat_rate(vat_rate: i32) -> Result<(), AppError> {
+  if !VAT_RATES.contains(&vat_rate) {
+    return Err(AppError::BadRequest("VAT rate must be one of 0, 5, 8, 10".to_string()));
+  }
+
+  Ok(())
+}
+
+fn validate_vat_rates(
+  vat_rate: Option<i32>,
+  line_vat_rates: Option<&Vec<InvoiceLineVatRate>>,
+) -> Result<(), AppError> {
+  if let Some(vat_rate) = vat_rate {
+    validate_vat_rate(vat_rate)?;
+  }
+
+  for line in line_vat_rates.into_iter().flatten() {
+    validate_vat_rate(line.vat_rate)?;
+  }
+
+  Ok(())
+}
+
+// Chuẩn hoá thông tin người mua; MST 10 số hoặc 13 số dạng 0101234567-001
+fn normalize_buyer(mut buyer: InvoiceBuyer) -> Result<InvoiceBuyer, AppError> {
+  buyer.buyer_tax_code = buyer.buyer_tax_code.trim().to_string();
+  buyer.buyer_company_name = buyer.buyer_company_name.trim().to_string();
+  buyer.buyer_address = buyer.buyer_address.trim().to_string();
+  buyer.buyer_name =
+    buyer.buyer_name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+  buyer.buyer_email =
+    buyer.buyer_email.map(|email| email.trim().to_string()).filter(|email| !email.is_empty());
+
+  let (main, branch) = match buyer.buyer_tax_code.split_once('-') {
+    Some((main, branch)) => (main, Some(branch)),
+    None => (buyer.buyer_tax_code.as_str(), None),
+  };
+  let is_digits =
+    |value: &str, len: usize| value.len() == len && value.chars().all(|c| c.is_ascii_digit());
+  if !is_digits(main, 10) || branch.is_some_and(|branch| !is_digits(branch, 3)) {
+    return Err(AppError::BadRequest("Invalid buyer tax code".to_string()));
+  }
+
+  if buyer.buyer_company_name.is_empty() {
+    return Err(AppError::BadRequest("Buyer company name is required".to_string()));
+  }
+
+  if buyer.buyer_address.is_empty() {
+    return Err(AppError::BadRequest("Buyer address is required".to_string()));
+  }
+
+  if buyer.buyer_email.as_deref().is_some_and(|email| !email.contains('@')) {
+    return Err(AppError::BadRequest("Invalid buyer email".to_string()));
+  }
+
+  Ok(buyer)
+}
+
+fn validate_reason(reason: &str) -> Result<(), AppError> {
+  if reason.trim().is_empty() {
+    return Err(AppError::BadRequest("Reason is required".to_string()));
+  }
+
+  Ok(())
+}
+
+pub struct InvoiceUseCase;
+
+impl InvoiceUseCase {
+  pub async fn create_invoice(
+    repo: &dyn InvoiceRepository,
+    provider: &dyn InvoiceProviderRepository,
+    seller: &SpaConfig,
+    mut payload: CreateInvoiceRequest,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail> {
+    payload.appointment_ids.sort_unstable();
+    payload.appointment_ids.dedup();
+    if payload.appointment_ids.is_empty() {
+      return Err(AppError::BadRequest("At least one appointment is required".to_string()));
+    }
+
+    payload.buyer = normalize_buyer(payload.buyer)?;
+    validate_vat_rates(payload.vat_rate, payload.line_vat_rates.as_ref())?;
+
+    let detail = repo.create_invoice(payload, seller, created_by).await?;
+
+    Self::submit(repo, provider, detail).await
+  }
+
+  pub async fn create_replacement(
+    repo: &dyn InvoiceRepository,
+    provider: &dyn InvoiceProviderRepository,
+    seller: &SpaConfig,
+    original_id: i64,
+    mut payload: CreateReplacementInvoiceRequest,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail> {
+    validate_reason(&payload.reason)?;
+    payload.buyer = normalize_buyer(payload.buyer)?;
+    validate_vat_rates(payload.vat_rate, payload.line_vat_rates.as_ref())?;
+
+    let detail = repo.create_replacement(original_id, payload, seller, created_by).await?;
+
+    Self::submit(repo, provider, detail).await
+  }
+
+  pub async fn create_adjustment(
+    repo: &dyn InvoiceRepository,
+    provider: &dyn InvoiceProviderRepository,
+    seller: &SpaConfig,
+    original_id: i64,
+    payload: CreateAdjustmentInvoiceRequest,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail> {
+    validate_reason(&payload.reason)?;
+
+    if payload.lines.is_empty() {
+      return Err(AppError::BadRequest("Adjustment lines are required".to_string()));
+    }
+
+    for line in &payload.lines {
+      if line.description.trim().is_empty() {
+        return Err(AppError::BadRequest("Line description is required".to_string()));
+      }
+      if line.quantity <= 0 {
+        return Err(AppError::BadRequest("Quantity must be greater than 0".to_string()));
+      }
+      if line.unit_price == 0 {
+        return Err(AppError::BadRequest("Unit price must not be zero".to_string()));
+      }
+      validate_vat_rate(line.vat_rate)?;
+    }
+
+    let detail = repo.create_adjustment(original_id, payload, seller, created_by).await?;
+
+    Self::submit(repo, provider, detail).await
+  }
+
+  pub async fn get_invoice(
+    repo: &dyn InvoiceRepository,
+    id: i64,
+  ) -> AppResult<InvoiceDetail> {
+    repo.get_invoice(id).await
+  }
+
+  pub async fn get_invoices(
+    repo: &dyn InvoiceRepository,
+    filter: InvoiceFilter,
+  ) -> AppResult<Vec<Invoice>> {
+    repo.get_invoices(filter).await
+  }
+
+  // Gửi lại hoá đơn chưa gửi được sang nhà cung cấp
+  pub async fn resubmit(
+    repo: &dyn InvoiceRepository,
+    provider: &dyn InvoiceProviderRepository,
+    id: i64,
+  ) -> AppResult<InvoiceDetail> {
+    let detail = repo.get_invoice(id).await?;
+
+    if detail.invoice.submission_status == "SUBMITTED" {
+      return Err(AppError::BadRequest("Invoice has already been submitted".to_string()));
+    }
+
+    Self::submit(repo, provider, detail).await
+  }
+
+  // Hoá đơn đã xuất vẫn giữ nguyên khi gửi lỗi, trạng thái gửi được ghi lại để gửi lại sau
+  async fn submit(
+    repo: &dyn InvoiceRepository,
+    provider: &dyn InvoiceProviderRepository,
+    mut detail: InvoiceDetail,
+  ) -> AppResult<InvoiceDetail> {
+    let submission = provider.submit(&detail.invoice).await.map_err(|err| {
+      tracing::error!("Failed to submit invoice #{}: {}", detail.invoice.id, err);
+      err.to_string()
+    });
+
+    detail.invoice = repo.update_submission(detail.invoice.id, submission).await?;
+
+    Ok(detail)
+  }
+}
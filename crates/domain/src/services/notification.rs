@@ -1,12 +1,15 @@
 use crate::{
   entities::{
     common::PaginationMetadata,
-    notification::{CreateNotification, Notification, NotificationFilter, UpdateNotification},
+    notification::{
+      BulkDeleteNotificationsRequest, BulkNotificationResult, BulkReadNotificationsRequest,
+      CreateNotification, Notification, NotificationFilter, UpdateNotification,
+    },
     user::UserWithPassword,
   },
   repositories::notification_repository::NotificationRepository,
 };
-use core_app::AppResult;
+use core_app::{AppResult, errors::AppError};
 use modql::filter::ListOptions;
 
 pub struct NotificationUseCase;
@@ -62,4 +65,44 @@ impl NotificationUseCase {
   ) -> AppResult<i64> {
     repo.un_read(user, filter).await
   }
+
+  pub async fn bulk_update_read(
+    repo: &dyn NotificationRepository,
+    user: UserWithPassword,
+    mut payload: BulkReadNotificationsRequest,
+  ) -> AppResult<BulkNotificationResult> {
+    payload.notification_type = payload
+      .notification_type
+      .map(|notification_type| notification_type.trim().to_uppercase())
+      .filter(|notification_type| !notification_type.is_empty());
+
+    let has_ids = payload.ids.as_ref().is_some_and(|ids| !ids.is_empty());
+    if !payload.all && !has_ids && payload.notification_type.is_none() {
+      return Err(AppError::BadRequest("Provide ids, notification_type or all = true".to_string()));
+    }
+    // all = true bỏ qua điều kiện ids để áp dụng cho mọi thông báo (có thể kèm loại)
+    if payload.all || !has_ids {
+      payload.ids = None;
+    }
+
+    let affected = repo.bulk_update_read(user, payload).await?;
+    Ok(BulkNotificationResult { affected })
+  }
+
+  pub async fn bulk_delete(
+    repo: &dyn NotificationRepository,
+    user: UserWithPassword,
+    mut payload: BulkDeleteNotificationsRequest,
+  ) -> AppResult<BulkNotificationResult> {
+    let has_ids = payload.ids.as_ref().is_some_and(|ids| !ids.is_empty());
+    if !has_ids && payload.before.is_none() {
+      return Err(AppError::BadRequest("Provide ids or before".to_string()));
+    }
+    if !has_ids {
+      payload.ids = None;
+    }
+
+    let affected = repo.bulk_delete(user, payload).await?;
+    Ok(BulkNotificationResult { affected })
+  }
 }
@@ -0,0 +1,54 @@
+use core_app::{AppResult, errors::AppError};
+
+use crate::{
+  entities::notification_outbox::{
+    NotificationOutbox, NotificationOutboxDetail, NotificationOutboxFilter,
+    NotificationOutboxStats, OUTBOX_STATUSES,
+  },
+  repositories::notification_outbox_repository::NotificationOutboxRepository,
+};
+
+pub struct NotificationOutboxUseCase;
+
+impl NotificationOutboxUseCase {
+  pub async fn list(
+    repo: &dyn NotificationOutboxRepository,
+    filter: NotificationOutboxFilter,
+  ) -> AppResult<Vec<NotificationOutbox>> {
+    if let Some(status) = filter.status.as_deref() {
+      if !OUTBOX_STATUSES.contains(&status) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid status, expected one of {}",
+          OUTBOX_STATUSES.join(", ")
+        )));
+      }
+    }
+
+    repo.list(filter).await
+  }
+
+  pub async fn get_by_id(
+    repo: &dyn NotificationOutboxRepository,
+    id: i64,
+  ) -> AppResult<NotificationOutboxDetail> {
+    repo.get_by_id(id).await
+  }
+
+  pub async fn get_stats(
+    repo: &dyn NotificationOutboxRepository
+  ) -> AppResult<NotificationOutboxStats> {
+    repo.get_stats().await
+  }
+
+  pub async fn retry(
+    repo: &dyn NotificationOutboxRepository,
+    id: i64,
+  ) -> AppResult<NotificationOutboxDetail> {
+    let detail = repo.get_by_id(id).await?;
+    if detail.outbox.status != "DEAD" {
+      return Err(AppError::BadRequest("Only dead notifications can be retried".to_string()));
+    }
+
+    repo.retry(id).await
+  }
+}
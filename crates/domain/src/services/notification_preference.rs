@@ -0,0 +1,66 @@
+use chrono::NaiveTime;
+use core_app::{AppResult, errors::AppError};
+
+use crate::{
+  entities::notification_preference::{
+    NOTIFICATION_CHANNELS, NOTIFICATION_TYPES, NotificationPreferences,
+    UpdateNotificationPreferencesRequest,
+  },
+  repositories::notification_preference_repository::NotificationPreferenceRepository,
+};
+
+pub struct NotificationPreferenceUseCase;
+
+impl NotificationPreferenceUseCase {
+  pub async fn get_preferences(
+    repo: &dyn NotificationPreferenceRepository,
+    user_id: i64,
+  ) -> AppResult<NotificationPreferences> {
+    repo.get_preferences(user_id).await
+  }
+
+  pub async fn update_preferences(
+    repo: &dyn NotificationPreferenceRepository,
+    user_id: i64,
+    mut payload: UpdateNotificationPreferencesRequest,
+  ) -> AppResult<NotificationPreferences> {
+    for preference in payload.preferences.iter_mut() {
+      preference.notification_type = preference.notification_type.trim().to_uppercase();
+      preference.channel = preference.channel.trim().to_uppercase();
+
+      if !NOTIFICATION_TYPES.contains(&preference.notification_type.as_str()) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid notification type, expected one of {}",
+          NOTIFICATION_TYPES.join(", ")
+        )));
+      }
+      if !NOTIFICATION_CHANNELS.contains(&preference.channel.as_str()) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid channel, expected one of {}",
+          NOTIFICATION_CHANNELS.join(", ")
+        )));
+      }
+    }
+
+    if let Some(quiet_hours) = payload.quiet_hours.as_mut() {
+      for time in [&mut quiet_hours.start_time, &mut quiet_hours.end_time] {
+        let parsed = NaiveTime::parse_from_str(time.trim(), "%H:%M")
+          .map_err(|_| AppError::BadRequest("Quiet hours must be in HH:MM format".to_string()))?;
+        *time = parsed.format("%H:%M").to_string();
+      }
+
+      if quiet_hours.start_time == quiet_hours.end_time {
+        return Err(AppError::BadRequest(
+          "Quiet hours start and end time must be different".to_string(),
+        ));
+      }
+
+      quiet_hours.timezone = quiet_hours.timezone.trim().to_string();
+      if !repo.is_valid_timezone(&quiet_hours.timezone).await? {
+        return Err(AppError::BadRequest("Invalid timezone".to_string()));
+      }
+    }
+
+    repo.update_preferences(user_id, payload).await
+  }
+}
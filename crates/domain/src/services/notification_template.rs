@@ -0,0 +1,80 @@
+use core_app::{AppResult, errors::AppError};
+
+use crate::{
+  entities::notification_template::{
+    NOTIFICATION_LOCALES, NotificationTemplate, NotificationTemplateFilter,
+    UpdateNotificationTemplateRequest,
+  },
+  repositories::notification_template_repository::NotificationTemplateRepository,
+};
+
+/// Thay các biến {ten_bien} trong mẫu bằng giá trị tương ứng, biến không có giữ nguyên
+pub fn render_template(
+  text: &str,
+  variables: &serde_json::Value,
+) -> String {
+  let mut rendered = text.to_string();
+  if let Some(variables) = variables.as_object() {
+    for (key, value) in variables {
+      let value = match value {
+        serde_json::Value::String(value) => value.clone(),
+        serde_json::Value::Null => String::new(),
+        value => value.to_string(),
+      };
+      rendered = rendered.replace(&format!("{{{}}}", key), &value);
+    }
+  }
+
+  rendered
+}
+
+pub fn validate_locale(locale: &str) -> AppResult<()> {
+  if !NOTIFICATION_LOCALES.contains(&locale) {
+    return Err(AppError::BadRequest(format!(
+      "Invalid language, expected one of {}",
+      NOTIFICATION_LOCALES.join(", ")
+    )));
+  }
+
+  Ok(())
+}
+
+pub struct NotificationTemplateUseCase;
+
+impl NotificationTemplateUseCase {
+  pub async fn list(
+    repo: &dyn NotificationTemplateRepository,
+    filter: NotificationTemplateFilter,
+  ) -> AppResult<Vec<NotificationTemplate>> {
+    if let Some(locale) = filter.locale.as_deref() {
+      validate_locale(locale)?;
+    }
+
+    repo.list(filter).await
+  }
+
+  pub async fn get_by_id(
+    repo: &dyn NotificationTemplateRepository,
+    id: i64,
+  ) -> AppResult<NotificationTemplate> {
+    repo.get_by_id(id).await
+  }
+
+  pub async fn update(
+    repo: &dyn NotificationTemplateRepository,
+    id: i64,
+    mut payload: UpdateNotificationTemplateRequest,
+  ) -> AppResult<NotificationTemplate> {
+    payload.title = payload.title.trim().to_string();
+    payload.body = payload.body.trim().to_string();
+
+    if payload.title.is_empty() || payload.body.is_empty() {
+      return Err(AppError::BadRequest("Title and body cannot be empty".to_string()));
+    }
+    if payload.title.len() > 255 {
+      return Err(AppError::BadRequest("Title cannot exceed 255 characters".to_string()));
+    }
+
+    repo.update(id, payload).await
+  }
+}
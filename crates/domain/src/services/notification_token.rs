@@ -33,6 +33,13 @@ impl NotificationTokenUseCase {
     repo.delete(id).await
   }
 
+  pub async fn delete_invalid_tokens(
+    repo: &dyn NotificationTokenRepository,
+    tokens: Vec<String>,
+  ) -> AppResult<u64> {
+    repo.delete_invalid_tokens(tokens).await
+  }
+
   pub async fn get_token_by_id(
     repo: &dyn NotificationTokenRepository,
     id: i64,
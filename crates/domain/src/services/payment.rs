@@ -0,0 +1,409 @@
+use chrono::{DateTime, Utc};
+use core_app::{AppResult, configs::BankConfig, errors::AppError};
+use utils::{
+  format_number::format_number,
+  vietqr::{build_vietqr_payload, generate_transfer_memo},
+};
+
+use crate::{
+  entities::{
+    payment::{
+      BankTransaction, BankTransactionFilter, BankTransferWebhook, CreatePaymentIntentRequest,
+      PAYMENT_INTENT_TYPES, PaymentIntent, PaymentIntentResponse,
+    },
+    user::UserWithPassword,
+  },
+  repositories::payment_repository::PaymentRepository,
+};
+
+fn to_response(
+  intent: PaymentIntent,
+  bank: &BankConfig,
+) -> PaymentIntentResponse {
+  PaymentIntentResponse {
+    qr_payload: build_vietqr_payload(&bank.bin, &bank.account_number, intent.amount, &intent.memo),
+    bank_bin: bank.bin.clone(),
+    bank_account_number: bank.account_number.clone(),
+    bank_account_name: bank.account_name.clone(),
+    intent,
+  }
+}
+
+/// Lý do giao dịch không khớp yêu cầu thanh toán: chuyển thiếu, chuyển thừa hoặc mã QR đã hết hạn.
+/// None nghĩa là giao dịch hợp lệ.
+pub fn transfer_mismatch_note(
+  amount: i64,
+  intent: &PaymentIntent,
+  now: DateTime<Utc>,
+) -> Option<String> {
+  if amount != intent.amount {
+    Some(format!(
+      "Số tiền chuyển {}đ khác số tiền cần thanh toán {}đ",
+      format_number(amount),
+      format_number(intent.amount)
+    ))
+  } else if intent.expires_at < now {
+    Some("Yêu cầu thanh toán đã hết hạn".to_string())
+  } else {
+    None
+  }
+}
+
+pub struct PaymentUseCase;
+
+impl PaymentUseCase {
+  pub async fn create_intent(
+    repo: &dyn PaymentRepository,
+    bank: &BankConfig,
+    user: UserWithPassword,
+    mut payload: CreatePaymentIntentRequest,
+  ) -> AppResult<PaymentIntentResponse> {
+    if bank.bin.is_empty() || bank.account_number.is_empty() {
+      return Err(AppError::BadRequest("Bank account is not configured".to_string()));
+    }
+
+    payload.intent_type = payload.intent_type.trim().to_uppercase();
+    if !PAYMENT_INTENT_TYPES.contains(&payload.intent_type.as_str()) {
+      return Err(AppError::BadRequest("Invalid intent type".to_string()));
+    }
+
+    match user.role.as_str() {
+      "CUSTOMER" => payload.user_id = Some(user.pk_user_id),
+      "RECEPTIONIST" | "ADMIN" => {},
+      _ => return Err(AppError::Forbidden("You don't have permission".to_string())),
+    }
+
+    if payload.intent_type == "TOP_UP" {
+      if payload.amount.is_none_or(|amount| amount <= 0) {
+        return Err(AppError::BadRequest("Amount must be greater than 0".to_string()));
+      }
+
+      if payload.user_id.is_none() {
+        return Err(AppError::BadRequest("user_id is required".to_string()));
+      }
+    } else if payload.appointment_id.is_none() {
+      return Err(AppError::BadRequest("appointment_id is required".to_string()));
+    }
+
+    let intent = repo.create_intent(payload, generate_transfer_memo(), user.pk_user_id).await?;
+
+    Ok(to_response(intent, bank))
+  }
+
+  pub async fn get_intent(
+    repo: &dyn PaymentRepository,
+    bank: &BankConfig,
+    user: UserWithPassword,
+    id: i64,
+  ) -> AppResult<PaymentIntentResponse> {
+    let intent = repo.get_intent(id).await?.ok_or(AppError::NotFound)?;
+
+    // Khách hàng chỉ xem được yêu cầu thanh toán của mình
+    if user.role == "CUSTOMER" && intent.user_id != user.pk_user_id {
+      return Err(AppError::NotFound);
+    }
+
+    Ok(to_response(intent, bank))
+  }
+
+  // Xử lý giao dịch chuyển khoản nhận từ webhook (chữ ký đã được kiểm tra ở handler)
+  pub async fn handle_bank_transfer(
+    repo: &dyn PaymentRepository,
+    bank: &BankConfig,
+    payload: BankTransferWebhook,
+    raw_payload: serde_json::Value,
+  ) -> AppResult<BankTransaction> {
+    if payload.transaction_id.trim().is_empty() {
+      return Err(AppError::BadRequest("transaction_id is required".to_string()));
+    }
+
+    if bank.account_number.is_empty() {
+      return Err(AppError::BadRequest("Bank account is not configured".to_string()));
+    }
+
+    // Chỉ nhận tiền vào đúng tài khoản của spa
+    if payload.account_number.as_deref().map(str::trim) != Some(bank.account_number.as_str()) {
+      return Err(AppError::BadRequest("Account number does not match".to_string()));
+    }
+
+    let transaction = repo.record_bank_transaction(payload, raw_payload).await?;
+
+    // Webhook gửi lại giao dịch đã đối soát thì trả về kết quả cũ. Giao dịch còn PENDING
+    // (lần trước lỗi giữa chừng) thì đối soát lại
+    if transaction.match_status != "PENDING" {
+      return Ok(transaction);
+    }
+
+    if transaction.amount <= 0 {
+      return repo
+        .flag_mismatch(transaction.id, None, "Số tiền giao dịch không hợp lệ".to_string())
+        .await;
+    }
+
+    repo.match_bank_transaction(transaction.id).await
+  }
+
+  pub async fn get_bank_transactions(
+    repo: &dyn PaymentRepository,
+    filter: BankTransactionFilter,
+  ) -> AppResult<Vec<BankTransaction>> {
+    repo.get_bank_transactions(filter).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Mutex;
+
+  use async_trait::async_trait;
+  use chrono::Duration;
+
+  use super::*;
+
+  fn intent(
+    amount: i64,
+    expires_in_minutes: i64,
+  ) -> PaymentIntent {
+    let now = Utc::now();
+    PaymentIntent {
+      id: 1,
+      user_id: 10,
+      intent_type: "APPOINTMENT".to_string(),
+      appointment_id: Some(100),
+      deposit_id: None,
+      amount,
+      memo: "NS12345678".to_string(),
+      status: "PENDING".to_string(),
+      expires_at: now + Duration::minutes(expires_in_minutes),
+      paid_at: None,
+      created_by: 10,
+      created_at: now,
+      updated_at: now,
+    }
+  }
+
+  fn bank() -> BankConfig {
+    BankConfig {
+      bin: "970436".to_string(),
+      account_number: "0123456789".to_string(),
+      account_name: "NASPA".to_string(),
+      webhook_secret: "secret".to_string(),
+    }
+  }
+
+  fn webhook(
+    transaction_id: &str,
+    amount: i64,
+  ) -> BankTransferWebhook {
+    BankTransferWebhook {
+      provider: Some("STUB".to_string()),
+      transaction_id: transaction_id.to_string(),
+      amount,
+      description: "NS12345678 thanh toan".to_string(),
+      account_number: Some("0123456789".to_string()),
+      transaction_time: None,
+    }
+  }
+
+  // Lưu giao dịch trong bộ nhớ, đối soát luôn khớp để đếm số lần được gọi
+  #[derive(Default)]
+  struct MemoryPaymentRepository {
+    transactions: Mutex<Vec<BankTransaction>>,
+    match_calls: Mutex<i64>,
+  }
+
+  impl MemoryPaymentRepository {
+    fn set_status(
+      &self,
+      transaction_id: i64,
+      match_status: &str,
+    ) -> BankTransaction {
+      let mut transactions = self.transactions.lock().unwrap();
+      let transaction = transactions.iter_mut().find(|t| t.id == transaction_id).unwrap();
+      transaction.match_status = match_status.to_string();
+      transaction.clone()
+    }
+  }
+
+  #[async_trait]
+  impl PaymentRepository for MemoryPaymentRepository {
+    async fn create_intent(
+      &self,
+      _: CreatePaymentIntentRequest,
+      _: String,
+      _: i64,
+    ) -> AppResult<PaymentIntent> {
+      Err(AppError::NotFound)
+    }
+
+    async fn get_intent(
+      &self,
+      _: i64,
+    ) -> AppResult<Option<PaymentIntent>> {
+      Ok(None)
+    }
+
+    async fn record_bank_transaction(
+      &self,
+      payload: BankTransferWebhook,
+      _: serde_json::Value,
+    ) -> AppResult<BankTransaction> {
+      let mut transactions = self.transactions.lock().unwrap();
+      if let Some(existing) =
+        transactions.iter().find(|t| t.provider_transaction_id == payload.transaction_id)
+      {
+        return Ok(existing.clone());
+      }
+
+      let now = Utc::now();
+      let transaction = BankTransaction {
+        id: transactions.len() as i64 + 1,
+        provider: payload.provider.unwrap_or_default(),
+        provider_transaction_id: payload.transaction_id,
+        amount: payload.amount,
+        description: payload.description,
+        account_number: payload.account_number,
+        transaction_time: payload.transaction_time,
+        payment_intent_id: None,
+        match_status: "PENDING".to_string(),
+        match_note: None,
+        created_at: now,
+        updated_at: now,
+      };
+      transactions.push(transaction.clone());
+      Ok(transaction)
+    }
+
+    async fn match_bank_transaction(
+      &self,
+      transaction_id: i64,
+    ) -> AppResult<BankTransaction> {
+      *self.match_calls.lock().unwrap() += 1;
+      Ok(self.set_status(transaction_id, "MATCHED"))
+    }
+
+    async fn flag_mismatch(
+      &self,
+      transaction_id: i64,
+      _: Option<i64>,
+      _: String,
+    ) -> AppResult<BankTransaction> {
+      Ok(self.set_status(transaction_id, "MISMATCH"))
+    }
+
+    async fn get_bank_transactions(
+      &self,
+      filter: BankTransactionFilter,
+    ) -> AppResult<Vec<BankTransaction>> {
+      let transactions = self.transactions.lock().unwrap();
+      Ok(
+        transactions
+          .iter()
+          .filter(|t| filter.match_status.as_ref().is_none_or(|status| &t.match_status == status))
+          .cloned()
+          .collect(),
+      )
+    }
+  }
+
+  #[test]
+  fn transfer_mismatch_note_cases() {
+    let now = Utc::now();
+    let cases = [
+      (500_000, intent(500_000, 30), false),
+      (300_000, intent(500_000, 30), true),
+      (600_000, intent(500_000, 30), true),
+      (500_000, intent(500_000, -1), true),
+    ];
+
+    for (amount, intent, is_mismatch) in cases {
+      assert_eq!(
+        transfer_mismatch_note(amount, &intent, now).is_some(),
+        is_mismatch,
+        "amount {} / intent {}",
+        amount,
+        intent.amount
+      );
+    }
+
+    assert_eq!(
+      transfer_mismatch_note(300_000, &intent(500_000, 30), now).unwrap(),
+      "Số tiền chuyển 300.000đ khác số tiền cần thanh toán 500.000đ"
+    );
+    assert_eq!(
+      transfer_mismatch_note(500_000, &intent(500_000, -1), now).unwrap(),
+      "Yêu cầu thanh toán đã hết hạn"
+    );
+  }
+
+  #[tokio::test]
+  async fn redelivered_webhook_is_matched_once() {
+    let repo = MemoryPaymentRepository::default();
+    let payload = webhook("FT001", 500_000);
+
+    let first =
+      PaymentUseCase::handle_bank_transfer(&repo, &bank(), payload.clone(), serde_json::json!({}))
+        .await
+        .unwrap();
+    let second =
+      PaymentUseCase::handle_bank_transfer(&repo, &bank(), payload, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(first.match_status, "MATCHED");
+    assert_eq!(second.id, first.id);
+    assert_eq!(second.match_status, "MATCHED");
+    assert_eq!(*repo.match_calls.lock().unwrap(), 1);
+  }
+
+  #[tokio::test]
+  async fn pending_transaction_is_matched_on_redelivery() {
+    let repo = MemoryPaymentRepository::default();
+    let payload = webhook("FT002", 500_000);
+
+    // Lần trước đã ghi nhận giao dịch nhưng tiến trình dừng trước khi đối soát
+    repo.record_bank_transaction(payload.clone(), serde_json::json!({})).await.unwrap();
+
+    let transaction =
+      PaymentUseCase::handle_bank_transfer(&repo, &bank(), payload, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(transaction.match_status, "MATCHED");
+    assert_eq!(*repo.match_calls.lock().unwrap(), 1);
+  }
+
+  #[tokio::test]
+  async fn invalid_amount_is_flagged_without_matching() {
+    let repo = MemoryPaymentRepository::default();
+
+    let transaction = PaymentUseCase::handle_bank_transfer(
+      &repo,
+      &bank(),
+      webhook("FT003", 0),
+      serde_json::json!({}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(transaction.match_status, "MISMATCH");
+    assert_eq!(*repo.match_calls.lock().unwrap(), 0);
+  }
+
+  #[tokio::test]
+  async fn other_account_is_rejected() {
+    let repo = MemoryPaymentRepository::default();
+
+    for account_number in [Some("9999999999".to_string()), None] {
+      let mut payload = webhook("FT004", 500_000);
+      payload.account_number = account_number;
+
+      let result =
+        PaymentUseCase::handle_bank_transfer(&repo, &bank(), payload, serde_json::json!({})).await;
+
+      assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    assert!(repo.transactions.lock().unwrap().is_empty());
+  }
+}
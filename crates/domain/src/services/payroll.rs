@@ -0,0 +1,138 @@
+use chrono::{FixedOffset, NaiveDate, Utc};
+use core_app::{AppResult, errors::AppError};
+
+use crate::{
+  entities::payroll::{
+    Commission, CommissionRule, CreateCommissionRuleRequest, CreatePayrollAdjustmentRequest,
+    PayrollPeriod, PayrollReport, UpdateCommissionRuleRequest,
+  },
+  repositories::payroll_repository::PayrollRepository,
+};
+
+// Kỳ lương hiện tại theo giờ Việt Nam (UTC+7)
+fn current_period() -> String {
+  let utc_plus_7 = FixedOffset::east_opt(7 * 3600).unwrap();
+  Utc::now().with_timezone(&utc_plus_7).format("%Y-%m").to_string()
+}
+
+fn validate_period(period: &str) -> Result<(), AppError> {
+  if period.len() != 7 || NaiveDate::parse_from_str(&format!("{}-01", period), "%Y-%m-%d").is_err()
+  {
+    return Err(AppError::BadRequest("Invalid period, expected YYYY-MM".to_string()));
+  }
+
+  Ok(())
+}
+
+fn validate_commission_value(
+  commission_type: &str,
+  value: i64,
+) -> Result<(), AppError> {
+  match commission_type {
+    "PERCENTAGE" if (0..=100).contains(&value) => Ok(()),
+    "PERCENTAGE" => Err(AppError::BadRequest("Percentage must be between 0 and 100".to_string())),
+    "FIXED" if value >= 0 => Ok(()),
+    "FIXED" => Err(AppError::BadRequest("Value must not be negative".to_string())),
+    _ => Err(AppError::BadRequest("Invalid commission type".to_string())),
+  }
+}
+
+pub struct PayrollUseCase;
+
+impl PayrollUseCase {
+  pub async fn get_commission_rules(
+    repo: &dyn PayrollRepository
+  ) -> AppResult<Vec<CommissionRule>> {
+    repo.get_commission_rules().await
+  }
+
+  pub async fn create_commission_rule(
+    repo: &dyn PayrollRepository,
+    mut payload: CreateCommissionRuleRequest,
+    created_by: i64,
+  ) -> AppResult<CommissionRule> {
+    validate_commission_value(&payload.commission_type, payload.value)?;
+
+    payload.technician_level = payload
+      .technician_level
+      .map(|level| level.trim().to_string())
+      .filter(|level| !level.is_empty());
+
+    repo.create_commission_rule(payload, created_by).await
+  }
+
+  pub async fn update_commission_rule(
+    repo: &dyn PayrollRepository,
+    id: i64,
+    payload: UpdateCommissionRuleRequest,
+  ) -> AppResult<CommissionRule> {
+    if payload.commission_type.is_some() || payload.value.is_some() {
+      let current = repo
+        .get_commission_rules()
+        .await?
+        .into_iter()
+        .find(|rule| rule.id == id)
+        .ok_or(AppError::NotFound)?;
+
+      validate_commission_value(
+        payload.commission_type.as_deref().unwrap_or(&current.commission_type),
+        payload.value.unwrap_or(current.value),
+      )?;
+    }
+
+    repo.update_commission_rule(id, payload).await
+  }
+
+  pub async fn delete_commission_rule(
+    repo: &dyn PayrollRepository,
+    id: i64,
+  ) -> AppResult<()> {
+    repo.delete_commission_rule(id).await
+  }
+
+  pub async fn get_payroll(
+    repo: &dyn PayrollRepository,
+    period: Option<String>,
+    technician_id: Option<i64>,
+  ) -> AppResult<PayrollReport> {
+    let period = period.unwrap_or_else(current_period);
+    validate_period(&period)?;
+
+    repo.get_payroll(period, technician_id).await
+  }
+
+  pub async fn lock_payroll_period(
+    repo: &dyn PayrollRepository,
+    period: String,
+    locked_by: i64,
+  ) -> AppResult<PayrollPeriod> {
+    validate_period(&period)?;
+
+    // "YYYY-MM" so sánh theo chuỗi được
+    if period > current_period() {
+      return Err(AppError::BadRequest("Cannot lock a future payroll period".to_string()));
+    }
+
+    repo.lock_payroll_period(period, locked_by).await
+  }
+
+  pub async fn create_adjustment(
+    repo: &dyn PayrollRepository,
+    payload: CreatePayrollAdjustmentRequest,
+    created_by: i64,
+  ) -> AppResult<Commission> {
+    if let Some(period) = payload.period.as_deref() {
+      validate_period(period)?;
+    }
+
+    if payload.amount == 0 {
+      return Err(AppError::BadRequest("Adjustment amount must not be zero".to_string()));
+    }
+
+    if payload.notes.trim().is_empty() {
+      return Err(AppError::BadRequest("Adjustment notes are required".to_string()));
+    }
+
+    repo.create_adjustment(payload, created_by).await
+  }
+}
@@ -9,6 +9,7 @@ use crate::{
     user::{User, UserWithPassword},
   },
   repositories::{image_repository::ImageRepository, profile_repository::ProfileRepository},
+  services::notification_template::validate_locale,
 };
 
 pub struct ProfileUseCase;
@@ -102,6 +103,10 @@ impl ProfileUseCase {
       }
     }
 
+    if let Some(language) = &payload.preferred_language {
+      validate_locale(language)?;
+    }
+
     profile_repo.update_profile(user, payload).await
   }
 
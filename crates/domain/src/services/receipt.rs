@@ -0,0 +1,82 @@
+use core_app::{AppResult, configs::SpaConfig, errors::AppError};
+use utils::helper::{sign_hmac_sha256, verify_hmac_sha256};
+
+use crate::{
+  entities::{
+    receipt::{ReceiptDocument, ReceiptSpaInfo},
+    user::UserWithPassword,
+  },
+  repositories::receipt_repository::ReceiptRepository,
+};
+
+fn share_payload(receipt_no: &str) -> Vec<u8> {
+  format!("receipt:{}", receipt_no).into_bytes()
+}
+
+fn with_spa_info(
+  mut document: ReceiptDocument,
+  spa: &SpaConfig,
+) -> ReceiptDocument {
+  document.spa = ReceiptSpaInfo {
+    name: spa.name.clone(),
+    address: spa.address.clone(),
+    phone: spa.phone.clone(),
+    tax_code: spa.tax_code.clone(),
+  };
+  document
+}
+
+pub struct ReceiptUseCase;
+
+impl ReceiptUseCase {
+  pub async fn get_receipt(
+    repo: &dyn ReceiptRepository,
+    spa: &SpaConfig,
+    share_secret: &str,
+    user: UserWithPassword,
+    appointment_id: i64,
+  ) -> AppResult<ReceiptDocument> {
+    if !["CUSTOMER", "RECEPTIONIST", "ADMIN"].contains(&user.role.as_str()) {
+      return Err(AppError::Forbidden("You don't have permission".to_string()));
+    }
+
+    // Kiểm tra quyền trước khi đọc hoá đơn; chỉ nhân viên được xuất bổ sung hoá đơn còn thiếu
+    let issued_by = if user.role == "CUSTOMER" {
+      let customer_id = repo.get_appointment_customer(appointment_id).await?;
+      if customer_id != Some(user.pk_user_id) {
+        return Err(AppError::NotFound);
+      }
+      None
+    } else {
+      Some(user.pk_user_id)
+    };
+
+    let mut document = repo.get_receipt_by_appointment(appointment_id, issued_by).await?;
+
+    if !share_secret.is_empty() {
+      document.share_token =
+        Some(sign_hmac_sha256(share_secret, &share_payload(&document.receipt_no)));
+    }
+
+    Ok(with_spa_info(document, spa))
+  }
+
+  // Xem hoá đơn qua link chia sẻ, không cần đăng nhập
+  pub async fn get_shared_receipt(
+    repo: &dyn ReceiptRepository,
+    spa: &SpaConfig,
+    share_secret: &str,
+    receipt_no: String,
+    token: &str,
+  ) -> AppResult<ReceiptDocument> {
+    if share_secret.is_empty()
+      || !verify_hmac_sha256(share_secret, &share_payload(&receipt_no), token)
+    {
+      return Err(AppError::NotFound);
+    }
+
+    let document = repo.get_receipt_by_no(receipt_no).await?;
+
+    Ok(with_spa_info(document, spa))
+  }
+}
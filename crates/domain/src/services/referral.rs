@@ -0,0 +1,79 @@
+use core_app::{AppResult, errors::AppError};
+
+use crate::{
+  entities::{
+    referral::{
+      REFERRAL_STATUSES, Referral, ReferralFilter, ReferralReportFilter, ReferralReportRow,
+      ReferralSummary,
+    },
+    user::UserWithPassword,
+  },
+  repositories::referral_repository::ReferralRepository,
+};
+
+// Mã giới thiệu không phân biệt hoa thường, chuỗi rỗng coi như không nhập
+pub fn normalize_referral_code(code: Option<String>) -> Option<String> {
+  code.map(|code| code.trim().to_uppercase()).filter(|code| !code.is_empty())
+}
+
+// Người giới thiệu chỉ thấy một phần số điện thoại của khách được giới thiệu
+fn mask_phone(phone: Option<String>) -> Option<String> {
+  phone.map(|phone| {
+    let chars: Vec<char> = phone.chars().collect();
+    if chars.len() <= 6 {
+      return "*".repeat(chars.len());
+    }
+
+    chars
+      .iter()
+      .enumerate()
+      .map(|(i, c)| if i < 3 || i >= chars.len() - 3 { *c } else { '*' })
+      .collect()
+  })
+}
+
+pub struct ReferralUseCase;
+
+impl ReferralUseCase {
+  pub async fn get_my_summary(
+    repo: &dyn ReferralRepository,
+    user: UserWithPassword,
+  ) -> AppResult<ReferralSummary> {
+    let mut summary = repo.get_summary(user.pk_user_id).await?;
+
+    for referral in summary.referrals.iter_mut() {
+      referral.referred_phone = mask_phone(referral.referred_phone.take());
+    }
+
+    Ok(summary)
+  }
+
+  pub async fn get_referrals(
+    repo: &dyn ReferralRepository,
+    filter: ReferralFilter,
+  ) -> AppResult<Vec<Referral>> {
+    if let Some(status) = filter.status.as_deref() {
+      if !REFERRAL_STATUSES.contains(&status) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid status, expected one of {}",
+          REFERRAL_STATUSES.join(", ")
+        )));
+      }
+    }
+
+    repo.get_referrals(filter).await
+  }
+
+  pub async fn get_report(
+    repo: &dyn ReferralRepository,
+    filter: ReferralReportFilter,
+  ) -> AppResult<Vec<ReferralReportRow>> {
+    if let (Some(start_date), Some(end_date)) = (filter.start_date, filter.end_date) {
+      if start_date > end_date {
+        return Err(AppError::BadRequest("start_date must be before end_date".to_string()));
+      }
+    }
+
+    repo.get_report(filter).await
+  }
+}
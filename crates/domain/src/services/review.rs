@@ -0,0 +1,260 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use core_app::{AppResult, configs::ReviewConfig, errors::AppError};
+
+use crate::{
+  entities::{
+    review::{
+      CreateReviewRequest, ModerateReviewRequest, ReplyReviewRequest, ReviewContext, ReviewDetail,
+      ReviewFilter, ReviewPhoto,
+    },
+    user::UserWithPassword,
+  },
+  repositories::{image_repository::ImageRepository, review_repository::ReviewRepository},
+};
+
+const MAX_PHOTOS: usize = 5;
+
+fn clean(value: Option<String>) -> Option<String> {
+  value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+fn validate_rating(rating: i32) -> Result<(), AppError> {
+  if !(1..=5).contains(&rating) {
+    return Err(AppError::BadRequest("Rating must be between 1 and 5".to_string()));
+  }
+
+  Ok(())
+}
+
+// Lễ tân và quản trị viên được ẩn / trả lời đánh giá và xem đánh giá đã ẩn
+fn is_moderator(user: &UserWithPassword) -> bool {
+  user.role == "ADMIN" || user.role == "RECEPTIONIST"
+}
+
+// Chỉ đánh giá lịch hẹn đã hoàn thành của chính mình, trong thời hạn cho phép
+fn ensure_reviewable(
+  user: &UserWithPassword,
+  context: &ReviewContext,
+  config: &ReviewConfig,
+) -> Result<(), AppError> {
+  if context.customer_id != Some(user.pk_user_id) {
+    return Err(AppError::Forbidden("You can only review your own appointments".to_string()));
+  }
+
+  if context.status != "COMPLETED" && context.status != "PAYMENT" {
+    return Err(AppError::BadRequest("Only completed appointments can be reviewed".to_string()));
+  }
+
+  if context
+    .finished_at
+    .is_some_and(|finished_at| finished_at + Duration::days(config.window_days) < Utc::now())
+  {
+    return Err(AppError::BadRequest(
+      "The review period for this appointment has ended".to_string(),
+    ));
+  }
+
+  Ok(())
+}
+
+// Đánh giá đã ẩn chỉ người viết và người kiểm duyệt xem được
+fn ensure_visible(
+  user: &UserWithPassword,
+  detail: &ReviewDetail,
+) -> Result<(), AppError> {
+  if detail.review.is_hidden && !is_moderator(user) && detail.review.customer_id != user.pk_user_id
+  {
+    return Err(AppError::NotFound);
+  }
+
+  Ok(())
+}
+
+pub struct ReviewUseCase;
+
+impl ReviewUseCase {
+  pub async fn create(
+    repo: &dyn ReviewRepository,
+    config: &ReviewConfig,
+    user: UserWithPassword,
+    mut payload: CreateReviewRequest,
+  ) -> AppResult<ReviewDetail> {
+    validate_rating(payload.rating)?;
+    payload.comment = clean(payload.comment);
+
+    let context = repo.get_context(payload.appointment_id).await?;
+    ensure_reviewable(&user, &context, config)?;
+
+    let mut technician_ids = Vec::new();
+    for technician in payload.technicians.iter_mut() {
+      validate_rating(technician.rating)?;
+      technician.comment = clean(technician.comment.take());
+
+      if !context.technician_ids.contains(&technician.technician_id) {
+        return Err(AppError::BadRequest(format!(
+          "Technician {} did not serve this appointment",
+          technician.technician_id
+        )));
+      }
+
+      if technician_ids.contains(&technician.technician_id) {
+        return Err(AppError::BadRequest(format!(
+          "Technician {} is rated more than once",
+          technician.technician_id
+        )));
+      }
+      technician_ids.push(technician.technician_id);
+    }
+
+    repo.create_review(user.pk_user_id, payload).await
+  }
+
+  pub async fn get_review(
+    repo: &dyn ReviewRepository,
+    user: UserWithPassword,
+    id: i64,
+  ) -> AppResult<ReviewDetail> {
+    let detail = repo.get_review(id).await?;
+    ensure_visible(&user, &detail)?;
+
+    Ok(detail)
+  }
+
+  pub async fn get_appointment_review(
+    repo: &dyn ReviewRepository,
+    user: UserWithPassword,
+    appointment_id: i64,
+  ) -> AppResult<Option<ReviewDetail>> {
+    let context = repo.get_context(appointment_id).await?;
+
+    let is_staff = user.role == "ADMIN" || user.role == "RECEPTIONIST" || user.role == "TECHNICIAN";
+    if !is_staff && context.customer_id != Some(user.pk_user_id) {
+      return Err(AppError::NotFound);
+    }
+
+    let detail = repo.get_appointment_review(appointment_id).await?;
+
+    Ok(detail.filter(|detail| ensure_visible(&user, detail).is_ok()))
+  }
+
+  pub async fn get_reviews(
+    repo: &dyn ReviewRepository,
+    user: UserWithPassword,
+    mut filter: ReviewFilter,
+  ) -> AppResult<Vec<ReviewDetail>> {
+    if !is_moderator(&user) {
+      filter.include_hidden = Some(false);
+    }
+
+    repo.get_reviews(filter).await
+  }
+
+  pub async fn get_my_reviews(
+    repo: &dyn ReviewRepository,
+    user: UserWithPassword,
+  ) -> AppResult<Vec<ReviewDetail>> {
+    repo
+      .get_reviews(ReviewFilter {
+        customer_id: Some(user.pk_user_id),
+        include_hidden: Some(true),
+        ..Default::default()
+      })
+      .await
+  }
+
+  pub async fn moderate(
+    repo: &dyn ReviewRepository,
+    user: UserWithPassword,
+    id: i64,
+    mut payload: ModerateReviewRequest,
+  ) -> AppResult<ReviewDetail> {
+    payload.reason = if payload.is_hidden { clean(payload.reason) } else { None };
+
+    repo.moderate(id, user.pk_user_id, payload).await
+  }
+
+  pub async fn reply(
+    repo: &dyn ReviewRepository,
+    user: UserWithPassword,
+    id: i64,
+    payload: ReplyReviewRequest,
+  ) -> AppResult<ReviewDetail> {
+    let reply = payload.reply.trim().to_string();
+    if reply.is_empty() {
+      return Err(AppError::BadRequest("Reply is required".to_string()));
+    }
+
+    repo.reply(id, user.pk_user_id, reply).await
+  }
+
+  pub async fn add_photo(
+    repo: &dyn ReviewRepository,
+    image_service: Arc<dyn ImageRepository>,
+    config: &ReviewConfig,
+    user: UserWithPassword,
+    review_id: i64,
+    data: &[u8],
+    content_type: &str,
+  ) -> AppResult<ReviewPhoto> {
+    let detail = repo.get_review(review_id).await?;
+    if detail.review.customer_id != user.pk_user_id {
+      return Err(AppError::NotFound);
+    }
+
+    let context = repo.get_context(detail.review.appointment_id).await?;
+    ensure_reviewable(&user, &context, config)?;
+
+    if detail.photos.len() >= MAX_PHOTOS {
+      return Err(AppError::BadRequest(format!("A review can have at most {} photos", MAX_PHOTOS)));
+    }
+
+    if data.is_empty() {
+      return Err(AppError::BadRequest("Image is required".to_string()));
+    }
+
+    const MAX_FILE_SIZE: usize = 5 * 1024 * 1024; // 5MB
+    const MAX_WIDTH: u32 = 1200;
+    const QUALITY: u8 = 80;
+
+    let image_path = image_service
+      .upload_and_resize(
+        data,
+        content_type,
+        user.pk_user_id,
+        MAX_FILE_SIZE,
+        MAX_WIDTH,
+        QUALITY,
+        "reviews",
+      )
+      .await?;
+
+    match repo.add_photo(review_id, image_path.clone()).await {
+      Ok(photo) => Ok(photo),
+      Err(err) => {
+        let _ = image_service.remove_old_image(&image_path).await;
+        Err(err)
+      },
+    }
+  }
+
+  // Người viết hoặc người kiểm duyệt được xoá ảnh
+  pub async fn delete_photo(
+    repo: &dyn ReviewRepository,
+    image_service: Arc<dyn ImageRepository>,
+    user: UserWithPassword,
+    review_id: i64,
+    photo_id: i64,
+  ) -> AppResult<()> {
+    let detail = repo.get_review(review_id).await?;
+    if !is_moderator(&user) && detail.review.customer_id != user.pk_user_id {
+      return Err(AppError::NotFound);
+    }
+
+    let photo = repo.delete_photo(review_id, photo_id).await?;
+    let _ = image_service.remove_old_image(&photo.image).await;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use core_app::{AppResult, errors::AppError};
+
+use crate::{
+  entities::{
+    shift::{
+      COUNTABLE_TENDERS, CloseShiftRequest, OpenShiftRequest, Shift, ShiftFilter, ShiftSummary,
+      ShiftVarianceReport,
+    },
+    user::UserWithPassword,
+  },
+  repositories::shift_repository::ShiftRepository,
+};
+
+fn validate_counted(request: &CloseShiftRequest) -> Result<(), AppError> {
+  let mut seen = HashSet::new();
+
+  for counted in &request.counted {
+    if !COUNTABLE_TENDERS.contains(&counted.tender_type.as_str()) {
+      return Err(AppError::BadRequest(format!(
+        "Tender {} cannot be counted, expected one of CASH, CARD, BANK_TRANSFER",
+        counted.tender_type
+      )));
+    }
+
+    if counted.amount < 0 {
+      return Err(AppError::BadRequest("Counted amount must not be negative".to_string()));
+    }
+
+    if !seen.insert(counted.tender_type.as_str()) {
+      return Err(AppError::BadRequest(format!("Duplicate tender {}", counted.tender_type)));
+    }
+  }
+
+  if !seen.contains("CASH") {
+    return Err(AppError::BadRequest("Counted cash amount is required".to_string()));
+  }
+
+  Ok(())
+}
+
+pub struct ShiftUseCase;
+
+impl ShiftUseCase {
+  pub async fn open_shift(
+    repo: &dyn ShiftRepository,
+    user: UserWithPassword,
+    request: OpenShiftRequest,
+  ) -> AppResult<Shift> {
+    if request.opening_float < 0 {
+      return Err(AppError::BadRequest("Opening float must not be negative".to_string()));
+    }
+
+    repo.open_shift(user.pk_user_id, request).await
+  }
+
+  pub async fn get_current_shift(
+    repo: &dyn ShiftRepository,
+    user: UserWithPassword,
+  ) -> AppResult<ShiftSummary> {
+    let shift = repo.get_open_shift(user.pk_user_id).await?.ok_or(AppError::NotFound)?;
+
+    repo.get_shift_summary(shift.id).await
+  }
+
+  pub async fn close_current_shift(
+    repo: &dyn ShiftRepository,
+    user: UserWithPassword,
+    request: CloseShiftRequest,
+  ) -> AppResult<ShiftSummary> {
+    validate_counted(&request)?;
+
+    let shift = repo
+      .get_open_shift(user.pk_user_id)
+      .await?
+      .ok_or_else(|| AppError::BadRequest("Bạn chưa mở ca làm việc".to_string()))?;
+
+    repo.close_shift(shift.id, user.pk_user_id, request).await
+  }
+
+  // Lễ tân chỉ xem được ca của mình
+  pub async fn get_shift(
+    repo: &dyn ShiftRepository,
+    user: UserWithPassword,
+    shift_id: i64,
+  ) -> AppResult<ShiftSummary> {
+    let summary = repo.get_shift_summary(shift_id).await?;
+
+    if user.role != "ADMIN" && summary.shift.receptionist_id != user.pk_user_id {
+      return Err(AppError::NotFound);
+    }
+
+    Ok(summary)
+  }
+
+  pub async fn get_shifts(
+    repo: &dyn ShiftRepository,
+    filter: ShiftFilter,
+  ) -> AppResult<Vec<Shift>> {
+    repo.get_shifts(filter).await
+  }
+
+  pub async fn get_variance_report(
+    repo: &dyn ShiftRepository,
+    filter: ShiftFilter,
+  ) -> AppResult<ShiftVarianceReport> {
+    repo.get_variance_report(filter).await
+  }
+}
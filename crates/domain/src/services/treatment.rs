@@ -0,0 +1,409 @@
+use std::sync::Arc;
+
+use core_app::{AppResult, errors::AppError};
+use serde_json::{Map, Value};
+
+use crate::{
+  entities::{
+    treatment::{
+      CreateTreatmentFieldRequest, CreateTreatmentPhotoRequest, CreateTreatmentRecordRequest,
+      CustomerTreatmentRecord, TREATMENT_FIELD_TYPES, TREATMENT_PHOTO_TYPES, TreatmentField,
+      TreatmentPhoto, TreatmentRecord, TreatmentRecordDetail, TreatmentRecordFilter,
+      TreatmentRecordHistory, UpdateTreatmentFieldRequest, UpdateTreatmentRecordRequest,
+    },
+    user::UserWithPassword,
+  },
+  repositories::{image_repository::ImageRepository, treatment_repository::TreatmentRepository},
+};
+
+fn clean(value: Option<String>) -> Option<String> {
+  value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+fn validate_options(
+  field_type: &str,
+  options: &Option<Vec<String>>,
+) -> Result<(), AppError> {
+  if field_type == "SELECT" && options.as_ref().is_none_or(|options| options.is_empty()) {
+    return Err(AppError::BadRequest("SELECT field requires options".to_string()));
+  }
+
+  Ok(())
+}
+
+fn as_object(fields: Option<Value>) -> Result<Map<String, Value>, AppError> {
+  match fields {
+    None | Some(Value::Null) => Ok(Map::new()),
+    Some(Value::Object(map)) => Ok(map),
+    Some(_) => Err(AppError::BadRequest("fields must be an object".to_string())),
+  }
+}
+
+fn validate_value(
+  field: &TreatmentField,
+  value: &Value,
+) -> Result<(), AppError> {
+  let valid = match field.field_type.as_str() {
+    "TEXT" => value.is_string(),
+    "NUMBER" => value.is_number(),
+    "BOOLEAN" => value.is_boolean(),
+    "SELECT" => value.as_str().is_some_and(|value| {
+      field
+        .options
+        .as_ref()
+        .and_then(|options| options.as_array())
+        .is_some_and(|options| options.iter().any(|option| option.as_str() == Some(value)))
+    }),
+    _ => false,
+  };
+
+  if !valid {
+    return Err(AppError::BadRequest(format!(
+      "Invalid value for field {} ({})",
+      field.field_key, field.field_type
+    )));
+  }
+
+  Ok(())
+}
+
+// Kiểm tra các key được gửi lên theo trường đang hoạt động, sau đó kiểm tra trường bắt buộc trên kết quả cuối
+fn validate_fields(
+  definitions: &[TreatmentField],
+  changes: &Map<String, Value>,
+  result: &Map<String, Value>,
+) -> Result<(), AppError> {
+  for (key, value) in changes {
+    let field = definitions
+      .iter()
+      .find(|field| &field.field_key == key)
+      .ok_or_else(|| AppError::BadRequest(format!("Unknown treatment field {}", key)))?;
+
+    if !value.is_null() {
+      validate_value(field, value)?;
+    }
+  }
+
+  for field in definitions.iter().filter(|field| field.is_required) {
+    if result.get(&field.field_key).is_none_or(|value| value.is_null()) {
+      return Err(AppError::BadRequest(format!("Field {} is required", field.field_key)));
+    }
+  }
+
+  Ok(())
+}
+
+// Chỉ kỹ thuật viên lập hồ sơ hoặc quản trị viên được sửa
+fn ensure_author(
+  user: &UserWithPassword,
+  record: &TreatmentRecord,
+) -> Result<(), AppError> {
+  if user.role != "ADMIN" && record.technician_id != user.pk_user_id {
+    return Err(AppError::Forbidden(
+      "Only the authoring technician can edit this record".to_string(),
+    ));
+  }
+
+  Ok(())
+}
+
+fn customer_view(
+  detail: TreatmentRecordDetail,
+  definitions: &[TreatmentField],
+) -> CustomerTreatmentRecord {
+  let record = detail.record;
+
+  let fields = match record.fields {
+    Value::Object(map) => Value::Object(
+      map
+        .into_iter()
+        .filter(|(key, _)| {
+          !definitions.iter().any(|field| {
+            &field.field_key == key
+              && field.is_internal
+              && (field.service_id.is_none() || field.service_id == record.service_id)
+          })
+        })
+        .collect(),
+    ),
+    other => other,
+  };
+
+  CustomerTreatmentRecord {
+    id: record.id,
+    appointment_id: record.appointment_id,
+    service_id: record.service_id,
+    service_name: record.service_name,
+    technician_name: record.technician_name,
+    skin_type: record.skin_type,
+    allergies: record.allergies,
+    reactions: record.reactions,
+    notes: record.notes,
+    fields,
+    photos: detail.photos,
+    created_at: record.created_at,
+    updated_at: record.updated_at,
+  }
+}
+
+pub struct TreatmentUseCase;
+
+impl TreatmentUseCase {
+  pub async fn get_fields(
+    repo: &dyn TreatmentRepository,
+    service_id: Option<i64>,
+  ) -> AppResult<Vec<TreatmentField>> {
+    repo.get_fields(service_id, false).await
+  }
+
+  pub async fn create_field(
+    repo: &dyn TreatmentRepository,
+    mut payload: CreateTreatmentFieldRequest,
+    created_by: i64,
+  ) -> AppResult<TreatmentField> {
+    payload.field_key = payload.field_key.trim().to_lowercase();
+    payload.label = payload.label.trim().to_string();
+    payload.field_type = payload.field_type.trim().to_uppercase();
+
+    if payload.field_key.is_empty()
+      || payload.field_key.len() > 50
+      || !payload.field_key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+      return Err(AppError::BadRequest(
+        "field_key must contain only letters, digits and underscores".to_string(),
+      ));
+    }
+
+    if payload.label.is_empty() {
+      return Err(AppError::BadRequest("Label is required".to_string()));
+    }
+
+    if !TREATMENT_FIELD_TYPES.contains(&payload.field_type.as_str()) {
+      return Err(AppError::BadRequest(format!(
+        "Invalid field type, expected one of {}",
+        TREATMENT_FIELD_TYPES.join(", ")
+      )));
+    }
+
+    validate_options(&payload.field_type, &payload.options)?;
+
+    repo.create_field(payload, created_by).await
+  }
+
+  pub async fn update_field(
+    repo: &dyn TreatmentRepository,
+    id: i64,
+    mut payload: UpdateTreatmentFieldRequest,
+  ) -> AppResult<TreatmentField> {
+    let current = repo
+      .get_fields(None, true)
+      .await?
+      .into_iter()
+      .find(|field| field.id == id)
+      .ok_or(AppError::NotFound)?;
+
+    payload.label = clean(payload.label);
+
+    if payload.options.is_some() {
+      validate_options(&current.field_type, &payload.options)?;
+    }
+
+    repo.update_field(id, payload).await
+  }
+
+  pub async fn create_record(
+    repo: &dyn TreatmentRepository,
+    user: UserWithPassword,
+    mut payload: CreateTreatmentRecordRequest,
+  ) -> AppResult<TreatmentRecordDetail> {
+    // Lấy khách hàng và nhóm dịch vụ từ lịch hẹn nếu có
+    if payload.appointment_id.is_some() || payload.appointment_service_id.is_some() {
+      let context =
+        repo.get_context(payload.appointment_id, payload.appointment_service_id).await?;
+      let customer_id = context
+        .customer_id
+        .ok_or_else(|| AppError::BadRequest("Appointment has no customer".to_string()))?;
+
+      if payload.customer_id.is_some_and(|id| id != customer_id) {
+        return Err(AppError::BadRequest("Customer does not match the appointment".to_string()));
+      }
+
+      payload.customer_id = Some(customer_id);
+      payload.appointment_id = Some(context.appointment_id);
+      if payload.appointment_service_id.is_some() {
+        payload.service_id = context.service_id;
+      }
+    }
+
+    let customer_id = payload.customer_id.ok_or_else(|| {
+      AppError::BadRequest("customer_id or appointment_id is required".to_string())
+    })?;
+
+    if !repo.is_customer(customer_id).await? {
+      return Err(AppError::BadRequest("Customer not found".to_string()));
+    }
+
+    let fields = as_object(payload.fields.take())?;
+    let definitions = repo.get_fields(payload.service_id, false).await?;
+    validate_fields(&definitions, &fields, &fields)?;
+
+    let fields = fields.into_iter().filter(|(_, value)| !value.is_null()).collect();
+    payload.fields = Some(Value::Object(fields));
+    payload.skin_type = clean(payload.skin_type);
+    payload.allergies = clean(payload.allergies);
+    payload.machine_settings = clean(payload.machine_settings);
+    payload.reactions = clean(payload.reactions);
+    payload.notes = clean(payload.notes);
+    payload.internal_notes = clean(payload.internal_notes);
+
+    repo.create_record(customer_id, user.pk_user_id, payload).await
+  }
+
+  pub async fn get_record(
+    repo: &dyn TreatmentRepository,
+    id: i64,
+  ) -> AppResult<TreatmentRecordDetail> {
+    repo.get_record(id).await
+  }
+
+  pub async fn get_records(
+    repo: &dyn TreatmentRepository,
+    filter: TreatmentRecordFilter,
+  ) -> AppResult<Vec<TreatmentRecord>> {
+    repo.get_records(filter).await
+  }
+
+  pub async fn update_record(
+    repo: &dyn TreatmentRepository,
+    user: UserWithPassword,
+    id: i64,
+    mut payload: UpdateTreatmentRecordRequest,
+  ) -> AppResult<TreatmentRecordDetail> {
+    let current = repo.get_record(id).await?.record;
+    ensure_author(&user, &current)?;
+
+    if let Some(fields) = payload.fields.take() {
+      let changes = as_object(Some(fields))?;
+      let mut merged = as_object(Some(current.fields))?;
+
+      for (key, value) in &changes {
+        match value {
+          Value::Null => merged.remove(key),
+          value => merged.insert(key.clone(), value.clone()),
+        };
+      }
+
+      let definitions = repo.get_fields(current.service_id, false).await?;
+      validate_fields(&definitions, &changes, &merged)?;
+
+      payload.fields = Some(Value::Object(merged));
+    }
+
+    repo.update_record(id, user.pk_user_id, payload).await
+  }
+
+  pub async fn get_record_history(
+    repo: &dyn TreatmentRepository,
+    id: i64,
+  ) -> AppResult<Vec<TreatmentRecordHistory>> {
+    repo.get_record(id).await?;
+
+    repo.get_record_history(id).await
+  }
+
+  pub async fn get_my_records(
+    repo: &dyn TreatmentRepository,
+    user: UserWithPassword,
+  ) -> AppResult<Vec<CustomerTreatmentRecord>> {
+    let records = repo.get_customer_records(user.pk_user_id).await?;
+    let definitions = repo.get_fields(None, true).await?;
+
+    Ok(records.into_iter().map(|detail| customer_view(detail, &definitions)).collect())
+  }
+
+  pub async fn add_photo(
+    repo: &dyn TreatmentRepository,
+    image_service: Arc<dyn ImageRepository>,
+    user: UserWithPassword,
+    record_id: i64,
+    data: &[u8],
+    content_type: &str,
+    payload: CreateTreatmentPhotoRequest,
+  ) -> AppResult<TreatmentPhoto> {
+    let record = repo.get_record(record_id).await?.record;
+    ensure_author(&user, &record)?;
+
+    let photo_type = payload.photo_type.trim().to_uppercase();
+    if !TREATMENT_PHOTO_TYPES.contains(&photo_type.as_str()) {
+      return Err(AppError::BadRequest("photo_type must be BEFORE or AFTER".to_string()));
+    }
+
+    if data.is_empty() {
+      return Err(AppError::BadRequest("Image is required".to_string()));
+    }
+
+    const MAX_FILE_SIZE: usize = 5 * 1024 * 1024; // 5MB
+    const MAX_WIDTH: u32 = 1600; // Giữ đủ chi tiết da để so sánh trước / sau
+    const QUALITY: u8 = 85;
+
+    let image_path = image_service
+      .upload_and_resize(
+        data,
+        content_type,
+        record.customer_id,
+        MAX_FILE_SIZE,
+        MAX_WIDTH,
+        QUALITY,
+        "treatments",
+      )
+      .await?;
+
+    match repo
+      .add_photo(record_id, photo_type, image_path.clone(), clean(payload.caption), user.pk_user_id)
+      .await
+    {
+      Ok(photo) => Ok(photo),
+      Err(err) => {
+        let _ = image_service.remove_old_image(&image_path).await;
+        Err(err)
+      },
+    }
+  }
+
+  // Nhân viên xem được mọi ảnh, khách hàng chỉ xem ảnh trong hồ sơ của mình
+  pub async fn get_photo_image(
+    repo: &dyn TreatmentRepository,
+    image_service: Arc<dyn ImageRepository>,
+    user: UserWithPassword,
+    record_id: i64,
+    photo_id: i64,
+  ) -> AppResult<(Vec<u8>, String)> {
+    let detail = repo.get_record(record_id).await?;
+
+    let is_staff = matches!(user.role.as_str(), "ADMIN" | "RECEPTIONIST" | "TECHNICIAN");
+    if !is_staff && detail.record.customer_id != user.pk_user_id {
+      return Err(AppError::NotFound);
+    }
+
+    let photo =
+      detail.photos.into_iter().find(|photo| photo.id == photo_id).ok_or(AppError::NotFound)?;
+
+    image_service.read_private_image(&photo.image).await
+  }
+
+  pub async fn delete_photo(
+    repo: &dyn TreatmentRepository,
+    image_service: Arc<dyn ImageRepository>,
+    user: UserWithPassword,
+    record_id: i64,
+    photo_id: i64,
+  ) -> AppResult<()> {
+    let record = repo.get_record(record_id).await?.record;
+    ensure_author(&user, &record)?;
+
+    let photo = repo.delete_photo(record_id, photo_id).await?;
+    let _ = image_service.remove_old_image(&photo.image).await;
+
+    Ok(())
+  }
+}
@@ -1,5 +1,5 @@
 use crate::{
-  entities::user::{PhoneFilterConvert, User, UserWithPassword},
+  entities::user::{PhoneFilterConvert, TechnicianWithRating, User, UserWithPassword},
   repositories::user_repository::UserRepository,
 };
 use core_app::AppResult;
@@ -10,7 +10,7 @@ impl UserUseCase {
   pub async fn get_all_technician(
     user_repo: &dyn UserRepository,
     user: UserWithPassword,
-  ) -> AppResult<Vec<User>> {
+  ) -> AppResult<Vec<TechnicianWithRating>> {
     user_repo.get_all_technician(user).await
   }
 
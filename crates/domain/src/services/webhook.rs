@@ -0,0 +1,155 @@
+use core_app::{AppResult, configs::WebhookConfig, errors::AppError};
+use utils::helper::generate_secret;
+
+use crate::{
+  entities::webhook::{
+    CreateWebhookSubscriptionRequest, UpdateWebhookSubscriptionRequest, WEBHOOK_DELIVERY_STATUSES,
+    WEBHOOK_EVENT_TYPES, WebhookDelivery, WebhookDeliveryFilter, WebhookSubscription,
+  },
+  repositories::webhook_repository::WebhookRepository,
+};
+
+const MIN_SECRET_LENGTH: usize = 16;
+
+// Ngoài dev chỉ nhận https. Địa chỉ host được repository kiểm tra (chặn SSRF)
+fn validate_url(
+  url: &str,
+  config: &WebhookConfig,
+) -> AppResult<()> {
+  if config.require_https && !url.starts_with("https://") {
+    return Err(AppError::BadRequest("URL must start with https://".to_string()));
+  }
+  if !(url.starts_with("https://") || url.starts_with("http://")) {
+    return Err(AppError::BadRequest("URL must start with http:// or https://".to_string()));
+  }
+  if url.len() > 500 {
+    return Err(AppError::BadRequest("URL cannot exceed 500 characters".to_string()));
+  }
+
+  Ok(())
+}
+
+fn validate_secret(secret: &str) -> AppResult<()> {
+  if secret.len() < MIN_SECRET_LENGTH {
+    return Err(AppError::BadRequest(format!(
+      "Secret must be at least {} characters",
+      MIN_SECRET_LENGTH
+    )));
+  }
+
+  Ok(())
+}
+
+fn normalize_event_types(event_types: Vec<String>) -> AppResult<Vec<String>> {
+  let mut normalized: Vec<String> = vec![];
+  for event_type in event_types {
+    let event_type = event_type.trim().to_string();
+    if !WEBHOOK_EVENT_TYPES.contains(&event_type.as_str()) {
+      return Err(AppError::BadRequest(format!(
+        "Invalid event type {}, expected one of {}",
+        event_type,
+        WEBHOOK_EVENT_TYPES.join(", ")
+      )));
+    }
+    if !normalized.contains(&event_type) {
+      normalized.push(event_type);
+    }
+  }
+
+  if normalized.is_empty() {
+    return Err(AppError::BadRequest("At least one event type is required".to_string()));
+  }
+
+  Ok(normalized)
+}
+
+pub struct WebhookUseCase;
+
+impl WebhookUseCase {
+  pub async fn list(repo: &dyn WebhookRepository) -> AppResult<Vec<WebhookSubscription>> {
+    repo.list().await
+  }
+
+  pub async fn get_by_id(
+    repo: &dyn WebhookRepository,
+    id: i64,
+  ) -> AppResult<WebhookSubscription> {
+    repo.get_by_id(id).await
+  }
+
+  pub async fn create(
+    repo: &dyn WebhookRepository,
+    config: &WebhookConfig,
+    mut payload: CreateWebhookSubscriptionRequest,
+    created_by: i64,
+  ) -> AppResult<WebhookSubscription> {
+    payload.url = payload.url.trim().to_string();
+    validate_url(&payload.url, config)?;
+    repo.check_public_url(&payload.url).await?;
+    payload.event_types = normalize_event_types(payload.event_types)?;
+
+    let secret = payload.secret.as_deref().map(str::trim).unwrap_or_default().to_string();
+    payload.secret = if secret.is_empty() {
+      Some(generate_secret())
+    } else {
+      validate_secret(&secret)?;
+      Some(secret)
+    };
+
+    repo.create(payload, created_by).await
+  }
+
+  pub async fn update(
+    repo: &dyn WebhookRepository,
+    config: &WebhookConfig,
+    id: i64,
+    mut payload: UpdateWebhookSubscriptionRequest,
+  ) -> AppResult<WebhookSubscription> {
+    if let Some(url) = payload.url.as_mut() {
+      *url = url.trim().to_string();
+      validate_url(url, config)?;
+      repo.check_public_url(url).await?;
+    }
+    if let Some(secret) = payload.secret.as_mut() {
+      *secret = secret.trim().to_string();
+      validate_secret(secret)?;
+    }
+    if let Some(event_types) = payload.event_types.take() {
+      payload.event_types = Some(normalize_event_types(event_types)?);
+    }
+
+    repo.update(id, payload).await
+  }
+
+  pub async fn delete(
+    repo: &dyn WebhookRepository,
+    id: i64,
+  ) -> AppResult<()> {
+    repo.delete(id).await
+  }
+
+  pub async fn list_deliveries(
+    repo: &dyn WebhookRepository,
+    subscription_id: i64,
+    filter: WebhookDeliveryFilter,
+  ) -> AppResult<Vec<WebhookDelivery>> {
+    if let Some(status) = filter.status.as_deref() {
+      if !WEBHOOK_DELIVERY_STATUSES.contains(&status) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid status, expected one of {}",
+          WEBHOOK_DELIVERY_STATUSES.join(", ")
+        )));
+      }
+    }
+
+    repo.get_by_id(subscription_id).await?;
+    repo.list_deliveries(subscription_id, filter).await
+  }
+
+  pub async fn redeliver(
+    repo: &dyn WebhookRepository,
+    delivery_id: i64,
+  ) -> AppResult<WebhookDelivery> {
+    repo.redeliver(delivery_id).await
+  }
+}
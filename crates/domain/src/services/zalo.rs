@@ -0,0 +1,96 @@
+use core_app::{AppResult, errors::AppError};
+
+use crate::{
+  entities::zalo::{
+    UpdateZaloEventTemplateRequest, ZALO_EVENT_TYPES, ZALO_MESSAGE_STATUSES, ZaloEventTemplate,
+    ZaloMessage, ZaloMessageFilter, ZaloWebhookEvent,
+  },
+  repositories::zalo_repository::ZaloRepository,
+  services::notification_template::render_template,
+};
+
+/// Dựng template_data gửi Zalo từ params của mẫu, thay các biến {ten_bien} bằng dữ liệu tương ứng
+pub fn render_params(
+  params: &serde_json::Value,
+  variables: &serde_json::Value,
+) -> serde_json::Value {
+  let rendered = params
+    .as_object()
+    .map(|params| {
+      params
+        .iter()
+        .map(|(key, value)| {
+          let value = render_template(value.as_str().unwrap_or_default(), variables);
+          (key.clone(), serde_json::Value::String(value))
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+
+  serde_json::Value::Object(rendered)
+}
+
+pub struct ZaloUseCase;
+
+impl ZaloUseCase {
+  pub async fn list_templates(repo: &dyn ZaloRepository) -> AppResult<Vec<ZaloEventTemplate>> {
+    repo.list_templates().await
+  }
+
+  pub async fn update_template(
+    repo: &dyn ZaloRepository,
+    event_type: &str,
+    mut payload: UpdateZaloEventTemplateRequest,
+  ) -> AppResult<ZaloEventTemplate> {
+    if !ZALO_EVENT_TYPES.contains(&event_type) {
+      return Err(AppError::BadRequest(format!(
+        "Invalid event type, expected one of {}",
+        ZALO_EVENT_TYPES.join(", ")
+      )));
+    }
+
+    if let Some(template_id) = payload.template_id.as_mut() {
+      *template_id = template_id.trim().to_string();
+      if template_id.is_empty() {
+        return Err(AppError::BadRequest("Template ID cannot be empty".to_string()));
+      }
+    }
+
+    // Tham số ZNS đều là chuỗi, giá trị có thể chứa biến {ten_bien} của lịch hẹn
+    if let Some(params) = payload.params.as_ref() {
+      let valid = params
+        .as_object()
+        .map(|params| params.values().all(|value| value.is_string()))
+        .unwrap_or(false);
+      if !valid {
+        return Err(AppError::BadRequest("Params must be an object of string values".to_string()));
+      }
+    }
+
+    repo.update_template(event_type, payload).await
+  }
+
+  pub async fn list_messages(
+    repo: &dyn ZaloRepository,
+    filter: ZaloMessageFilter,
+  ) -> AppResult<Vec<ZaloMessage>> {
+    if let Some(status) = filter.status.as_deref() {
+      if !ZALO_MESSAGE_STATUSES.contains(&status) {
+        return Err(AppError::BadRequest(format!(
+          "Invalid status, expected one of {}",
+          ZALO_MESSAGE_STATUSES.join(", ")
+        )));
+      }
+    }
+
+    repo.list_messages(filter).await
+  }
+
+  pub async fn record_callback(
+    repo: &dyn ZaloRepository,
+    event: ZaloWebhookEvent,
+    payload: serde_json::Value,
+  ) -> AppResult<()> {
+    repo.record_callback(event, payload).await
+  }
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use core_app::configs::SpaConfig;
+use domain::entities::consent::ConsentQuestion;
+use image::imageops::FilterType;
+use serde_json::{Map, Value};
+use utils::helper::remove_vietnamese_accents;
+
+use crate::documents::{TextLine, pdf::PdfImage, row, wrap};
+
+// Kích thước tối đa của ảnh chữ ký nhúng vào PDF (pixel)
+const SIGNATURE_MAX_WIDTH: u32 = 400;
+const SIGNATURE_MAX_HEIGHT: u32 = 160;
+
+pub struct ConsentDocument<'a> {
+  pub spa: &'a SpaConfig,
+  pub title: &'a str,
+  pub description: Option<&'a str>,
+  pub customer_name: Option<&'a str>,
+  pub customer_phone: Option<&'a str>,
+  pub appointment_id: i64,
+  pub appointment_time: Option<&'a str>,
+  pub questions: &'a [ConsentQuestion],
+  pub answers: &'a Map<String, Value>,
+  pub signed_at: DateTime<Utc>,
+  pub ip_address: Option<&'a str>,
+}
+
+fn answer_text(
+  question: &ConsentQuestion,
+  answer: Option<&Value>,
+) -> String {
+  match answer {
+    None | Some(Value::Null) => "(khong tra loi)".to_string(),
+    Some(Value::Bool(true)) if question.question_type == "BOOLEAN" => "[x] Co".to_string(),
+    Some(Value::Bool(false)) => "[ ] Khong".to_string(),
+    Some(Value::String(text)) => remove_vietnamese_accents(text),
+    Some(other) => other.to_string(),
+  }
+}
+
+// Dàn trang phiếu đã ký, nội dung bỏ dấu vì font chuẩn của PDF không đủ ký tự tiếng Việt
+pub fn layout_consent(
+  document: &ConsentDocument,
+  width: usize,
+) -> Vec<TextLine> {
+  let text = |value: &str| remove_vietnamese_accents(value);
+  let utc_plus_7 = FixedOffset::east_opt(7 * 3600).unwrap();
+
+  let mut lines = vec![TextLine::center(text(&document.spa.name)).bold().large()];
+  for info in [text(&document.spa.address), text(&document.spa.phone)] {
+    for line in wrap(&info, width) {
+      lines.push(TextLine::center(line));
+    }
+  }
+
+  lines.push(TextLine::left(""));
+  for line in wrap(&text(document.title).to_uppercase(), width) {
+    lines.push(TextLine::center(line).bold());
+  }
+  if let Some(description) = document.description {
+    lines.push(TextLine::left(""));
+    for line in wrap(&text(description), width) {
+      lines.push(TextLine::left(line));
+    }
+  }
+
+  lines.push(TextLine::left(""));
+  if let Some(name) = document.customer_name {
+    lines.push(row("Khach hang:", &text(name), width));
+  }
+  if let Some(phone) = document.customer_phone {
+    lines.push(row("SDT:", phone, width));
+  }
+  lines.push(row("Lich hen:", &format!("#{}", document.appointment_id), width));
+  if let Some(time) = document.appointment_time {
+    lines.push(row("Thoi gian hen:", time, width));
+  }
+  lines.push(TextLine::left("-".repeat(width)));
+
+  for (index, question) in document.questions.iter().enumerate() {
+    let label = format!("{}. {}", index + 1, text(&question.label));
+    for line in wrap(&label, width) {
+      lines.push(TextLine::left(line).bold());
+    }
+    let answer = answer_text(question, document.answers.get(&question.key));
+    for line in wrap(&answer, width.saturating_sub(3)) {
+      lines.push(TextLine::left(format!("   {}", line)));
+    }
+  }
+
+  lines.push(TextLine::left("-".repeat(width)));
+  let signed_at =
+    document.signed_at.with_timezone(&utc_plus_7).format("%d/%m/%Y %H:%M:%S").to_string();
+  lines.push(row("Ky luc (UTC+7):", &signed_at, width));
+  if let Some(ip_address) = document.ip_address {
+    lines.push(row("Dia chi IP:", ip_address, width));
+  }
+  lines.push(TextLine::left(""));
+  lines.push(TextLine::left("Chu ky khach hang:").bold());
+
+  lines
+}
+
+// Giải mã ảnh chữ ký, nền trong suốt được phủ trắng
+pub fn decode_signature(data: &[u8]) -> Option<PdfImage> {
+  let image = image::load_from_memory(data).ok()?;
+  let image = if image.width() > SIGNATURE_MAX_WIDTH || image.height() > SIGNATURE_MAX_HEIGHT {
+    image.resize(SIGNATURE_MAX_WIDTH, SIGNATURE_MAX_HEIGHT, FilterType::Triangle)
+  } else {
+    image
+  };
+  let rgba = image.to_rgba8();
+
+  let mut rgb = Vec::with_capacity(rgba.len() / 4 * 3);
+  for pixel in rgba.pixels() {
+    let alpha = pixel[3] as u32;
+    for channel in &pixel.0[..3] {
+      rgb.push(((*channel as u32 * alpha + 255 * (255 - alpha)) / 255) as u8);
+    }
+  }
+
+  Some(PdfImage { width: rgba.width(), height: rgba.height(), rgb })
+}
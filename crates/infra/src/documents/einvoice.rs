@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use core_app::configs::SpaConfig;
+use domain::entities::invoice::{Invoice, InvoiceBuyer};
+
+// Phiên bản định dạng XML hoá đơn điện tử theo Quyết định 1450/QĐ-TCT
+const XML_VERSION: &str = "2.0.1";
+
+#[derive(Debug, Clone)]
+pub struct EInvoiceLine {
+  pub line_type: String,
+  pub description: String,
+  pub unit: String,
+  pub quantity: i32,
+  pub unit_price: i64,
+  pub amount: i64,
+  pub vat_rate: i32,
+  pub vat_amount: i64,
+}
+
+pub struct EInvoiceDocument<'a> {
+  pub invoice_type: &'a str,
+  pub template_code: &'a str,
+  pub symbol: &'a str,
+  pub invoice_number: i64,
+  pub issued_date: NaiveDate,
+  pub payment_method: &'a str,
+  pub seller: &'a SpaConfig,
+  pub buyer: &'a InvoiceBuyer,
+  pub lines: &'a [EInvoiceLine],
+  pub total_before_tax: i64,
+  pub total_vat: i64,
+  pub total_amount: i64,
+  pub amount_in_words: &'a str,
+  pub reason: Option<&'a str>,
+  // Hoá đơn bị thay thế / điều chỉnh
+  pub original: Option<&'a Invoice>,
+}
+
+fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&apos;"),
+      c if c.is_control() && c != '\n' && c != '\t' => {},
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+fn tag(
+  xml: &mut String,
+  name: &str,
+  value: &str,
+) {
+  xml.push_str(&format!("<{}>{}</{}>", name, escape(value), name));
+}
+
+// XML hoá đơn GTGT theo cấu trúc chung của các nhà cung cấp hoá đơn điện tử (phần DLHDon, chưa ký số)
+pub fn build_einvoice_xml(document: &EInvoiceDocument) -> String {
+  let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+  xml.push_str("<HDon><DLHDon Id=\"data\">");
+
+  // Thông tin chung
+  xml.push_str("<TTChung>");
+  tag(&mut xml, "PBan", XML_VERSION);
+  tag(&mut xml, "THDon", "HÓA ĐƠN GIÁ TRỊ GIA TĂNG");
+  tag(&mut xml, "KHMSHDon", document.template_code);
+  tag(&mut xml, "KHHDon", document.symbol);
+  tag(&mut xml, "SHDon", &document.invoice_number.to_string());
+  tag(&mut xml, "NLap", &document.issued_date.format("%Y-%m-%d").to_string());
+  tag(&mut xml, "DVTTe", "VND");
+  tag(&mut xml, "TGia", "1");
+  tag(&mut xml, "HTTToan", document.payment_method);
+  if let Some(original) = document.original {
+    // 1: thay thế, 2: điều chỉnh
+    let relation = if document.invoice_type == "REPLACEMENT" { "1" } else { "2" };
+    xml.push_str("<TTHDLQuan>");
+    tag(&mut xml, "TCHDon", relation);
+    tag(&mut xml, "LHDCLQuan", "1");
+    tag(&mut xml, "KHMSHDCLQuan", &original.template_code);
+    tag(&mut xml, "KHHDCLQuan", &original.symbol);
+    tag(&mut xml, "SHDCLQuan", &original.invoice_number.to_string());
+    tag(&mut xml, "NLHDCLQuan", &original.issued_date.format("%Y-%m-%d").to_string());
+    tag(&mut xml, "GChu", document.reason.unwrap_or_default());
+    xml.push_str("</TTHDLQuan>");
+  }
+  xml.push_str("</TTChung>");
+
+  xml.push_str("<NDHDon>");
+
+  // Người bán
+  xml.push_str("<NBan>");
+  tag(&mut xml, "Ten", &document.seller.name);
+  tag(&mut xml, "MST", &document.seller.tax_code);
+  tag(&mut xml, "DChi", &document.seller.address);
+  tag(&mut xml, "SDThoai", &document.seller.phone);
+  xml.push_str("</NBan>");
+
+  // Người mua
+  xml.push_str("<NMua>");
+  tag(&mut xml, "Ten", &document.buyer.buyer_company_name);
+  tag(&mut xml, "MST", &document.buyer.buyer_tax_code);
+  tag(&mut xml, "DChi", &document.buyer.buyer_address);
+  if let Some(name) = document.buyer.buyer_name.as_deref() {
+    tag(&mut xml, "HVTNMHang", name);
+  }
+  if let Some(email) = document.buyer.buyer_email.as_deref() {
+    tag(&mut xml, "DCTDTu", email);
+  }
+  xml.push_str("</NMua>");
+
+  // Danh sách dịch vụ; dòng chiết khấu (TChat = 3) ghi số dương
+  xml.push_str("<DSHHDVu>");
+  for (index, line) in document.lines.iter().enumerate() {
+    let is_discount = line.line_type == "DISCOUNT";
+    let money = |value: i64| if is_discount { value.abs() } else { value }.to_string();
+
+    xml.push_str("<HHDVu>");
+    tag(&mut xml, "TChat", if is_discount { "3" } else { "1" });
+    tag(&mut xml, "STT", &(index + 1).to_string());
+    tag(&mut xml, "THHDVu", &line.description);
+    tag(&mut xml, "DVTinh", &line.unit);
+    tag(&mut xml, "SLuong", &line.quantity.to_string());
+    tag(&mut xml, "DGia", &money(line.unit_price));
+    tag(&mut xml, "ThTien", &money(line.amount));
+    tag(&mut xml, "TSuat", &format!("{}%", line.vat_rate));
+    tag(&mut xml, "TThue", &money(line.vat_amount));
+    xml.push_str("</HHDVu>");
+  }
+  xml.push_str("</DSHHDVu>");
+
+  // Tổng hợp theo từng thuế suất
+  let mut by_rate: BTreeMap<i32, (i64, i64)> = BTreeMap::new();
+  for line in document.lines {
+    let entry = by_rate.entry(line.vat_rate).or_default();
+    entry.0 += line.amount;
+    entry.1 += line.vat_amount;
+  }
+
+  xml.push_str("<TToan><THTTLTSuat>");
+  for (vat_rate, (amount, vat_amount)) in by_rate {
+    xml.push_str("<LTSuat>");
+    tag(&mut xml, "TSuat", &format!("{}%", vat_rate));
+    tag(&mut xml, "ThTien", &amount.to_string());
+    tag(&mut xml, "TThue", &vat_amount.to_string());
+    xml.push_str("</LTSuat>");
+  }
+  xml.push_str("</THTTLTSuat>");
+  tag(&mut xml, "TgTCThue", &document.total_before_tax.to_string());
+  tag(&mut xml, "TgTThue", &document.total_vat.to_string());
+  tag(&mut xml, "TgTTTBSo", &document.total_amount.to_string());
+  tag(&mut xml, "TgTTTBChu", document.amount_in_words);
+  xml.push_str("</TToan>");
+
+  xml.push_str("</NDHDon></DLHDon></HDon>");
+
+  xml
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::Utc;
+
+  use super::*;
+
+  fn line(
+    line_type: &str,
+    description: &str,
+    amount: i64,
+    vat_rate: i32,
+    vat_amount: i64,
+  ) -> EInvoiceLine {
+    EInvoiceLine {
+      line_type: line_type.to_string(),
+      description: description.to_string(),
+      unit: "Lượt".to_string(),
+      quantity: 1,
+      unit_price: amount,
+      amount,
+      vat_rate,
+      vat_amount,
+    }
+  }
+
+  fn seller() -> SpaConfig {
+    SpaConfig {
+      name: "NaSpa & Beauty".to_string(),
+      address: "1 Lê Lợi".to_string(),
+      phone: "0900000000".to_string(),
+      tax_code: "0312345678".to_string(),
+    }
+  }
+
+  fn buyer() -> InvoiceBuyer {
+    InvoiceBuyer {
+      buyer_tax_code: "0109876543".to_string(),
+      buyer_company_name: "Công ty <A> \"B\"".to_string(),
+      buyer_address: "2 Hai Bà Trưng\u{0}".to_string(),
+      buyer_name: None,
+      buyer_email: Some("ketoan@example.com".to_string()),
+    }
+  }
+
+  fn original() -> Invoice {
+    Invoice {
+      id: 1,
+      invoice_type: "ORIGINAL".to_string(),
+      original_invoice_id: None,
+      template_code: "1".to_string(),
+      symbol: "C25TNS".to_string(),
+      invoice_number: 7,
+      issued_date: NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+      status: "ISSUED".to_string(),
+      buyer_tax_code: String::new(),
+      buyer_company_name: String::new(),
+      buyer_address: String::new(),
+      buyer_name: None,
+      buyer_email: None,
+      payment_method: "TM/CK".to_string(),
+      total_before_tax: 0,
+      total_vat: 0,
+      total_amount: 0,
+      amount_in_words: String::new(),
+      reason: None,
+      xml_content: String::new(),
+      submission_status: "PENDING".to_string(),
+      provider: None,
+      provider_reference: None,
+      submission_error: None,
+      submitted_at: None,
+      created_by: 1,
+      created_at: Utc::now(),
+      updated_at: Utc::now(),
+    }
+  }
+
+  fn build(
+    invoice_type: &str,
+    lines: &[EInvoiceLine],
+    original: Option<&Invoice>,
+  ) -> String {
+    let seller = seller();
+    let buyer = buyer();
+    build_einvoice_xml(&EInvoiceDocument {
+      invoice_type,
+      template_code: "1",
+      symbol: "C25TNS",
+      invoice_number: 8,
+      issued_date: NaiveDate::from_ymd_opt(2025, 3, 2).unwrap(),
+      payment_method: "TM/CK",
+      seller: &seller,
+      buyer: &buyer,
+      lines,
+      total_before_tax: 0,
+      total_vat: 0,
+      total_amount: 0,
+      amount_in_words: "Không đồng",
+      reason: Some("Sai tên người mua"),
+      original,
+    })
+  }
+
+  #[test]
+  fn escapes_text_and_drops_control_characters() {
+    let xml = build("ORIGINAL", &[], None);
+
+    assert!(xml.contains("<Ten>NaSpa &amp; Beauty</Ten>"));
+    assert!(xml.contains("<Ten>Công ty &lt;A&gt; &quot;B&quot;</Ten>"));
+    assert!(xml.contains("<DChi>2 Hai Bà Trưng</DChi>"));
+    assert!(xml.contains("<DCTDTu>ketoan@example.com</DCTDTu>"));
+    assert!(!xml.contains("<HVTNMHang>"));
+    assert!(!xml.contains("<TTHDLQuan>"));
+  }
+
+  #[test]
+  fn discount_lines_are_written_as_positive_amounts() {
+    let lines = [
+      line("SERVICE", "Massage", 324_074, 8, 25_926),
+      line("DISCOUNT", "Chiết khấu", -46_296, 8, -3_704),
+    ];
+    let xml = build("ORIGINAL", &lines, None);
+
+    assert!(xml.contains(
+      "<HHDVu><TChat>1</TChat><STT>1</STT><THHDVu>Massage</THHDVu><DVTinh>Lượt</DVTinh>\
+       <SLuong>1</SLuong><DGia>324074</DGia><ThTien>324074</ThTien><TSuat>8%</TSuat>\
+       <TThue>25926</TThue></HHDVu>"
+    ));
+    assert!(xml.contains(
+      "<HHDVu><TChat>3</TChat><STT>2</STT><THHDVu>Chiết khấu</THHDVu><DVTinh>Lượt</DVTinh>\
+       <SLuong>1</SLuong><DGia>46296</DGia><ThTien>46296</ThTien><TSuat>8%</TSuat>\
+       <TThue>3704</TThue></HHDVu>"
+    ));
+    // Tổng theo thuế suất vẫn trừ phần chiết khấu
+    assert!(
+      xml.contains("<LTSuat><TSuat>8%</TSuat><ThTien>277778</ThTien><TThue>22222</TThue></LTSuat>")
+    );
+  }
+
+  #[test]
+  fn totals_are_grouped_by_vat_rate() {
+    let lines = [
+      line("SERVICE", "Massage", 100_000, 10, 10_000),
+      line("SERVICE", "Xông hơi", 200_000, 8, 16_000),
+      line("SERVICE", "Gội đầu", 50_000, 8, 4_000),
+    ];
+    let xml = build("ORIGINAL", &lines, None);
+
+    let rate_8 = "<LTSuat><TSuat>8%</TSuat><ThTien>250000</ThTien><TThue>20000</TThue></LTSuat>";
+    let rate_10 = "<LTSuat><TSuat>10%</TSuat><ThTien>100000</ThTien><TThue>10000</TThue></LTSuat>";
+    assert!(xml.contains(&format!("<THTTLTSuat>{}{}</THTTLTSuat>", rate_8, rate_10)));
+  }
+
+  #[test]
+  fn related_invoice_relation_depends_on_type() {
+    let original = original();
+
+    for (invoice_type, relation) in [("REPLACEMENT", "1"), ("ADJUSTMENT", "2")] {
+      let xml = build(invoice_type, &[], Some(&original));
+
+      assert!(xml.contains(&format!(
+        "<TTHDLQuan><TCHDon>{}</TCHDon><LHDCLQuan>1</LHDCLQuan><KHMSHDCLQuan>1</KHMSHDCLQuan>\
+         <KHHDCLQuan>C25TNS</KHHDCLQuan><SHDCLQuan>7</SHDCLQuan>\
+         <NLHDCLQuan>2025-03-01</NLHDCLQuan><GChu>Sai tên người mua</GChu></TTHDLQuan>",
+        relation
+      )));
+    }
+  }
+}
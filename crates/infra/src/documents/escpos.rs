@@ -0,0 +1,32 @@
+use crate::documents::{Align, TextLine};
+
+// Khổ 80mm, font A: 48 ký tự mỗi dòng
+pub const ESCPOS_COLUMNS: usize = 48;
+
+const ESC: u8 = 0x1b;
+const GS: u8 = 0x1d;
+
+// Lệnh ESC/POS cho máy in nhiệt: khởi tạo, in từng dòng rồi đẩy giấy và cắt
+pub fn render_escpos(lines: &[TextLine]) -> Vec<u8> {
+  let mut bytes = vec![ESC, b'@'];
+
+  for line in lines {
+    let align = match line.align {
+      Align::Left => 0,
+      Align::Center => 1,
+    };
+    bytes.extend_from_slice(&[ESC, b'a', align]);
+    bytes.extend_from_slice(&[ESC, b'E', line.bold as u8]);
+    bytes.extend_from_slice(&[GS, b'!', if line.large { 0x11 } else { 0x00 }]);
+
+    // Máy in dùng bảng mã ASCII, ký tự ngoài bảng thay bằng '?'
+    bytes.extend(line.text.chars().map(|c| if c.is_ascii() { c as u8 } else { b'?' }));
+    bytes.push(b'\n');
+  }
+
+  bytes.extend_from_slice(&[ESC, b'E', 0, GS, b'!', 0]);
+  bytes.extend_from_slice(&[ESC, b'd', 4]);
+  bytes.extend_from_slice(&[GS, b'V', 66, 0]);
+
+  bytes
+}
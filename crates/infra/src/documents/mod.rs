@@ -0,0 +1,81 @@
+pub mod consent;
+pub mod einvoice;
+pub mod escpos;
+pub mod pdf;
+pub mod receipt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+  Left,
+  Center,
+}
+
+// Một dòng chữ đơn cách (monospace), dùng chung cho bản in PDF và máy in nhiệt
+#[derive(Debug, Clone)]
+pub struct TextLine {
+  pub text: String,
+  pub align: Align,
+  pub bold: bool,
+  // Chữ cỡ lớn cho tiêu đề
+  pub large: bool,
+}
+
+impl TextLine {
+  pub fn left(text: impl Into<String>) -> Self {
+    Self { text: text.into(), align: Align::Left, bold: false, large: false }
+  }
+
+  pub fn center(text: impl Into<String>) -> Self {
+    Self { text: text.into(), align: Align::Center, bold: false, large: false }
+  }
+
+  pub fn bold(mut self) -> Self {
+    self.bold = true;
+    self
+  }
+
+  pub fn large(mut self) -> Self {
+    self.large = true;
+    self
+  }
+}
+
+// Cột trái và cột phải trên cùng một dòng, cột trái bị cắt nếu quá dài
+pub fn row(
+  label: &str,
+  value: &str,
+  width: usize,
+) -> TextLine {
+  let value_len = value.chars().count();
+  let max_label = width.saturating_sub(value_len + 1);
+  let label: String = label.chars().take(max_label).collect();
+  let padding = width.saturating_sub(label.chars().count() + value_len);
+
+  TextLine::left(format!("{}{}{}", label, " ".repeat(padding), value))
+}
+
+// Ngắt dòng theo từ cho vừa khổ giấy
+pub fn wrap(
+  text: &str,
+  width: usize,
+) -> Vec<String> {
+  let mut lines = Vec::new();
+  let mut current = String::new();
+
+  for word in text.split_whitespace() {
+    let word: String = word.chars().take(width).collect();
+    if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+      lines.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+      current.push(' ');
+    }
+    current.push_str(&word);
+  }
+
+  if !current.is_empty() {
+    lines.push(current);
+  }
+
+  lines
+}
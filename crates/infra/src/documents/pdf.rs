@@ -0,0 +1,200 @@
+use crate::documents::{Align, TextLine};
+
+const MARGIN: f32 = 36.0;
+const FONT_SIZE: f32 = 10.0;
+const LARGE_FONT_SIZE: f32 = 14.0;
+const LEADING: f32 = 13.0;
+// Courier: mỗi ký tự rộng 0.6 lần cỡ chữ
+const CHAR_WIDTH: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperSize {
+  A4,
+  A5,
+}
+
+impl PaperSize {
+  pub fn from_name(name: Option<&str>) -> Option<Self> {
+    match name.map(|name| name.to_uppercase()).as_deref() {
+      None | Some("A4") => Some(Self::A4),
+      Some("A5") => Some(Self::A5),
+      _ => None,
+    }
+  }
+
+  // Kích thước trang theo point (1/72 inch)
+  fn dimensions(self) -> (f32, f32) {
+    match self {
+      Self::A4 => (595.0, 842.0),
+      Self::A5 => (420.0, 595.0),
+    }
+  }
+
+  // Số ký tự tối đa trên một dòng với cỡ chữ thường
+  pub fn columns(self) -> usize {
+    let (width, _) = self.dimensions();
+    ((width - 2.0 * MARGIN) / (FONT_SIZE * CHAR_WIDTH)) as usize
+  }
+}
+
+fn escape(text: &str) -> String {
+  text
+    .chars()
+    .map(|c| match c {
+      '(' | ')' | '\\' => format!("\\{}", c),
+      c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+      _ => "?".to_string(),
+    })
+    .collect()
+}
+
+// Ảnh RGB 8 bit chưa nén, dùng cho chữ ký
+#[derive(Debug, Clone)]
+pub struct PdfImage {
+  pub width: u32,
+  pub height: u32,
+  pub rgb: Vec<u8>,
+}
+
+// Chiều rộng tối đa khi vẽ ảnh (point)
+const IMAGE_MAX_WIDTH: f32 = 200.0;
+
+// Tạo file PDF 1.4 tối giản với font chuẩn Courier, tự sang trang khi hết chỗ
+pub fn render_pdf(
+  lines: &[TextLine],
+  paper: PaperSize,
+) -> Vec<u8> {
+  render_pdf_with_image(lines, paper, None)
+}
+
+// Như render_pdf, ảnh (nếu có) được vẽ ngay sau dòng chữ cuối cùng
+pub fn render_pdf_with_image(
+  lines: &[TextLine],
+  paper: PaperSize,
+  image: Option<&PdfImage>,
+) -> Vec<u8> {
+  let (page_width, page_height) = paper.dimensions();
+
+  let mut pages: Vec<String> = Vec::new();
+  let mut content = String::new();
+  let mut y = page_height - MARGIN;
+
+  for line in lines {
+    let size = if line.large { LARGE_FONT_SIZE } else { FONT_SIZE };
+    let leading = if line.large { LEADING * LARGE_FONT_SIZE / FONT_SIZE } else { LEADING };
+
+    if y - leading < MARGIN {
+      pages.push(std::mem::take(&mut content));
+      y = page_height - MARGIN;
+    }
+    y -= leading;
+
+    let text_width = line.text.chars().count() as f32 * size * CHAR_WIDTH;
+    let x = match line.align {
+      Align::Left => MARGIN,
+      Align::Center => ((page_width - text_width) / 2.0).max(MARGIN),
+    };
+    let font = if line.bold { "F2" } else { "F1" };
+
+    content.push_str(&format!(
+      "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+      font,
+      size,
+      x,
+      y,
+      escape(&line.text)
+    ));
+  }
+
+  // Trang chứa ảnh dùng XObject /Im1
+  let mut image_page = None;
+  if let Some(image) = image.filter(|image| image.width > 0 && image.height > 0) {
+    let width = (image.width as f32).min(IMAGE_MAX_WIDTH);
+    let height = width * image.height as f32 / image.width as f32;
+
+    if y - height - LEADING < MARGIN {
+      pages.push(std::mem::take(&mut content));
+      y = page_height - MARGIN;
+    }
+    y -= height + LEADING / 2.0;
+
+    content
+      .push_str(&format!("q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im1 Do Q\n", width, height, MARGIN, y));
+    image_page = Some(pages.len());
+  }
+  pages.push(content);
+
+  // 1: Catalog, 2: Pages, 3-4: font, 5: ảnh (nếu có), sau đó mỗi trang gồm Page và Contents
+  let mut objects: Vec<Vec<u8>> = vec![
+    b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+    Vec::new(),
+    b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold /Encoding /WinAnsiEncoding >>"
+      .to_vec(),
+  ];
+
+  if let (Some(image), Some(_)) = (image, image_page) {
+    let mut object = format!(
+      "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Length {} >>\nstream\n",
+      image.width,
+      image.height,
+      image.rgb.len()
+    )
+    .into_bytes();
+    object.extend_from_slice(&image.rgb);
+    object.extend_from_slice(b"\nendstream");
+    objects.push(object);
+  }
+  let image_id = objects.len();
+
+  let mut kids = Vec::new();
+  for (index, page) in pages.iter().enumerate() {
+    let page_id = objects.len() + 1;
+    let x_object = if image_page == Some(index) {
+      format!(" /XObject << /Im1 {} 0 R >>", image_id)
+    } else {
+      String::new()
+    };
+
+    kids.push(format!("{} 0 R", page_id));
+    objects.push(
+      format!(
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >>{} >> /Contents {} 0 R >>",
+        page_width,
+        page_height,
+        x_object,
+        page_id + 1
+      )
+      .into_bytes(),
+    );
+    objects.push(format!("<< /Length {} >>\nstream\n{}endstream", page.len(), page).into_bytes());
+  }
+  objects[1] =
+    format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes();
+
+  let mut pdf = b"%PDF-1.4\n".to_vec();
+  let mut offsets = Vec::new();
+  for (index, object) in objects.iter().enumerate() {
+    offsets.push(pdf.len());
+    pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+    pdf.extend_from_slice(object);
+    pdf.extend_from_slice(b"\nendobj\n");
+  }
+
+  let xref_offset = pdf.len();
+  let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+  for offset in offsets {
+    xref.push_str(&format!("{:010} 00000 n \n", offset));
+  }
+  pdf.extend_from_slice(xref.as_bytes());
+  pdf.extend_from_slice(
+    format!(
+      "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+      objects.len() + 1,
+      xref_offset
+    )
+    .as_bytes(),
+  );
+
+  pdf
+}
@@ -0,0 +1,115 @@
+use chrono::FixedOffset;
+use domain::entities::receipt::ReceiptDocument;
+use utils::{format_number::format_number, helper::remove_vietnamese_accents};
+
+use crate::documents::{TextLine, row, wrap};
+
+fn money(amount: i64) -> String {
+  if amount < 0 { format!("-{}", format_number(-amount)) } else { format_number(amount) }
+}
+
+fn tender_label(tender_type: &str) -> &str {
+  match tender_type {
+    "WALLET" => "Vi",
+    "CASH" => "Tien mat",
+    "BANK_TRANSFER" => "Chuyen khoan",
+    "CARD" => "The",
+    "POINTS" => "Diem tich luy",
+    other => other,
+  }
+}
+
+// Dàn trang hoá đơn thành các dòng `width` ký tự. Font chuẩn của PDF và máy in nhiệt
+// không có đủ ký tự tiếng Việt nên nội dung được bỏ dấu
+pub fn layout_receipt(
+  document: &ReceiptDocument,
+  width: usize,
+) -> Vec<TextLine> {
+  let text = |value: &str| remove_vietnamese_accents(value);
+  let separator = || TextLine::left("-".repeat(width));
+  let utc_plus_7 = FixedOffset::east_opt(7 * 3600).unwrap();
+
+  let mut lines = vec![TextLine::center(text(&document.spa.name)).bold().large()];
+  for info in [text(&document.spa.address), text(&document.spa.phone)] {
+    for line in wrap(&info, width) {
+      lines.push(TextLine::center(line));
+    }
+  }
+  if !document.spa.tax_code.is_empty() {
+    lines.push(TextLine::center(format!("MST: {}", document.spa.tax_code)));
+  }
+
+  lines.push(TextLine::left(""));
+  lines.push(TextLine::center("HOA DON THANH TOAN").bold());
+  lines.push(TextLine::center(format!("So: {}", document.receipt_no)));
+  lines.push(TextLine::left(""));
+
+  let issued_at =
+    document.issued_at.with_timezone(&utc_plus_7).format("%d/%m/%Y %H:%M").to_string();
+  lines.push(row("Ngay:", &issued_at, width));
+  lines.push(row("Lich hen:", &format!("#{}", document.appointment_id), width));
+  if let Some(name) = document.customer_name.as_deref() {
+    lines.push(row("Khach hang:", &text(name), width));
+  }
+  if let Some(phone) = document.customer_phone.as_deref() {
+    lines.push(row("SDT:", phone, width));
+  }
+  if let Some(cashier) = document.cashier_name.as_deref() {
+    lines.push(row("Thu ngan:", &text(cashier), width));
+  }
+
+  lines.push(separator());
+  for line in &document.lines {
+    for name in wrap(&text(&line.service_name), width) {
+      lines.push(TextLine::left(name));
+    }
+    lines.push(row(
+      &format!("  {} x {}", line.quantity, money(line.unit_price)),
+      &money(line.unit_price * line.quantity as i64),
+      width,
+    ));
+    if line.discount > 0 {
+      lines.push(row("  Giam gia", &money(-line.discount), width));
+    }
+  }
+
+  lines.push(separator());
+  lines.push(row("Tam tinh", &money(document.subtotal), width));
+  if document.surcharge > 0 {
+    lines.push(row("Phu thu", &money(document.surcharge), width));
+  }
+  if document.promotion > 0 {
+    lines.push(row("Khuyen mai", &money(-document.promotion), width));
+  }
+  lines.push(row("TONG CONG (VND)", &money(document.total), width).bold());
+
+  lines.push(separator());
+  lines.push(TextLine::left("Thanh toan").bold());
+  for tender in &document.tenders {
+    let label = match tender.reference.as_deref() {
+      Some(reference) if !reference.is_empty() => {
+        format!("  {} ({})", tender_label(&tender.tender_type), text(reference))
+      },
+      _ => format!("  {}", tender_label(&tender.tender_type)),
+    };
+    lines.push(row(&label, &money(tender.amount), width));
+  }
+  if document.tip_total > 0 {
+    lines.push(row("Tien tip", &money(document.tip_total), width));
+  }
+
+  lines.push(separator());
+  if document.points_used > 0 {
+    lines.push(row("Diem da dung", &format_number(document.points_used), width));
+  }
+  lines.push(row(
+    "Diem tich luy duoc cong",
+    &format!("+{}", format_number(document.points_earned)),
+    width,
+  ));
+
+  lines.push(TextLine::left(""));
+  lines.push(TextLine::center("Cam on quy khach, hen gap lai!"));
+
+  lines
+}
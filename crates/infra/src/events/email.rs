@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use core_app::configs::{EmailConfig, SpaConfig};
+use domain::services::email::escape_html;
+use lettre::{
+  AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+  message::{Attachment, Mailbox, MultiPart, SinglePart, header::ContentType},
+  transport::smtp::authentication::Credentials,
+};
+
+pub struct EmailAttachment {
+  pub filename: String,
+  pub content_type: String,
+  pub content: Vec<u8>,
+}
+
+pub struct EmailService {
+  transport: AsyncSmtpTransport<Tokio1Executor>,
+  from: Mailbox,
+}
+
+impl EmailService {
+  // Trả về None khi chưa cấu hình SMTP để worker ghi lỗi vào hàng đợi thay vì panic
+  pub fn try_new(config: &EmailConfig) -> Result<Option<Self>, anyhow::Error> {
+    if config.smtp_host.is_empty() {
+      return Ok(None);
+    }
+
+    let builder = match config.smtp_tls.as_str() {
+      "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host),
+      "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?,
+      _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?,
+    }
+    .port(config.smtp_port)
+    .timeout(Some(Duration::from_secs(config.timeout_seconds.max(1))));
+
+    let builder = if config.smtp_username.is_empty() {
+      builder
+    } else {
+      builder
+        .credentials(Credentials::new(config.smtp_username.clone(), config.smtp_password.clone()))
+    };
+
+    let from = Mailbox::new(Some(config.from_name.clone()), config.from_address.parse()?);
+
+    Ok(Some(Self { transport: builder.build(), from }))
+  }
+
+  pub async fn send(
+    &self,
+    to: &str,
+    subject: &str,
+    html: String,
+    attachments: Vec<EmailAttachment>,
+  ) -> Result<(), anyhow::Error> {
+    let builder = Message::builder().from(self.from.clone()).to(to.parse()?).subject(subject);
+
+    let message = if attachments.is_empty() {
+      builder.singlepart(SinglePart::html(html))?
+    } else {
+      let mut multipart = MultiPart::mixed().singlepart(SinglePart::html(html));
+      for attachment in attachments {
+        multipart = multipart.singlepart(
+          Attachment::new(attachment.filename)
+            .body(attachment.content, ContentType::parse(&attachment.content_type)?),
+        );
+      }
+      builder.multipart(multipart)?
+    };
+
+    self.transport.send(message).await?;
+    Ok(())
+  }
+}
+
+/// Khung HTML chung của mọi email: tên spa ở đầu, địa chỉ/số điện thoại ở cuối
+pub fn render_layout(
+  spa: &SpaConfig,
+  locale: &str,
+  subject: &str,
+  body: &str,
+) -> String {
+  let footer = [spa.name.as_str(), spa.address.as_str(), spa.phone.as_str()]
+    .iter()
+    .filter(|line| !line.is_empty())
+    .map(|line| escape_html(line))
+    .collect::<Vec<_>>()
+    .join("<br>");
+
+  format!(
+    r#"<!DOCTYPE html>
+<html lang="{locale}">
+<head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><title>{subject}</title></head>
+<body style="margin:0;padding:0;background:#f4f4f5;font-family:Arial,Helvetica,sans-serif;color:#18181b">
+<table role="presentation" width="100%" cellpadding="0" cellspacing="0"><tr><td align="center" style="padding:24px">
+<table role="presentation" width="600" cellpadding="0" cellspacing="0" style="max-width:600px;background:#ffffff;border-radius:8px">
+<tr><td style="padding:24px;border-bottom:1px solid #e4e4e7;font-size:20px;font-weight:bold">{name}</td></tr>
+<tr><td style="padding:24px;font-size:15px;line-height:1.6">{body}</td></tr>
+<tr><td style="padding:16px 24px;border-top:1px solid #e4e4e7;font-size:12px;color:#71717a">{footer}</td></tr>
+</table>
+</td></tr></table>
+</body>
+</html>"#,
+    locale = escape_html(locale),
+    subject = escape_html(subject),
+    name = escape_html(&spa.name),
+    body = body,
+    footer = footer,
+  )
+}
@@ -1,2 +1,4 @@
+pub mod email;
+pub mod realtime;
 pub mod twilio;
 pub mod zalo;
@@ -0,0 +1,59 @@
+use core_app::{AppResult, errors::AppError};
+use domain::entities::realtime_event::{REALTIME_CHANNEL, RealtimeEvent};
+use sqlx::{PgPool, postgres::PgListener};
+use tokio::{
+  sync::broadcast,
+  time::{Duration, sleep},
+};
+
+/// Phát sự kiện qua NOTIFY. Chạy trong transaction thì sự kiện chỉ tới client khi commit.
+pub async fn publish_event<'e>(
+  executor: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+  event: &RealtimeEvent,
+) -> AppResult<()> {
+  sqlx::query("SELECT pg_notify($1, $2)")
+    .bind(REALTIME_CHANNEL)
+    .bind(serde_json::json!(event).to_string())
+    .execute(executor)
+    .await
+    .map(|_| ())
+    .map_err(|e| AppError::Unhandled(Box::new(e)))
+}
+
+/// Nghe kênh realtime và chuyển payload cho các kết nối SSE, mất kết nối thì tự kết nối lại
+pub async fn start_event_listener(
+  db: PgPool,
+  sender: broadcast::Sender<String>,
+) {
+  loop {
+    let mut listener = match PgListener::connect_with(&db).await {
+      Ok(listener) => listener,
+      Err(e) => {
+        tracing::error!("Failed to connect realtime listener: {:?}", e);
+        sleep(Duration::from_secs(5)).await;
+        continue;
+      },
+    };
+
+    if let Err(e) = listener.listen(REALTIME_CHANNEL).await {
+      tracing::error!("Failed to listen on {}: {:?}", REALTIME_CHANNEL, e);
+      sleep(Duration::from_secs(5)).await;
+      continue;
+    }
+
+    loop {
+      match listener.recv().await {
+        // Không có client nào đang kết nối thì send trả lỗi, bỏ qua
+        Ok(notification) => {
+          let _ = sender.send(notification.payload().to_string());
+        },
+        Err(e) => {
+          tracing::error!("Realtime listener error: {:?}", e);
+          break;
+        },
+      }
+    }
+
+    sleep(Duration::from_secs(5)).await;
+  }
+}
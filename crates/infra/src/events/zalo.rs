@@ -1,12 +1,18 @@
 use chrono::Utc;
-use domain::entities::zalo::{
-  RefreshTokenData, SendMessagePayload, TemplateData, ZaloTemplate, ZaloTemplateResponse, ZaloToken,
+use domain::{
+  entities::zalo::{
+    RefreshTokenData, SendMessagePayload, ZaloEventTemplate, ZaloTemplate, ZaloTemplateResponse,
+    ZaloToken,
+  },
+  services::zalo::render_params,
 };
 use dotenv::var;
 use reqwest;
 use serde_json;
 use sqlx::PgPool;
 
+const SEND_TEMPLATE_URL: &str = "https://business.openapi.zalo.me/message/template";
+
 #[derive(Debug)]
 pub struct ZaloService {
   app_id: String,
@@ -14,16 +20,26 @@ pub struct ZaloService {
   secret_key: String,
 }
 
+// Zalo nhận số điện thoại dạng 84xxxxxxxxx
+pub fn normalize_phone(phone: &str) -> String {
+  let phone = phone.trim().trim_start_matches('+');
+  match phone.strip_prefix('0') {
+    Some(rest) => format!("84{}", rest),
+    None => phone.to_string(),
+  }
+}
+
 impl ZaloService {
   pub fn new() -> Self {
     tracing::info!("ZaloService create new servive");
-    let app_id = var("ZALO_APP_ID").expect("ZALO_APP_ID must be set");
-    tracing::info!("Get env: {}", app_id);
-    let grant_type = "refresh_token".to_string();
-    tracing::info!("Get env: {}", grant_type);
-    let secret_key = var("ZALO_APP_SECRET_KEY").expect("ZALO_APP_SECRET_KEY must be set");
-    tracing::info!("Get env: {}", secret_key);
-    Self { app_id, grant_type, secret_key }
+    Self::try_new().expect("ZALO_APP_ID and ZALO_APP_SECRET_KEY must be set")
+  }
+
+  // Worker gửi tin dùng hàm này để không panic khi môi trường chưa cấu hình Zalo
+  pub fn try_new() -> Option<Self> {
+    let app_id = var("ZALO_APP_ID").ok().filter(|value| !value.is_empty())?;
+    let secret_key = var("ZALO_APP_SECRET_KEY").ok().filter(|value| !value.is_empty())?;
+    Some(Self { app_id, grant_type: "refresh_token".to_string(), secret_key })
   }
 
   pub async fn get_zalo_token(
@@ -42,19 +58,34 @@ impl ZaloService {
     Ok(token)
   }
 
+  /// Làm mới access token. Zalo huỷ refresh token cũ sau mỗi lần dùng nên dòng token được khoá
+  /// trong suốt lần làm mới; tiến trình chờ khoá thấy token đã đổi thì dùng luôn token mới.
   pub async fn refresh_token_zalo(
     &self,
     db: &PgPool,
     token: ZaloToken,
   ) -> Result<ZaloToken, anyhow::Error> {
     tracing::info!("refresh token start");
+    let mut tx = db.begin().await?;
+    let current =
+      sqlx::query_as::<_, ZaloToken>("SELECT * FROM users.zalo_tokens WHERE id = $1 FOR UPDATE")
+        .bind(token.id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    if current.refresh_token != token.refresh_token || current.access_token != token.access_token {
+      tracing::info!("Zalo token already refreshed by another request");
+      tx.commit().await?;
+      return Ok(current);
+    }
+
     let response = reqwest::Client::new()
       .post("https://oauth.zaloapp.com/v4/oa/access_token")
       .header("secret_key", &self.secret_key)
       .form(&[
         ("grant_type", &self.grant_type),
         ("app_id", &self.app_id),
-        ("refresh_token", &token.refresh_token),
+        ("refresh_token", &current.refresh_token),
       ])
       .send()
       .await
@@ -63,7 +94,6 @@ impl ZaloService {
         err
       })?;
 
-    tracing::error!("response.status().is_success()");
     if !response.status().is_success() {
       let error = response.text().await?;
       tracing::error!("Failed to refresh token: {}", error);
@@ -71,10 +101,7 @@ impl ZaloService {
     }
 
     let response_text = response.text().await?;
-    tracing::info!("response: {:#?}", response_text);
-
     let response_json: serde_json::Value = serde_json::from_str(&response_text)?;
-    tracing::info!("response json: {:#?}", response_json);
 
     if let Some(error_code) = response_json.get("error").and_then(|e| e.as_i64()) {
       match error_code {
@@ -102,9 +129,11 @@ impl ZaloService {
       Utc::now()
         + chrono::Duration::seconds(response_data.expires_in.parse::<i64>().unwrap_or(89000)),
     )
-    .bind(token.id)
-    .fetch_one(db)
+    .bind(current.id)
+    .fetch_one(&mut *tx)
     .await?;
+    tx.commit().await?;
+
     Ok(token)
   }
 
@@ -154,56 +183,115 @@ impl ZaloService {
     }
   }
 
+  /// Gửi tin ZNS theo mẫu, trả về msg_id của Zalo.
+  /// Access token bị Zalo báo không hợp lệ (-124) thì làm mới và gửi lại một lần.
+  pub async fn send_template(
+    &self,
+    db: &PgPool,
+    phone: &str,
+    template_id: &str,
+    template_data: serde_json::Value,
+    tracking_id: &str,
+  ) -> Result<String, anyhow::Error> {
+    let payload = SendMessagePayload {
+      phone: normalize_phone(phone),
+      template_id: template_id.to_string(),
+      template_data,
+      tracking_id: tracking_id.to_string(),
+    };
+
+    let mut token = ZaloService::get_zalo_token(self, db).await?;
+    let mut refreshed = false;
+    loop {
+      let response = reqwest::Client::new()
+        .post(SEND_TEMPLATE_URL)
+        .header("access_token", &token.access_token)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|err| anyhow::anyhow!("Failed to send request: {}", err))?;
+
+      if !response.status().is_success() {
+        let error = response.text().await?;
+        return Err(anyhow::anyhow!("Failed to send message: {}", error));
+      }
+
+      let response_text = response.text().await?;
+      tracing::info!("Send message response: {}", response_text);
+      let response_json: serde_json::Value = serde_json::from_str(&response_text)?;
+
+      match response_json.get("error").and_then(|e| e.as_i64()) {
+        Some(0) => {
+          return response_json
+            .pointer("/data/msg_id")
+            .and_then(|msg_id| msg_id.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Zalo response has no msg_id"));
+        },
+        Some(-124) if !refreshed => {
+          token = ZaloService::refresh_token_zalo(self, db, token).await?;
+          refreshed = true;
+        },
+        error_code => {
+          let error_message =
+            response_json.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
+          return Err(anyhow::anyhow!(
+            "Zalo API error: {} (code: {:?})",
+            error_message,
+            error_code
+          ));
+        },
+      }
+    }
+  }
+
   pub async fn send_message_otp(
     &self,
     db: &PgPool,
     phone: &str,
     otp: &str,
   ) -> Result<(), anyhow::Error> {
-    let token: ZaloToken = ZaloService::get_zalo_token(self, db).await.map_err(|err| {
-      tracing::error!("Failed to get Zalo token: {}", err);
-      anyhow::anyhow!("Failed to get Zalo token: {}", err)
-    })?;
-
-    let templates =
-      ZaloService::get_all_templates(self, db, token.clone()).await.map_err(|err| {
-        tracing::error!("Failed to get templates: {}", err);
-        anyhow::anyhow!("Failed to get templates: {}", err)
-      })?;
+    let variables = serde_json::json!({ "otp": otp });
+    let tracking_id = format!("{} {}", phone, otp);
 
-    if templates.is_empty() {
-      return Err(anyhow::anyhow!("No templates found"));
-    }
+    // Dùng mẫu đã gắn cho OTP, chưa cấu hình thì giữ cách cũ là lấy mẫu đầu tiên của OA
+    let mapping = sqlx::query_as::<_, ZaloEventTemplate>(
+      "SELECT * FROM users.zalo_templates WHERE event_type = 'OTP' AND enabled",
+    )
+    .fetch_optional(db)
+    .await?;
 
-    let template_id = templates[0].template_id;
+    let (template_id, template_data) = match mapping {
+      Some(ZaloEventTemplate { template_id: Some(template_id), params, .. }) => {
+        (template_id, render_params(&params, &variables))
+      },
+      _ => {
+        tracing::warn!("OTP Zalo template is not configured, using the first OA template");
+        let token: ZaloToken = ZaloService::get_zalo_token(self, db).await.map_err(|err| {
+          tracing::error!("Failed to get Zalo token: {}", err);
+          anyhow::anyhow!("Failed to get Zalo token: {}", err)
+        })?;
 
-    let payload = SendMessagePayload {
-      template_id: template_id.to_string(),
-      phone: phone.to_string(),
-      template_data: TemplateData { otp: otp.to_string() },
-      tracking_id: format!("{} {}", phone, otp),
+        let templates = ZaloService::get_all_templates(self, db, token).await.map_err(|err| {
+          tracing::error!("Failed to get templates: {}", err);
+          anyhow::anyhow!("Failed to get templates: {}", err)
+        })?;
+
+        let Some(template) = templates.first() else {
+          return Err(anyhow::anyhow!("No templates found"));
+        };
+
+        (template.template_id.to_string(), variables)
+      },
     };
 
-    let response = reqwest::Client::new()
-      .post(format!("https://business.openapi.zalo.me/message/template"))
-      .header("access_token", token.access_token)
-      .json(&payload)
-      .send()
+    ZaloService::send_template(self, db, phone, &template_id, template_data, &tracking_id)
       .await
       .map_err(|err| {
-        tracing::error!("Failed to send request: {}", err);
-        anyhow::anyhow!("Failed to send request: {}", err)
+        tracing::error!("Failed to send message: {}", err);
+        err
       })?;
 
-    if !response.status().is_success() {
-      let error = response.text().await?;
-      tracing::error!("Failed to send message: {}", error);
-      return Err(anyhow::anyhow!("Failed to send message: {}", error));
-    }
-
-    let response_text = response.text().await?;
-    tracing::info!("Send message response: {}", response_text);
-
     Ok(())
   }
 }
@@ -5,6 +5,41 @@ use gcp_auth::{CustomServiceAccount, TokenProvider};
 use reqwest::Client;
 use serde_json::json;
 use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+// Client FCM dùng chung cho cả tiến trình, token OAuth được gcp_auth cache và tự làm mới
+static SHARED_SERVICE: OnceCell<Arc<NotificationService>> = OnceCell::const_new();
+
+// Kết quả gửi tới một token: Invalid là token không còn dùng được, Failed có thể thử lại
+#[derive(Debug, Clone, PartialEq)]
+pub enum FcmSendResult {
+  Sent,
+  InvalidToken(String),
+  Failed(String),
+}
+
+// Token đã gỡ app (404 / UNREGISTERED) hoặc bị FCM báo sai ở trường message.token thì gửi lại cũng
+// không thành công; INVALID_ARGUMENT do payload sai không phải lỗi của token
+fn is_invalid_token_error(
+  status: reqwest::StatusCode,
+  body: &str,
+) -> bool {
+  if status == reqwest::StatusCode::NOT_FOUND {
+    return true;
+  }
+
+  let details = serde_json::from_str::<serde_json::Value>(body)
+    .ok()
+    .and_then(|body| body["error"]["details"].as_array().cloned())
+    .unwrap_or_default();
+
+  details.iter().any(|detail| {
+    detail["errorCode"] == "UNREGISTERED"
+      || detail["fieldViolations"].as_array().is_some_and(|violations| {
+        violations.iter().any(|violation| violation["field"] == "message.token")
+      })
+  })
+}
 
 pub struct NotificationService {
   http_client: Client,
@@ -22,60 +57,89 @@ impl NotificationService {
     Ok(Self { http_client: Client::new(), token_provider: Arc::new(service_account), project_id })
   }
 
-  pub async fn send_notification(
+  // Khởi tạo một lần và dùng lại, lỗi khởi tạo không được cache để lần sau thử lại
+  pub async fn shared() -> Result<Arc<Self>> {
+    SHARED_SERVICE.get_or_try_init(|| async { Ok(Arc::new(Self::new().await?)) }).await.cloned()
+  }
+
+  pub async fn send_to_token(
     &self,
-    notification: Notification,
-    tokens: Vec<String>,
-  ) -> Result<bool> {
-    let mut success = true;
-    let auth_token = self
+    title: &str,
+    body: &str,
+    data: Option<&serde_json::Value>,
+    token: &str,
+  ) -> FcmSendResult {
+    let auth_token = match self
       .token_provider
       .token(&["https://www.googleapis.com/auth/firebase.messaging"])
       .await
-      .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    {
+      Ok(auth_token) => auth_token,
+      Err(err) => return FcmSendResult::Failed(err.to_string()),
+    };
 
-    for token in tokens {
-      let mut message = json!({
-        "message": {
-          "token": token,
-          "notification": {
-            "title": notification.title,
-            "body": notification.body
-          }
-        }
-      });
-
-      // Convert all data values to strings
-      if let Some(data) = notification.data.clone() {
-        if !data.is_null() {
-          let mut string_data = serde_json::Map::new();
-          if let Some(obj) = data.as_object() {
-            for (key, value) in obj {
-              string_data.insert(key.clone(), json!(value.to_string()));
-            }
-            message["message"]["data"] = json!(string_data);
-          }
+    let mut message = json!({
+      "message": {
+        "token": token,
+        "notification": {
+          "title": title,
+          "body": body
         }
       }
+    });
 
-      tracing::info!("{:?} message", message);
+    // Convert all data values to strings
+    if let Some(obj) = data.and_then(|data| data.as_object()) {
+      let mut string_data = serde_json::Map::new();
+      for (key, value) in obj {
+        string_data.insert(key.clone(), json!(value.to_string()));
+      }
+      message["message"]["data"] = json!(string_data);
+    }
 
-      let response = self
-        .http_client
-        .post(format!("https://fcm.googleapis.com/v1/projects/{}/messages:send", self.project_id))
-        .header("Authorization", format!("Bearer {}", auth_token.as_str()))
-        .json(&message)
-        .send()
-        .await?;
+    let response = match self
+      .http_client
+      .post(format!("https://fcm.googleapis.com/v1/projects/{}/messages:send", self.project_id))
+      .header("Authorization", format!("Bearer {}", auth_token.as_str()))
+      .json(&message)
+      .send()
+      .await
+    {
+      Ok(response) => response,
+      Err(err) => return FcmSendResult::Failed(err.to_string()),
+    };
 
-      if !response.status().is_success() {
-        let error = response.text().await?;
-        tracing::error!("Failed to send notification to token {}: {}", token, error);
-        success = false;
-      }
+    let status = response.status();
+    if status.is_success() {
+      return FcmSendResult::Sent;
     }
 
-    Ok(success)
+    let error = response.text().await.unwrap_or_default();
+    tracing::error!("Failed to send notification to token {}: {} {}", token, status, error);
+
+    if is_invalid_token_error(status, &error) {
+      FcmSendResult::InvalidToken(format!("{} {}", status, error))
+    } else {
+      FcmSendResult::Failed(format!("{} {}", status, error))
+    }
+  }
+
+  /// Gửi tới từng token và trả về kết quả theo token để bên gọi xử lý token hỏng
+  pub async fn send_notification(
+    &self,
+    notification: Notification,
+    tokens: Vec<String>,
+  ) -> Result<Vec<(String, FcmSendResult)>> {
+    let mut results = Vec::with_capacity(tokens.len());
+
+    for token in tokens {
+      let result = self
+        .send_to_token(&notification.title, &notification.body, notification.data.as_ref(), &token)
+        .await;
+      results.push((token, result));
+    }
+
+    Ok(results)
   }
 
   pub async fn send_appointment_confirmation(
@@ -84,7 +148,7 @@ impl NotificationService {
     appointment_id: i64,
     appointment_time: chrono::DateTime<chrono::Utc>,
     tokens: Vec<String>,
-  ) -> Result<bool> {
+  ) -> Result<Vec<(String, FcmSendResult)>> {
     let notification = Notification {
       id: 1,
       user_id: Some(user_id),
@@ -108,3 +172,23 @@ impl NotificationService {
     self.send_notification(notification, tokens).await
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use reqwest::StatusCode;
+
+  #[test]
+  fn prunes_unregistered_and_invalid_tokens_only() {
+    let unregistered = r#"{"error":{"code":404,"status":"NOT_FOUND","details":[{"@type":"type.googleapis.com/google.firebase.fcm.v1.FcmError","errorCode":"UNREGISTERED"}]}}"#;
+    let bad_token = r#"{"error":{"code":400,"status":"INVALID_ARGUMENT","details":[{"@type":"type.googleapis.com/google.firebase.fcm.v1.FcmError","errorCode":"INVALID_ARGUMENT"},{"@type":"type.googleapis.com/google.rpc.BadRequest","fieldViolations":[{"field":"message.token","description":"Invalid registration token"}]}]}}"#;
+    let bad_payload = r#"{"error":{"code":400,"status":"INVALID_ARGUMENT","details":[{"@type":"type.googleapis.com/google.firebase.fcm.v1.FcmError","errorCode":"INVALID_ARGUMENT"},{"@type":"type.googleapis.com/google.rpc.BadRequest","fieldViolations":[{"field":"message.data[0].value","description":"Invalid value"}]}]}}"#;
+
+    assert!(is_invalid_token_error(StatusCode::NOT_FOUND, unregistered));
+    assert!(is_invalid_token_error(StatusCode::NOT_FOUND, ""));
+    assert!(is_invalid_token_error(StatusCode::BAD_REQUEST, bad_token));
+    assert!(!is_invalid_token_error(StatusCode::BAD_REQUEST, bad_payload));
+    assert!(!is_invalid_token_error(StatusCode::BAD_REQUEST, "not json"));
+    assert!(!is_invalid_token_error(StatusCode::SERVICE_UNAVAILABLE, ""));
+  }
+}
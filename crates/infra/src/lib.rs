@@ -1,4 +1,5 @@
 pub mod database;
+pub mod documents;
 pub mod events;
 pub mod firebase;
 pub mod middleware;
@@ -41,6 +41,22 @@ pub async fn mw_response(
   let response = next.run(req).instrument(span).await;
 
   // ---- Xử lý Response ----
+  // Response dạng file tải về (PDF, lệnh máy in...) hoặc luồng SSE giữ nguyên body, không bọc JSON
+  let is_event_stream = response
+    .headers()
+    .get(http::header::CONTENT_TYPE)
+    .is_some_and(|value| value.as_bytes().starts_with(b"text/event-stream"));
+  if response.status().is_success()
+    && (response.headers().contains_key(http::header::CONTENT_DISPOSITION) || is_event_stream)
+  {
+    let mut response = response;
+    response.headers_mut().insert(
+      http::header::HeaderName::from_static(REQUEST_ID_HEADER),
+      http::HeaderValue::from_str(&request_id.to_string()).unwrap(),
+    );
+    return Ok(response);
+  }
+
   let response_status = response.status();
   let response_headers = response.headers().clone(); // Clone headers nếu cần giữ lại
 
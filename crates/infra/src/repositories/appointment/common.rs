@@ -1,11 +1,13 @@
 use core_app::{AppResult, errors::AppError};
-use domain::entities::appointment::AppointmentService;
 use domain::entities::appointment::{AppointmentFilter, AppointmentWithServices};
+use domain::entities::appointment::{
+  AppointmentService, AppointmentServiceRequest, PaymentTender, TENDER_TYPES,
+};
 use domain::entities::common::PaginationMetadata;
 use domain::entities::service_child::ServiceChild;
 use domain::entities::user::Point;
 use modql::filter::{ListOptions, OrderBy};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 
 pub use crate::repositories::appointment::send_noti::*;
 use crate::repositories::base::pagination;
@@ -50,27 +52,157 @@ pub async fn count_appointment_by_user_id_and_status(
   Ok(res)
 }
 
+/// Chuẩn hoá danh sách dịch vụ khi đặt lịch: gom các dòng trùng dịch vụ và kỹ thuật viên,
+/// gán kỹ thuật viên mặc định của lịch hẹn và thứ tự thực hiện nếu chưa có.
+pub fn normalize_services(
+  services: &[AppointmentServiceRequest],
+  default_technician_id: Option<i64>,
+) -> Vec<AppointmentServiceRequest> {
+  let mut lines: Vec<AppointmentServiceRequest> = Vec::new();
+  for service in services {
+    let technician_id = service.technician_id.or(default_technician_id);
+    let quantity = service.quantity.unwrap_or(1);
+    match lines.iter_mut().find(|line| {
+      line.service_id == service.service_id
+        && line.technician_id == technician_id
+        && line.start_time == service.start_time
+    }) {
+      Some(line) => {
+        line.quantity = Some(line.quantity.unwrap_or(1) + quantity);
+        line.discount = Some(line.discount.unwrap_or(0) + service.discount.unwrap_or(0));
+      },
+      None => lines.push(AppointmentServiceRequest {
+        service_id: service.service_id,
+        technician_id,
+        quantity: Some(quantity),
+        sequence: service.sequence,
+        start_time: service.start_time.clone(),
+        end_time: service.end_time.clone(),
+        discount: service.discount,
+      }),
+    }
+  }
+
+  for (index, line) in lines.iter_mut().enumerate() {
+    if line.sequence.is_none() {
+      line.sequence = Some(index as i32 + 1);
+    }
+  }
+  lines.sort_by_key(|line| line.sequence);
+  lines
+}
+
+pub async fn check_technicians(
+  db: &PgPool,
+  technician_ids: Vec<i64>,
+) -> AppResult<()> {
+  if technician_ids.is_empty() {
+    return Ok(());
+  }
+
+  let count = sqlx::query_scalar::<_, i64>(
+    r#"
+      SELECT COUNT(*) FROM users.tbl_users
+      WHERE pk_user_id = ANY($1) AND role = 'TECHNICIAN' AND is_active = true
+    "#,
+  )
+  .bind(&technician_ids)
+  .fetch_one(db)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  let mut unique_ids = technician_ids.clone();
+  unique_ids.sort();
+  unique_ids.dedup();
+  if count != unique_ids.len() as i64 {
+    return Err(AppError::BadRequest("Technician not found".to_string()));
+  }
+
+  Ok(())
+}
+
+// Lỗi ghi dòng dịch vụ, chiết khấu vượt thành tiền thì báo rõ thay vì lỗi ràng buộc của DB
+fn map_line_error(err: sqlx::Error) -> AppError {
+  match err {
+    sqlx::Error::Database(db_err)
+      if db_err.constraint() == Some("appointments_services_discount_limit_check") =>
+    {
+      AppError::BadRequest("Discount cannot exceed the service line total".to_string())
+    },
+    err => AppError::BadRequest(err.to_string()),
+  }
+}
+
+/// Thêm dịch vụ vào lịch hẹn, lưu lại tên, nhóm dịch vụ, đơn giá và chiết khấu tại thời điểm đặt lịch.
 pub async fn insert_appointment_service<'e>(
   db: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
   appointment_id: i64,
-  service_id: i64,
+  line: &AppointmentServiceRequest,
+  start_time: Option<String>,
   updated_by: i64,
-  technician_id: Option<i64>,
 ) -> AppResult<AppointmentService> {
   let res = sqlx::query_as::<_, AppointmentService>(
     r#"
-      INSERT INTO users.appointments_services (appointment_id, service_id, updated_by, technician_id)
-      VALUES ($1, $2, $3, $4)
+      INSERT INTO users.appointments_services (
+        appointment_id, service_id, updated_by, technician_id, quantity, sequence,
+        start_time, end_time, discount, service_name, service_name_en, service_name_ko,
+        unit_price, parent_service_id, parent_service_name
+      )
+      SELECT $1, s.id, $3, $4, $5, $6, $7, $8, $9, s.service_name, s.service_name_en,
+        s.service_name_ko, s.price, p.id, p.service_name
+      FROM users.service_items s
+      JOIN users.services p ON p.id = s.parent_service_id
+      WHERE s.id = $2
       RETURNING *
     "#,
   )
   .bind(appointment_id)
-  .bind(service_id)
+  .bind(line.service_id)
+  .bind(updated_by)
+  .bind(line.technician_id)
+  .bind(line.quantity.unwrap_or(1))
+  .bind(line.sequence.unwrap_or(1))
+  .bind(line.start_time.clone().or(start_time))
+  .bind(line.end_time.clone())
+  .bind(line.discount.unwrap_or(0))
+  .fetch_optional(db)
+  .await
+  .map_err(map_line_error)?
+  .ok_or(AppError::BadRequest("Service not found".to_string()))?;
+
+  Ok(res)
+}
+
+/// Cập nhật số lượng, thứ tự, thời gian và chiết khấu của dịch vụ đã có, giữ nguyên giá đã lưu.
+pub async fn update_appointment_service_line<'e>(
+  db: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+  id: i64,
+  line: &AppointmentServiceRequest,
+  updated_by: i64,
+) -> AppResult<AppointmentService> {
+  let res = sqlx::query_as::<_, AppointmentService>(
+    r#"
+      UPDATE users.appointments_services
+      SET quantity = $1,
+          sequence = $2,
+          start_time = COALESCE($3, start_time),
+          end_time = COALESCE($4, end_time),
+          discount = COALESCE($7, discount),
+          updated_by = $5
+      WHERE id = $6
+      RETURNING *
+    "#,
+  )
+  .bind(line.quantity.unwrap_or(1))
+  .bind(line.sequence.unwrap_or(1))
+  .bind(line.start_time.clone())
+  .bind(line.end_time.clone())
   .bind(updated_by)
-  .bind(technician_id)
+  .bind(id)
+  .bind(line.discount)
   .fetch_one(db)
   .await
-  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+  .map_err(map_line_error)?;
 
   Ok(res)
 }
@@ -94,6 +226,275 @@ pub async fn check_appointment_service(
   Ok(res)
 }
 
+// Lịch hẹn kèm dịch vụ, khách, lễ tân, kỹ thuật viên; dùng được trong transaction
+pub async fn get_appointment_with_services<'e>(
+  db: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+  id: i64,
+) -> AppResult<AppointmentWithServices> {
+  let res = sqlx::query_as::<_, AppointmentWithServices>(
+    r#"
+     SELECT 
+        a.*,
+        COALESCE(jsonb_agg(to_jsonb(s.*) || jsonb_build_object(
+          'service_name', aps.service_name,
+          'service_name_en', aps.service_name_en,
+          'service_name_ko', aps.service_name_ko,
+          'price', aps.unit_price,
+          'appointment_service_id', aps.id,
+          'quantity', aps.quantity,
+          'discount', aps.discount,
+          'sequence', aps.sequence,
+          'technician_id', aps.technician_id,
+          'start_time', aps.start_time,
+          'end_time', aps.end_time,
+          'status', aps.status
+        ) ORDER BY aps.sequence) FILTER (WHERE s.id IS NOT NULL), '[]'::jsonb) AS services,
+        json_build_object(
+          'id', u.pk_user_id,
+          'full_name', u.full_name,
+          'phone', u.phone
+        ) AS user,
+       CASE 
+      WHEN a.receptionist_id IS NULL THEN NULL
+      ELSE json_build_object(
+          'id', u2.pk_user_id,
+          'full_name', u2.full_name,
+          'phone', u2.phone
+      )
+      END AS receptionist,
+      CASE 
+      WHEN a.technician_id IS NULL THEN NULL
+      ELSE json_build_object(
+          'id', u3.pk_user_id,
+          'full_name', u3.full_name,
+          'phone', u3.phone
+      )
+  END AS technician
+    FROM users.appointments a
+    LEFT JOIN users.appointments_services aps ON a.id = aps.appointment_id
+    LEFT JOIN users.service_items s ON aps.service_id = s.id
+    LEFT JOIN users.tbl_users u ON a.user_id = u.pk_user_id
+    LEFT JOIN users.tbl_users u2 ON a.receptionist_id = u2.pk_user_id
+    LEFT JOIN users.tbl_users u3 ON a.technician_id = u3.pk_user_id
+    WHERE a.id = $1
+    GROUP BY a.id, u.pk_user_id, u.full_name, u.phone, u2.pk_user_id, u2.full_name, u2.phone, u3.pk_user_id, u3.full_name, u3.phone
+   "#,
+  )
+  .bind(id)
+  .fetch_optional(db)
+  .await?
+  .ok_or(AppError::NotFound)?;
+
+  Ok(res)
+}
+
+pub async fn get_appointment_services<'e>(
+  db: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+  appointment_id: i64,
+) -> AppResult<Vec<AppointmentService>> {
+  let res = sqlx::query_as::<_, AppointmentService>(
+    r#"
+      SELECT * FROM users.appointments_services
+      WHERE appointment_id = $1
+      ORDER BY sequence, id
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_all(db)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  Ok(res)
+}
+
+/// Tổng tiền dịch vụ của lịch hẹn tính theo giá đã lưu lúc đặt lịch.
+pub async fn sum_appointment_services<'e>(
+  db: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+  appointment_id: i64,
+) -> AppResult<i64> {
+  let res = sqlx::query_scalar::<_, i64>(
+    r#"
+      SELECT COALESCE(SUM(unit_price * quantity - discount), 0)::BIGINT
+      FROM users.appointments_services
+      WHERE appointment_id = $1
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_one(db)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  Ok(res)
+}
+
+/// Giá trị quy đổi của 1 điểm tích luỹ khi dùng để thanh toán (VND)
+pub const POINT_VALUE: i64 = 100;
+
+/// Số điểm bị trừ và số điểm được cộng khi thanh toán, trả về (points_used, points_earned).
+/// Phần thanh toán bằng điểm không được tích thêm điểm, mỗi 1.000đ còn lại được 1 điểm
+pub fn loyalty_points(
+  total_price: i64,
+  points_amount: i64,
+) -> (i64, i64) {
+  let points_used = (points_amount as u64).div_ceil(POINT_VALUE as u64) as i64;
+  let points_earned = (((total_price - points_amount) as f64) / 1000.0).round() as i64;
+  (points_used, points_earned)
+}
+
+/// Chuyển yêu cầu thanh toán kiểu cũ (user_balance + payment_method) sang danh sách tender.
+pub fn build_legacy_tenders(
+  user_balance: i64,
+  total_price: i64,
+  payment_method: Option<&str>,
+) -> Vec<PaymentTender> {
+  let mut tenders = Vec::new();
+
+  if user_balance > 0 {
+    tenders.push(PaymentTender {
+      tender_type: "WALLET".to_string(),
+      amount: user_balance,
+      reference: None,
+    });
+  }
+
+  let remaining = total_price - user_balance;
+  if remaining > 0 {
+    let tender_type = payment_method
+      .map(|method| method.trim().to_uppercase())
+      .filter(|method| TENDER_TYPES.contains(&method.as_str()))
+      .unwrap_or_else(|| "CASH".to_string());
+    tenders.push(PaymentTender { tender_type, amount: remaining, reference: None });
+  }
+
+  tenders
+}
+
+/// Ghi từng hình thức thanh toán của lịch hẹn. Ví, tiền mặt, chuyển khoản và thẻ
+/// được ghi kèm một giao dịch PAYMENT trong bảng deposits (WITHDRAW chỉ dùng cho rút tiền).
+pub async fn insert_appointment_payments(
+  conn: &mut PgConnection,
+  appointment_id: i64,
+  user_id: i64,
+  tenders: &[PaymentTender],
+  created_by: i64,
+) -> AppResult<()> {
+  for tender in tenders {
+    let mut deposit_id = None;
+    if matches!(tender.tender_type.as_str(), "WALLET" | "CASH" | "BANK_TRANSFER" | "CARD") {
+      deposit_id = Some(
+        sqlx::query_scalar::<_, i64>(
+          r#"
+            INSERT INTO users.deposits (
+              user_id, amount, payment_method, status, created_by, deposit_type, transaction_id, notes
+            )
+            VALUES ($1, $2, $3, 'COMPLETED', $4, 'PAYMENT', $5, $6)
+            RETURNING id
+          "#,
+        )
+        .bind(user_id)
+        .bind(tender.amount)
+        .bind(&tender.tender_type)
+        .bind(created_by)
+        .bind(&tender.reference)
+        .bind(format!("Thanh toán lịch hẹn #{}", appointment_id))
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|err| AppError::Unhandled(Box::new(err)))?,
+      );
+    }
+
+    sqlx::query(
+      r#"
+        INSERT INTO users.appointment_payments (
+          appointment_id, tender_type, amount, reference, deposit_id, created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+      "#,
+    )
+    .bind(appointment_id)
+    .bind(&tender.tender_type)
+    .bind(tender.amount)
+    .bind(&tender.reference)
+    .bind(deposit_id)
+    .bind(created_by)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+  }
+
+  Ok(())
+}
+
+/// Chia tiền tip cho các kỹ thuật viên theo tỉ lệ tiền dịch vụ mỗi người đã làm,
+/// phần lẻ cộng cho người làm nhiều nhất.
+pub async fn insert_appointment_tips(
+  conn: &mut PgConnection,
+  appointment_id: i64,
+  default_technician_id: Option<i64>,
+  tip: i64,
+  payment_method: &str,
+  created_by: i64,
+) -> AppResult<Vec<(i64, i64)>> {
+  let mut shares = sqlx::query_as::<_, (i64, i64)>(
+    r#"
+      SELECT technician_id, COALESCE(SUM(unit_price * quantity - discount), 0)::BIGINT AS amount
+      FROM users.appointments_services
+      WHERE appointment_id = $1 AND technician_id IS NOT NULL AND status != 'CANCELLED'
+      GROUP BY technician_id
+      ORDER BY amount DESC, technician_id
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_all(&mut *conn)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  if shares.is_empty() {
+    match default_technician_id {
+      Some(technician_id) => shares.push((technician_id, 0)),
+      None => {
+        return Err(AppError::BadRequest(
+          "Lịch hẹn chưa có kỹ thuật viên để nhận tiền tip".to_string(),
+        ));
+      },
+    }
+  }
+
+  let total: i64 = shares.iter().map(|&(_, amount)| amount.max(0)).sum();
+  let mut tips: Vec<(i64, i64)> = shares
+    .iter()
+    .map(|&(technician_id, amount)| {
+      let amount = if total > 0 { tip * amount.max(0) / total } else { tip / shares.len() as i64 };
+      (technician_id, amount)
+    })
+    .collect();
+
+  let remainder = tip - tips.iter().map(|(_, amount)| amount).sum::<i64>();
+  tips[0].1 += remainder;
+  tips.retain(|(_, amount)| *amount > 0);
+
+  for (technician_id, amount) in &tips {
+    sqlx::query(
+      r#"
+        INSERT INTO users.appointment_tips (
+          appointment_id, technician_id, amount, payment_method, created_by
+        )
+        VALUES ($1, $2, $3, $4, $5)
+      "#,
+    )
+    .bind(appointment_id)
+    .bind(technician_id)
+    .bind(amount)
+    .bind(payment_method)
+    .bind(created_by)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+  }
+
+  Ok(tips)
+}
+
 pub async fn get_appointments(
   db: &PgPool,
   filter: Option<AppointmentFilter>,
@@ -105,11 +506,20 @@ pub async fn get_appointments(
       SELECT 
         a.*,
         COALESCE(json_agg(json_build_object(
-          'id', s.id,
-          'service_name', s.service_name,
-          'service_name_en', s.service_name_en,
-          'price', s.price
-        )) FILTER (WHERE s.id IS NOT NULL), '[]'::json) AS services,
+          'id', aps.service_id,
+          'appointment_service_id', aps.id,
+          'service_name', aps.service_name,
+          'service_name_en', aps.service_name_en,
+          'service_name_ko', aps.service_name_ko,
+          'price', aps.unit_price,
+          'quantity', aps.quantity,
+          'discount', aps.discount,
+          'sequence', aps.sequence,
+          'technician_id', aps.technician_id,
+          'start_time', aps.start_time,
+          'end_time', aps.end_time,
+          'status', aps.status
+        ) ORDER BY aps.sequence) FILTER (WHERE aps.id IS NOT NULL), '[]'::json) AS services,
         json_build_object(
           'id', u.pk_user_id,
           'full_name', u.full_name,
@@ -133,7 +543,6 @@ pub async fn get_appointments(
         END AS technician
       FROM users.appointments a
       LEFT JOIN users.appointments_services aps ON a.id = aps.appointment_id
-      LEFT JOIN users.service_items s ON aps.service_id = s.id
       LEFT JOIN users.tbl_users u ON a.user_id = u.pk_user_id
       LEFT JOIN users.tbl_users u2 ON a.receptionist_id = u2.pk_user_id
       LEFT JOIN users.tbl_users u3 ON a.technician_id = u3.pk_user_id
@@ -1,25 +1,34 @@
-use super::notification_token::SqlxNotiTokenRepository;
 use crate::repositories::{
-  appointment::common::get_membership_level, notification::SqlxNotificationRepository,
+  appointment::common::{
+    build_legacy_tenders, get_membership_level, insert_appointment_payments,
+    insert_appointment_tips, loyalty_points,
+  },
+  email::enqueue_appointment_email,
+  payroll::create_commissions,
+  receipt::issue_receipt,
+  referral::insert_referral,
+  webhook::enqueue_appointment_event,
+  zalo::enqueue_zalo_message,
 };
 use async_trait::async_trait;
 use core_app::{AppResult, errors::AppError};
 use domain::{
   entities::{
     appointment::{
-      Appointment, AppointmentExtra, AppointmentFilter, AppointmentWithServices,
-      AppointmentWithUserDelete, CreateAppointmentRequest, PaymentAppointmentRequest,
-      UpdateAppointmentRequest,
+      Appointment, AppointmentExtra, AppointmentFilter, AppointmentService,
+      AppointmentWithServices, AppointmentWithUserDelete, CreateAppointmentRequest,
+      PaymentAppointmentRequest, UpdateAppointmentRequest, UpdateAppointmentServiceRequest,
     },
     common::PaginationMetadata,
+    notification_template::NotificationMessage,
+    referral::NewReferral,
     user::{User, UserWithPassword},
   },
   repositories::appointment_repository::AppointmentRepository,
 };
 use modql::filter::ListOptions;
 use serde_json;
-use sqlx::PgPool;
-use std::sync::Arc;
+use sqlx::{PgConnection, PgPool};
 use utils::format_number::format_number;
 pub mod common;
 pub mod send_noti;
@@ -29,6 +38,252 @@ pub struct SqlxAppointmentRepository {
   pub db: PgPool,
 }
 
+/// Ghi nhận thanh toán lịch hẹn trong transaction của nơi gọi: trừ ví/điểm, chia tip, hoa hồng,
+/// cấp hoá đơn và đưa thông báo vào hàng đợi. actor_id là nhân viên chịu trách nhiệm thu tiền.
+pub async fn pay_appointment(
+  conn: &mut PgConnection,
+  actor_id: i64,
+  id: i64,
+  payload: &PaymentAppointmentRequest,
+) -> AppResult<()> {
+  // Khoá lịch hẹn và khách hàng đến hết transaction để hai lần thanh toán/tip đồng thời
+  // không cùng đọc một trạng thái và số dư
+  let appointment = sqlx::query_as::<_, Appointment>(
+    r#"
+    SELECT *
+    FROM users.appointments
+    WHERE id = $1
+    FOR UPDATE
+    "#,
+  )
+  .bind(id)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  let get_user = sqlx::query_as::<_, User>(
+    r#"
+  SELECT *
+  FROM users.tbl_users
+  WHERE pk_user_id = $1
+  FOR UPDATE
+  "#,
+  )
+  .bind(appointment.user_id)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  if appointment.status != "COMPLETED" {
+    return Err(AppError::BadRequest(
+      "Lịch hẹn cần được hoàn thành trước khi thanh toán".to_string(),
+    ));
+  }
+
+  if payload.user_balance > get_user.balance {
+    return Err(AppError::BadRequest("Số dư tiền khách hàng không đúng".to_string()));
+  }
+
+  let tenders = match payload.tenders.clone() {
+    Some(tenders) => tenders,
+    None => build_legacy_tenders(
+      payload.user_balance,
+      appointment.total_price,
+      payload.payment_method.as_deref(),
+    ),
+  };
+
+  let paid: i64 = tenders.iter().map(|tender| tender.amount).sum();
+  if paid != appointment.total_price {
+    return Err(AppError::BadRequest(format!(
+      "Tổng tiền thanh toán {} không khớp với tổng hoá đơn {}",
+      format_number(paid),
+      format_number(appointment.total_price)
+    )));
+  }
+
+  let sum_tender = |tender_type: &str| -> i64 {
+    tenders.iter().filter(|tender| tender.tender_type == tender_type).map(|t| t.amount).sum()
+  };
+  let wallet_amount = sum_tender("WALLET");
+  let points_amount = sum_tender("POINTS");
+  let (points_used, point) = loyalty_points(appointment.total_price, points_amount);
+
+  if points_used > get_user.loyalty_points {
+    return Err(AppError::BadRequest("Điểm tích luỹ của khách hàng không đủ".to_string()));
+  }
+
+  // Tiền tip tách riêng khỏi doanh thu, không tính điểm tích luỹ
+  let tip = match (payload.tip_amount, payload.tip_percent) {
+    (Some(amount), _) => amount,
+    (None, Some(percent)) => ((appointment.total_price * percent) as f64 / 100.0).round() as i64,
+    _ => 0,
+  };
+  let tip_payment_method =
+    payload.tip_payment_method.clone().unwrap_or_else(|| "CASH".to_string());
+  let tip_from_wallet = tip > 0 && tip_payment_method == "WALLET";
+
+  if wallet_amount + if tip_from_wallet { tip } else { 0 } > get_user.balance {
+    return Err(AppError::BadRequest("Số dư ví của khách hàng không đủ".to_string()));
+  }
+
+  let new_point = get_user.loyalty_points - points_used + point;
+  let member_ship = get_membership_level(new_point);
+
+  insert_appointment_payments(&mut *conn, id, appointment.user_id, &tenders, actor_id)
+    .await?;
+
+  let updated = sqlx::query(
+    r#"
+    UPDATE users.tbl_users
+    SET balance = balance - $1,
+        loyalty_points = $2,
+        membership_level = $3
+    WHERE pk_user_id = $4 AND balance >= $1
+    "#,
+  )
+  .bind(wallet_amount)
+  .bind(new_point)
+  .bind(member_ship)
+  .bind(appointment.user_id)
+  .execute(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  if updated.rows_affected() == 0 {
+    return Err(AppError::BadRequest("Số dư ví của khách hàng không đủ".to_string()));
+  }
+
+  let mut tips = Vec::new();
+  if tip > 0 {
+    tips = insert_appointment_tips(
+      &mut *conn,
+      id,
+      appointment.technician_id,
+      tip,
+      &tip_payment_method,
+      actor_id,
+    )
+    .await?;
+
+    if tip_from_wallet {
+      let _ = sqlx::query_as::<_, domain::entities::deposit::Deposit>(
+        r#"
+      INSERT INTO users.deposits (
+        user_id, amount, payment_method, status, created_by, deposit_type, notes
+      )
+      VALUES ($1, $2, $3, $4, $5, $6, $7)
+      RETURNING *
+      "#,
+      )
+      .bind(appointment.user_id)
+      .bind(tip)
+      .bind("WALLET")
+      .bind("COMPLETED")
+      .bind(actor_id)
+      .bind("TIP")
+      .bind(format!("Tiền tip lịch hẹn #{}", id))
+      .fetch_one(&mut *conn)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+      let updated = sqlx::query(
+        r#"
+      UPDATE users.tbl_users
+      SET balance = balance - $1
+      WHERE pk_user_id = $2 AND balance >= $1
+      "#,
+      )
+      .bind(tip)
+      .bind(appointment.user_id)
+      .execute(&mut *conn)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+      if updated.rows_affected() == 0 {
+        return Err(AppError::BadRequest("Số dư ví của khách hàng không đủ".to_string()));
+      }
+    }
+  }
+
+  // Ghi nhận hoa hồng cho kỹ thuật viên theo từng dịch vụ
+  create_commissions(&mut *conn, id, actor_id).await?;
+
+  // Update appointment status to PAID
+  sqlx::query_as::<_, Appointment>(
+    r#"
+    UPDATE users.appointments
+    SET status = 'PAYMENT', updated_by = $1
+    WHERE id = $2 AND status = 'COMPLETED'
+    RETURNING *
+    "#,
+  )
+  .bind(actor_id)
+  .bind(id)
+  .fetch_optional(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?
+  .ok_or(AppError::BadRequest("Lịch hẹn cần được hoàn thành trước khi thanh toán".to_string()))?;
+
+  // Cấp số hoá đơn cùng transaction để không bị nhảy số khi thanh toán lỗi
+  issue_receipt(&mut *conn, id, actor_id).await?;
+  enqueue_zalo_message(&mut *conn, id, "PAYMENT_RECEIPT").await?;
+  enqueue_appointment_email(&mut *conn, id, "PAYMENT_RECEIPT").await?;
+  enqueue_appointment_event(
+    &mut *conn,
+    "appointment.status_changed",
+    id,
+    serde_json::json!({ "old_status": appointment.status }),
+  )
+  .await?;
+  enqueue_appointment_event(&mut *conn, "appointment.paid", id, serde_json::json!({})).await?;
+
+  create_notification(
+    &mut *conn,
+    appointment.user_id,
+    NotificationMessage::new(
+      "APPOINTMENT_PAID_POINTS",
+      serde_json::json!({
+        "user_name": payload.full_name,
+        "points": format_number(point)
+      }),
+    ),
+    "CUSTOMER".to_string(),
+    Some(id),
+    Some(serde_json::json!({
+      "appointment_id": id,
+      "user_name": payload.full_name,
+      "start_time": appointment.start_time,
+      "user_id": appointment.user_id
+    })),
+  )
+  .await?;
+
+  for (technician_id, amount) in tips {
+    create_notification(
+      &mut *conn,
+      technician_id,
+      NotificationMessage::new(
+        "TIP_RECEIVED",
+        serde_json::json!({
+          "user_name": payload.full_name,
+          "amount": format_number(amount)
+        }),
+      ),
+      "TECHNICIAN".to_string(),
+      Some(id),
+      Some(serde_json::json!({
+        "appointment_id": id,
+        "tip_amount": amount,
+        "start_time": appointment.start_time,
+      })),
+    )
+    .await?;
+  }
+
+  Ok(())
+}
+
 #[async_trait]
 impl AppointmentRepository for SqlxAppointmentRepository {
   async fn create_appointment(
@@ -36,17 +291,19 @@ impl AppointmentRepository for SqlxAppointmentRepository {
     user: UserWithPassword,
     payload: CreateAppointmentRequest,
     create_by_role: String,
+    referral: Option<NewReferral>,
   ) -> AppResult<AppointmentWithServices> {
     let updated_by = user.pk_user_id;
     let db = self.db.clone();
-    let services = payload.services.clone();
-    let services_for_check = services.clone();
-    for service in services_for_check {
-      let is_exit = common::check_exit_service(&db, service).await?;
+    let services = common::normalize_services(&payload.services, payload.technician_id);
+    for service in &services {
+      let is_exit = common::check_exit_service(&db, service.service_id).await?;
       if !is_exit {
         return Err(AppError::BadRequest("Service not found".to_string()));
       }
     }
+    common::check_technicians(&db, services.iter().filter_map(|s| s.technician_id).collect())
+      .await?;
 
     let count_pending =
       common::count_appointment_by_user_id_and_status(&db, payload.user_id, "PENDING".to_string())
@@ -59,34 +316,10 @@ impl AppointmentRepository for SqlxAppointmentRepository {
 
     let mut tx = db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
 
-    // Calculate initial price based on services
-    let initial_price = if !services.is_empty() {
-      sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COALESCE(SUM(price), 0)
-        FROM users.service_items
-        WHERE id = ANY($1)
-        "#,
-      )
-      .bind(&services)
-      .fetch_one(&mut *tx)
-      .await
-      .map_err(|err| AppError::BadRequest(err.to_string()))?
-    } else {
-      0
-    };
-
     // Apply surcharge and promotion (get from payload or default to 0)
     let surcharge = payload.surcharge.unwrap_or(0i64);
     let promotion = payload.promotion.unwrap_or(0i64);
 
-    let final_price = initial_price + surcharge - promotion;
-
-    // Validate final price is non-negative (optional, but good practice)
-    if final_price < 0 {
-      return Err(AppError::BadRequest("Calculated price cannot be negative".to_string()));
-    }
-
     let res = sqlx::query_as::<_, Appointment>(
       r#"
         INSERT INTO users.appointments (
@@ -108,86 +341,100 @@ impl AppointmentRepository for SqlxAppointmentRepository {
     .bind(payload.notes)
     .bind(surcharge)
     .bind(promotion)
-    .bind(initial_price)
-    .bind(final_price)
+    .bind(0i64)
+    .bind(0i64)
     .fetch_one(&mut *tx)
     .await
     .map_err(|err| AppError::Unhandled(Box::new(err)))?;
 
-    for service in services {
+    for service in &services {
       common::insert_appointment_service(
         &mut *tx,
         res.id,
         service,
+        Some(res.start_time.clone()),
         updated_by,
-        payload.technician_id,
       )
       .await?;
     }
 
-    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    // Tính giá dựa trên giá dịch vụ đã lưu
+    let initial_price = common::sum_appointment_services(&mut *tx, res.id).await?;
+    let final_price = initial_price + surcharge - promotion;
 
-    let appointment: AppointmentWithServices = self.get_appointment_by_id(user, res.id).await?;
-    let user_full_name =
-      appointment.user.get("full_name").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    // Validate final price is non-negative (optional, but good practice)
+    if final_price < 0 {
+      return Err(AppError::BadRequest("Calculated price cannot be negative".to_string()));
+    }
 
-    // Create notification in background
-    let db = self.db.clone();
-    let notification_repo = Arc::new(SqlxNotificationRepository { db: db.clone() });
-    let notification_token_repo = Arc::new(SqlxNotiTokenRepository { db: db.clone() });
+    sqlx::query(r#"UPDATE users.appointments SET price = $1, total_price = $2 WHERE id = $3"#)
+      .bind(initial_price)
+      .bind(final_price)
+      .bind(res.id)
+      .execute(&mut *tx)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
 
+    // Lỗi ghi mã giới thiệu sẽ huỷ luôn lịch hẹn, lễ tân gửi lại không bị đặt trùng
+    if let Some(referral) = referral {
+      insert_referral(&mut tx, referral).await?;
+    }
+
+    let user_full_name = sqlx::query_scalar::<_, Option<String>>(
+      r#"SELECT full_name FROM users.tbl_users WHERE pk_user_id = $1"#,
+    )
+    .bind(payload.user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .flatten()
+    .unwrap_or_default();
+
+    // Thông báo được ghi vào outbox cùng transaction, worker sẽ gửi sau khi commit
     let mut type_send = "ALLRECEPTIONIST".to_string();
 
     if create_by_role == "RECEPTIONIST".to_string() {
       type_send = "CUSTOMER".to_string();
     }
-    tokio::spawn(async move {
-      match create_notification(
-        &db,
-        notification_repo.clone(),
-        notification_token_repo.clone(),
-        payload.user_id,
-        "Lịch hẹn mới".to_string(),
-        format!("{} vừa đặt lịch hẹn thành công! Vui lòng vào kiểm tra. ", user_full_name),
-        type_send.clone(),
-        Some(res.id),
-        Some(serde_json::json!({
-          "appointment_id": res.id,
-          "user_name": user_full_name,
-          "start_time": res.start_time,
-          "user_id": res.user_id
-        })),
-      )
-      .await
-      {
-        Ok(_) => tracing::info!("Notification sent successfully"),
-        Err(e) => tracing::error!("Failed to send notification: {:?}", e),
-      }
+    create_notification(
+      &mut tx,
+      payload.user_id,
+      NotificationMessage::new(
+        "APPOINTMENT_CREATED",
+        serde_json::json!({ "user_name": user_full_name }),
+      ),
+      type_send.clone(),
+      Some(res.id),
+      Some(serde_json::json!({
+        "appointment_id": res.id,
+        "user_name": user_full_name,
+        "start_time": res.start_time,
+        "user_id": res.user_id
+      })),
+    )
+    .await?;
 
-      if let Some(tech_id) = payload.technician_id {
-        match create_notification(
-          &db,
-          notification_repo,
-          notification_token_repo,
-          tech_id,
-          "Phân công lịch hẹn".to_string(),
-          format!("{} vừa đặt lịch hẹn thành công! Vui lòng vào kiểm tra. ", user_full_name),
-          "TECHNICIAN".to_string(),
-          Some(res.id),
-          Some(serde_json::json!({
-            "appointment_id": res.id,
-            "user_name": user_full_name,
-            "start_time": res.start_time,
-            "user_id": res.user_id
-          })),
-        )
-        .await
-        {
-          Ok(_) => tracing::info!("Notification sent successfully"),
-          Err(e) => tracing::error!("Failed to send notification: {:?}", e),
-        }
-      }
-    });
+    send_noti_line_technicians(
+      &mut tx,
+      res.id,
+      NotificationMessage::new(
+        "APPOINTMENT_CREATED_TECHNICIAN",
+        serde_json::json!({ "user_name": user_full_name }),
+      ),
+      Some(serde_json::json!({
+        "appointment_id": res.id,
+        "user_name": user_full_name,
+        "start_time": res.start_time,
+        "user_id": res.user_id
+      })),
+    )
+    .await?;
+
+    enqueue_appointment_event(&mut tx, "appointment.created", res.id, serde_json::json!({}))
+      .await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let appointment: AppointmentWithServices = self.get_appointment_by_id(user, res.id).await?;
 
     Ok(appointment)
   }
@@ -200,16 +447,6 @@ impl AppointmentRepository for SqlxAppointmentRepository {
   ) -> AppResult<AppointmentWithServices> {
     let updated_by = user.pk_user_id;
     let db = self.db.clone();
-    let services = payload.services.clone().unwrap_or_default();
-    let services_for_check = services.clone();
-    if !services.is_empty() {
-      for service in services_for_check {
-        let is_exit = common::check_exit_service(&db, service).await?;
-        if !is_exit {
-          return Err(AppError::BadRequest("Service not found".to_string()));
-        }
-      }
-    }
 
     // Lấy thông tin cũ trước khi update
     let old_appointment: AppointmentWithUserDelete =
@@ -221,24 +458,102 @@ impl AppointmentRepository for SqlxAppointmentRepository {
       .await?
       .ok_or(AppError::NotFound)?;
 
+    let default_technician_id = payload.technician_id.or(old_appointment.technician_id);
+    let services = common::normalize_services(
+      &payload.services.clone().unwrap_or_default(),
+      default_technician_id,
+    );
+    for service in &services {
+      let is_exit = common::check_exit_service(&db, service.service_id).await?;
+      if !is_exit {
+        return Err(AppError::BadRequest("Service not found".to_string()));
+      }
+    }
+    let mut technician_ids: Vec<i64> = services.iter().filter_map(|s| s.technician_id).collect();
+    technician_ids.extend(payload.technician_id);
+    common::check_technicians(&db, technician_ids).await?;
+
     let mut tx = db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
 
-    // Calculate initial price based on services
-    let initial_price = if !services.is_empty() {
-      sqlx::query_scalar::<_, i64>(
+    if !services.is_empty() {
+      // Giữ lại các dòng cùng dịch vụ và kỹ thuật viên, dòng không còn trong danh sách sẽ bị xoá
+      let mut existing = common::get_appointment_services(&mut *tx, id).await?;
+      let mut matched = Vec::new();
+      for service in &services {
+        let found = existing.iter().position(|ap| {
+          ap.service_id == service.service_id && ap.technician_id == service.technician_id
+        });
+        matched.push(found.map(|index| existing.remove(index)));
+      }
+
+      let removed_ids: Vec<i64> = existing.iter().map(|ap| ap.id).collect();
+      sqlx::query(r#"DELETE FROM users.appointments_services WHERE id = ANY($1)"#)
+        .bind(&removed_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| {
+          tracing::error!("Failed to delete old services: {}", err);
+          AppError::BadRequest(err.to_string())
+        })?;
+
+      for (service, ap) in services.iter().zip(matched) {
+        match ap {
+          // Dịch vụ đã có giữ nguyên giá lúc đặt lịch
+          Some(ap) => {
+            common::update_appointment_service_line(&mut *tx, ap.id, service, updated_by).await?;
+          },
+          None => {
+            common::insert_appointment_service(
+              &mut *tx,
+              id,
+              service,
+              Some(payload.start_time.clone().unwrap_or(old_appointment.start_time.clone())),
+              updated_by,
+            )
+            .await?;
+          },
+        }
+      }
+    } else if let Some(technician_id) = payload.technician_id {
+      // Đổi kỹ thuật viên của lịch hẹn: chuyển các dòng của kỹ thuật viên cũ sang kỹ thuật viên mới
+      sqlx::query(
         r#"
-        SELECT COALESCE(SUM(price), 0)
-        FROM users.service_items
-        WHERE id = ANY($1)
+        UPDATE users.appointments_services
+        SET technician_id = $1, updated_by = $2
+        WHERE appointment_id = $3
+        AND (technician_id IS NULL OR technician_id IS NOT DISTINCT FROM $4)
         "#,
       )
-      .bind(&services)
-      .fetch_one(&mut *tx)
+      .bind(technician_id)
+      .bind(updated_by)
+      .bind(id)
+      .bind(old_appointment.technician_id)
+      .execute(&mut *tx)
       .await
-      .map_err(|err| AppError::BadRequest(err.to_string()))?
-    } else {
-      old_appointment.price // Use old_appointment.price for base service price if services are not updated
-    };
+      .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    }
+
+    // Lịch hẹn hoàn thành hoặc bị huỷ thì các dịch vụ chưa xong cũng theo trạng thái đó
+    if let Some(status) = payload.status.as_deref() {
+      if status == "COMPLETED" || status == "CANCELLED" {
+        sqlx::query(
+          r#"
+          UPDATE users.appointments_services
+          SET status = $1, updated_by = $2
+          WHERE appointment_id = $3 AND status NOT IN ('COMPLETED', 'CANCELLED')
+          "#,
+        )
+        .bind(status)
+        .bind(updated_by)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| AppError::BadRequest(err.to_string()))?;
+      }
+    }
+
+    // Calculate initial price based on the booked service snapshot
+    let initial_price = common::sum_appointment_services(&mut *tx, id).await?;
 
     tracing::info!("Calculated initial price: {:?}", initial_price); // Update tracing message
 
@@ -254,7 +569,7 @@ impl AppointmentRepository for SqlxAppointmentRepository {
       return Err(AppError::BadRequest("Final price cannot be negative".to_string()));
     }
 
-    let res: AppointmentWithUserDelete = sqlx::query_as::<_, AppointmentWithUserDelete>(
+    let _ = sqlx::query_as::<_, AppointmentWithUserDelete>(
       r#"
         UPDATE users.appointments
         SET
@@ -297,47 +612,7 @@ impl AppointmentRepository for SqlxAppointmentRepository {
     .await
     .map_err(|err| AppError::BadRequest(err.to_string()))?;
 
-    if !services.is_empty() {
-      sqlx::query(
-        r#"
-        DELETE FROM users.appointments_services
-        WHERE appointment_id = $1
-        AND service_id NOT IN (
-            SELECT unnest($2::bigint[])
-        )
-        "#,
-      )
-      .bind(id)
-      .bind(&services)
-      .execute(&mut *tx)
-      .await
-      .map_err(|err| {
-        tracing::error!("Failed to delete old services: {}", err);
-        AppError::BadRequest(err.to_string())
-      })?;
-
-      for service in services {
-        let ap = common::check_appointment_service(&db, res.id, service).await?;
-        if ap.is_none() {
-          common::insert_appointment_service(
-            &mut *tx,
-            res.id,
-            service,
-            updated_by,
-            payload.technician_id,
-          )
-          .await?;
-        }
-      }
-    }
-
-    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
-
-    let res = self.get_appointment(user.clone(), id).await?;
-    let db = self.db.clone();
-    let notification_repo = Arc::new(SqlxNotificationRepository { db: db.clone() });
-    let notification_token_repo = Arc::new(SqlxNotiTokenRepository { db: db.clone() });
-    let res_clone = res.clone();
+    let res = common::get_appointment_with_services(&mut *tx, id).await?;
 
     // Kiểm tra các thay đổi quan trọng
     let mut has_important_changes = false;
@@ -371,202 +646,186 @@ impl AppointmentRepository for SqlxAppointmentRepository {
         }
       }
 
-      tracing::info!("Starting...");
+      send_noti_update(&mut tx, user, res.clone(), send_status).await?;
+    }
+
+    // Tin ZNS cho khách: xác nhận, huỷ hoặc đổi giờ lịch hẹn
+    let start_time_changed = payload
+      .start_time
+      .as_ref()
+      .is_some_and(|start_time| start_time != &old_appointment.start_time);
+    let zalo_event =
+      match payload.status.as_deref().filter(|status| *status != old_appointment.status) {
+        Some("CONFIRMED") => Some("APPOINTMENT_CONFIRMED"),
+        Some("CANCELLED") => Some("APPOINTMENT_CANCELLED"),
+        _ if start_time_changed => Some("APPOINTMENT_RESCHEDULED"),
+        _ => None,
+      };
+    if let Some(event_type) = zalo_event {
+      enqueue_zalo_message(&mut tx, id, event_type).await?;
+    }
+    if zalo_event == Some("APPOINTMENT_CONFIRMED") {
+      enqueue_appointment_email(&mut tx, id, "APPOINTMENT_CONFIRMED").await?;
+    }
 
-      tokio::spawn(async move {
-        let _ = send_noti_update(
-          &db,
-          notification_repo,
-          notification_token_repo,
-          user,
-          res_clone,
-          send_status,
+    if let Some(status) = payload.status.as_deref() {
+      if status != old_appointment.status {
+        enqueue_appointment_event(
+          &mut tx,
+          "appointment.status_changed",
+          id,
+          serde_json::json!({ "old_status": old_appointment.status }),
         )
-        .await;
-      });
+        .await?;
+      }
     }
 
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
     Ok(res)
   }
 
-  async fn payment_appointment(
+  async fn update_appointment_service(
     &self,
     user: UserWithPassword,
+    appointment_id: i64,
     id: i64,
-    payload: PaymentAppointmentRequest,
+    payload: UpdateAppointmentServiceRequest,
   ) -> AppResult<AppointmentWithServices> {
-    let appointment = sqlx::query_as::<_, Appointment>(
-      r#"
-      SELECT *
-      FROM users.appointments
-      WHERE id = $1
-      "#,
+    let old_line = sqlx::query_as::<_, AppointmentService>(
+      r#"SELECT * FROM users.appointments_services WHERE id = $1 AND appointment_id = $2"#,
     )
     .bind(id)
-    .fetch_one(&self.db)
-    .await
-    .map_err(|err| AppError::BadRequest(err.to_string()))?;
-
-    let get_user = sqlx::query_as::<_, User>(
-      r#"
-    SELECT *
-    FROM users.tbl_users
-    WHERE pk_user_id = $1
-    "#,
-    )
-    .bind(appointment.user_id)
-    .fetch_one(&self.db)
-    .await
-    .map_err(|err| AppError::BadRequest(err.to_string()))?;
-
-    if appointment.status != "COMPLETED" {
-      return Err(AppError::BadRequest(
-        "Lịch hẹn cần được hoàn thành trước khi thanh toán".to_string(),
-      ));
-    }
+    .bind(appointment_id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
 
-    if payload.user_balance > get_user.balance {
-      return Err(AppError::BadRequest("Số dư tiền khách hàng không đúng".to_string()));
+    // Kỹ thuật viên chỉ được cập nhật dịch vụ của mình và không được tự phân công lại
+    if user.role == "TECHNICIAN"
+      && (old_line.technician_id != Some(user.pk_user_id) || payload.technician_id.is_some())
+    {
+      return Err(AppError::Forbidden("You don't have permission".to_string()));
     }
-    let mut amount_payment = appointment.total_price;
 
-    if payload.user_balance > 0 {
-      amount_payment = amount_payment - payload.user_balance;
+    if let Some(technician_id) = payload.technician_id {
+      common::check_technicians(&self.db, vec![technician_id]).await?;
     }
 
-    let point = ((appointment.total_price as f64) / 1000.0).round() as i64;
-
     let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
 
-    let new_point = get_user.loyalty_points + point;
-    let member_ship = get_membership_level(new_point);
-
-    if amount_payment > 0 {
-      let _ = sqlx::query_as::<_, domain::entities::deposit::Deposit>(
-        r#"
-      INSERT INTO users.deposits (
-        user_id, amount, payment_method, status, created_by, deposit_type
-      )
-      VALUES ($1, $2, $3, $4, $5, $6)
-      RETURNING *
-      "#,
-      )
-      .bind(appointment.user_id)
-      .bind(amount_payment)
-      .bind(payload.payment_method.clone())
-      .bind("COMPLETED")
-      .bind(user.pk_user_id)
-      .bind("PAYMENT")
-      .fetch_one(&mut *tx)
-      .await
-      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
-    }
-
-    if payload.user_balance > 0 {
-      let _ = sqlx::query_as::<_, domain::entities::deposit::Deposit>(
-        r#"
-      INSERT INTO users.deposits (
-        user_id, amount, payment_method, status, created_by, deposit_type
-      )
-      VALUES ($1, $2, $3, $4, $5, $6)
-      RETURNING *
-      "#,
-      )
-      .bind(appointment.user_id)
-      .bind(payload.user_balance)
-      .bind(payload.payment_method)
-      .bind("COMPLETED")
-      .bind(user.pk_user_id)
-      .bind("WITHDRAW")
-      .fetch_one(&mut *tx)
-      .await
-      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
-
-      sqlx::query(
-        r#"
-      UPDATE users.tbl_users
-      SET balance = balance - $1,
-          loyalty_points = loyalty_points + $2,
-          membership_level=$3    
-      WHERE pk_user_id = $4
-      "#,
-      )
-      .bind(payload.user_balance)
-      .bind(point)
-      .bind(member_ship)
-      .bind(appointment.user_id)
-      .execute(&mut *tx)
-      .await
-      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
-    } else {
-      sqlx::query(
-        r#"
-      UPDATE users.tbl_users
-      SET loyalty_points = loyalty_points + $1, membership_level=$2
-      WHERE pk_user_id = $3
-      "#,
-      )
-      .bind(point)
-      .bind(member_ship)
-      .bind(appointment.user_id)
-      .execute(&mut *tx)
-      .await
-      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
-    }
-
-    // Update appointment status to PAID
-    let _ = sqlx::query_as::<_, Appointment>(
+    let line = sqlx::query_as::<_, AppointmentService>(
       r#"
-      UPDATE users.appointments
-      SET status = 'PAYMENT', updated_by = $1
-      WHERE id = $2
+      UPDATE users.appointments_services
+      SET technician_id = COALESCE($1, technician_id),
+          start_time = COALESCE($2, start_time),
+          end_time = COALESCE($3, end_time),
+          status = COALESCE($4, status),
+          updated_by = $5
+      WHERE id = $6
       RETURNING *
       "#,
     )
+    .bind(payload.technician_id)
+    .bind(payload.start_time)
+    .bind(payload.end_time)
+    .bind(payload.status)
     .bind(user.pk_user_id)
     .bind(id)
     .fetch_one(&mut *tx)
     .await
-    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
 
-    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    let res = common::get_appointment_with_services(&mut *tx, appointment_id).await?;
 
-    // Get updated appointment with services
-    let result = self.get_appointment(user, id).await?;
+    let user_full_name =
+      res.user.get("full_name").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let receptionist_id =
+      res.receptionist.as_ref().and_then(|r| r.get("id")).and_then(|v| v.as_i64()).unwrap_or(0);
+    let service_name = line.service_name.clone().unwrap_or_default();
+    let start_time = line.start_time.clone().unwrap_or(res.start_time.clone());
+    let data = serde_json::json!({
+      "type": "APPOINTMENT",
+      "appointment_id": appointment_id,
+      "appointment_service_id": line.id,
+      "user_name": user_full_name,
+      "start_time": start_time
+    });
 
-    // Send notification
-    let db = self.db.clone();
-    let notification_repo = Arc::new(SqlxNotificationRepository { db: db.clone() });
-    let notification_token_repo = Arc::new(SqlxNotiTokenRepository { db: db.clone() });
-    let result_clone = result.clone();
-
-    tokio::spawn(async move {
-      match create_notification(
-        &db,
-        notification_repo,
-        notification_token_repo,
-        appointment.user_id,
-        "Thanh toán thành công".to_string(),
-        format!(
-          "Lịch hẹn của {} đã được thanh toán thành công. Bạn được nhận thêm vào {} điểm",
-          payload.full_name,
-          format_number(point)
+    let variables = serde_json::json!({
+      "service_name": service_name,
+      "user_name": user_full_name,
+      "start_time": start_time
+    });
+
+    if line.technician_id != old_line.technician_id {
+      if let Some(technician_id) = line.technician_id {
+        create_notification(
+          &mut tx,
+          technician_id,
+          NotificationMessage::new("SERVICE_ASSIGNED", variables.clone()),
+          "TECHNICIAN".to_string(),
+          Some(appointment_id),
+          Some(data.clone()),
+        )
+        .await?;
+      }
+
+      if let Some(old_technician_id) = old_line.technician_id {
+        create_notification(
+          &mut tx,
+          old_technician_id,
+          NotificationMessage::new("SERVICE_UNASSIGNED", variables.clone()),
+          "TECHNICIAN".to_string(),
+          Some(appointment_id),
+          Some(data.clone()),
+        )
+        .await?;
+      }
+    }
+
+    // Kỹ thuật viên cập nhật tiến độ dịch vụ thì báo cho lễ tân
+    if line.status != old_line.status && user.role == "TECHNICIAN" {
+      let receiver = if receptionist_id > 0 {
+        "RECEPTIONIST".to_string()
+      } else {
+        "ALLRECEPTIONIST".to_string()
+      };
+      create_notification(
+        &mut tx,
+        receptionist_id,
+        NotificationMessage::new(
+          "SERVICE_STATUS_UPDATED",
+          serde_json::json!({
+            "service_name": service_name,
+            "user_name": user_full_name,
+            "status": line.status
+          }),
         ),
-        "CUSTOMER".to_string(),
-        Some(result_clone.id),
-        Some(serde_json::json!({
-          "appointment_id": result_clone.id,
-          "user_name": payload.full_name,
-          "start_time": result_clone.start_time,
-          "user_id": appointment.user_id
-        })),
+        receiver,
+        Some(appointment_id),
+        Some(data),
       )
-      .await
-      {
-        Ok(_) => tracing::info!("Payment notification sent successfully"),
-        Err(e) => tracing::error!("Failed to send payment notification: {:?}", e),
-      }
-    });
+      .await?;
+    }
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(res)
+  }
+
+  async fn payment_appointment(
+    &self,
+    user: UserWithPassword,
+    id: i64,
+    payload: PaymentAppointmentRequest,
+  ) -> AppResult<AppointmentWithServices> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    pay_appointment(&mut tx, user.pk_user_id, id, &payload).await?;
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    // Get updated appointment with services
+    let result = self.get_appointment(user, id).await?;
 
     Ok(result)
   }
@@ -576,48 +835,7 @@ impl AppointmentRepository for SqlxAppointmentRepository {
     _: UserWithPassword,
     id: i64,
   ) -> AppResult<AppointmentWithServices> {
-    let res = sqlx::query_as::<_, AppointmentWithServices>(
-      r#"
-       SELECT 
-          a.*,
-          COALESCE(json_agg(s.*) FILTER (WHERE s.id IS NOT NULL), '[]'::json) AS services,
-          json_build_object(
-            'id', u.pk_user_id,
-            'full_name', u.full_name,
-            'phone', u.phone
-          ) AS user,
-         CASE 
-        WHEN a.receptionist_id IS NULL THEN NULL
-        ELSE json_build_object(
-            'id', u2.pk_user_id,
-            'full_name', u2.full_name,
-            'phone', u2.phone
-        )
-        END AS receptionist,
-        CASE 
-        WHEN a.technician_id IS NULL THEN NULL
-        ELSE json_build_object(
-            'id', u3.pk_user_id,
-            'full_name', u3.full_name,
-            'phone', u3.phone
-        )
-    END AS technician
-      FROM users.appointments a
-      LEFT JOIN users.appointments_services aps ON a.id = aps.appointment_id
-      LEFT JOIN users.service_items s ON aps.service_id = s.id
-      LEFT JOIN users.tbl_users u ON a.user_id = u.pk_user_id
-      LEFT JOIN users.tbl_users u2 ON a.receptionist_id = u2.pk_user_id
-      LEFT JOIN users.tbl_users u3 ON a.technician_id = u3.pk_user_id
-      WHERE a.id = $1
-      GROUP BY a.id, u.pk_user_id, u.full_name, u.phone, u2.pk_user_id, u2.full_name, u2.phone, u3.pk_user_id, u3.full_name, u3.phone
-     "#,
-    )
-    .bind(id)
-    .fetch_optional(&self.db)
-    .await?
-    .ok_or(AppError::NotFound)?;
-
-    Ok(res)
+    common::get_appointment_with_services(&self.db, id).await
   }
 
   async fn get_appointment_by_id(
@@ -629,7 +847,20 @@ impl AppointmentRepository for SqlxAppointmentRepository {
        r#"
        SELECT 
           a.*,
-          COALESCE(json_agg(s.*) FILTER (WHERE s.id IS NOT NULL), '[]'::json) AS services,
+          COALESCE(jsonb_agg(to_jsonb(s.*) || jsonb_build_object(
+            'service_name', aps.service_name,
+            'service_name_en', aps.service_name_en,
+            'service_name_ko', aps.service_name_ko,
+            'price', aps.unit_price,
+            'appointment_service_id', aps.id,
+            'quantity', aps.quantity,
+            'discount', aps.discount,
+            'sequence', aps.sequence,
+            'technician_id', aps.technician_id,
+            'start_time', aps.start_time,
+            'end_time', aps.end_time,
+            'status', aps.status
+          ) ORDER BY aps.sequence) FILTER (WHERE s.id IS NOT NULL), '[]'::jsonb) AS services,
           json_build_object(
             'id', u.pk_user_id,
             'full_name', u.full_name,
@@ -719,11 +950,20 @@ impl AppointmentRepository for SqlxAppointmentRepository {
           r#"
           SELECT a.*, 
                  json_agg(json_build_object(
-                   'id', s.id,
-                   'service_name', s.service_name,
-                   'service_name_en', s.service_name_en,
-                   'price', s.price
-                 )) as services,
+                   'id', aps.service_id,
+                   'appointment_service_id', aps.id,
+                   'service_name', aps.service_name,
+                   'service_name_en', aps.service_name_en,
+                   'service_name_ko', aps.service_name_ko,
+                   'price', aps.unit_price,
+                   'quantity', aps.quantity,
+                   'discount', aps.discount,
+                   'sequence', aps.sequence,
+                   'technician_id', aps.technician_id,
+                   'start_time', aps.start_time,
+                   'end_time', aps.end_time,
+                   'status', aps.status
+                 ) ORDER BY aps.sequence) as services,
                   json_build_object(
                     'id', u.pk_user_id,
                   'full_name', u.full_name,
@@ -731,7 +971,6 @@ impl AppointmentRepository for SqlxAppointmentRepository {
                  ) as user
           FROM users.appointments a
           LEFT JOIN users.appointments_services aps ON a.id = aps.appointment_id
-          LEFT JOIN users.service_items s ON aps.service_id = s.id
           LEFT JOIN users.tbl_users u ON a.user_id = u.pk_user_id
           WHERE a.user_id = $1 
           AND a.status = 'CONFIRMED'
@@ -757,11 +996,20 @@ impl AppointmentRepository for SqlxAppointmentRepository {
           r#"
           SELECT a.*, 
                  json_agg(json_build_object(
-                   'id', s.id,
-                   'service_name', s.service_name,
-                   'service_name_en', s.service_name_en,
-                   'price', s.price
-                 )) as services,
+                   'id', aps.service_id,
+                   'appointment_service_id', aps.id,
+                   'service_name', aps.service_name,
+                   'service_name_en', aps.service_name_en,
+                   'service_name_ko', aps.service_name_ko,
+                   'price', aps.unit_price,
+                   'quantity', aps.quantity,
+                   'discount', aps.discount,
+                   'sequence', aps.sequence,
+                   'technician_id', aps.technician_id,
+                   'start_time', aps.start_time,
+                   'end_time', aps.end_time,
+                   'status', aps.status
+                 ) ORDER BY aps.sequence) as services,
                   json_build_object(
                     'id', u.pk_user_id,
                   'full_name', u.full_name,
@@ -769,7 +1017,6 @@ impl AppointmentRepository for SqlxAppointmentRepository {
                  ) as user
           FROM users.appointments  a
           LEFT JOIN users.appointments_services aps ON a.id = aps.appointment_id
-          LEFT JOIN users.service_items s ON aps.service_id = s.id
           LEFT JOIN users.tbl_users u ON a.user_id = u.pk_user_id
           WHERE a.user_id = $1 
           AND a.status = 'PENDING'
@@ -793,11 +1040,20 @@ impl AppointmentRepository for SqlxAppointmentRepository {
         r#"
           SELECT a.*, 
                  json_agg(json_build_object(
-                   'id', s.id,
-                   'service_name', s.service_name,
-                   'service_name_en', s.service_name_en,
-                   'price', s.price
-                 )) as services,
+                   'id', aps.service_id,
+                   'appointment_service_id', aps.id,
+                   'service_name', aps.service_name,
+                   'service_name_en', aps.service_name_en,
+                   'service_name_ko', aps.service_name_ko,
+                   'price', aps.unit_price,
+                   'quantity', aps.quantity,
+                   'discount', aps.discount,
+                   'sequence', aps.sequence,
+                   'technician_id', aps.technician_id,
+                   'start_time', aps.start_time,
+                   'end_time', aps.end_time,
+                   'status', aps.status
+                 ) ORDER BY aps.sequence) as services,
                    json_build_object(
                     'id', u.pk_user_id,
                   'full_name', u.full_name,
@@ -805,7 +1061,6 @@ impl AppointmentRepository for SqlxAppointmentRepository {
                  ) as user
           FROM users.appointments a
           LEFT JOIN users.appointments_services aps ON a.id = aps.appointment_id
-          LEFT JOIN users.service_items s ON aps.service_id = s.id
           LEFT JOIN users.tbl_users u ON a.user_id = u.pk_user_id
           WHERE a.status IN ('PENDING')
           GROUP BY a.id, u.pk_user_id, u.full_name, u.phone
@@ -824,11 +1079,20 @@ impl AppointmentRepository for SqlxAppointmentRepository {
         r#"
           SELECT a.*, 
                  json_agg(json_build_object(
-                   'id', s.id,
-                   'service_name', s.service_name,
-                   'service_name_en', s.service_name_en,
-                   'price', s.price
-                 )) as services,
+                   'id', aps.service_id,
+                   'appointment_service_id', aps.id,
+                   'service_name', aps.service_name,
+                   'service_name_en', aps.service_name_en,
+                   'service_name_ko', aps.service_name_ko,
+                   'price', aps.unit_price,
+                   'quantity', aps.quantity,
+                   'discount', aps.discount,
+                   'sequence', aps.sequence,
+                   'technician_id', aps.technician_id,
+                   'start_time', aps.start_time,
+                   'end_time', aps.end_time,
+                   'status', aps.status
+                 ) ORDER BY aps.sequence) as services,
                   json_build_object(
                     'id', u.pk_user_id,
                   'full_name', u.full_name,
@@ -836,9 +1100,8 @@ impl AppointmentRepository for SqlxAppointmentRepository {
                  ) as user
           FROM users.appointments a
           LEFT JOIN users.appointments_services aps ON a.id = aps.appointment_id
-          LEFT JOIN users.service_items s ON aps.service_id = s.id
           LEFT JOIN users.tbl_users u ON a.user_id = u.pk_user_id
-          WHERE a.technician_id = $1
+          WHERE aps.technician_id = $1
           AND a.status IN ('CONFIRMED')
           GROUP BY a.id, u.pk_user_id, u.full_name, u.phone
           ORDER BY TO_TIMESTAMP(a.start_time, 'HH24:MI DD/MM/YYYY') ASC
@@ -871,11 +1134,20 @@ impl AppointmentRepository for SqlxAppointmentRepository {
       SELECT 
         a.*,
         COALESCE(json_agg(json_build_object(
-          'id', s.id,
-          'service_name', s.service_name,
-          'service_name_en', s.service_name_en,
-          'price', s.price
-        )) FILTER (WHERE s.id IS NOT NULL), '[]'::json) AS services,
+          'id', aps.service_id,
+          'appointment_service_id', aps.id,
+          'service_name', aps.service_name,
+          'service_name_en', aps.service_name_en,
+          'service_name_ko', aps.service_name_ko,
+          'price', aps.unit_price,
+          'quantity', aps.quantity,
+          'discount', aps.discount,
+          'sequence', aps.sequence,
+          'technician_id', aps.technician_id,
+          'start_time', aps.start_time,
+          'end_time', aps.end_time,
+          'status', aps.status
+        ) ORDER BY aps.sequence) FILTER (WHERE aps.id IS NOT NULL), '[]'::json) AS services,
         json_build_object(
           'id', u.pk_user_id,
           'full_name', u.full_name,
@@ -899,11 +1171,10 @@ impl AppointmentRepository for SqlxAppointmentRepository {
         END AS technician
       FROM users.appointments a
       INNER JOIN users.appointments_services aps ON a.id = aps.appointment_id
-      LEFT JOIN users.service_items s ON aps.service_id = s.id
       LEFT JOIN users.tbl_users u ON a.user_id = u.pk_user_id
       LEFT JOIN users.tbl_users u2 ON a.receptionist_id = u2.pk_user_id
       LEFT JOIN users.tbl_users u3 ON a.technician_id = u3.pk_user_id
-      WHERE a.technician_id = $1
+      WHERE aps.technician_id = $1
       AND ($2::bigint IS NULL OR a.user_id = $2)
       AND ($3::bigint IS NULL OR a.receptionist_id = $3)
       AND ($4::text IS NULL OR a.status = $4)
@@ -931,7 +1202,7 @@ impl AppointmentRepository for SqlxAppointmentRepository {
       SELECT COUNT(DISTINCT a.id)
       FROM users.appointments a
       INNER JOIN users.appointments_services aps ON a.id = aps.appointment_id
-      WHERE a.technician_id = $1
+      WHERE aps.technician_id = $1
       AND ($2::bigint IS NULL OR a.user_id = $2)
       AND ($3::bigint IS NULL OR a.receptionist_id = $3)
       AND ($4::text IS NULL OR a.status = $4)
@@ -1,48 +1,16 @@
-use crate::firebase::NotificationService;
+use crate::repositories::notification_outbox::enqueue_notification;
 use core_app::{AppResult, errors::AppError};
 use domain::entities::appointment::AppointmentWithServices;
 use domain::entities::notification::CreateNotification;
+use domain::entities::notification_template::NotificationMessage;
 use domain::entities::user::UserWithPassword;
-use domain::repositories::noti_token_repository::NotificationTokenRepository;
-use domain::repositories::notification_repository::NotificationRepository;
-use sqlx::PgPool;
-use std::sync::Arc;
-
-pub async fn get_token_reception(db: &PgPool) -> AppResult<Vec<String>> {
-  let tokens = sqlx::query_scalar::<_, String>(
-    r#"
-      SELECT token FROM users.notification_tokens a 
-      INNER JOIN users.tbl_users b ON a.user_id = b.pk_user_id 
-      WHERE b.role = 'RECEPTIONIST'
-    "#,
-  )
-  .fetch_all(db)
-  .await
-  .map_err(|err| AppError::BadRequest(err.to_string()))?;
-  Ok(tokens)
-}
-
-pub async fn get_token_technician(db: &PgPool) -> AppResult<Vec<String>> {
-  let tokens = sqlx::query_scalar::<_, String>(
-    r#"
-      SELECT token FROM users.notification_tokens a 
-      INNER JOIN users.tbl_users b ON a.user_id = b.pk_user_id 
-      WHERE b.role = 'TECHNICIAN'
-    "#,
-  )
-  .fetch_all(db)
-  .await
-  .map_err(|err| AppError::BadRequest(err.to_string()))?;
-  Ok(tokens)
-}
+use sqlx::PgConnection;
 
+/// Lưu thông báo lịch hẹn (in-app) và đưa vào hàng đợi gửi FCM trong cùng connection/transaction.
 pub async fn create_notification(
-  db: &PgPool,
-  notification_repo: Arc<dyn NotificationRepository>,
-  noti_token_repo: Arc<dyn NotificationTokenRepository>,
+  conn: &mut PgConnection,
   user_id: i64,
-  title: String,
-  body: String,
+  message: NotificationMessage,
   receiver: String,
   appointment_id: Option<i64>,
   data: Option<serde_json::Value>,
@@ -50,154 +18,93 @@ pub async fn create_notification(
   tracing::info!("Starting notification creation for user_id: {}", user_id);
   let notification = CreateNotification {
     user_id: if user_id == 0 { None } else { Some(user_id) },
-    title,
-    body,
+    title: String::new(),
+    body: String::new(),
     data,
-    receiver: receiver.clone(),
+    receiver,
     notification_type: "APPOINTMENT".to_string(),
     appointment_id,
+    template: Some(message),
   };
-  let noti = notification_repo
-    .create(notification)
-    .await
-    .map_err(|err| AppError::BadRequest(err.to_string()))?;
-  tracing::info!("Notification created successfully: {:#?}", noti);
 
-  let mut tokens = vec![];
+  let outbox_id = enqueue_notification(conn, notification, true).await?;
+  tracing::info!("Notification queued successfully: outbox {}", outbox_id);
+  Ok(())
+}
 
-  match receiver.as_str() {
-    "RECEPTIONIST" => {
-      // Gửi cho lễ tân cụ thể
-      let t = noti_token_repo.get_token_by_user_id(user_id).await?;
-      tokens = t.iter().map(|item| item.token.clone()).collect();
-    },
-    "ALLRECEPTIONIST" => {
-      // Gửi cho tất cả lễ tân
-      tokens = get_token_reception(db).await?;
-    },
-    "TECHNICIAN" => {
-      // Gửi cho kỹ thuật viên cụ thể
-      let t = noti_token_repo.get_token_by_user_id(user_id).await?;
-      tokens = t.iter().map(|item| item.token.clone()).collect();
-    },
-    "ALLTECHNICIAN" => {
-      // Gửi cho tất cả kỹ thuật viên
-      tokens = get_token_technician(db).await?;
-    },
-    "CUSTOMER" => {
-      // Gửi cho user cụ thể
-      let t = noti_token_repo.get_token_by_user_id(user_id).await?;
-      tokens = t.iter().map(|item| item.token.clone()).collect();
-    },
-    "ALL" => {
-      // Gửi cho tất cả user
-      let reception_tokens = get_token_reception(db).await?;
-      let technician_tokens = get_token_technician(db).await?;
-      tokens.extend(reception_tokens);
-      tokens.extend(technician_tokens);
-    },
-    _ => {
-      tracing::info!("Invalid notification receiver: {}", receiver);
-      return Ok(());
-    },
-  }
+/// Gửi thông báo cho từng kỹ thuật viên của lịch hẹn, chỉ kèm các dịch vụ được phân công cho họ
+/// (biến `services` của mẫu).
+pub async fn send_noti_line_technicians(
+  conn: &mut PgConnection,
+  appointment_id: i64,
+  message: NotificationMessage,
+  data: Option<serde_json::Value>,
+) -> AppResult<()> {
+  let assignments = sqlx::query_as::<_, (i64, String)>(
+    r#"
+      SELECT
+        technician_id,
+        string_agg(
+          COALESCE(service_name, '')
+            || CASE WHEN quantity > 1 THEN ' x' || quantity ELSE '' END
+            || COALESCE(' (' || start_time || ')', ''),
+          ', ' ORDER BY sequence
+        )
+      FROM users.appointments_services
+      WHERE appointment_id = $1 AND technician_id IS NOT NULL
+      GROUP BY technician_id
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_all(&mut *conn)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
 
-  if tokens.is_empty() {
-    tracing::info!("No tokens found for notification receiver: {}", receiver);
-    return Ok(());
-  }
+  for (technician_id, services) in assignments {
+    let mut message = message.clone();
+    if let Some(variables) = message.variables.as_object_mut() {
+      variables.insert("services".to_string(), serde_json::Value::String(services));
+    }
 
-  let notification_service = NotificationService::new().await.map_err(|err| {
-    tracing::error!("Failed to initialize notification service: {:?}", err);
-    AppError::BadRequest(err.to_string())
-  })?;
+    create_notification(
+      conn,
+      technician_id,
+      message,
+      "TECHNICIAN".to_string(),
+      Some(appointment_id),
+      data.clone(),
+    )
+    .await?;
+  }
 
-  let success = notification_service.send_notification(noti, tokens).await;
-  tracing::info!("Firebase notification send result: {:#?}", success);
   Ok(())
 }
 
+/// Chỉ gửi FCM (không lưu vào danh sách thông báo), vẫn đi qua hàng đợi để được thử lại khi lỗi.
 pub async fn send_firebase_notification(
-  db: &PgPool,
-  noti_token_repo: Arc<dyn NotificationTokenRepository>,
+  conn: &mut PgConnection,
   user_id: i64,
-  title: String,
-  body: String,
+  message: NotificationMessage,
   receiver: String,
   data: Option<serde_json::Value>,
 ) -> AppResult<()> {
-  let mut tokens = vec![];
-
-  match receiver.as_str() {
-    "RECEPTIONIST" => {
-      // Gửi cho lễ tân cụ thể
-      let t = noti_token_repo.get_token_by_user_id(user_id).await?;
-      tokens = t.iter().map(|item| item.token.clone()).collect();
-    },
-    "ALLRECEPTIONIST" => {
-      // Gửi cho tất cả lễ tân
-      tokens = get_token_reception(db).await?;
-    },
-    "TECHNICIAN" => {
-      // Gửi cho kỹ thuật viên cụ thể
-      let t = noti_token_repo.get_token_by_user_id(user_id).await?;
-      tokens = t.iter().map(|item| item.token.clone()).collect();
-    },
-    "ALLTECHNICIAN" => {
-      // Gửi cho tất cả kỹ thuật viên
-      tokens = get_token_technician(db).await?;
-    },
-    "CUSTOMER" => {
-      // Gửi cho user cụ thể
-      let t = noti_token_repo.get_token_by_user_id(user_id).await?;
-      tokens = t.iter().map(|item| item.token.clone()).collect();
-    },
-    "ALL" => {
-      // Gửi cho tất cả user
-      let reception_tokens = get_token_reception(db).await?;
-      let technician_tokens = get_token_technician(db).await?;
-      tokens.extend(reception_tokens);
-      tokens.extend(technician_tokens);
-    },
-    _ => {
-      tracing::info!("Invalid notification receiver: {}", receiver);
-      return Ok(());
-    },
-  }
-
-  if tokens.is_empty() {
-    tracing::info!("No tokens found for notification receiver: {}", receiver);
-    return Ok(());
-  }
-
-  let notification_service = NotificationService::new().await.map_err(|err| {
-    tracing::error!("Failed to initialize notification service: {:?}", err);
-    AppError::BadRequest(err.to_string())
-  })?;
-
-  let notification = domain::entities::notification::Notification {
-    id: 0,
+  let notification = CreateNotification {
     user_id: if user_id == 0 { None } else { Some(user_id) },
-    title,
-    body,
+    title: String::new(),
+    body: String::new(),
     receiver,
     notification_type: "APPOINTMENT".to_string(),
     data,
     appointment_id: None,
-    is_read: false,
-    created_at: chrono::Utc::now(),
-    updated_at: chrono::Utc::now(),
+    template: Some(message),
   };
 
-  let success = notification_service.send_notification(notification, tokens).await;
-  tracing::info!("Firebase notification send result: {:#?}", success);
+  enqueue_notification(conn, notification, false).await?;
   Ok(())
 }
 
 pub async fn send_noti_update(
-  db: &PgPool,
-  notification_repo: Arc<dyn NotificationRepository>,
-  noti_token_repo: Arc<dyn NotificationTokenRepository>,
+  conn: &mut PgConnection,
   user: UserWithPassword,
   res: AppointmentWithServices,
   new_status: Option<String>,
@@ -209,121 +116,62 @@ pub async fn send_noti_update(
   let technician_id =
     res.technician.as_ref().and_then(|r| r.get("id")).and_then(|v| v.as_i64()).unwrap_or(0);
 
-  // Xác định nội dung thông báo dựa trên role và status mới
-  let (title, body) = match user.role.as_str() {
+  // Xác định mẫu thông báo dựa trên role và status mới
+  let event_type = match user.role.as_str() {
     "CUSTOMER" => {
       if let Some(status) = new_status.clone() {
         if status == "CANCELLED" {
           // User hủy lịch hẹn
-          (
-            "Hủy lịch hẹn".to_string(),
-            format!("{} đã hủy lịch hẹn. Thời gian: {}", user_full_name, res.start_time),
-          )
+          "APPOINTMENT_CANCELLED_BY_CUSTOMER"
         } else {
-          // Cập nhật thông tin khác
-          (
-            "Cập nhật lịch hẹn".to_string(),
-            format!(
-              "{} đã cập nhật thông tin lịch hẹn. Thời gian: {}",
-              user_full_name, res.start_time
-            ),
-          )
+          // Các thay đổi trạng thái khác của khách không gửi thông báo
+          return Ok(());
         }
       } else {
         // Cập nhật thông tin khác
-        (
-          "Cập nhật lịch hẹn".to_string(),
-          format!("Lịch hẹn của {} đã được cập nhật. Vui lòng kiểm tra.", user_full_name),
-        )
+        "APPOINTMENT_UPDATED"
       }
     },
     "RECEPTIONIST" => {
       if let Some(status) = new_status.clone() {
         match status.as_str() {
-          "CONFIRMED" => {
-            // Lễ tân xác nhận lịch hẹn
-            (
-              "Lịch hẹn đã được xác nhận".to_string(),
-              format!(
-                "Lịch hẹn của {} đã được xác nhận. Thời gian: {}",
-                user_full_name, res.start_time
-              ),
-            )
-          },
-          "PAYMENT" => {
-            // Thanh toán thành công
-            (
-              "Thanh toán thành công".to_string(),
-              format!(
-                "Lịch hẹn của {} đã được thanh toán thành công. Thời gian: {}",
-                user_full_name, res.start_time
-              ),
-            )
-          },
-          "CANCELLED" => {
-            // Lễ tân hủy lịch hẹn
-            (
-              "Hủy lịch hẹn".to_string(),
-              format!("Lịch hẹn của {} đã bị hủy. Thời gian: {}", user_full_name, res.start_time),
-            )
-          },
+          // Lễ tân xác nhận lịch hẹn
+          "CONFIRMED" => "APPOINTMENT_CONFIRMED",
+          // Thanh toán thành công
+          "PAYMENT" => "APPOINTMENT_PAID",
+          // Lễ tân hủy lịch hẹn
+          "CANCELLED" => "APPOINTMENT_CANCELLED",
           _ => return Ok(()), // Không gửi thông báo cho các status khác
         }
+      } else if technician_id > 0 {
+        // Nếu có thay đổi kỹ thuật viên
+        "APPOINTMENT_ASSIGNED"
       } else {
         // Cập nhật thông tin khác
-        if technician_id > 0 {
-          // Nếu có thay đổi kỹ thuật viên
-          (
-            "Phân công lịch hẹn".to_string(),
-            format!(
-              "Bạn đã được phân công cho lịch hẹn của {}. Thời gian: {}",
-              user_full_name, res.start_time
-            ),
-          )
-        } else {
-          // Cập nhật thông tin khác
-          (
-            "Cập nhật lịch hẹn".to_string(),
-            format!("Lịch hẹn của {} đã được cập nhật. Vui lòng kiểm tra.", user_full_name),
-          )
-        }
+        "APPOINTMENT_UPDATED"
       }
     },
     "TECHNICIAN" => {
       if let Some(status) = new_status.clone() {
         match status.as_str() {
-          "COMPLETED" => {
-            // Kỹ thuật viên hoàn thành lịch hẹn
-            (
-              "Hoàn thành lịch hẹn".to_string(),
-              format!(
-                "Lịch hẹn của {} đã được hoàn thành. Vui lòng thanh toán với lễ tân",
-                user_full_name
-              ),
-            )
-          },
-          "IN_PROGRESS" => {
-            // Kỹ thuật viên bắt đầu thực hiện dịch vụ
-            (
-              "Bắt đầu thực hiện dịch vụ".to_string(),
-              format!(
-                "Kỹ thuật viên đã bắt đầu thực hiện dịch vụ cho lịch hẹn của {}. Thời gian: {}",
-                user_full_name, res.start_time
-              ),
-            )
-          },
+          // Kỹ thuật viên hoàn thành lịch hẹn
+          "COMPLETED" => "APPOINTMENT_COMPLETED",
+          // Kỹ thuật viên bắt đầu thực hiện dịch vụ
+          "IN_PROGRESS" => "APPOINTMENT_IN_PROGRESS",
           _ => return Ok(()), // Không gửi thông báo cho các status khác
         }
       } else {
         // Cập nhật thông tin khác
-        (
-          "Cập nhật lịch hẹn".to_string(),
-          format!("Lịch hẹn của {} đã được cập nhật. Vui lòng kiểm tra.", user_full_name),
-        )
+        "APPOINTMENT_UPDATED"
       }
     },
     _ => return Ok(()),
   };
+  let variables = serde_json::json!({
+    "user_name": user_full_name,
+    "start_time": res.start_time
+  });
+  let message = NotificationMessage::new(event_type, variables.clone());
 
   // Gửi thông báo cho các bên liên quan
   match user.role.as_str() {
@@ -332,12 +180,9 @@ pub async fn send_noti_update(
         if status == "CANCELLED" {
           // Gửi cho tất cả lễ tân - Lưu vào DB vì đây là thông báo quan trọng
           let _ = create_notification(
-            &db,
-            notification_repo.clone(),
-            noti_token_repo.clone(),
+            &mut *conn,
             receptionist_id,
-            title.clone(),
-            body.clone(),
+            message.clone(),
             "ALLRECEPTIONIST".to_string(),
             Some(res.id),
             Some(serde_json::json!({
@@ -353,11 +198,9 @@ pub async fn send_noti_update(
       } else {
         // Gửi thông báo tạm thời cho lễ tân khi user cập nhật thông tin
         let _ = send_firebase_notification(
-          &db,
-          noti_token_repo.clone(),
+          &mut *conn,
           receptionist_id,
-          title.clone(),
-          body.clone(),
+          message.clone(),
           "ALLRECEPTIONIST".to_string(),
           Some(serde_json::json!({
             "type": "APPOINTMENT",
@@ -376,12 +219,9 @@ pub async fn send_noti_update(
           "CONFIRMED" => {
             // Gửi cho user - Lưu vào DB vì đây là thông báo quan trọng
             let _ = create_notification(
-              &db,
-              notification_repo.clone(),
-              noti_token_repo.clone(),
+              &mut *conn,
               user_id,
-              title.clone(),
-              body.clone(),
+              message.clone(),
               "CUSTOMER".to_string(),
               Some(res.id),
               Some(serde_json::json!({
@@ -392,45 +232,26 @@ pub async fn send_noti_update(
             )
             .await?;
 
-            // Gửi cho kỹ thuật viên nếu đã được phân công
-            if technician_id > 0 {
-              let mut t = title.clone();
-              let mut b = body.clone();
-              if t == "Lịch hẹn đã được xác nhận".to_string() {
-                t = "Phân công lịch hẹn".to_string();
-                b = format!(
-                  "Bạn đã được phân công cho lịch hẹn của {}. Thời gian: {}",
-                  user_full_name, res.start_time
-                )
-              }
-              let _ = create_notification(
-                &db,
-                notification_repo.clone(),
-                noti_token_repo.clone(),
-                technician_id,
-                t,
-                b,
-                "TECHNICIAN".to_string(),
-                Some(res.id),
-                Some(serde_json::json!({
-                  "type": "APPOINTMENT",
-                  "appointment_id": res.id,
-                  "user_name": user_full_name,
-                  "start_time": res.start_time
-                })),
-              )
-              .await?;
-            }
+            // Gửi cho các kỹ thuật viên được phân công dịch vụ
+            let _ = send_noti_line_technicians(
+              &mut *conn,
+              res.id,
+              NotificationMessage::new("APPOINTMENT_ASSIGNED", variables.clone()),
+              Some(serde_json::json!({
+                "type": "APPOINTMENT",
+                "appointment_id": res.id,
+                "user_name": user_full_name,
+                "start_time": res.start_time
+              })),
+            )
+            .await?;
           },
           "PAYMENT" => {
             // Gửi cho user - Lưu vào DB vì đây là thông báo quan trọng về thanh toán
             let _ = create_notification(
-              &db,
-              notification_repo.clone(),
-              noti_token_repo.clone(),
+              &mut *conn,
               user_id,
-              title.clone(),
-              body.clone(),
+              message.clone(),
               "CUSTOMER".to_string(),
               Some(res.id),
               Some(serde_json::json!({
@@ -445,12 +266,9 @@ pub async fn send_noti_update(
           "CANCELLED" => {
             // Gửi cho user - Lưu vào DB vì đây là thông báo quan trọng
             let _ = create_notification(
-              &db,
-              notification_repo.clone(),
-              noti_token_repo.clone(),
+              &mut *conn,
               user_id,
-              title.clone(),
-              body.clone(),
+              message.clone(),
               "CUSTOMER".to_string(),
               Some(res.id),
               Some(serde_json::json!({
@@ -461,42 +279,30 @@ pub async fn send_noti_update(
             )
             .await?;
 
-            // Gửi cho kỹ thuật viên nếu đã được phân công - Lưu vào DB
-            if technician_id > 0 {
-              let _ = create_notification(
-                &db,
-                notification_repo.clone(),
-                noti_token_repo.clone(),
-                technician_id,
-                title.clone(),
-                body.clone(),
-                "TECHNICIAN".to_string(),
-                Some(res.id),
-                Some(serde_json::json!({
-                  "type": "APPOINTMENT",
-                  "appointment_id": res.id,
-                  "user_name": user_full_name,
-                  "start_time": res.start_time
-                })),
-              )
-              .await?;
-            }
+            // Gửi cho các kỹ thuật viên được phân công dịch vụ - Lưu vào DB
+            let _ = send_noti_line_technicians(
+              &mut *conn,
+              res.id,
+              NotificationMessage::new("APPOINTMENT_CANCELLED_TECHNICIAN", variables.clone()),
+              Some(serde_json::json!({
+                "type": "APPOINTMENT",
+                "appointment_id": res.id,
+                "user_name": user_full_name,
+                "start_time": res.start_time
+              })),
+            )
+            .await?;
           },
           _ => return Ok(()),
         }
       } else {
         // Cập nhật thông tin khác
         if technician_id > 0 {
-          // Gửi thông báo cho kỹ thuật viên mới
-          let _ = create_notification(
-            &db,
-            notification_repo.clone(),
-            noti_token_repo.clone(),
-            technician_id,
-            title.clone(),
-            body.clone(),
-            "TECHNICIAN".to_string(),
-            Some(res.id),
+          // Gửi thông báo cho các kỹ thuật viên theo dịch vụ được phân công
+          let _ = send_noti_line_technicians(
+            &mut *conn,
+            res.id,
+            message.clone(),
             Some(serde_json::json!({
               "type": "APPOINTMENT",
               "appointment_id": res.id,
@@ -511,15 +317,9 @@ pub async fn send_noti_update(
             if let Some(old_tech_id) = old_tech.get("id").and_then(|v| v.as_i64()) {
               if old_tech_id != technician_id {
                 let _ = create_notification(
-                  &db,
-                  notification_repo.clone(),
-                  noti_token_repo.clone(),
+                  &mut *conn,
                   old_tech_id,
-                  "Hủy phân công lịch hẹn".to_string(),
-                  format!(
-                    "Lịch hẹn của {} đã được phân công cho kỹ thuật viên khác. Thời gian: {}",
-                    user_full_name, res.start_time
-                  ),
+                  NotificationMessage::new("APPOINTMENT_UNASSIGNED", variables.clone()),
                   "TECHNICIAN".to_string(),
                   Some(res.id),
                   Some(serde_json::json!({
@@ -537,11 +337,9 @@ pub async fn send_noti_update(
           // Cập nhật thông tin khác - Gửi cho user
 
           let _ = send_firebase_notification(
-            &db,
-            noti_token_repo.clone(),
+            &mut *conn,
             user_id,
-            title.clone(),
-            body.clone(),
+            message.clone(),
             "CUSTOMER".to_string(),
             Some(serde_json::json!({
               "type": "APPOINTMENT",
@@ -560,12 +358,9 @@ pub async fn send_noti_update(
           "COMPLETED" => {
             // Gửi cho user và lễ tân - Lưu vào DB vì đây là thông báo quan trọng
             let _ = create_notification(
-              &db,
-              notification_repo.clone(),
-              noti_token_repo.clone(),
+              &mut *conn,
               user_id,
-              title.clone(),
-              body.clone(),
+              message.clone(),
               "CUSTOMER".to_string(),
               Some(res.id),
               Some(serde_json::json!({
@@ -578,12 +373,9 @@ pub async fn send_noti_update(
 
             if receptionist_id > 0 {
               let _ = create_notification(
-                &db,
-                notification_repo.clone(),
-                noti_token_repo.clone(),
+                &mut *conn,
                 receptionist_id,
-                title.clone(),
-                body.clone(),
+                message.clone(),
                 "RECEPTIONIST".to_string(),
                 Some(res.id),
                 Some(serde_json::json!({
@@ -599,12 +391,9 @@ pub async fn send_noti_update(
           "IN_PROGRESS" => {
             // Gửi cho user - Lưu vào DB vì đây là thông báo quan trọng về tiến trình
             let _ = create_notification(
-              &db,
-              notification_repo.clone(),
-              noti_token_repo.clone(),
+              &mut *conn,
               user_id,
-              title.clone(),
-              body.clone(),
+              message.clone(),
               "CUSTOMER".to_string(),
               Some(res.id),
               Some(serde_json::json!({
@@ -618,12 +407,9 @@ pub async fn send_noti_update(
             // Gửi cho lễ tân - Lưu vào DB
             if receptionist_id > 0 {
               let _ = create_notification(
-                &db,
-                notification_repo.clone(),
-                noti_token_repo.clone(),
+                &mut *conn,
                 receptionist_id,
-                title.clone(),
-                body.clone(),
+                message.clone(),
                 "RECEPTIONIST".to_string(),
                 Some(res.id),
                 Some(serde_json::json!({
@@ -641,11 +427,9 @@ pub async fn send_noti_update(
       } else {
         // Cập nhật thông tin khác - Gửi cho user
         let _ = send_firebase_notification(
-          &db,
-          noti_token_repo.clone(),
+          &mut *conn,
           user_id,
-          title.clone(),
-          body.clone(),
+          message.clone(),
           "CUSTOMER".to_string(),
           Some(serde_json::json!({
             "type": "APPOINTMENT",
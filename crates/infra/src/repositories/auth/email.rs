@@ -0,0 +1,289 @@
+use crate::repositories::email::enqueue_email;
+use chrono::{Duration, Utc};
+use core_app::{AppResult, AppState, errors::AppError};
+use domain::{
+  entities::{
+    auth::{
+      ClaimsSetPassword, EmailCode, ForgotPasswordEmailRequest, VerifyEmailCodeRequest,
+      VerifyEmailRequest, VerifyPhoneCodeResponse,
+    },
+    user::{User, UserWithPassword},
+  },
+  services::email::validate_email,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+use utils::helper::{encode_token, generate_phone_code};
+
+const VERIFY_EMAIL: &str = "VERIFY_EMAIL";
+const RESET_PASSWORD: &str = "RESET_PASSWORD";
+// Nhập sai quá số lần này thì mã hết hiệu lực, phải yêu cầu mã mới
+const MAX_CODE_ATTEMPTS: i32 = 5;
+// Khoảng cách tối thiểu giữa hai lần gửi mã cho cùng mục đích
+const RESEND_INTERVAL_SECONDS: i64 = 60;
+
+// Email hiện tại của tài khoản, bắt buộc phải có để xác minh
+fn current_email(user: &UserWithPassword) -> AppResult<String> {
+  let email = user
+    .email_address
+    .as_deref()
+    .filter(|email| !email.trim().is_empty())
+    .ok_or(AppError::BadRequest("Email address is not set".to_string()))?;
+
+  validate_email(email)
+}
+
+async fn get_user_by_verified_email(
+  db: &PgPool,
+  email: &str,
+) -> AppResult<Option<UserWithPassword>> {
+  let user = sqlx::query_as::<_, UserWithPassword>(
+    r#"
+    SELECT * FROM users.tbl_users
+    WHERE LOWER(email_address) = LOWER($1) AND is_email_verified
+    "#,
+  )
+  .bind(email)
+  .fetch_optional(db)
+  .await?;
+
+  Ok(user)
+}
+
+/// Tạo mã mới và đưa email chứa mã vào hàng đợi. Mã cũ cùng mục đích hết hiệu lực.
+/// Trả về false nếu vừa gửi mã trong vòng một phút.
+async fn issue_email_code(
+  state: &AppState,
+  user: &UserWithPassword,
+  email: &str,
+  purpose: &str,
+  event_type: &str,
+) -> AppResult<bool> {
+  let recently_sent = sqlx::query_scalar::<_, bool>(
+    r#"
+    SELECT EXISTS (
+      SELECT 1 FROM users.email_codes
+      WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL AND created_at > $3
+    )
+    "#,
+  )
+  .bind(user.pk_user_id)
+  .bind(purpose)
+  .bind(Utc::now() - Duration::seconds(RESEND_INTERVAL_SECONDS))
+  .fetch_one(&state.db)
+  .await?;
+
+  if recently_sent {
+    return Ok(false);
+  }
+
+  let ttl_minutes = state.config.email.code_ttl_minutes.max(1);
+  let code = generate_phone_code();
+  let mut tx = state.db.begin().await?;
+
+  sqlx::query(
+    r#"
+    UPDATE users.email_codes SET used_at = NOW()
+    WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL
+    "#,
+  )
+  .bind(user.pk_user_id)
+  .bind(purpose)
+  .execute(&mut *tx)
+  .await?;
+
+  sqlx::query(
+    r#"
+    INSERT INTO users.email_codes (user_id, email, code, purpose, expires_at)
+    VALUES ($1, $2, $3, $4, $5)
+    "#,
+  )
+  .bind(user.pk_user_id)
+  .bind(email)
+  .bind(&code)
+  .bind(purpose)
+  .bind(Utc::now() + Duration::minutes(ttl_minutes))
+  .execute(&mut *tx)
+  .await?;
+
+  enqueue_email(
+    &mut tx,
+    Some(user.pk_user_id),
+    email,
+    &user.preferred_language,
+    event_type,
+    serde_json::json!({
+      "customer_name": user.full_name.clone().unwrap_or_default(),
+      "code": code,
+      "ttl_minutes": ttl_minutes,
+    }),
+  )
+  .await?;
+
+  tx.commit().await?;
+
+  Ok(true)
+}
+
+/// Kiểm tra mã mới nhất của email, nhập sai thì tăng số lần thử (ghi ngay, không nằm trong
+/// transaction của nơi gọi để lỗi không làm mất lượt đếm)
+async fn check_email_code(
+  db: &PgPool,
+  user_id: i64,
+  email: &str,
+  purpose: &str,
+  code: &str,
+) -> AppResult<EmailCode> {
+  let email_code = sqlx::query_as::<_, EmailCode>(
+    r#"
+    SELECT * FROM users.email_codes
+    WHERE user_id = $1 AND purpose = $2 AND LOWER(email) = LOWER($3) AND used_at IS NULL
+    ORDER BY created_at DESC
+    LIMIT 1
+    "#,
+  )
+  .bind(user_id)
+  .bind(purpose)
+  .bind(email)
+  .fetch_optional(db)
+  .await?
+  .ok_or(AppError::BadRequest("Invalid email code".to_string()))?;
+
+  if Utc::now() > email_code.expires_at || email_code.attempts >= MAX_CODE_ATTEMPTS {
+    return Err(AppError::BadRequest("Email code expired".to_string()));
+  }
+
+  if email_code.code != code.trim() {
+    sqlx::query("UPDATE users.email_codes SET attempts = attempts + 1 WHERE id = $1")
+      .bind(email_code.id)
+      .execute(db)
+      .await?;
+    return Err(AppError::BadRequest("Invalid email code".to_string()));
+  }
+
+  Ok(email_code)
+}
+
+// Đánh dấu mã đã dùng, hai yêu cầu dùng cùng một mã thì chỉ một yêu cầu thành công
+async fn mark_email_code_used<'e, E>(
+  executor: E,
+  id: i64,
+) -> AppResult<()>
+where
+  E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+  let result =
+    sqlx::query("UPDATE users.email_codes SET used_at = NOW() WHERE id = $1 AND used_at IS NULL")
+      .bind(id)
+      .execute(executor)
+      .await?;
+
+  if result.rows_affected() == 0 {
+    return Err(AppError::BadRequest("Invalid email code".to_string()));
+  }
+
+  Ok(())
+}
+
+pub async fn send_email_verification(
+  state: Arc<AppState>,
+  user: UserWithPassword,
+) -> AppResult<bool> {
+  let email = current_email(&user)?;
+  if user.is_email_verified {
+    return Err(AppError::BadRequest("Email address is already verified".to_string()));
+  }
+
+  issue_email_code(&state, &user, &email, VERIFY_EMAIL, "EMAIL_VERIFICATION").await
+}
+
+pub async fn verify_email(
+  state: Arc<AppState>,
+  user: UserWithPassword,
+  req: VerifyEmailRequest,
+) -> AppResult<User> {
+  let email = current_email(&user)?;
+  if user.is_email_verified {
+    return Err(AppError::BadRequest("Email address is already verified".to_string()));
+  }
+
+  let email_code =
+    check_email_code(&state.db, user.pk_user_id, &email, VERIFY_EMAIL, &req.code).await?;
+
+  let mut tx = state.db.begin().await?;
+  mark_email_code_used(&mut *tx, email_code.id).await?;
+
+  // Mỗi địa chỉ chỉ được xác minh cho một tài khoản (unique index trên email đã xác minh)
+  let user = sqlx::query_as::<_, UserWithPassword>(
+    r#"
+    UPDATE users.tbl_users SET is_email_verified = TRUE
+    WHERE pk_user_id = $1 AND LOWER(email_address) = LOWER($2)
+    RETURNING *
+    "#,
+  )
+  .bind(user.pk_user_id)
+  .bind(&email)
+  .fetch_optional(&mut *tx)
+  .await
+  .map_err(|err| match err {
+    sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+      AppError::BadRequest("Email address is already used by another account".to_string())
+    },
+    err => AppError::Unhandled(Box::new(err)),
+  })?
+  .ok_or(AppError::BadRequest("Invalid email code".to_string()))?;
+
+  tx.commit().await?;
+
+  Ok(User::from(user))
+}
+
+/// Gửi mã đặt lại mật khẩu tới email đã xác minh. Email không thuộc tài khoản nào, tài khoản bị
+/// khoá hay đang bị giới hạn gửi đều trả về true để không lộ địa chỉ nào đã đăng ký.
+pub async fn forgot_password_email(
+  state: Arc<AppState>,
+  req: ForgotPasswordEmailRequest,
+) -> AppResult<bool> {
+  let email = validate_email(&req.email)?;
+  let user = get_user_by_verified_email(&state.db, &email).await?.filter(|user| user.is_active);
+
+  if let Some(user) = user {
+    issue_email_code(&state, &user, &email, RESET_PASSWORD, "PASSWORD_RESET").await?;
+  }
+
+  Ok(true)
+}
+
+/// Đổi mã đặt lại mật khẩu lấy token dùng cho /auth/set-password, giống luồng số điện thoại
+pub async fn verify_email_code(
+  state: Arc<AppState>,
+  req: VerifyEmailCodeRequest,
+) -> AppResult<VerifyPhoneCodeResponse> {
+  let email = validate_email(&req.email)?;
+  let user = get_user_by_verified_email(&state.db, &email)
+    .await?
+    .filter(|user| user.is_active)
+    .ok_or(AppError::BadRequest("Invalid email code".to_string()))?;
+
+  let email_code =
+    check_email_code(&state.db, user.pk_user_id, &email, RESET_PASSWORD, &req.code).await?;
+  mark_email_code_used(&state.db, email_code.id).await?;
+
+  let phone = user.phone.clone().unwrap_or_default();
+  let access_duration = Duration::minutes(state.config.token.access_token_set_password_minutes);
+  let access_claims = ClaimsSetPassword {
+    sub: user.pk_user_id.to_string(),
+    phone: phone.clone(),
+    exp: (Utc::now() + access_duration).timestamp() as usize,
+  };
+  let access_token = encode_token(&access_claims, &state.config.token.jwt_secret_key)?;
+
+  Ok(VerifyPhoneCodeResponse {
+    user_id: user.pk_user_id,
+    token: access_token,
+    phone,
+    code: None,
+    is_active: user.is_active,
+    is_verify: user.is_verify,
+  })
+}
@@ -1,4 +1,4 @@
-use super::base::create;
+use super::{base::create, referral::apply_signup_referral};
 use crate::database::schema::{DB, UserDmc};
 use axum::extract::Request;
 use chrono::{Duration, Utc};
@@ -15,8 +15,10 @@ use domain::entities::{
     ResendCodeRequest, SetPasswordRequest, SigninRequest, SigninRequestByPhone, SigninResponse,
     VerifyFireCodeRequest, VerifyPhoneCodeRequest, VerifyPhoneCodeResponse,
   },
+  referral::ReferralCandidate,
   user::{RequestCreateUser, Role, User, UserWithPassword},
 };
+use domain::services::referral::normalize_referral_code;
 use modql::filter::{FilterNode, OpValInt64, OpValString};
 use std::sync::Arc;
 use tracing::error;
@@ -25,6 +27,7 @@ use utils::{
   password::{hash_password, verify_password},
 };
 mod common;
+pub mod email;
 pub use common::get_user_by_id;
 
 pub async fn base_login(
@@ -286,6 +289,21 @@ pub async fn set_password(
 
   let mut tx = state.db.begin().await?;
 
+  // Chỉ nhận mã giới thiệu khi khách đặt mật khẩu lần đầu (đăng ký mới)
+  let referral_code = normalize_referral_code(req.referral_code);
+  if let (Some(referral_code), None) = (referral_code, user.password_hash.as_ref()) {
+    let candidate = ReferralCandidate {
+      user_id: Some(user.pk_user_id),
+      phone: user.phone.clone().unwrap_or(req.phone.clone()),
+      device_token: req.device_token.filter(|token| !token.is_empty()),
+    };
+
+    if let Err(e) = apply_signup_referral(&mut tx, referral_code, candidate).await {
+      tx.rollback().await?;
+      return Err(e);
+    }
+  }
+
   let user_updated =
     update_user_password(&mut *tx, user.pk_user_id, &password_hash, user.is_verify, full_name)
       .await;
@@ -0,0 +1,413 @@
+use crate::repositories::notification_outbox::enqueue_notification;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use core_app::{AppResult, configs::CampaignConfig, errors::AppError};
+use domain::{
+  entities::{
+    campaign::{
+      Campaign, CampaignAudience, CampaignFilter, CampaignMessage, CampaignStats,
+      CreateCampaignRequest, UpdateCampaignRequest,
+    },
+    notification::CreateNotification,
+    notification_template::DEFAULT_LOCALE,
+  },
+  repositories::campaign_repository::CampaignRepository,
+};
+use sqlx::{PgPool, Postgres, postgres::PgArguments, query::QueryScalar};
+
+pub struct SqlxCampaignRepository {
+  pub db: PgPool,
+}
+
+// Khách hàng đang hoạt động thoả bộ lọc, tham số $1..$7 được gán bởi bind_audience.
+// Lần ghé và tổng chi tiêu chỉ tính lịch hẹn đã thanh toán, ngày sinh lưu dạng DD/MM/YYYY.
+const AUDIENCE_QUERY: &str = r#"
+  SELECT u.pk_user_id AS user_id
+  FROM users.tbl_users u
+  LEFT JOIN LATERAL (
+    SELECT
+      MAX(COALESCE(a.paid_at, a.updated_at)) AS last_visit,
+      COALESCE(SUM(a.total_price), 0)::BIGINT AS total_spent
+    FROM users.appointments a
+    WHERE a.user_id = u.pk_user_id AND a.status = 'PAYMENT'
+  ) v ON TRUE
+  WHERE u.role = 'CUSTOMER'
+    AND u.is_active
+    AND ($1::varchar[] IS NULL OR u.membership_level = ANY($1))
+    AND ($2::int IS NULL OR v.last_visit >= NOW() - make_interval(days => $2))
+    AND ($3::int IS NULL OR v.last_visit IS NULL OR v.last_visit < NOW() - make_interval(days => $3))
+    AND ($4::bigint IS NULL OR v.total_spent >= $4)
+    AND ($5::bigint IS NULL OR v.total_spent <= $5)
+    AND (
+      $6::int IS NULL
+      OR CASE
+        WHEN u.date_of_birth ~ '^\d{1,2}/\d{1,2}/\d{4}$'
+        THEN split_part(u.date_of_birth, '/', 2)::int = $6
+        ELSE FALSE
+      END
+    )
+    AND (
+      $7::bigint[] IS NULL
+      OR EXISTS (
+        SELECT 1
+        FROM users.appointments a
+        INNER JOIN users.appointments_services s ON s.appointment_id = a.id
+        WHERE a.user_id = u.pk_user_id AND a.status = 'PAYMENT' AND s.service_id = ANY($7)
+      )
+    )
+"#;
+
+fn bind_audience<'q>(
+  query: QueryScalar<'q, Postgres, i64, PgArguments>,
+  audience: CampaignAudience,
+) -> QueryScalar<'q, Postgres, i64, PgArguments> {
+  query
+    .bind(audience.membership_levels)
+    .bind(audience.visited_within_days)
+    .bind(audience.not_visited_within_days)
+    .bind(audience.min_total_spent)
+    .bind(audience.max_total_spent)
+    .bind(audience.birthday_month)
+    .bind(audience.service_ids)
+}
+
+// Nội dung theo ngôn ngữ của người nhận, chưa có bản dịch thì dùng bản tiếng Việt
+fn pick_message<'a>(
+  messages: &'a [CampaignMessage],
+  locale: &str,
+) -> Option<&'a CampaignMessage> {
+  messages
+    .iter()
+    .find(|message| message.locale == locale)
+    .or_else(|| messages.iter().find(|message| message.locale == DEFAULT_LOCALE))
+}
+
+/// Chạy một lượt gửi chiến dịch: chốt danh sách người nhận cho các chiến dịch đến giờ gửi,
+/// sau đó đưa tối đa `batch_size` người nhận (tính chung cho mọi chiến dịch) vào outbox.
+/// Chiến dịch hết người nhận chờ gửi thì chuyển COMPLETED. Trả về số thông báo đã xếp hàng.
+pub async fn process_campaigns(
+  db: &PgPool,
+  config: &CampaignConfig,
+) -> AppResult<usize> {
+  let mut tx = db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let due = sqlx::query_as::<_, Campaign>(
+    r#"
+    SELECT * FROM users.campaigns
+    WHERE status = 'SCHEDULED' AND scheduled_at <= NOW()
+    ORDER BY scheduled_at
+    FOR UPDATE SKIP LOCKED
+    "#,
+  )
+  .fetch_all(&mut *tx)
+  .await?;
+
+  for campaign in due {
+    let query = format!(
+      r#"
+      WITH inserted AS (
+        INSERT INTO users.campaign_recipients (campaign_id, user_id)
+        SELECT $8, audience.user_id FROM ({AUDIENCE_QUERY}) audience
+        ON CONFLICT (campaign_id, user_id) DO NOTHING
+        RETURNING 1
+      )
+      SELECT COUNT(*) FROM inserted
+      "#
+    );
+    let total = bind_audience(sqlx::query_scalar::<_, i64>(&query), campaign.audience)
+      .bind(campaign.id)
+      .fetch_one(&mut *tx)
+      .await?;
+
+    sqlx::query("UPDATE users.campaigns SET status = 'SENDING', started_at = NOW() WHERE id = $1")
+      .bind(campaign.id)
+      .execute(&mut *tx)
+      .await?;
+
+    tracing::info!("Campaign {} started with {} recipients", campaign.id, total);
+  }
+
+  tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let sending = sqlx::query_as::<_, Campaign>(
+    "SELECT * FROM users.campaigns WHERE status = 'SENDING' ORDER BY started_at, id",
+  )
+  .fetch_all(db)
+  .await?;
+
+  let mut queued = 0usize;
+  for campaign in sending {
+    let remaining = config.batch_size.max(1) - queued as i64;
+    if remaining <= 0 {
+      break;
+    }
+
+    let mut tx = db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    // Khoá chiến dịch để không có hai worker gửi trùng, chiến dịch vừa bị huỷ thì bỏ qua
+    let locked = sqlx::query_scalar::<_, i64>(
+      "SELECT id FROM users.campaigns WHERE id = $1 AND status = 'SENDING' FOR UPDATE SKIP LOCKED",
+    )
+    .bind(campaign.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if locked.is_none() {
+      continue;
+    }
+
+    let recipients = sqlx::query_as::<_, (i64, i64, String)>(
+      r#"
+      SELECT r.id, r.user_id, u.preferred_language
+      FROM users.campaign_recipients r
+      INNER JOIN users.tbl_users u ON u.pk_user_id = r.user_id
+      WHERE r.campaign_id = $1 AND r.status = 'PENDING'
+      ORDER BY r.id
+      LIMIT $2
+      "#,
+    )
+    .bind(campaign.id)
+    .bind(remaining)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (recipient_id, user_id, locale) in recipients.iter() {
+      // Chiến dịch luôn có bản tiếng Việt nên chỉ bỏ qua khi dữ liệu bị sửa tay
+      let outbox_id = match pick_message(&campaign.messages, locale) {
+        Some(message) => {
+          let notification = CreateNotification {
+            user_id: Some(*user_id),
+            title: message.title.clone(),
+            body: message.body.clone(),
+            receiver: "CUSTOMER".to_string(),
+            notification_type: "PROMOTION".to_string(),
+            data: Some(serde_json::json!({
+              "type": "PROMOTION",
+              "campaign_id": campaign.id,
+              "deep_link": campaign.deep_link
+            })),
+            appointment_id: None,
+            template: None,
+          };
+
+          Some(enqueue_notification(&mut tx, notification, true).await?)
+        },
+        None => None,
+      };
+
+      sqlx::query(
+        r#"
+        UPDATE users.campaign_recipients
+        SET status = 'QUEUED', outbox_id = $2, queued_at = NOW()
+        WHERE id = $1
+        "#,
+      )
+      .bind(recipient_id)
+      .bind(outbox_id)
+      .execute(&mut *tx)
+      .await?;
+    }
+
+    if (recipients.len() as i64) < remaining {
+      sqlx::query(
+        r#"
+        UPDATE users.campaigns SET status = 'COMPLETED', completed_at = NOW()
+        WHERE id = $1 AND status = 'SENDING'
+          AND NOT EXISTS (
+            SELECT 1 FROM users.campaign_recipients
+            WHERE campaign_id = $1 AND status = 'PENDING'
+          )
+        "#,
+      )
+      .bind(campaign.id)
+      .execute(&mut *tx)
+      .await?;
+    }
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    queued += recipients.len();
+  }
+
+  Ok(queued)
+}
+
+#[async_trait]
+impl CampaignRepository for SqlxCampaignRepository {
+  async fn list(
+    &self,
+    filter: CampaignFilter,
+  ) -> AppResult<Vec<Campaign>> {
+    let campaigns = sqlx::query_as::<_, Campaign>(
+      r#"
+      SELECT * FROM users.campaigns
+      WHERE ($1::text IS NULL OR status = $1)
+      ORDER BY created_at DESC
+      "#,
+    )
+    .bind(filter.status)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(campaigns)
+  }
+
+  async fn get_by_id(
+    &self,
+    id: i64,
+  ) -> AppResult<Campaign> {
+    let campaign = sqlx::query_as::<_, Campaign>("SELECT * FROM users.campaigns WHERE id = $1")
+      .bind(id)
+      .fetch_optional(&self.db)
+      .await?
+      .ok_or(AppError::NotFound)?;
+
+    Ok(campaign)
+  }
+
+  // failed là thông báo đã xử lý xong nhưng không tới được thiết bị nào (lỗi hoặc khách không có token)
+  async fn get_stats(
+    &self,
+    id: i64,
+  ) -> AppResult<CampaignStats> {
+    let stats = sqlx::query_as::<_, CampaignStats>(
+      r#"
+      SELECT
+        COUNT(*) AS recipients,
+        COUNT(*) FILTER (WHERE r.status = 'PENDING') AS pending,
+        COUNT(*) FILTER (WHERE r.status = 'QUEUED') AS queued,
+        COUNT(*) FILTER (WHERE d.sent) AS delivered,
+        COUNT(*) FILTER (WHERE o.status IN ('SENT', 'DEAD') AND NOT COALESCE(d.sent, FALSE)) AS failed,
+        COUNT(r.opened_at) AS opened
+      FROM users.campaign_recipients r
+      LEFT JOIN users.notification_outbox o ON o.id = r.outbox_id
+      LEFT JOIN LATERAL (
+        SELECT bool_or(nd.status = 'SENT') AS sent
+        FROM users.notification_deliveries nd
+        WHERE nd.outbox_id = r.outbox_id
+      ) d ON TRUE
+      WHERE r.campaign_id = $1
+      "#,
+    )
+    .bind(id)
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(stats)
+  }
+
+  async fn create(
+    &self,
+    created_by: i64,
+    payload: CreateCampaignRequest,
+  ) -> AppResult<Campaign> {
+    let campaign = sqlx::query_as::<_, Campaign>(
+      r#"
+      INSERT INTO users.campaigns (name, audience, messages, deep_link, created_by)
+      VALUES ($1, $2, $3, $4, $5)
+      RETURNING *
+      "#,
+    )
+    .bind(payload.name)
+    .bind(serde_json::json!(payload.audience))
+    .bind(serde_json::json!(payload.messages))
+    .bind(payload.deep_link)
+    .bind(created_by)
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(campaign)
+  }
+
+  async fn update(
+    &self,
+    id: i64,
+    payload: UpdateCampaignRequest,
+  ) -> AppResult<Campaign> {
+    let campaign = sqlx::query_as::<_, Campaign>(
+      r#"
+      UPDATE users.campaigns
+      SET name = COALESCE($2, name),
+          audience = COALESCE($3, audience),
+          messages = COALESCE($4, messages),
+          deep_link = COALESCE($5, deep_link)
+      WHERE id = $1 AND status IN ('DRAFT', 'SCHEDULED')
+      RETURNING *
+      "#,
+    )
+    .bind(id)
+    .bind(payload.name)
+    .bind(payload.audience.map(|audience| serde_json::json!(audience)))
+    .bind(payload.messages.map(|messages| serde_json::json!(messages)))
+    .bind(payload.deep_link)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or_else(|| {
+      AppError::Conflict("Campaign status has changed, please reload and try again".to_string())
+    })?;
+
+    Ok(campaign)
+  }
+
+  async fn update_status(
+    &self,
+    id: i64,
+    from_statuses: &[&str],
+    status: &str,
+    scheduled_at: Option<DateTime<Utc>>,
+  ) -> AppResult<Campaign> {
+    let campaign = sqlx::query_as::<_, Campaign>(
+      r#"
+      UPDATE users.campaigns
+      SET status = $2, scheduled_at = $3
+      WHERE id = $1 AND status = ANY($4)
+      RETURNING *
+      "#,
+    )
+    .bind(id)
+    .bind(status)
+    .bind(scheduled_at)
+    .bind(from_statuses)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or_else(|| {
+      AppError::Conflict("Campaign status has changed, please reload and try again".to_string())
+    })?;
+
+    Ok(campaign)
+  }
+
+  async fn count_audience(
+    &self,
+    audience: CampaignAudience,
+  ) -> AppResult<i64> {
+    let query = format!("SELECT COUNT(*) FROM ({AUDIENCE_QUERY}) audience");
+    let total =
+      bind_audience(sqlx::query_scalar::<_, i64>(&query), audience).fetch_one(&self.db).await?;
+
+    Ok(total)
+  }
+
+  async fn mark_opened(
+    &self,
+    id: i64,
+    user_id: i64,
+  ) -> AppResult<()> {
+    let result = sqlx::query(
+      r#"
+      UPDATE users.campaign_recipients
+      SET opened_at = COALESCE(opened_at, NOW())
+      WHERE campaign_id = $1 AND user_id = $2 AND status = 'QUEUED'
+      "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(&self.db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+      return Err(AppError::NotFound);
+    }
+
+    Ok(())
+  }
+}
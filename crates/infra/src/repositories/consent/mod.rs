@@ -0,0 +1,324 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use core_app::{AppResult, configs::SpaConfig, errors::AppError};
+use domain::{
+  entities::consent::{
+    AppointmentConsentForm, ConsentSubmission, ConsentSubmissionFilter, ConsentTemplate,
+    CreateConsentTemplateRequest, NewConsentSubmission, UpdateConsentTemplateRequest,
+  },
+  repositories::consent_repository::ConsentRepository,
+};
+use sqlx::{FromRow, PgPool};
+
+use crate::documents::{
+  consent::{ConsentDocument, decode_signature, layout_consent},
+  pdf::{PaperSize, render_pdf_with_image},
+};
+
+pub struct SqlxConsentRepository {
+  pub db: PgPool,
+}
+
+#[derive(FromRow)]
+struct SigningContext {
+  customer_name: Option<String>,
+  customer_phone: Option<String>,
+  start_time: String,
+}
+
+const SELECT_TEMPLATE: &str = r#"
+  SELECT t.*, s.service_name
+  FROM users.consent_templates t
+  LEFT JOIN users.services s ON s.id = t.service_id
+"#;
+
+// Không lấy pdf_content khi đọc danh sách
+const SELECT_SUBMISSION: &str = r#"
+  SELECT cs.id, cs.template_id, cs.customer_id, u.full_name AS customer_name, cs.appointment_id,
+         cs.template_snapshot, cs.answers, cs.signature_image, cs.signed_at, cs.ip_address,
+         cs.user_agent, cs.created_at
+  FROM users.consent_submissions cs
+  LEFT JOIN users.tbl_users u ON u.pk_user_id = cs.customer_id
+"#;
+
+// Mẫu áp dụng cho lịch hẹn: mẫu dùng chung và mẫu của nhóm dịch vụ (services) có trong lịch hẹn
+const SELECT_APPOINTMENT_FORM: &str = r#"
+  SELECT t.*, s.service_name, a.id AS appointment_id, cs.id AS submission_id, cs.signed_at
+  FROM users.appointments a
+  JOIN users.consent_templates t ON t.is_active AND (
+    t.service_id IS NULL OR t.service_id IN (
+      SELECT si.parent_service_id
+      FROM users.appointments_services aps
+      JOIN users.service_items si ON si.id = aps.service_id
+      WHERE aps.appointment_id = a.id
+    )
+  )
+  LEFT JOIN users.services s ON s.id = t.service_id
+  LEFT JOIN users.consent_submissions cs ON cs.template_id = t.id AND cs.customer_id = a.user_id
+"#;
+
+#[async_trait]
+impl ConsentRepository for SqlxConsentRepository {
+  async fn get_templates(
+    &self,
+    service_id: Option<i64>,
+    include_inactive: bool,
+  ) -> AppResult<Vec<ConsentTemplate>> {
+    let templates = sqlx::query_as::<_, ConsentTemplate>(&format!(
+      r#"
+      {}
+      WHERE ($1::int8 IS NULL OR t.service_id IS NULL OR t.service_id = $1)
+        AND ($2 OR t.is_active)
+      ORDER BY t.service_id NULLS FIRST, t.id
+      "#,
+      SELECT_TEMPLATE
+    ))
+    .bind(service_id)
+    .bind(include_inactive)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(templates)
+  }
+
+  async fn get_template(
+    &self,
+    id: i64,
+  ) -> AppResult<ConsentTemplate> {
+    let template =
+      sqlx::query_as::<_, ConsentTemplate>(&format!("{} WHERE t.id = $1", SELECT_TEMPLATE))
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(template)
+  }
+
+  async fn create_template(
+    &self,
+    payload: CreateConsentTemplateRequest,
+    created_by: i64,
+  ) -> AppResult<ConsentTemplate> {
+    let id: i64 = sqlx::query_scalar(
+      r#"
+      INSERT INTO users.consent_templates (service_id, title, description, questions, created_by)
+      VALUES ($1, $2, $3, $4, $5)
+      RETURNING id
+      "#,
+    )
+    .bind(payload.service_id)
+    .bind(payload.title)
+    .bind(payload.description)
+    .bind(serde_json::json!(payload.questions))
+    .bind(created_by)
+    .fetch_one(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    self.get_template(id).await
+  }
+
+  async fn update_template(
+    &self,
+    id: i64,
+    payload: UpdateConsentTemplateRequest,
+  ) -> AppResult<ConsentTemplate> {
+    let updated = sqlx::query(
+      r#"
+      UPDATE users.consent_templates
+      SET title = COALESCE($1, title),
+          description = COALESCE($2, description),
+          questions = COALESCE($3, questions),
+          is_active = COALESCE($4, is_active)
+      WHERE id = $5
+      "#,
+    )
+    .bind(payload.title)
+    .bind(payload.description)
+    .bind(payload.questions.map(|questions| serde_json::json!(questions)))
+    .bind(payload.is_active)
+    .bind(id)
+    .execute(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    if updated.rows_affected() == 0 {
+      return Err(AppError::NotFound);
+    }
+
+    self.get_template(id).await
+  }
+
+  async fn get_appointment_customer(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<Option<i64>> {
+    let customer_id: Option<i64> =
+      sqlx::query_scalar("SELECT user_id FROM users.appointments WHERE id = $1")
+        .bind(appointment_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(customer_id)
+  }
+
+  async fn get_appointment_forms(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<Vec<AppointmentConsentForm>> {
+    let forms = sqlx::query_as::<_, AppointmentConsentForm>(&format!(
+      "{} WHERE a.id = $1 ORDER BY t.service_id NULLS FIRST, t.id",
+      SELECT_APPOINTMENT_FORM
+    ))
+    .bind(appointment_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(forms)
+  }
+
+  async fn get_pending_forms(
+    &self,
+    customer_id: i64,
+  ) -> AppResult<Vec<AppointmentConsentForm>> {
+    let forms = sqlx::query_as::<_, AppointmentConsentForm>(&format!(
+      r#"
+      {}
+      WHERE a.user_id = $1 AND a.status IN ('PENDING', 'CONFIRMED') AND cs.id IS NULL
+      ORDER BY a.start_time, t.id
+      "#,
+      SELECT_APPOINTMENT_FORM
+    ))
+    .bind(customer_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(forms)
+  }
+
+  async fn create_submission(
+    &self,
+    submission: NewConsentSubmission,
+    signature: &[u8],
+    spa: &SpaConfig,
+  ) -> AppResult<ConsentSubmission> {
+    let context = sqlx::query_as::<_, SigningContext>(
+      r#"
+      SELECT u.full_name AS customer_name, u.phone AS customer_phone, a.start_time
+      FROM users.appointments a
+      LEFT JOIN users.tbl_users u ON u.pk_user_id = a.user_id
+      WHERE a.id = $1
+      "#,
+    )
+    .bind(submission.appointment_id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let signature_image = decode_signature(signature)
+      .ok_or_else(|| AppError::BadRequest("Invalid signature image".to_string()))?;
+
+    let signed_at = Utc::now();
+    let template = &submission.template;
+    let answers = submission.answers.as_object().cloned().unwrap_or_default();
+    let document = ConsentDocument {
+      spa,
+      title: &template.title,
+      description: template.description.as_deref(),
+      customer_name: context.customer_name.as_deref(),
+      customer_phone: context.customer_phone.as_deref(),
+      appointment_id: submission.appointment_id,
+      appointment_time: Some(context.start_time.as_str()),
+      questions: &template.questions,
+      answers: &answers,
+      signed_at,
+      ip_address: submission.ip_address.as_deref(),
+    };
+    let pdf = render_pdf_with_image(
+      &layout_consent(&document, PaperSize::A4.columns()),
+      PaperSize::A4,
+      Some(&signature_image),
+    );
+
+    let id: i64 = sqlx::query_scalar(
+      r#"
+      INSERT INTO users.consent_submissions (
+        template_id, customer_id, appointment_id, template_snapshot, answers, signature_image,
+        signed_at, ip_address, user_agent, pdf_content
+      )
+      VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+      ON CONFLICT (template_id, customer_id) DO NOTHING
+      RETURNING id
+      "#,
+    )
+    .bind(template.id)
+    .bind(submission.customer_id)
+    .bind(submission.appointment_id)
+    .bind(serde_json::json!(template))
+    .bind(&submission.answers)
+    .bind(&submission.signature_image)
+    .bind(signed_at)
+    .bind(&submission.ip_address)
+    .bind(&submission.user_agent)
+    .bind(pdf)
+    .fetch_optional(&self.db)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?
+    .ok_or_else(|| AppError::BadRequest("You have already signed this form".to_string()))?;
+
+    self.get_submission(id).await
+  }
+
+  async fn get_submission(
+    &self,
+    id: i64,
+  ) -> AppResult<ConsentSubmission> {
+    let submission =
+      sqlx::query_as::<_, ConsentSubmission>(&format!("{} WHERE cs.id = $1", SELECT_SUBMISSION))
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(submission)
+  }
+
+  async fn get_submission_pdf(
+    &self,
+    id: i64,
+  ) -> AppResult<Vec<u8>> {
+    let pdf: Vec<u8> =
+      sqlx::query_scalar("SELECT pdf_content FROM users.consent_submissions WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(pdf)
+  }
+
+  async fn get_submissions(
+    &self,
+    filter: ConsentSubmissionFilter,
+  ) -> AppResult<Vec<ConsentSubmission>> {
+    let submissions = sqlx::query_as::<_, ConsentSubmission>(&format!(
+      r#"
+      {}
+      WHERE ($1::int8 IS NULL OR cs.customer_id = $1)
+        AND ($2::int8 IS NULL OR cs.appointment_id = $2)
+        AND ($3::int8 IS NULL OR cs.template_id = $3)
+      ORDER BY cs.signed_at DESC
+      "#,
+      SELECT_SUBMISSION
+    ))
+    .bind(filter.customer_id)
+    .bind(filter.appointment_id)
+    .bind(filter.template_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(submissions)
+  }
+}
@@ -1,6 +1,6 @@
 use crate::repositories::{
-  appointment::send_noti::send_firebase_notification, notification::SqlxNotificationRepository,
-  notification_token::SqlxNotiTokenRepository,
+  email::enqueue_deposit_email, notification_outbox::enqueue_notification,
+  webhook::enqueue_webhook_event,
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -9,22 +9,77 @@ use domain::{
   entities::{
     common::PaginationMetadata,
     deposit::{
-      CreateDepositRequest, Deposit, DepositDetail, DepositFilter, UpdateDepositStatusRequest,
+      BankAccount, CreateDepositRequest, CreateTopUpRequest, CreateWithdrawalRequest, Deposit,
+      DepositDetail, DepositFilter, UpdateDepositStatusRequest, UpsertBankAccountRequest,
     },
     notification::CreateNotification,
+    notification_template::NotificationMessage,
   },
-  repositories::{
-    deposit_repository::DepositRepository, notification_repository::NotificationRepository,
-  },
+  repositories::deposit_repository::DepositRepository,
 };
 use modql::filter::ListOptions;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use utils::format_number::format_number;
 
 pub struct SqlxDepositRepository {
   pub db: PgPool,
 }
 
+// Ghi thông báo giao dịch ví (lưu DB + hàng đợi Firebase) trong transaction của giao dịch
+pub async fn notify_deposit(
+  conn: &mut PgConnection,
+  user_id: i64,
+  receiver: &str,
+  message: NotificationMessage,
+  deposit: &Deposit,
+) -> AppResult<()> {
+  let data = Some(serde_json::json!({
+    "type": "DEPOSIT",
+    "deposit_id": deposit.id,
+    "amount": deposit.amount,
+    "status": deposit.status
+  }));
+
+  let notification = CreateNotification {
+    user_id: if user_id == 0 { None } else { Some(user_id) },
+    title: String::new(),
+    body: String::new(),
+    receiver: receiver.to_string(),
+    notification_type: "DEPOSIT".to_string(),
+    data,
+    appointment_id: None,
+    template: Some(message),
+  };
+
+  enqueue_notification(conn, notification, true).await?;
+  Ok(())
+}
+
+// Trừ số dư ví, báo lỗi nếu không đủ tiền
+async fn hold_balance(
+  conn: &mut PgConnection,
+  user_id: i64,
+  amount: i64,
+) -> AppResult<()> {
+  let result = sqlx::query(
+    r#"
+      UPDATE users.tbl_users
+      SET balance = balance - $1
+      WHERE pk_user_id = $2 AND balance >= $1
+    "#,
+  )
+  .bind(amount)
+  .bind(user_id)
+  .execute(&mut *conn)
+  .await?;
+
+  if result.rows_affected() == 0 {
+    return Err(AppError::BadRequest("Số dư ví không đủ".to_string()));
+  }
+
+  Ok(())
+}
+
 #[async_trait]
 impl DepositRepository for SqlxDepositRepository {
   async fn create_deposit(
@@ -34,6 +89,9 @@ impl DepositRepository for SqlxDepositRepository {
   ) -> AppResult<Deposit> {
     let mut tx = self.db.begin().await?;
 
+    // Nhân viên tạo giao dịch mặc định là đã hoàn tất
+    let status = request.status.clone().unwrap_or_else(|| "COMPLETED".to_string());
+
     let deposit = match sqlx::query_as::<_, Deposit>(
       r#"
             INSERT INTO users.deposits (
@@ -44,7 +102,7 @@ impl DepositRepository for SqlxDepositRepository {
     )
     .bind(request.user_id)
     .bind(request.amount)
-    .bind(request.status)
+    .bind(&status)
     .bind(request.payment_method)
     .bind(request.notes)
     .bind(created_by)
@@ -59,7 +117,19 @@ impl DepositRepository for SqlxDepositRepository {
       },
     };
 
-    // Update user balance immediately
+    // Rút tiền: trừ ví ngay khi tạo (kể cả PENDING) để giữ số tiền chờ chi trả
+    if deposit.deposit_type == "WITHDRAW" && deposit.status != "CANCELLED" {
+      hold_balance(&mut tx, deposit.user_id, deposit.amount).await?;
+      tx.commit().await?;
+      return Ok(deposit);
+    }
+
+    // Chỉ cộng số dư khi giao dịch nạp tiền đã hoàn tất, PENDING sẽ được cộng lúc duyệt
+    if deposit.deposit_type != "DEPOSIT" || deposit.status != "COMPLETED" {
+      tx.commit().await?;
+      return Ok(deposit);
+    }
+
     if let Err(err) = sqlx::query(
       r#"
             UPDATE users.tbl_users
@@ -76,78 +146,264 @@ impl DepositRepository for SqlxDepositRepository {
       return Err(AppError::BadRequest(err.to_string()));
     }
 
-    // Create notification
-    let notification_repo = SqlxNotificationRepository { db: self.db.clone() };
-    let notification = CreateNotification {
-      user_id: Some(request.user_id),
-      title: "Nạp tiền thành công".to_string(),
-      body: format!("Bạn đã nạp thành công {}đ vào tài khoản", format_number(deposit.amount)),
-      receiver: "CUSTOMER".to_string(),
-      notification_type: "DEPOSIT".to_string(),
-      data: Some(serde_json::json!({
-        "type": "DEPOSIT",
-        "deposit_id": deposit.id,
-        "amount": deposit.amount,
-        "status": deposit.status
-      })),
-      appointment_id: None,
-    };
+    notify_deposit(
+      &mut tx,
+      deposit.user_id,
+      "CUSTOMER",
+      NotificationMessage::new(
+        "DEPOSIT_COMPLETED",
+        serde_json::json!({ "amount": format_number(deposit.amount) }),
+      ),
+      &deposit,
+    )
+    .await?;
+    enqueue_webhook_event(&mut *tx, "deposit.completed", serde_json::json!(deposit)).await?;
+    enqueue_deposit_email(&mut tx, &deposit).await?;
 
-    if let Err(err) = notification_repo.create(notification).await {
-      tx.rollback().await?;
-      return Err(err);
+    tx.commit().await?;
+
+    Ok(deposit)
+  }
+
+  async fn create_top_up(
+    &self,
+    user_id: i64,
+    request: CreateTopUpRequest,
+  ) -> AppResult<Deposit> {
+    let mut tx = self.db.begin().await?;
+
+    let deposit = sqlx::query_as::<_, Deposit>(
+      r#"
+            INSERT INTO users.deposits (
+                user_id, amount, status, payment_method, notes, created_by, deposit_type, proof_image
+            ) VALUES ($1, $2, 'PENDING', $3, $4, $1, 'DEPOSIT', $5)
+            RETURNING *
+            "#,
+    )
+    .bind(user_id)
+    .bind(request.amount)
+    .bind(request.payment_method.unwrap_or_else(|| "BANK_TRANSFER".to_string()))
+    .bind(request.notes)
+    .bind(request.image)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    // Báo cho lễ tân có yêu cầu nạp tiền cần duyệt
+    notify_deposit(
+      &mut tx,
+      0,
+      "ALLRECEPTIONIST",
+      NotificationMessage::new(
+        "DEPOSIT_REQUESTED",
+        serde_json::json!({ "amount": format_number(deposit.amount) }),
+      ),
+      &deposit,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(deposit)
+  }
+
+  async fn create_withdrawal(
+    &self,
+    user_id: i64,
+    request: CreateWithdrawalRequest,
+    daily_limit: i64,
+  ) -> AppResult<Deposit> {
+    let mut tx = self.db.begin().await?;
+
+    // Khoá dòng user để các yêu cầu rút tiền đồng thời được kiểm tra hạn mức lần lượt
+    sqlx::query(r#"SELECT pk_user_id FROM users.tbl_users WHERE pk_user_id = $1 FOR UPDATE"#)
+      .bind(user_id)
+      .fetch_optional(&mut *tx)
+      .await?
+      .ok_or(AppError::NotFound)?;
+
+    let bank_account =
+      sqlx::query_as::<_, BankAccount>(r#"SELECT * FROM users.bank_accounts WHERE user_id = $1"#)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::BadRequest(
+          "Vui lòng cập nhật tài khoản ngân hàng nhận tiền".to_string(),
+        ))?;
+
+    // Hạn mức theo ngày (giờ Việt Nam), chỉ tính yêu cầu rút về ngân hàng chưa bị từ chối
+    let withdrawn_today = sqlx::query_scalar::<_, i64>(
+      r#"
+        SELECT COALESCE(SUM(amount), 0)::BIGINT
+        FROM users.deposits
+        WHERE user_id = $1
+          AND deposit_type = 'WITHDRAW'
+          AND bank_account_number IS NOT NULL
+          AND status != 'CANCELLED'
+          AND (created_at AT TIME ZONE 'Asia/Ho_Chi_Minh')::date
+            = (NOW() AT TIME ZONE 'Asia/Ho_Chi_Minh')::date
+      "#,
+    )
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if withdrawn_today + request.amount > daily_limit {
+      return Err(AppError::BadRequest(format!(
+        "Vượt hạn mức rút tiền trong ngày, bạn còn được rút {}đ",
+        format_number((daily_limit - withdrawn_today).max(0))
+      )));
     }
 
-    // Send Firebase notification
-    let noti_token_repo = SqlxNotiTokenRepository { db: self.db.clone() };
-    let _ = send_firebase_notification(
-      &self.db,
-      std::sync::Arc::new(noti_token_repo),
-      request.user_id,
-      "Nạp tiền thành công".to_string(),
-      format!("Bạn đã nạp thành công {}đ vào tài khoản", format_number(deposit.amount)),
-      "CUSTOMER".to_string(),
-      Some(serde_json::json!({
-        "type": "DEPOSIT",
-        "deposit_id": deposit.id,
-        "amount": deposit.amount,
-        "status": deposit.status
-      })),
+    hold_balance(&mut tx, user_id, request.amount).await?;
+
+    let deposit = sqlx::query_as::<_, Deposit>(
+      r#"
+        INSERT INTO users.deposits (
+          user_id, amount, status, payment_method, notes, created_by, deposit_type,
+          bank_name, bank_account_number, bank_account_name
+        ) VALUES ($1, $2, 'PENDING', 'BANK_TRANSFER', $3, $1, 'WITHDRAW', $4, $5, $6)
+        RETURNING *
+      "#,
+    )
+    .bind(user_id)
+    .bind(request.amount)
+    .bind(request.notes)
+    .bind(bank_account.bank_name)
+    .bind(bank_account.account_number)
+    .bind(bank_account.account_name)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    notify_deposit(
+      &mut tx,
+      0,
+      "ALLRECEPTIONIST",
+      NotificationMessage::new(
+        "WITHDRAWAL_REQUESTED",
+        serde_json::json!({ "amount": format_number(deposit.amount) }),
+      ),
+      &deposit,
     )
-    .await;
+    .await?;
 
     tx.commit().await?;
 
     Ok(deposit)
   }
 
+  async fn get_bank_account(
+    &self,
+    user_id: i64,
+  ) -> AppResult<Option<BankAccount>> {
+    let bank_account =
+      sqlx::query_as::<_, BankAccount>(r#"SELECT * FROM users.bank_accounts WHERE user_id = $1"#)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+    Ok(bank_account)
+  }
+
+  async fn upsert_bank_account(
+    &self,
+    user_id: i64,
+    request: UpsertBankAccountRequest,
+  ) -> AppResult<BankAccount> {
+    let bank_account = sqlx::query_as::<_, BankAccount>(
+      r#"
+        INSERT INTO users.bank_accounts (user_id, bank_name, account_number, account_name)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id) DO UPDATE
+        SET bank_name = EXCLUDED.bank_name,
+            account_number = EXCLUDED.account_number,
+            account_name = EXCLUDED.account_name
+        RETURNING *
+      "#,
+    )
+    .bind(user_id)
+    .bind(request.bank_name)
+    .bind(request.account_number)
+    .bind(request.account_name)
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(bank_account)
+  }
+
   async fn update_deposit_status(
     &self,
     deposit_id: i64,
     request: UpdateDepositStatusRequest,
+    reviewed_by: i64,
   ) -> AppResult<Deposit> {
     let mut tx = self.db.begin().await?;
 
-    let status = request.status.clone();
+    // Chỉ giao dịch đang PENDING mới được duyệt/từ chối, điều kiện nằm trong câu UPDATE
+    // để hai nhân viên duyệt cùng lúc thì chỉ một người thành công
     let deposit = sqlx::query_as::<_, Deposit>(
       r#"
             UPDATE users.deposits
             SET 
                 status = $1,
                 notes = COALESCE($2, notes),
+                transaction_id = COALESCE($3, transaction_id),
+                rejection_reason = $4,
+                reviewed_by = $5,
+                reviewed_at = CURRENT_TIMESTAMP,
                 updated_at = CURRENT_TIMESTAMP
-            WHERE id = $3
+            WHERE id = $6 AND status = 'PENDING'
             RETURNING *
             "#,
     )
-    .bind(status)
+    .bind(request.status)
     .bind(request.notes)
+    .bind(request.transaction_id)
+    .bind(request.rejection_reason)
+    .bind(reviewed_by)
     .bind(deposit_id)
-    .fetch_one(&mut *tx)
+    .fetch_optional(&mut *tx)
     .await
     .map_err(|err| AppError::Unhandled(Box::new(err)))?;
 
-    if request.status.as_deref() == Some("COMPLETED") {
+    let deposit = match deposit {
+      Some(deposit) => deposit,
+      None => {
+        tx.rollback().await?;
+        let exists =
+          sqlx::query_scalar::<_, String>(r#"SELECT status FROM users.deposits WHERE id = $1"#)
+            .bind(deposit_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        return match exists {
+          Some(status) => {
+            Err(AppError::BadRequest(format!("Cannot change status of a {} deposit", status)))
+          },
+          None => Err(AppError::NotFound),
+        };
+      },
+    };
+
+    let is_withdraw = deposit.deposit_type == "WITHDRAW";
+    let is_completed = deposit.status == "COMPLETED";
+
+    // Rút tiền cần mã giao dịch chuyển khoản trả cho khách để đối soát
+    if is_withdraw && is_completed && deposit.transaction_id.is_none() {
+      tx.rollback().await?;
+      return Err(AppError::BadRequest(
+        "Payout reference (transaction_id) is required".to_string(),
+      ));
+    }
+
+    // Nạp tiền được duyệt thì cộng ví, rút tiền bị từ chối thì hoàn lại số tiền đang giữ
+    let refund = match (deposit.deposit_type.as_str(), is_completed) {
+      ("DEPOSIT", true) | ("WITHDRAW", false) => deposit.amount,
+      _ => 0,
+    };
+
+    if refund > 0 {
       sqlx::query(
         r#"
                 UPDATE users.tbl_users
@@ -155,48 +411,34 @@ impl DepositRepository for SqlxDepositRepository {
                 WHERE pk_user_id = $2
                 "#,
       )
-      .bind(deposit.amount)
+      .bind(refund)
       .bind(deposit.user_id)
       .execute(&mut *tx)
       .await
       .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    }
 
-      // Create notification for completed deposit
-      let notification_repo = SqlxNotificationRepository { db: self.db.clone() };
-      let notification = CreateNotification {
-        user_id: Some(deposit.user_id),
-        title: "Nạp tiền thành công".to_string(),
-        body: format!("Bạn đã nạp thành công {}đ vào tài khoản", format_number(deposit.amount)),
-        receiver: "CUSTOMER".to_string(),
-        notification_type: "DEPOSIT".to_string(),
-        data: Some(serde_json::json!({
-          "type": "DEPOSIT",
-          "deposit_id": deposit.id,
-          "amount": deposit.amount,
-          "status": deposit.status
-        })),
-        appointment_id: None,
-      };
-
-      notification_repo.create(notification).await?;
-
-      // Send Firebase notification for completed deposit
-      let noti_token_repo = SqlxNotiTokenRepository { db: self.db.clone() };
-      send_firebase_notification(
-        &self.db,
-        std::sync::Arc::new(noti_token_repo),
-        deposit.user_id,
-        "Nạp tiền thành công".to_string(),
-        format!("Bạn đã nạp thành công {}đ vào tài khoản", format_number(deposit.amount)),
-        "CUSTOMER".to_string(),
-        Some(serde_json::json!({
-          "type": "DEPOSIT",
-          "deposit_id": deposit.id,
-          "amount": deposit.amount,
-          "status": deposit.status
-        })),
-      )
-      .await?;
+    let event_type = match (is_withdraw, is_completed) {
+      (false, true) => "DEPOSIT_COMPLETED",
+      (false, false) => "DEPOSIT_REJECTED",
+      (true, true) => "WITHDRAWAL_COMPLETED",
+      (true, false) => "WITHDRAWAL_REJECTED",
+    };
+    let message = NotificationMessage::new(
+      event_type,
+      serde_json::json!({
+        "amount": format_number(deposit.amount),
+        "reason": deposit.rejection_reason.clone().unwrap_or_default(),
+        "bank_name": deposit.bank_name.clone().unwrap_or_default(),
+        "bank_account_number": deposit.bank_account_number.clone().unwrap_or_default(),
+        "transaction_id": deposit.transaction_id.clone().unwrap_or_default()
+      }),
+    );
+
+    notify_deposit(&mut tx, deposit.user_id, "CUSTOMER", message, &deposit).await?;
+    if deposit.deposit_type == "DEPOSIT" && is_completed {
+      enqueue_webhook_event(&mut *tx, "deposit.completed", serde_json::json!(deposit)).await?;
+      enqueue_deposit_email(&mut tx, &deposit).await?;
     }
 
     tx.commit().await?;
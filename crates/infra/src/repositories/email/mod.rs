@@ -0,0 +1,465 @@
+use crate::{
+  events::email::{EmailAttachment, EmailService, render_layout},
+  repositories::notification_preference::is_channel_enabled,
+};
+use async_trait::async_trait;
+use chrono::{Duration, FixedOffset, NaiveDateTime, Utc};
+use core_app::{
+  AppResult,
+  configs::{EmailConfig, SpaConfig},
+  errors::AppError,
+};
+use domain::{
+  entities::{
+    deposit::Deposit,
+    email::{
+      EmailMessage, EmailMessageFilter, EmailTemplate, EmailTemplateFilter,
+      UpdateEmailTemplateRequest,
+    },
+    notification_template::DEFAULT_LOCALE,
+  },
+  repositories::email_repository::EmailRepository,
+  services::email::{AppointmentEvent, appointment_ics, render_email},
+};
+use sqlx::{FromRow, PgConnection, PgPool};
+use utils::format_number::format_number;
+
+pub struct SqlxEmailRepository {
+  pub db: PgPool,
+}
+
+// Email đang PROCESSING quá thời gian này coi như worker đã chết giữa chừng
+const STALE_LOCK_MINUTES: i64 = 5;
+// Lịch hẹn chưa có giờ kết thúc thì file lịch tính 60 phút
+const DEFAULT_APPOINTMENT_MINUTES: i64 = 60;
+// Giờ lịch hẹn lưu dạng chuỗi theo giờ Việt Nam
+const VIETNAM_OFFSET_SECONDS: i32 = 7 * 3600;
+
+#[derive(FromRow)]
+struct EmailRecipient {
+  email_address: Option<String>,
+  full_name: Option<String>,
+  preferred_language: String,
+  is_email_verified: bool,
+}
+
+#[derive(FromRow)]
+struct EmailAppointment {
+  user_id: Option<i64>,
+  start_time: String,
+  end_time: Option<String>,
+  total_price: i64,
+  technician_name: Option<String>,
+  services: Option<String>,
+  receipt_no: Option<String>,
+}
+
+/// Ghi email vào hàng đợi bằng transaction của nghiệp vụ, địa chỉ nhận do nơi gọi quyết định
+/// (mã xác minh được gửi tới cả địa chỉ chưa xác minh)
+pub async fn enqueue_email(
+  conn: &mut PgConnection,
+  user_id: Option<i64>,
+  to_address: &str,
+  locale: &str,
+  event_type: &str,
+  variables: serde_json::Value,
+) -> AppResult<i64> {
+  let id = sqlx::query_scalar::<_, i64>(
+    r#"
+    INSERT INTO users.email_messages (event_type, user_id, to_address, locale, variables)
+    VALUES ($1, $2, $3, $4, $5)
+    RETURNING id
+    "#,
+  )
+  .bind(event_type)
+  .bind(user_id)
+  .bind(to_address)
+  .bind(locale)
+  .bind(variables)
+  .fetch_one(&mut *conn)
+  .await?;
+
+  Ok(id)
+}
+
+/// Email thông báo cho khách: chỉ gửi tới email đã xác minh và khi khách chưa tắt kênh EMAIL
+/// của loại thông báo này
+async fn enqueue_user_email(
+  conn: &mut PgConnection,
+  user_id: i64,
+  notification_type: &str,
+  event_type: &str,
+  mut variables: serde_json::Value,
+) -> AppResult<Option<i64>> {
+  let recipient = sqlx::query_as::<_, EmailRecipient>(
+    r#"
+    SELECT email_address, full_name, preferred_language, is_email_verified
+    FROM users.tbl_users WHERE pk_user_id = $1
+    "#,
+  )
+  .bind(user_id)
+  .fetch_optional(&mut *conn)
+  .await?;
+
+  let Some(recipient) = recipient.filter(|recipient| recipient.is_email_verified) else {
+    return Ok(None);
+  };
+  let Some(email) = recipient.email_address.filter(|email| !email.is_empty()) else {
+    return Ok(None);
+  };
+  if !is_channel_enabled(conn, user_id, notification_type, "EMAIL").await? {
+    return Ok(None);
+  }
+
+  variables["customer_name"] = recipient.full_name.unwrap_or_default().into();
+  let id = enqueue_email(
+    conn,
+    Some(user_id),
+    &email,
+    &recipient.preferred_language,
+    event_type,
+    variables,
+  )
+  .await?;
+
+  Ok(Some(id))
+}
+
+/// Email xác nhận lịch hẹn (APPOINTMENT_CONFIRMED) hoặc biên nhận thanh toán (PAYMENT_RECEIPT),
+/// nội dung lấy theo lịch hẹn tại thời điểm thay đổi
+pub async fn enqueue_appointment_email(
+  conn: &mut PgConnection,
+  appointment_id: i64,
+  event_type: &str,
+) -> AppResult<Option<i64>> {
+  let appointment = sqlx::query_as::<_, EmailAppointment>(
+    r#"
+    SELECT a.user_id, a.start_time, a.end_time, a.total_price, t.full_name AS technician_name,
+      (
+        SELECT string_agg(aps.service_name, ', ' ORDER BY aps.sequence, aps.id)
+        FROM users.appointments_services aps
+        WHERE aps.appointment_id = a.id
+      ) AS services,
+      (
+        SELECT r.receipt_no FROM users.receipts r
+        WHERE r.appointment_id = a.id
+        ORDER BY r.issued_at DESC
+        LIMIT 1
+      ) AS receipt_no
+    FROM users.appointments a
+    LEFT JOIN users.tbl_users t ON t.pk_user_id = a.technician_id
+    WHERE a.id = $1
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_optional(&mut *conn)
+  .await?
+  .ok_or(AppError::NotFound)?;
+
+  let Some(user_id) = appointment.user_id else {
+    return Ok(None);
+  };
+
+  let notification_type = if event_type == "PAYMENT_RECEIPT" { "PAYMENT" } else { "APPOINTMENT" };
+  let variables = serde_json::json!({
+    "appointment_id": appointment_id,
+    "start_time": appointment.start_time,
+    "end_time": appointment.end_time,
+    "services": appointment.services.unwrap_or_default(),
+    "technician_name": appointment.technician_name.unwrap_or_default(),
+    "total_price": format_number(appointment.total_price),
+    "receipt_no": appointment.receipt_no.unwrap_or_default(),
+  });
+
+  enqueue_user_email(conn, user_id, notification_type, event_type, variables).await
+}
+
+/// Biên nhận nạp tiền, số dư đọc trong cùng transaction nên đã gồm khoản vừa nạp
+pub async fn enqueue_deposit_email(
+  conn: &mut PgConnection,
+  deposit: &Deposit,
+) -> AppResult<Option<i64>> {
+  let balance =
+    sqlx::query_scalar::<_, i64>("SELECT balance FROM users.tbl_users WHERE pk_user_id = $1")
+      .bind(deposit.user_id)
+      .fetch_optional(&mut *conn)
+      .await?
+      .unwrap_or_default();
+
+  let variables = serde_json::json!({
+    "deposit_id": deposit.id,
+    "amount": format_number(deposit.amount),
+    "payment_method": deposit.payment_method,
+    "balance": format_number(balance),
+  });
+
+  enqueue_user_email(conn, deposit.user_id, "DEPOSIT", "DEPOSIT_RECEIPT", variables).await
+}
+
+fn retry_delay(
+  config: &EmailConfig,
+  attempts: i32,
+) -> Duration {
+  let exponent = (attempts - 1).clamp(0, 10) as u32;
+
+  Duration::seconds(config.retry_base_seconds.max(1).saturating_mul(1 << exponent))
+}
+
+fn parse_appointment_time(value: &str) -> Option<chrono::DateTime<Utc>> {
+  let offset = FixedOffset::east_opt(VIETNAM_OFFSET_SECONDS)?;
+  NaiveDateTime::parse_from_str(value.trim(), "%H:%M %d/%m/%Y")
+    .ok()?
+    .and_local_timezone(offset)
+    .single()
+    .map(|time| time.with_timezone(&Utc))
+}
+
+// File .ics đính kèm email xác nhận lịch hẹn, bỏ qua nếu giờ hẹn không đọc được
+fn appointment_attachment(
+  message: &EmailMessage,
+  subject: &str,
+  config: &EmailConfig,
+  spa: &SpaConfig,
+) -> Option<EmailAttachment> {
+  let variables = &message.variables;
+  let start = parse_appointment_time(variables["start_time"].as_str()?)?;
+  let end = variables["end_time"]
+    .as_str()
+    .and_then(parse_appointment_time)
+    .filter(|end| *end > start)
+    .unwrap_or(start + Duration::minutes(DEFAULT_APPOINTMENT_MINUTES));
+
+  let domain = config.from_address.rsplit('@').next().unwrap_or("naspa.vn");
+  let summary = match variables["services"].as_str().filter(|services| !services.is_empty()) {
+    Some(services) => format!("{} - {}", spa.name, services),
+    None => spa.name.clone(),
+  };
+  let ics = appointment_ics(&AppointmentEvent {
+    // UID cố định theo lịch hẹn để lịch của khách cập nhật thay vì tạo sự kiện mới
+    uid: format!("appointment-{}@{}", variables["appointment_id"], domain),
+    start,
+    end,
+    summary: &summary,
+    description: subject,
+    location: &spa.address,
+    organizer_name: &config.from_name,
+    organizer_email: &config.from_address,
+  });
+
+  Some(EmailAttachment {
+    filename: "appointment.ics".to_string(),
+    content_type: "text/calendar; method=REQUEST; charset=UTF-8".to_string(),
+    content: ics.into_bytes(),
+  })
+}
+
+async fn send_email(
+  db: &PgPool,
+  service: &EmailService,
+  message: &EmailMessage,
+  config: &EmailConfig,
+  spa: &SpaConfig,
+) -> Result<String, String> {
+  // Không có mẫu theo ngôn ngữ của khách thì dùng mẫu tiếng Việt
+  let template = sqlx::query_as::<_, EmailTemplate>(
+    r#"
+    SELECT * FROM users.email_templates
+    WHERE event_type = $1 AND locale IN ($2, $3)
+    ORDER BY (locale = $2) DESC
+    LIMIT 1
+    "#,
+  )
+  .bind(&message.event_type)
+  .bind(&message.locale)
+  .bind(DEFAULT_LOCALE)
+  .fetch_optional(db)
+  .await
+  .map_err(|err| err.to_string())?
+  .ok_or(format!("Email template {} not found", message.event_type))?;
+
+  let (subject, body) = render_email(&template, &message.variables);
+  let html = render_layout(spa, &template.locale, &subject, &body);
+  let attachments = if message.event_type == "APPOINTMENT_CONFIRMED" {
+    appointment_attachment(message, &subject, config, spa).into_iter().collect()
+  } else {
+    Vec::new()
+  };
+
+  service
+    .send(&message.to_address, &subject, html, attachments)
+    .await
+    .map_err(|err| err.to_string())?;
+
+  Ok(subject)
+}
+
+/// Lấy một lô email đến hạn gửi (SKIP LOCKED để chạy được nhiều instance) và gửi qua SMTP.
+/// Trả về số email đã xử lý.
+pub async fn dispatch_emails(
+  db: &PgPool,
+  config: &EmailConfig,
+  spa: &SpaConfig,
+) -> AppResult<usize> {
+  sqlx::query(
+    r#"
+    UPDATE users.email_messages
+    SET status = 'PENDING', locked_at = NULL
+    WHERE status = 'PROCESSING' AND locked_at < $1
+    "#,
+  )
+  .bind(Utc::now() - Duration::minutes(STALE_LOCK_MINUTES))
+  .execute(db)
+  .await?;
+
+  let batch = sqlx::query_as::<_, EmailMessage>(
+    r#"
+    UPDATE users.email_messages
+    SET status = 'PROCESSING', locked_at = NOW()
+    WHERE id IN (
+      SELECT id FROM users.email_messages
+      WHERE status = 'PENDING' AND next_attempt_at <= NOW()
+      ORDER BY next_attempt_at, id
+      LIMIT $1
+      FOR UPDATE SKIP LOCKED
+    )
+    RETURNING *
+    "#,
+  )
+  .bind(config.batch_size.max(1))
+  .fetch_all(db)
+  .await?;
+
+  if batch.is_empty() {
+    return Ok(0);
+  }
+
+  let service = EmailService::try_new(config).map_err(|err| err.to_string());
+  for message in &batch {
+    let result = match &service {
+      Ok(Some(service)) => send_email(db, service, message, config, spa).await,
+      Ok(None) => Err("SMTP is not configured".to_string()),
+      Err(err) => Err(err.clone()),
+    };
+
+    let attempts = message.attempts + 1;
+    match result {
+      Ok(subject) => {
+        sqlx::query(
+          r#"
+          UPDATE users.email_messages
+          SET status = 'SENT', attempts = $2, subject = $3, sent_at = NOW(), locked_at = NULL,
+              last_error = NULL
+          WHERE id = $1
+          "#,
+        )
+        .bind(message.id)
+        .bind(attempts)
+        .bind(subject)
+        .execute(db)
+        .await?;
+      },
+      Err(err) if attempts >= config.max_attempts => {
+        tracing::error!("Email message {} failed: {}", message.id, err);
+        sqlx::query(
+          r#"
+          UPDATE users.email_messages
+          SET status = 'FAILED', attempts = $2, locked_at = NULL, last_error = $3
+          WHERE id = $1
+          "#,
+        )
+        .bind(message.id)
+        .bind(attempts)
+        .bind(err)
+        .execute(db)
+        .await?;
+      },
+      Err(err) => {
+        sqlx::query(
+          r#"
+          UPDATE users.email_messages
+          SET status = 'PENDING', attempts = $2, locked_at = NULL, last_error = $3,
+              next_attempt_at = $4
+          WHERE id = $1
+          "#,
+        )
+        .bind(message.id)
+        .bind(attempts)
+        .bind(err)
+        .bind(Utc::now() + retry_delay(config, attempts))
+        .execute(db)
+        .await?;
+      },
+    }
+  }
+
+  Ok(batch.len())
+}
+
+#[async_trait]
+impl EmailRepository for SqlxEmailRepository {
+  async fn list_templates(
+    &self,
+    filter: EmailTemplateFilter,
+  ) -> AppResult<Vec<EmailTemplate>> {
+    let templates = sqlx::query_as::<_, EmailTemplate>(
+      r#"
+      SELECT * FROM users.email_templates
+      WHERE ($1::text IS NULL OR event_type = $1)
+        AND ($2::text IS NULL OR locale = $2)
+      ORDER BY event_type, locale
+      "#,
+    )
+    .bind(filter.event_type)
+    .bind(filter.locale)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(templates)
+  }
+
+  async fn update_template(
+    &self,
+    id: i64,
+    payload: UpdateEmailTemplateRequest,
+  ) -> AppResult<EmailTemplate> {
+    let template = sqlx::query_as::<_, EmailTemplate>(
+      r#"
+      UPDATE users.email_templates
+      SET subject = $2, body = $3
+      WHERE id = $1
+      RETURNING *
+      "#,
+    )
+    .bind(id)
+    .bind(payload.subject)
+    .bind(payload.body)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(template)
+  }
+
+  async fn list_messages(
+    &self,
+    filter: EmailMessageFilter,
+  ) -> AppResult<Vec<EmailMessage>> {
+    let messages = sqlx::query_as::<_, EmailMessage>(
+      r#"
+      SELECT * FROM users.email_messages
+      WHERE ($1::text IS NULL OR status = $1)
+        AND ($2::text IS NULL OR event_type = $2)
+        AND ($3::int8 IS NULL OR user_id = $3)
+      ORDER BY created_at DESC
+      LIMIT 200
+      "#,
+    )
+    .bind(filter.status)
+    .bind(filter.event_type)
+    .bind(filter.user_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(messages)
+  }
+}
@@ -1,13 +1,13 @@
 use async_trait::async_trait;
 use core_app::AppResult;
 use core_app::errors::AppError;
-use domain::repositories::image_repository::ImageRepository;
+use domain::repositories::image_repository::{ImageRepository, PRIVATE_IMAGE_DIRS};
 use fast_image_resize::images::Image;
 use fast_image_resize::{PixelType, ResizeOptions, Resizer};
 use image::codecs::jpeg::JpegEncoder;
 use image::{DynamicImage, GenericImageView, ImageFormat};
 use std::io::Cursor;
-use std::path::Path;
+use std::path::{Component, Path};
 use std::sync::Arc;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
@@ -28,7 +28,7 @@ impl ImageRepository for LocalImageService {
     max_file_size: usize, // Kích thước tối đa (bytes)
     max_width: u32,       // Chiều rộng tối đa để resize
     quality: u8,          // Chất lượng ảnh (0-100)
-    sub_dir: &str,        // Thư mục con trong uploads/ (ví dụ: "avatar", "service")
+    sub_dir: &str, // Thư mục con trong uploads/ (hoặc private_uploads/, xem PRIVATE_IMAGE_DIRS)
   ) -> AppResult<String> {
     // Kiểm tra kích thước file
     let start = Instant::now();
@@ -210,9 +210,10 @@ impl ImageRepository for LocalImageService {
     }
     tracing::info!("Encoded image buffer size: {} bytes", buffer.len());
 
-    // Tạo thư mục uploads/ và thư mục con nếu chưa tồn tại
+    // Tạo thư mục uploads/ (hoặc private_uploads/) và thư mục con nếu chưa tồn tại
     let io_start = Instant::now();
-    let uploads_dir = Path::new("uploads").join(sub_dir);
+    let root = if PRIVATE_IMAGE_DIRS.contains(&sub_dir) { "private_uploads" } else { "uploads" };
+    let uploads_dir = Path::new(root).join(sub_dir);
     if !uploads_dir.exists() {
       fs::create_dir_all(&uploads_dir)
         .await
@@ -245,7 +246,7 @@ impl ImageRepository for LocalImageService {
     }
 
     let path = Path::new(image_path);
-    if !path.starts_with("uploads/") {
+    if !path.starts_with("uploads/") && !path.starts_with("private_uploads/") {
       tracing::warn!("Invalid image path, must be in uploads/ directory: {:?}", path);
       return Ok(());
     }
@@ -266,4 +267,32 @@ impl ImageRepository for LocalImageService {
       Ok(())
     }
   }
+
+  /// Đọc ảnh trong private_uploads/, trả về dữ liệu và content type
+  async fn read_private_image(
+    &self,
+    image_path: &str,
+  ) -> AppResult<(Vec<u8>, String)> {
+    let path = Path::new(image_path);
+    if !path.starts_with("private_uploads/")
+      || path.components().any(|component| !matches!(component, Component::Normal(_)))
+    {
+      tracing::warn!("Invalid private image path: {:?}", path);
+      return Err(AppError::NotFound);
+    }
+
+    let content_type = match path.extension().and_then(|extension| extension.to_str()) {
+      Some("jpg") => "image/jpeg",
+      Some("png") => "image/png",
+      Some("webp") => "image/webp",
+      _ => return Err(AppError::NotFound),
+    };
+
+    let data = fs::read(path).await.map_err(|err| {
+      tracing::warn!("Failed to read private image {:?}: {}", path, err);
+      AppError::NotFound
+    })?;
+
+    Ok((data, content_type.to_string()))
+  }
 }
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use core_app::{AppResult, errors::AppError};
+use domain::{
+  entities::invoice::{Invoice, InvoiceSubmission},
+  repositories::invoice_repository::InvoiceProviderRepository,
+};
+use tokio::fs;
+
+// Adapter ghi file XML vào thư mục để phần mềm của nhà cung cấp (hoặc test) đọc và ký số
+pub struct FileDropInvoiceProvider {
+  pub dir: String,
+}
+
+#[async_trait]
+impl InvoiceProviderRepository for FileDropInvoiceProvider {
+  async fn submit(
+    &self,
+    invoice: &Invoice,
+  ) -> AppResult<InvoiceSubmission> {
+    fs::create_dir_all(&self.dir).await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let path = PathBuf::from(&self.dir).join(format!(
+      "{}{}-{:08}.xml",
+      invoice.template_code, invoice.symbol, invoice.invoice_number
+    ));
+
+    fs::write(&path, invoice.xml_content.as_bytes())
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(InvoiceSubmission {
+      provider: "FILE_DROP".to_string(),
+      reference: path.to_string_lossy().into_owned(),
+    })
+  }
+}
@@ -0,0 +1,627 @@
+use std::collections::HashMap;
+
+use crate::documents::einvoice::{EInvoiceDocument, EInvoiceLine, build_einvoice_xml};
+use async_trait::async_trait;
+use chrono::{FixedOffset, Utc};
+use core_app::{AppResult, configs::SpaConfig, errors::AppError};
+use domain::{
+  entities::invoice::{
+    CreateAdjustmentInvoiceRequest, CreateInvoiceRequest, CreateReplacementInvoiceRequest,
+    DEFAULT_VAT_RATE, Invoice, InvoiceBuyer, InvoiceDetail, InvoiceFilter, InvoiceLine,
+    InvoiceLineVatRate, InvoiceSubmission,
+  },
+  repositories::invoice_repository::InvoiceRepository,
+};
+use sqlx::{FromRow, PgConnection, PgPool};
+use utils::format_number::number_to_vietnamese_words;
+
+pub mod file_drop;
+
+// Mẫu số 1: hoá đơn GTGT
+const TEMPLATE_CODE: &str = "1";
+
+pub struct SqlxInvoiceRepository {
+  pub db: PgPool,
+}
+
+#[derive(FromRow)]
+struct InvoiceAppointment {
+  id: i64,
+  status: String,
+  surcharge: i64,
+  promotion: i64,
+}
+
+#[derive(FromRow)]
+struct InvoiceAppointmentService {
+  id: i64,
+  appointment_id: i64,
+  service_name: String,
+  quantity: i32,
+  unit_price: i64,
+  discount: i64,
+}
+
+struct DraftLine {
+  appointment_id: Option<i64>,
+  appointment_service_id: Option<i64>,
+  line: EInvoiceLine,
+}
+
+// Giá lịch hẹn đã gồm thuế nên tách ngược ra tiền trước thuế và tiền thuế
+fn line_from_gross(
+  line_type: &str,
+  description: String,
+  quantity: i32,
+  gross: i64,
+  vat_rate: i32,
+) -> EInvoiceLine {
+  let amount = (gross as f64 * 100.0 / (100 + vat_rate) as f64).round() as i64;
+
+  EInvoiceLine {
+    line_type: line_type.to_string(),
+    description,
+    unit: "Lượt".to_string(),
+    quantity,
+    unit_price: (amount as f64 / quantity.max(1) as f64).round() as i64,
+    amount,
+    vat_rate,
+    vat_amount: gross - amount,
+  }
+}
+
+// Ký hiệu hoá đơn: C = có mã của cơ quan thuế, 2 số cuối năm, T = doanh nghiệp đăng ký sử dụng
+fn current_symbol() -> (String, chrono::NaiveDate) {
+  let utc_plus_7 = FixedOffset::east_opt(7 * 3600).unwrap();
+  let today = Utc::now().with_timezone(&utc_plus_7).date_naive();
+
+  (format!("C{}TNS", today.format("%y")), today)
+}
+
+async fn next_invoice_number(
+  conn: &mut PgConnection,
+  symbol: &str,
+) -> AppResult<i64> {
+  let number: i64 = sqlx::query_scalar(
+    r#"
+    INSERT INTO users.invoice_counters (symbol, last_number)
+    VALUES ($1, 1)
+    ON CONFLICT (symbol) DO UPDATE SET last_number = users.invoice_counters.last_number + 1
+    RETURNING last_number
+    "#,
+  )
+  .bind(symbol)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(number)
+}
+
+// Lập các dòng hoá đơn từ lịch hẹn đã thanh toán và chưa nằm trong hoá đơn nào còn hiệu lực
+async fn appointment_lines(
+  conn: &mut PgConnection,
+  appointment_ids: &[i64],
+  vat_rate: Option<i32>,
+  line_vat_rates: Option<Vec<InvoiceLineVatRate>>,
+) -> AppResult<Vec<DraftLine>> {
+  let appointments = sqlx::query_as::<_, InvoiceAppointment>(
+    r#"
+    SELECT id, status, surcharge, promotion
+    FROM users.appointments
+    WHERE id = ANY($1)
+    ORDER BY id
+    FOR UPDATE
+    "#,
+  )
+  .bind(appointment_ids)
+  .fetch_all(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  if appointments.len() != appointment_ids.len() {
+    return Err(AppError::NotFound);
+  }
+
+  if let Some(appointment) = appointments.iter().find(|a| a.status != "PAYMENT") {
+    return Err(AppError::BadRequest(format!("Lịch hẹn #{} chưa được thanh toán", appointment.id)));
+  }
+
+  let invoiced: Option<i64> = sqlx::query_scalar(
+    r#"
+    SELECT l.appointment_id
+    FROM users.invoice_lines l
+    JOIN users.invoices i ON i.id = l.invoice_id
+    WHERE l.appointment_id = ANY($1)
+      AND i.status = 'ISSUED'
+      AND i.invoice_type <> 'ADJUSTMENT'
+    LIMIT 1
+    "#,
+  )
+  .bind(appointment_ids)
+  .fetch_optional(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?
+  .flatten();
+
+  if let Some(appointment_id) = invoiced {
+    return Err(AppError::BadRequest(format!("Lịch hẹn #{} đã được xuất hoá đơn", appointment_id)));
+  }
+
+  let services = sqlx::query_as::<_, InvoiceAppointmentService>(
+    r#"
+    SELECT aps.id, aps.appointment_id,
+           COALESCE(aps.service_name, s.service_name) AS service_name,
+           COALESCE(aps.quantity, 1) AS quantity,
+           aps.unit_price, aps.discount
+    FROM users.appointments_services aps
+    JOIN users.service_items s ON s.id = aps.service_id
+    WHERE aps.appointment_id = ANY($1) AND aps.status <> 'CANCELLED'
+    ORDER BY aps.appointment_id, aps.sequence, aps.id
+    "#,
+  )
+  .bind(appointment_ids)
+  .fetch_all(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let default_rate = vat_rate.unwrap_or(DEFAULT_VAT_RATE);
+  let overrides: HashMap<i64, i32> = line_vat_rates
+    .unwrap_or_default()
+    .into_iter()
+    .map(|line| (line.appointment_service_id, line.vat_rate))
+    .collect();
+
+  if let Some(id) = overrides.keys().find(|id| !services.iter().any(|s| s.id == **id)) {
+    return Err(AppError::BadRequest(format!("Dịch vụ #{} không thuộc các lịch hẹn này", id)));
+  }
+
+  let mut lines = Vec::new();
+  for appointment in &appointments {
+    for service in services.iter().filter(|s| s.appointment_id == appointment.id) {
+      let vat_rate = overrides.get(&service.id).copied().unwrap_or(default_rate);
+      let gross = service.unit_price * service.quantity as i64 - service.discount;
+
+      lines.push(DraftLine {
+        appointment_id: Some(appointment.id),
+        appointment_service_id: Some(service.id),
+        line: line_from_gross(
+          "SERVICE",
+          service.service_name.clone(),
+          service.quantity,
+          gross,
+          vat_rate,
+        ),
+      });
+    }
+
+    if appointment.surcharge > 0 {
+      lines.push(DraftLine {
+        appointment_id: Some(appointment.id),
+        appointment_service_id: None,
+        line: line_from_gross(
+          "SURCHARGE",
+          format!("Phụ thu lịch hẹn #{}", appointment.id),
+          1,
+          appointment.surcharge,
+          default_rate,
+        ),
+      });
+    }
+
+    if appointment.promotion > 0 {
+      lines.push(DraftLine {
+        appointment_id: Some(appointment.id),
+        appointment_service_id: None,
+        line: line_from_gross(
+          "DISCOUNT",
+          format!("Khuyến mại lịch hẹn #{}", appointment.id),
+          1,
+          -appointment.promotion,
+          default_rate,
+        ),
+      });
+    }
+  }
+
+  if lines.is_empty() {
+    return Err(AppError::BadRequest("Không có dịch vụ nào để xuất hoá đơn".to_string()));
+  }
+
+  Ok(lines)
+}
+
+struct NewInvoice<'a> {
+  invoice_type: &'a str,
+  original: Option<&'a Invoice>,
+  buyer: &'a InvoiceBuyer,
+  lines: Vec<DraftLine>,
+  reason: Option<String>,
+  seller: &'a SpaConfig,
+  created_by: i64,
+}
+
+// Cấp số, dựng XML và lưu hoá đơn cùng các dòng
+async fn insert_invoice(
+  conn: &mut PgConnection,
+  new_invoice: NewInvoice<'_>,
+) -> AppResult<InvoiceDetail> {
+  let (symbol, issued_date) = current_symbol();
+  let invoice_number = next_invoice_number(conn, &symbol).await?;
+
+  let total_before_tax: i64 = new_invoice.lines.iter().map(|draft| draft.line.amount).sum();
+  let total_vat: i64 = new_invoice.lines.iter().map(|draft| draft.line.vat_amount).sum();
+  let total_amount = total_before_tax + total_vat;
+  let amount_in_words = number_to_vietnamese_words(total_amount);
+  let payment_method = "TM/CK";
+
+  let lines: Vec<EInvoiceLine> = new_invoice.lines.iter().map(|draft| draft.line.clone()).collect();
+  let xml_content = build_einvoice_xml(&EInvoiceDocument {
+    invoice_type: new_invoice.invoice_type,
+    template_code: TEMPLATE_CODE,
+    symbol: &symbol,
+    invoice_number,
+    issued_date,
+    payment_method,
+    seller: new_invoice.seller,
+    buyer: new_invoice.buyer,
+    lines: &lines,
+    total_before_tax,
+    total_vat,
+    total_amount,
+    amount_in_words: &amount_in_words,
+    reason: new_invoice.reason.as_deref(),
+    original: new_invoice.original,
+  });
+
+  let invoice = sqlx::query_as::<_, Invoice>(
+    r#"
+    INSERT INTO users.invoices (
+      invoice_type, original_invoice_id, template_code, symbol, invoice_number, issued_date,
+      buyer_tax_code, buyer_company_name, buyer_address, buyer_name, buyer_email, payment_method,
+      total_before_tax, total_vat, total_amount, amount_in_words, reason, xml_content, created_by
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+    RETURNING *
+    "#,
+  )
+  .bind(new_invoice.invoice_type)
+  .bind(new_invoice.original.map(|original| original.id))
+  .bind(TEMPLATE_CODE)
+  .bind(&symbol)
+  .bind(invoice_number)
+  .bind(issued_date)
+  .bind(&new_invoice.buyer.buyer_tax_code)
+  .bind(&new_invoice.buyer.buyer_company_name)
+  .bind(&new_invoice.buyer.buyer_address)
+  .bind(&new_invoice.buyer.buyer_name)
+  .bind(&new_invoice.buyer.buyer_email)
+  .bind(payment_method)
+  .bind(total_before_tax)
+  .bind(total_vat)
+  .bind(total_amount)
+  .bind(&amount_in_words)
+  .bind(&new_invoice.reason)
+  .bind(&xml_content)
+  .bind(new_invoice.created_by)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let mut invoice_lines = Vec::with_capacity(new_invoice.lines.len());
+  for (index, draft) in new_invoice.lines.into_iter().enumerate() {
+    let line = sqlx::query_as::<_, InvoiceLine>(
+      r#"
+      INSERT INTO users.invoice_lines (
+        invoice_id, line_no, line_type, appointment_id, appointment_service_id, description,
+        unit, quantity, unit_price, amount, vat_rate, vat_amount, total
+      )
+      VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+      RETURNING *
+      "#,
+    )
+    .bind(invoice.id)
+    .bind((index + 1) as i32)
+    .bind(&draft.line.line_type)
+    .bind(draft.appointment_id)
+    .bind(draft.appointment_service_id)
+    .bind(&draft.line.description)
+    .bind(&draft.line.unit)
+    .bind(draft.line.quantity)
+    .bind(draft.line.unit_price)
+    .bind(draft.line.amount)
+    .bind(draft.line.vat_rate)
+    .bind(draft.line.vat_amount)
+    .bind(draft.line.amount + draft.line.vat_amount)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    invoice_lines.push(line);
+  }
+
+  Ok(InvoiceDetail { invoice, lines: invoice_lines })
+}
+
+// Khoá hoá đơn gốc, chỉ thay thế / điều chỉnh được hoá đơn còn hiệu lực và không phải hoá đơn điều chỉnh
+async fn lock_original_invoice(
+  conn: &mut PgConnection,
+  original_id: i64,
+) -> AppResult<Invoice> {
+  let original =
+    sqlx::query_as::<_, Invoice>("SELECT * FROM users.invoices WHERE id = $1 FOR UPDATE")
+      .bind(original_id)
+      .fetch_optional(&mut *conn)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?
+      .ok_or(AppError::NotFound)?;
+
+  if original.status != "ISSUED" {
+    return Err(AppError::BadRequest("Hoá đơn đã bị thay thế".to_string()));
+  }
+
+  if original.invoice_type == "ADJUSTMENT" {
+    return Err(AppError::BadRequest(
+      "Không thể thay thế hoặc điều chỉnh hoá đơn điều chỉnh".to_string(),
+    ));
+  }
+
+  Ok(original)
+}
+
+#[async_trait]
+impl InvoiceRepository for SqlxInvoiceRepository {
+  async fn create_invoice(
+    &self,
+    payload: CreateInvoiceRequest,
+    seller: &SpaConfig,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let lines = appointment_lines(
+      &mut tx,
+      &payload.appointment_ids,
+      payload.vat_rate,
+      payload.line_vat_rates,
+    )
+    .await?;
+
+    let detail = insert_invoice(
+      &mut tx,
+      NewInvoice {
+        invoice_type: "ORIGINAL",
+        original: None,
+        buyer: &payload.buyer,
+        lines,
+        reason: None,
+        seller,
+        created_by,
+      },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(detail)
+  }
+
+  async fn create_replacement(
+    &self,
+    original_id: i64,
+    payload: CreateReplacementInvoiceRequest,
+    seller: &SpaConfig,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let original = lock_original_invoice(&mut tx, original_id).await?;
+
+    let appointment_ids: Vec<i64> = sqlx::query_scalar(
+      r#"
+      SELECT DISTINCT appointment_id
+      FROM users.invoice_lines
+      WHERE invoice_id = $1 AND appointment_id IS NOT NULL
+      ORDER BY appointment_id
+      "#,
+    )
+    .bind(original.id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    sqlx::query("UPDATE users.invoices SET status = 'REPLACED' WHERE id = $1")
+      .bind(original.id)
+      .execute(&mut *tx)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let lines =
+      appointment_lines(&mut tx, &appointment_ids, payload.vat_rate, payload.line_vat_rates)
+        .await?;
+
+    let detail = insert_invoice(
+      &mut tx,
+      NewInvoice {
+        invoice_type: "REPLACEMENT",
+        original: Some(&original),
+        buyer: &payload.buyer,
+        lines,
+        reason: Some(payload.reason.trim().to_string()),
+        seller,
+        created_by,
+      },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(detail)
+  }
+
+  async fn create_adjustment(
+    &self,
+    original_id: i64,
+    payload: CreateAdjustmentInvoiceRequest,
+    seller: &SpaConfig,
+    created_by: i64,
+  ) -> AppResult<InvoiceDetail> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let original = lock_original_invoice(&mut tx, original_id).await?;
+    let buyer = InvoiceBuyer {
+      buyer_tax_code: original.buyer_tax_code.clone(),
+      buyer_company_name: original.buyer_company_name.clone(),
+      buyer_address: original.buyer_address.clone(),
+      buyer_name: original.buyer_name.clone(),
+      buyer_email: original.buyer_email.clone(),
+    };
+
+    // Dòng điều chỉnh nhập theo đơn giá chưa thuế
+    let lines = payload
+      .lines
+      .into_iter()
+      .map(|line| {
+        let amount = line.unit_price * line.quantity as i64;
+
+        DraftLine {
+          appointment_id: None,
+          appointment_service_id: None,
+          line: EInvoiceLine {
+            line_type: "ADJUSTMENT".to_string(),
+            description: line.description.trim().to_string(),
+            unit: "Lượt".to_string(),
+            quantity: line.quantity,
+            unit_price: line.unit_price,
+            amount,
+            vat_rate: line.vat_rate,
+            vat_amount: (amount as f64 * line.vat_rate as f64 / 100.0).round() as i64,
+          },
+        }
+      })
+      .collect();
+
+    let detail = insert_invoice(
+      &mut tx,
+      NewInvoice {
+        invoice_type: "ADJUSTMENT",
+        original: Some(&original),
+        buyer: &buyer,
+        lines,
+        reason: Some(payload.reason.trim().to_string()),
+        seller,
+        created_by,
+      },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(detail)
+  }
+
+  async fn get_invoice(
+    &self,
+    id: i64,
+  ) -> AppResult<InvoiceDetail> {
+    let invoice = sqlx::query_as::<_, Invoice>("SELECT * FROM users.invoices WHERE id = $1")
+      .bind(id)
+      .fetch_optional(&self.db)
+      .await?
+      .ok_or(AppError::NotFound)?;
+
+    let lines = sqlx::query_as::<_, InvoiceLine>(
+      "SELECT * FROM users.invoice_lines WHERE invoice_id = $1 ORDER BY line_no",
+    )
+    .bind(id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(InvoiceDetail { invoice, lines })
+  }
+
+  async fn get_invoices(
+    &self,
+    filter: InvoiceFilter,
+  ) -> AppResult<Vec<Invoice>> {
+    let invoices = sqlx::query_as::<_, Invoice>(
+      r#"
+        SELECT * FROM users.invoices i
+        WHERE ($1::text IS NULL OR i.status = $1)
+          AND ($2::text IS NULL OR i.invoice_type = $2)
+          AND ($3::text IS NULL OR i.buyer_tax_code = $3)
+          AND ($4::int8 IS NULL OR EXISTS (
+            SELECT 1 FROM users.invoice_lines l WHERE l.invoice_id = i.id AND l.appointment_id = $4
+          ))
+        ORDER BY i.created_at DESC
+      "#,
+    )
+    .bind(filter.status)
+    .bind(filter.invoice_type)
+    .bind(filter.buyer_tax_code)
+    .bind(filter.appointment_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(invoices)
+  }
+
+  async fn update_submission(
+    &self,
+    id: i64,
+    submission: Result<InvoiceSubmission, String>,
+  ) -> AppResult<Invoice> {
+    let (provider, reference, error) = match submission {
+      Ok(submission) => (Some(submission.provider), Some(submission.reference), None),
+      Err(error) => (None, None, Some(error)),
+    };
+
+    // submission_error = NULL nghĩa là gửi thành công
+    let invoice = sqlx::query_as::<_, Invoice>(
+      r#"
+      UPDATE users.invoices
+      SET submission_status = CASE WHEN $4::text IS NULL THEN 'SUBMITTED' ELSE 'FAILED' END,
+          provider = COALESCE($2, provider),
+          provider_reference = COALESCE($3, provider_reference),
+          submission_error = $4,
+          submitted_at = CASE WHEN $4::text IS NULL THEN NOW() ELSE submitted_at END
+      WHERE id = $1
+      RETURNING *
+      "#,
+    )
+    .bind(id)
+    .bind(provider)
+    .bind(reference)
+    .bind(error)
+    .fetch_optional(&self.db)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(invoice)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn line_from_gross_splits_vat_back_out() {
+    // (số lượng, giá đã gồm thuế, thuế suất, tiền trước thuế, tiền thuế, đơn giá)
+    let cases = [
+      (1, 350_000, 8, 324_074, 25_926, 324_074),
+      (3, 840_000, 8, 777_778, 62_222, 259_259),
+      (1, 280_000, 10, 254_545, 25_455, 254_545),
+      (2, 300_000, 0, 300_000, 0, 150_000),
+      (1, -50_000, 8, -46_296, -3_704, -46_296),
+    ];
+
+    for (quantity, gross, vat_rate, amount, vat_amount, unit_price) in cases {
+      let line = line_from_gross("SERVICE", String::new(), quantity, gross, vat_rate);
+
+      assert_eq!((line.amount, line.vat_amount, line.unit_price), (amount, vat_amount, unit_price));
+      assert_eq!(line.amount + line.vat_amount, gross);
+    }
+  }
+}
@@ -3,45 +3,130 @@ use chrono::Utc;
 use core_app::{AppResult, errors::AppError};
 use domain::entities::common::PaginationMetadata;
 use domain::entities::notification::{
-  CreateNotification, Notification, NotificationFilter, UpdateNotification,
+  BulkDeleteNotificationsRequest, BulkReadNotificationsRequest, CreateNotification, Notification,
+  NotificationFilter, UpdateNotification,
 };
+use domain::entities::realtime_event::RealtimeEvent;
 use domain::entities::user::UserWithPassword;
 use domain::repositories::notification_repository::NotificationRepository;
 use modql::filter::ListOptions;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashSet;
+
+use super::notification_template::apply_template;
+use crate::events::realtime::publish_event;
 
 pub struct SqlxNotificationRepository {
   pub db: PgPool,
 }
 
+// Lưu thông báo in-app, dùng được trong transaction của nghiệp vụ.
+// Sự kiện realtime được phát cùng transaction nên client chỉ nhận khi nghiệp vụ commit.
+pub async fn insert_notification(
+  conn: &mut PgConnection,
+  payload: CreateNotification,
+) -> AppResult<Notification> {
+  let notification = sqlx::query_as::<_, Notification>(
+    r#"
+    INSERT INTO users.notifications (
+      user_id, title, body, receiver, notification_type, data, appointment_id, is_read, created_at, updated_at
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, false, $8, $8)
+    RETURNING *
+    "#,
+  )
+  .bind(payload.user_id)
+  .bind(payload.title)
+  .bind(payload.body)
+  .bind(payload.receiver)
+  .bind(payload.notification_type)
+  .bind(payload.data)
+  .bind(payload.appointment_id)
+  .bind(Utc::now())
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  let event = RealtimeEvent::NotificationCreated {
+    notification_id: notification.id,
+    user_id: notification.user_id,
+    receiver: notification.receiver.clone(),
+  };
+  publish_event(&mut *conn, &event).await?;
+
+  Ok(notification)
+}
+
+// Mỗi người nhận bị ảnh hưởng chỉ cần một sự kiện để client tải lại số chưa đọc
+async fn publish_changed(
+  conn: &mut PgConnection,
+  changed: Vec<(Option<i64>, String)>,
+) -> AppResult<()> {
+  let changed: HashSet<(Option<i64>, String)> = changed.into_iter().collect();
+  for (user_id, receiver) in changed {
+    publish_event(&mut *conn, &RealtimeEvent::NotificationChanged { user_id, receiver }).await?;
+  }
+
+  Ok(())
+}
+
+/// Chuyển thông báo in-app cũ hơn `days` ngày sang bảng notifications_archive theo từng lô
+pub async fn archive_old_notifications(
+  db: &PgPool,
+  days: i64,
+) -> AppResult<u64> {
+  const BATCH_SIZE: i64 = 1000;
+  let mut total = 0;
+
+  loop {
+    let archived = sqlx::query(
+      r#"
+      WITH moved AS (
+        DELETE FROM users.notifications
+        WHERE id IN (
+          SELECT id FROM users.notifications
+          WHERE created_at < NOW() - make_interval(days => $1::int)
+          ORDER BY id
+          LIMIT $2
+        )
+        RETURNING *
+      )
+      INSERT INTO users.notifications_archive (
+        id, user_id, appointment_id, title, body, receiver, data, is_read, created_at, updated_at,
+        notification_type
+      )
+      SELECT
+        id, user_id, appointment_id, title, body, receiver, data, is_read, created_at, updated_at,
+        notification_type
+      FROM moved
+      ON CONFLICT (id) DO NOTHING
+      "#,
+    )
+    .bind(days as i32)
+    .bind(BATCH_SIZE)
+    .execute(db)
+    .await?
+    .rows_affected();
+
+    total += archived;
+    if archived < BATCH_SIZE as u64 {
+      break;
+    }
+  }
+
+  Ok(total)
+}
+
 #[async_trait]
 impl NotificationRepository for SqlxNotificationRepository {
   async fn create(
     &self,
     payload: CreateNotification,
   ) -> AppResult<Notification> {
-    let notification = sqlx::query_as::<_, Notification>(
-      r#"
-      INSERT INTO users.notifications (
-        user_id, title, body, receiver, notification_type, data, appointment_id, is_read, created_at, updated_at
-      )
-      VALUES ($1, $2, $3, $4, $5, $6, $7, false, $8, $8)
-      RETURNING *
-      "#,
-    )
-    .bind(payload.user_id)
-    .bind(payload.title)
-    .bind(payload.body)
-    .bind(payload.receiver)
-    .bind(payload.notification_type)
-    .bind(payload.data)
-    .bind(payload.appointment_id)
-    .bind(Utc::now())
-    .fetch_one(&self.db)
-    .await
-    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    let payload = apply_template(&mut conn, payload).await?;
 
-    Ok(notification)
+    insert_notification(&mut conn, payload).await
   }
 
   async fn update(
@@ -64,6 +149,12 @@ impl NotificationRepository for SqlxNotificationRepository {
     .await
     .map_err(|err| AppError::BadRequest(err.to_string()))?;
 
+    let event = RealtimeEvent::NotificationChanged {
+      user_id: notification.user_id,
+      receiver: notification.receiver.clone(),
+    };
+    publish_event(&self.db, &event).await?;
+
     Ok(notification)
   }
 
@@ -240,18 +331,24 @@ impl NotificationRepository for SqlxNotificationRepository {
     &self,
     id: i64,
   ) -> AppResult<bool> {
-    let result = sqlx::query(
+    let deleted = sqlx::query_as::<_, (Option<i64>, String)>(
       r#"
       DELETE FROM users.notifications
       WHERE id = $1
+      RETURNING user_id, receiver
       "#,
     )
     .bind(id)
-    .execute(&self.db)
+    .fetch_optional(&self.db)
     .await
     .map_err(|err| AppError::BadRequest(err.to_string()))?;
 
-    Ok(result.rows_affected() > 0)
+    let Some((user_id, receiver)) = deleted else {
+      return Ok(false);
+    };
+    publish_event(&self.db, &RealtimeEvent::NotificationChanged { user_id, receiver }).await?;
+
+    Ok(true)
   }
 
   async fn un_read(
@@ -291,4 +388,88 @@ impl NotificationRepository for SqlxNotificationRepository {
 
     Ok(count)
   }
+
+  // Thông báo của chính người dùng và thông báo nhóm theo vai trò (dùng chung trạng thái đọc);
+  // admin xem được mọi thông báo nhưng chỉ đổi hàng loạt thông báo của mình
+  async fn bulk_update_read(
+    &self,
+    user: UserWithPassword,
+    payload: BulkReadNotificationsRequest,
+  ) -> AppResult<u64> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let changed = sqlx::query_as::<_, (Option<i64>, String)>(
+      r#"
+      UPDATE users.notifications
+      SET is_read = $5
+      WHERE (
+        ($2 = 'CUSTOMER' AND user_id = $1 AND receiver = 'CUSTOMER')
+        OR
+        ($2 = 'RECEPTIONIST' AND (
+          (user_id = $1 AND receiver = 'RECEPTIONIST')
+          OR receiver = 'ALLRECEPTIONIST'
+        ))
+        OR
+        ($2 = 'TECHNICIAN' AND (
+          (user_id = $1 AND receiver = 'TECHNICIAN')
+          OR receiver = 'ALLTECHNICIAN'
+        ))
+        OR
+        ($2 = 'ADMIN' AND user_id = $1)
+      )
+      AND ($3::bigint[] IS NULL OR id = ANY($3))
+      AND ($4::text IS NULL OR notification_type = $4)
+      AND is_read IS DISTINCT FROM $5
+      RETURNING user_id, receiver
+      "#,
+    )
+    .bind(user.pk_user_id)
+    .bind(user.role)
+    .bind(payload.ids)
+    .bind(payload.notification_type)
+    .bind(payload.is_read)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    let affected = changed.len() as u64;
+    publish_changed(&mut tx, changed).await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(affected)
+  }
+
+  // Người dùng (kể cả admin) chỉ xoá được thông báo của riêng mình, thông báo nhóm do job lưu trữ dọn
+  async fn bulk_delete(
+    &self,
+    user: UserWithPassword,
+    payload: BulkDeleteNotificationsRequest,
+  ) -> AppResult<u64> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let deleted = sqlx::query_as::<_, (Option<i64>, String)>(
+      r#"
+      DELETE FROM users.notifications
+      WHERE user_id = $1 AND ($2 = 'ADMIN' OR receiver = $2)
+        AND ($3::bigint[] IS NULL OR id = ANY($3))
+        AND ($4::timestamptz IS NULL OR created_at < $4)
+      RETURNING user_id, receiver
+      "#,
+    )
+    .bind(user.pk_user_id)
+    .bind(user.role)
+    .bind(payload.ids)
+    .bind(payload.before)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    let affected = deleted.len() as u64;
+    publish_changed(&mut tx, deleted).await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(affected)
+  }
 }
@@ -0,0 +1,550 @@
+use crate::{
+  firebase::{FcmSendResult, NotificationService},
+  repositories::{
+    notification::insert_notification, notification_preference::is_channel_enabled,
+    notification_template::apply_template, notification_template::get_templates_by_locale,
+    notification_token::delete_invalid_tokens,
+  },
+};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use core_app::{AppResult, configs::NotificationConfig, errors::AppError};
+use domain::{
+  entities::{
+    notification::CreateNotification,
+    notification_outbox::{
+      NotificationDelivery, NotificationOutbox, NotificationOutboxDetail, NotificationOutboxFilter,
+      NotificationOutboxStats,
+    },
+    notification_preference::TRANSACTIONAL_NOTIFICATION_TYPES,
+    notification_template::DEFAULT_LOCALE,
+  },
+  repositories::notification_outbox_repository::NotificationOutboxRepository,
+  services::notification_template::render_template,
+};
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+
+pub struct SqlxNotificationOutboxRepository {
+  pub db: PgPool,
+}
+
+// Thông báo đang PROCESSING quá thời gian này coi như worker đã chết giữa chừng
+const STALE_LOCK_MINUTES: i64 = 5;
+// Token đang trong khung giờ yên lặng được hoãn và kiểm tra lại sau khoảng này
+const QUIET_HOURS_RECHECK_MINUTES: i64 = 15;
+
+/// Ghi thông báo đẩy vào outbox bằng connection/transaction của nghiệp vụ để thông báo chỉ tồn tại
+/// khi thay đổi nghiệp vụ được commit. `persist = true` thì lưu thêm vào danh sách thông báo in-app.
+/// Thông báo theo mẫu được lưu kèm biến để dựng lại theo ngôn ngữ từng người nhận khi gửi.
+/// Người nhận tắt kênh in-app cho loại thông báo này thì chỉ gửi đẩy.
+pub async fn enqueue_notification(
+  conn: &mut PgConnection,
+  notification: CreateNotification,
+  persist: bool,
+) -> AppResult<i64> {
+  let notification = apply_template(conn, notification).await?;
+  let persist = match notification.user_id {
+    Some(user_id) if persist && is_individual_receiver(&notification.receiver) => {
+      is_channel_enabled(conn, user_id, &notification.notification_type, "IN_APP").await?
+    },
+    _ => persist,
+  };
+  let (event_type, variables) = match notification.template.clone() {
+    Some(message) => (Some(message.event_type), Some(message.variables)),
+    None => (None, None),
+  };
+
+  let notification_id =
+    if persist { Some(insert_notification(conn, notification.clone()).await?.id) } else { None };
+
+  let id = sqlx::query_scalar::<_, i64>(
+    r#"
+    INSERT INTO users.notification_outbox (
+      notification_id, user_id, receiver, title, body, notification_type, data, event_type,
+      variables
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+    RETURNING id
+    "#,
+  )
+  .bind(notification_id)
+  .bind(notification.user_id)
+  .bind(notification.receiver)
+  .bind(notification.title)
+  .bind(notification.body)
+  .bind(notification.notification_type)
+  .bind(notification.data)
+  .bind(event_type)
+  .bind(variables)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  Ok(id)
+}
+
+fn is_individual_receiver(receiver: &str) -> bool {
+  matches!(receiver, "RECEPTIONIST" | "TECHNICIAN" | "CUSTOMER")
+}
+
+// Lấy danh sách FCM token theo người nhận tại thời điểm gửi, bỏ qua người đã tắt thông báo đẩy
+async fn resolve_tokens(
+  db: &PgPool,
+  receiver: &str,
+  user_id: Option<i64>,
+  notification_type: &str,
+) -> AppResult<Vec<String>> {
+  let roles: Vec<&str> = match receiver {
+    "ALLRECEPTIONIST" => vec!["RECEPTIONIST"],
+    "ALLTECHNICIAN" => vec!["TECHNICIAN"],
+    "ALL" => vec!["RECEPTIONIST", "TECHNICIAN"],
+    "RECEPTIONIST" | "TECHNICIAN" | "CUSTOMER" => vec![],
+    _ => {
+      tracing::info!("Invalid notification receiver: {}", receiver);
+      return Ok(vec![]);
+    },
+  };
+
+  let tokens = if roles.is_empty() {
+    let Some(user_id) = user_id else {
+      return Ok(vec![]);
+    };
+
+    sqlx::query_scalar::<_, String>(
+      r#"
+      SELECT DISTINCT a.token FROM users.notification_tokens a
+      WHERE a.user_id = $1
+        AND NOT EXISTS (
+          SELECT 1 FROM users.notification_preferences p
+          WHERE p.user_id = a.user_id AND p.notification_type = $2 AND p.channel = 'PUSH'
+            AND NOT p.enabled
+        )
+      "#,
+    )
+    .bind(user_id)
+    .bind(notification_type)
+    .fetch_all(db)
+    .await?
+  } else {
+    sqlx::query_scalar::<_, String>(
+      r#"
+      SELECT DISTINCT a.token FROM users.notification_tokens a
+      INNER JOIN users.tbl_users b ON a.user_id = b.pk_user_id
+      WHERE b.role = ANY($1)
+        AND NOT EXISTS (
+          SELECT 1 FROM users.notification_preferences p
+          WHERE p.user_id = a.user_id AND p.notification_type = $2 AND p.channel = 'PUSH'
+            AND NOT p.enabled
+        )
+      "#,
+    )
+    .bind(roles)
+    .bind(notification_type)
+    .fetch_all(db)
+    .await?
+  };
+
+  Ok(tokens)
+}
+
+fn retry_delay(
+  config: &NotificationConfig,
+  attempts: i32,
+) -> Duration {
+  let exponent = (attempts - 1).clamp(0, 20) as u32;
+  let seconds = config.retry_base_seconds.max(1).saturating_mul(1 << exponent);
+
+  Duration::seconds(seconds.min(config.retry_max_seconds.max(1)))
+}
+
+// Gửi một thông báo tới các token chưa thành công, cập nhật kết quả từng token và lịch thử lại
+async fn deliver(
+  db: &PgPool,
+  service: Result<&NotificationService, &str>,
+  outbox: &NotificationOutbox,
+  config: &NotificationConfig,
+) -> AppResult<()> {
+  // Lần gửi đầu tiên mới xác định danh sách token, các lần sau chỉ gửi lại token lỗi
+  if outbox.attempts == 0 {
+    let tokens =
+      resolve_tokens(db, &outbox.receiver, outbox.user_id, &outbox.notification_type).await?;
+    sqlx::query(
+      r#"
+      INSERT INTO users.notification_deliveries (outbox_id, token)
+      SELECT $1, UNNEST($2::varchar[])
+      ON CONFLICT (outbox_id, token) DO NOTHING
+      "#,
+    )
+    .bind(outbox.id)
+    .bind(tokens)
+    .execute(db)
+    .await?;
+  }
+
+  // Đã hết lượt thử lại thì chỉ còn gửi nốt các token bị hoãn vì giờ yên lặng
+  let deliveries = sqlx::query_as::<_, NotificationDelivery>(
+    r#"
+    SELECT * FROM users.notification_deliveries
+    WHERE outbox_id = $1 AND (status = 'PENDING' OR (status = 'FAILED' AND $2 < $3))
+    ORDER BY id
+    "#,
+  )
+  .bind(outbox.id)
+  .bind(outbox.attempts)
+  .bind(config.max_attempts)
+  .fetch_all(db)
+  .await?;
+
+  // Thông báo không phải giao dịch thì hoãn các token có chủ đang trong khung giờ yên lặng
+  let quiet_tokens: Vec<String> = if TRANSACTIONAL_NOTIFICATION_TYPES
+    .contains(&outbox.notification_type.as_str())
+    || deliveries.is_empty()
+  {
+    vec![]
+  } else {
+    let tokens: Vec<String> = deliveries.iter().map(|delivery| delivery.token.clone()).collect();
+    sqlx::query_scalar::<_, String>(
+      r#"
+        SELECT t.token FROM users.notification_tokens t
+        INNER JOIN users.notification_quiet_hours q ON q.user_id = t.user_id
+        WHERE t.token = ANY($1) AND q.enabled
+          AND CASE
+            WHEN q.start_time < q.end_time THEN
+              (NOW() AT TIME ZONE q.timezone)::time >= q.start_time
+              AND (NOW() AT TIME ZONE q.timezone)::time < q.end_time
+            ELSE
+              (NOW() AT TIME ZONE q.timezone)::time >= q.start_time
+              OR (NOW() AT TIME ZONE q.timezone)::time < q.end_time
+          END
+        "#,
+    )
+    .bind(tokens)
+    .fetch_all(db)
+    .await?
+  };
+  let (deliveries, deferred): (Vec<_>, Vec<_>) =
+    deliveries.into_iter().partition(|delivery| !quiet_tokens.contains(&delivery.token));
+
+  // Thông báo theo mẫu được dịch theo ngôn ngữ của chủ token
+  let (templates, token_locales) = match outbox.event_type.as_deref() {
+    Some(event_type) if !deliveries.is_empty() => {
+      let tokens: Vec<String> = deliveries.iter().map(|delivery| delivery.token.clone()).collect();
+      let token_locales = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT t.token, u.preferred_language FROM users.notification_tokens t
+        INNER JOIN users.tbl_users u ON u.pk_user_id = t.user_id
+        WHERE t.token = ANY($1)
+        "#,
+      )
+      .bind(tokens)
+      .fetch_all(db)
+      .await?;
+
+      (get_templates_by_locale(db, event_type).await?, token_locales.into_iter().collect())
+    },
+    _ => (HashMap::new(), HashMap::new()),
+  };
+  let variables = outbox.variables.clone().unwrap_or_default();
+
+  let mut last_error = None;
+  let mut invalid_tokens = vec![];
+  for delivery in deliveries {
+    let locale = token_locales.get(&delivery.token).map(String::as_str).unwrap_or(DEFAULT_LOCALE);
+    let (title, body) = match templates.get(locale).or_else(|| templates.get(DEFAULT_LOCALE)) {
+      Some(template) => {
+        (render_template(&template.title, &variables), render_template(&template.body, &variables))
+      },
+      None => (outbox.title.clone(), outbox.body.clone()),
+    };
+
+    let result = match service {
+      Ok(service) => {
+        service.send_to_token(&title, &body, outbox.data.as_ref(), &delivery.token).await
+      },
+      Err(err) => FcmSendResult::Failed(err.to_string()),
+    };
+
+    let (status, error) = match result {
+      FcmSendResult::Sent => ("SENT", None),
+      FcmSendResult::InvalidToken(err) => {
+        invalid_tokens.push(delivery.token.clone());
+        ("INVALID", Some(err))
+      },
+      FcmSendResult::Failed(err) => {
+        last_error = Some(err.clone());
+        ("FAILED", Some(err))
+      },
+    };
+
+    sqlx::query(
+      r#"
+      UPDATE users.notification_deliveries
+      SET status = $2, attempts = attempts + 1, last_error = $3,
+          sent_at = CASE WHEN $2 = 'SENT' THEN NOW() ELSE sent_at END
+      WHERE id = $1
+      "#,
+    )
+    .bind(delivery.id)
+    .bind(status)
+    .bind(error)
+    .execute(db)
+    .await?;
+  }
+
+  delete_invalid_tokens(db, &invalid_tokens).await?;
+
+  let attempts = outbox.attempts + 1;
+  match last_error {
+    // Chỉ còn token đang trong giờ yên lặng, chờ tới lượt kiểm tra sau (không tính là lần gửi lỗi)
+    None if !deferred.is_empty() => {
+      sqlx::query(
+        r#"
+        UPDATE users.notification_outbox
+        SET status = 'PENDING', locked_at = NULL, next_attempt_at = $2
+        WHERE id = $1
+        "#,
+      )
+      .bind(outbox.id)
+      .bind(Utc::now() + Duration::minutes(QUIET_HOURS_RECHECK_MINUTES))
+      .execute(db)
+      .await?;
+    },
+    // Token lỗi đã hết lượt thử từ trước, các token bị hoãn đã gửi xong nên chuyển dead-letter
+    None if outbox.attempts >= config.max_attempts => {
+      tracing::error!(
+        "Notification {} moved to dead-letter: {}",
+        outbox.id,
+        outbox.last_error.as_deref().unwrap_or_default()
+      );
+      sqlx::query(
+        r#"
+        UPDATE users.notification_outbox
+        SET status = 'DEAD', locked_at = NULL
+        WHERE id = $1
+        "#,
+      )
+      .bind(outbox.id)
+      .execute(db)
+      .await?;
+    },
+    None => {
+      sqlx::query(
+        r#"
+        UPDATE users.notification_outbox
+        SET status = 'SENT', attempts = $2, sent_at = NOW(), locked_at = NULL, last_error = NULL
+        WHERE id = $1
+        "#,
+      )
+      .bind(outbox.id)
+      .bind(attempts)
+      .execute(db)
+      .await?;
+    },
+    // Hết lượt thử lại nhưng còn token đang hoãn vì giờ yên lặng: chờ gửi nốt rồi mới dead-letter
+    Some(err) if attempts >= config.max_attempts && !deferred.is_empty() => {
+      sqlx::query(
+        r#"
+        UPDATE users.notification_outbox
+        SET status = 'PENDING', attempts = $2, locked_at = NULL, last_error = $3,
+            next_attempt_at = $4
+        WHERE id = $1
+        "#,
+      )
+      .bind(outbox.id)
+      .bind(attempts)
+      .bind(err)
+      .bind(Utc::now() + Duration::minutes(QUIET_HOURS_RECHECK_MINUTES))
+      .execute(db)
+      .await?;
+    },
+    Some(err) if attempts >= config.max_attempts => {
+      tracing::error!("Notification {} moved to dead-letter: {}", outbox.id, err);
+      sqlx::query(
+        r#"
+        UPDATE users.notification_outbox
+        SET status = 'DEAD', attempts = $2, locked_at = NULL, last_error = $3
+        WHERE id = $1
+        "#,
+      )
+      .bind(outbox.id)
+      .bind(attempts)
+      .bind(err)
+      .execute(db)
+      .await?;
+    },
+    Some(err) => {
+      sqlx::query(
+        r#"
+        UPDATE users.notification_outbox
+        SET status = 'PENDING', attempts = $2, locked_at = NULL, last_error = $3,
+            next_attempt_at = $4
+        WHERE id = $1
+        "#,
+      )
+      .bind(outbox.id)
+      .bind(attempts)
+      .bind(err)
+      .bind(Utc::now() + retry_delay(config, attempts))
+      .execute(db)
+      .await?;
+    },
+  }
+
+  Ok(())
+}
+
+/// Lấy một lô thông báo đến hạn gửi (SKIP LOCKED để chạy được nhiều instance) và gửi qua FCM.
+/// Trả về số thông báo đã xử lý.
+pub async fn dispatch_notifications(
+  db: &PgPool,
+  config: &NotificationConfig,
+) -> AppResult<usize> {
+  sqlx::query(
+    r#"
+    UPDATE users.notification_outbox
+    SET status = 'PENDING', locked_at = NULL
+    WHERE status = 'PROCESSING' AND locked_at < $1
+    "#,
+  )
+  .bind(Utc::now() - Duration::minutes(STALE_LOCK_MINUTES))
+  .execute(db)
+  .await?;
+
+  let batch = sqlx::query_as::<_, NotificationOutbox>(
+    r#"
+    UPDATE users.notification_outbox
+    SET status = 'PROCESSING', locked_at = NOW()
+    WHERE id IN (
+      SELECT id FROM users.notification_outbox
+      WHERE status = 'PENDING' AND next_attempt_at <= NOW()
+      ORDER BY next_attempt_at, id
+      LIMIT $1
+      FOR UPDATE SKIP LOCKED
+    )
+    RETURNING *
+    "#,
+  )
+  .bind(config.batch_size.max(1))
+  .fetch_all(db)
+  .await?;
+
+  if batch.is_empty() {
+    return Ok(0);
+  }
+
+  let service = NotificationService::shared().await.map_err(|err| {
+    tracing::error!("Failed to initialize notification service: {:?}", err);
+    format!("Notification service unavailable: {}", err)
+  });
+
+  for outbox in &batch {
+    let service = service.as_deref().map_err(|err| err.as_str());
+    if let Err(err) = deliver(db, service, outbox, config).await {
+      tracing::error!("Failed to deliver notification {}: {:?}", outbox.id, err);
+      // Trả lại hàng đợi, lần sau thử lại như một lần gửi lỗi
+      let attempts = outbox.attempts + 1;
+      let _ = sqlx::query(
+        r#"
+        UPDATE users.notification_outbox
+        SET status = CASE WHEN $2 >= $3 THEN 'DEAD' ELSE 'PENDING' END,
+            attempts = $2, locked_at = NULL, last_error = $4, next_attempt_at = $5
+        WHERE id = $1
+        "#,
+      )
+      .bind(outbox.id)
+      .bind(attempts)
+      .bind(config.max_attempts)
+      .bind(format!("{:?}", err))
+      .bind(Utc::now() + retry_delay(config, attempts))
+      .execute(db)
+      .await;
+    }
+  }
+
+  Ok(batch.len())
+}
+
+#[async_trait]
+impl NotificationOutboxRepository for SqlxNotificationOutboxRepository {
+  async fn list(
+    &self,
+    filter: NotificationOutboxFilter,
+  ) -> AppResult<Vec<NotificationOutbox>> {
+    let outbox = sqlx::query_as::<_, NotificationOutbox>(
+      r#"
+      SELECT * FROM users.notification_outbox
+      WHERE ($1::text IS NULL OR status = $1)
+        AND ($2::int8 IS NULL OR user_id = $2)
+        AND ($3::text IS NULL OR notification_type = $3)
+      ORDER BY created_at DESC
+      LIMIT 200
+      "#,
+    )
+    .bind(filter.status)
+    .bind(filter.user_id)
+    .bind(filter.notification_type)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(outbox)
+  }
+
+  async fn get_by_id(
+    &self,
+    id: i64,
+  ) -> AppResult<NotificationOutboxDetail> {
+    let outbox = sqlx::query_as::<_, NotificationOutbox>(
+      "SELECT * FROM users.notification_outbox WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let deliveries = sqlx::query_as::<_, NotificationDelivery>(
+      "SELECT * FROM users.notification_deliveries WHERE outbox_id = $1 ORDER BY id",
+    )
+    .bind(id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(NotificationOutboxDetail { outbox, deliveries })
+  }
+
+  async fn get_stats(&self) -> AppResult<NotificationOutboxStats> {
+    let stats = sqlx::query_as::<_, NotificationOutboxStats>(
+      r#"
+      SELECT
+        COUNT(*) FILTER (WHERE status = 'PENDING') AS pending,
+        COUNT(*) FILTER (WHERE status = 'PROCESSING') AS processing,
+        COUNT(*) FILTER (WHERE status = 'SENT') AS sent,
+        COUNT(*) FILTER (WHERE status = 'DEAD') AS dead
+      FROM users.notification_outbox
+      "#,
+    )
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(stats)
+  }
+
+  async fn retry(
+    &self,
+    id: i64,
+  ) -> AppResult<NotificationOutboxDetail> {
+    sqlx::query(
+      r#"
+      UPDATE users.notification_outbox
+      SET status = 'PENDING', next_attempt_at = NOW(), last_error = NULL, locked_at = NULL,
+          attempts = 0
+      WHERE id = $1 AND status = 'DEAD'
+      "#,
+    )
+    .bind(id)
+    .execute(&self.db)
+    .await?;
+
+    self.get_by_id(id).await
+  }
+}
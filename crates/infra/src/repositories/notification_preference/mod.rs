@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use core_app::{AppResult, errors::AppError};
+use domain::{
+  entities::notification_preference::{
+    NOTIFICATION_CHANNELS, NOTIFICATION_TYPES, NotificationPreference, NotificationPreferences,
+    QuietHours, UpdateNotificationPreferencesRequest,
+  },
+  repositories::notification_preference_repository::NotificationPreferenceRepository,
+};
+use sqlx::{PgConnection, PgPool};
+
+pub struct SqlxNotificationPreferenceRepository {
+  pub db: PgPool,
+}
+
+/// Người dùng có nhận loại thông báo này qua kênh này không, mặc định là có
+pub async fn is_channel_enabled(
+  conn: &mut PgConnection,
+  user_id: i64,
+  notification_type: &str,
+  channel: &str,
+) -> AppResult<bool> {
+  let enabled = sqlx::query_scalar::<_, bool>(
+    r#"
+    SELECT enabled FROM users.notification_preferences
+    WHERE user_id = $1 AND notification_type = $2 AND channel = $3
+    "#,
+  )
+  .bind(user_id)
+  .bind(notification_type)
+  .bind(channel)
+  .fetch_optional(&mut *conn)
+  .await?
+  .unwrap_or(true);
+
+  Ok(enabled)
+}
+
+#[async_trait]
+impl NotificationPreferenceRepository for SqlxNotificationPreferenceRepository {
+  async fn get_preferences(
+    &self,
+    user_id: i64,
+  ) -> AppResult<NotificationPreferences> {
+    let quiet_hours = sqlx::query_as::<_, QuietHours>(
+      r#"
+      SELECT enabled, to_char(start_time, 'HH24:MI') AS start_time,
+        to_char(end_time, 'HH24:MI') AS end_time, timezone
+      FROM users.notification_quiet_hours
+      WHERE user_id = $1
+      "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&self.db)
+    .await?
+    .unwrap_or_default();
+
+    let saved = sqlx::query_as::<_, NotificationPreference>(
+      r#"
+      SELECT notification_type, channel, enabled FROM users.notification_preferences
+      WHERE user_id = $1
+      "#,
+    )
+    .bind(user_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    let mut preferences = vec![];
+    for notification_type in NOTIFICATION_TYPES {
+      for channel in NOTIFICATION_CHANNELS {
+        let enabled = saved
+          .iter()
+          .find(|item| item.notification_type == notification_type && item.channel == channel)
+          .map(|item| item.enabled)
+          .unwrap_or(true);
+
+        preferences.push(NotificationPreference {
+          notification_type: notification_type.to_string(),
+          channel: channel.to_string(),
+          enabled,
+        });
+      }
+    }
+
+    Ok(NotificationPreferences { quiet_hours, preferences })
+  }
+
+  async fn is_valid_timezone(
+    &self,
+    timezone: &str,
+  ) -> AppResult<bool> {
+    let exists = sqlx::query_scalar::<_, bool>(
+      "SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1)",
+    )
+    .bind(timezone)
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(exists)
+  }
+
+  async fn update_preferences(
+    &self,
+    user_id: i64,
+    payload: UpdateNotificationPreferencesRequest,
+  ) -> AppResult<NotificationPreferences> {
+    let mut tx = self.db.begin().await?;
+
+    if let Some(quiet_hours) = payload.quiet_hours {
+      sqlx::query(
+        r#"
+        INSERT INTO users.notification_quiet_hours (user_id, enabled, start_time, end_time, timezone)
+        VALUES ($1, $2, $3::time, $4::time, $5)
+        ON CONFLICT (user_id)
+        DO UPDATE SET enabled = EXCLUDED.enabled, start_time = EXCLUDED.start_time,
+          end_time = EXCLUDED.end_time, timezone = EXCLUDED.timezone
+        "#,
+      )
+      .bind(user_id)
+      .bind(quiet_hours.enabled)
+      .bind(quiet_hours.start_time)
+      .bind(quiet_hours.end_time)
+      .bind(quiet_hours.timezone)
+      .execute(&mut *tx)
+      .await
+      .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    }
+
+    for preference in payload.preferences {
+      sqlx::query(
+        r#"
+        INSERT INTO users.notification_preferences (user_id, notification_type, channel, enabled)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, notification_type, channel)
+        DO UPDATE SET enabled = EXCLUDED.enabled
+        "#,
+      )
+      .bind(user_id)
+      .bind(preference.notification_type)
+      .bind(preference.channel)
+      .bind(preference.enabled)
+      .execute(&mut *tx)
+      .await
+      .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    }
+
+    tx.commit().await?;
+
+    self.get_preferences(user_id).await
+  }
+}
@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use core_app::{AppResult, errors::AppError};
+use domain::{
+  entities::{
+    notification::CreateNotification,
+    notification_template::{
+      DEFAULT_LOCALE, NotificationTemplate, NotificationTemplateFilter,
+      UpdateNotificationTemplateRequest,
+    },
+  },
+  repositories::notification_template_repository::NotificationTemplateRepository,
+  services::notification_template::render_template,
+};
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+
+pub struct SqlxNotificationTemplateRepository {
+  pub db: PgPool,
+}
+
+// Ngôn ngữ nhận thông báo của người dùng, thông báo gửi theo nhóm dùng tiếng Việt
+async fn get_user_locale(
+  conn: &mut PgConnection,
+  user_id: Option<i64>,
+) -> AppResult<String> {
+  let Some(user_id) = user_id else {
+    return Ok(DEFAULT_LOCALE.to_string());
+  };
+
+  let locale = sqlx::query_scalar::<_, String>(
+    "SELECT preferred_language FROM users.tbl_users WHERE pk_user_id = $1",
+  )
+  .bind(user_id)
+  .fetch_optional(&mut *conn)
+  .await?
+  .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+
+  Ok(locale)
+}
+
+/// Dựng tiêu đề/nội dung của thông báo theo mẫu bằng ngôn ngữ của người nhận,
+/// mẫu chưa có bản dịch thì dùng bản tiếng Việt.
+pub async fn apply_template(
+  conn: &mut PgConnection,
+  mut notification: CreateNotification,
+) -> AppResult<CreateNotification> {
+  let Some(message) = notification.template.as_ref() else {
+    return Ok(notification);
+  };
+
+  let locale = get_user_locale(conn, notification.user_id).await?;
+  let template = sqlx::query_as::<_, NotificationTemplate>(
+    r#"
+    SELECT * FROM users.notification_templates
+    WHERE event_type = $1 AND locale IN ($2, $3)
+    ORDER BY (locale = $2) DESC
+    LIMIT 1
+    "#,
+  )
+  .bind(&message.event_type)
+  .bind(locale)
+  .bind(DEFAULT_LOCALE)
+  .fetch_optional(&mut *conn)
+  .await?
+  .ok_or_else(|| {
+    AppError::BadRequest(format!("Notification template {} not found", message.event_type))
+  })?;
+
+  notification.title = render_template(&template.title, &message.variables);
+  notification.body = render_template(&template.body, &message.variables);
+
+  Ok(notification)
+}
+
+/// Toàn bộ bản dịch của một loại sự kiện, dùng khi gửi cho nhiều người nhận khác ngôn ngữ
+pub async fn get_templates_by_locale(
+  db: &PgPool,
+  event_type: &str,
+) -> AppResult<HashMap<String, NotificationTemplate>> {
+  let templates = sqlx::query_as::<_, NotificationTemplate>(
+    "SELECT * FROM users.notification_templates WHERE event_type = $1",
+  )
+  .bind(event_type)
+  .fetch_all(db)
+  .await?;
+
+  Ok(templates.into_iter().map(|template| (template.locale.clone(), template)).collect())
+}
+
+#[async_trait]
+impl NotificationTemplateRepository for SqlxNotificationTemplateRepository {
+  async fn list(
+    &self,
+    filter: NotificationTemplateFilter,
+  ) -> AppResult<Vec<NotificationTemplate>> {
+    let templates = sqlx::query_as::<_, NotificationTemplate>(
+      r#"
+      SELECT * FROM users.notification_templates
+      WHERE ($1::text IS NULL OR event_type = $1)
+        AND ($2::text IS NULL OR locale = $2)
+      ORDER BY event_type, locale
+      "#,
+    )
+    .bind(filter.event_type)
+    .bind(filter.locale)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(templates)
+  }
+
+  async fn get_by_id(
+    &self,
+    id: i64,
+  ) -> AppResult<NotificationTemplate> {
+    let template = sqlx::query_as::<_, NotificationTemplate>(
+      "SELECT * FROM users.notification_templates WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(template)
+  }
+
+  async fn update(
+    &self,
+    id: i64,
+    payload: UpdateNotificationTemplateRequest,
+  ) -> AppResult<NotificationTemplate> {
+    let template = sqlx::query_as::<_, NotificationTemplate>(
+      r#"
+      UPDATE users.notification_templates
+      SET title = $2, body = $3
+      WHERE id = $1
+      RETURNING *
+      "#,
+    )
+    .bind(id)
+    .bind(payload.title)
+    .bind(payload.body)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(template)
+  }
+}
@@ -10,70 +10,93 @@ pub struct SqlxNotiTokenRepository {
   pub db: PgPool,
 }
 
+/// Xoá các token FCM báo UNREGISTERED/INVALID_ARGUMENT để không gửi lại ở những lần sau
+pub async fn delete_invalid_tokens(
+  db: &PgPool,
+  tokens: &[String],
+) -> AppResult<u64> {
+  if tokens.is_empty() {
+    return Ok(0);
+  }
+
+  let result = sqlx::query(r#"DELETE FROM "users"."notification_tokens" WHERE token = ANY($1)"#)
+    .bind(tokens)
+    .execute(db)
+    .await?;
+
+  if result.rows_affected() > 0 {
+    tracing::info!("Removed {} invalid notification tokens", result.rows_affected());
+  }
+
+  Ok(result.rows_affected())
+}
+
+/// Xoá các token không được app đăng ký lại trong `days` ngày
+pub async fn expire_stale_tokens(
+  db: &PgPool,
+  days: i64,
+) -> AppResult<u64> {
+  let result = sqlx::query(
+    r#"DELETE FROM "users"."notification_tokens" WHERE last_seen_at < NOW() - make_interval(days => $1)"#,
+  )
+  .bind(days.max(1) as i32)
+  .execute(db)
+  .await?;
+
+  Ok(result.rows_affected())
+}
+
 #[async_trait]
 impl NotificationTokenRepository for SqlxNotiTokenRepository {
   async fn create(
     &self,
     payload: PayloadNotificationToken,
   ) -> AppResult<NotificationToken> {
-    // Check if token already exists
-    let existing_token: Option<NotificationToken> = sqlx::query_as::<_, NotificationToken>(
+    let mut tx = self.db.begin().await?;
+
+    // App đăng ký lại token cũ thì chỉ cập nhật last_seen_at
+    let token = sqlx::query_as::<_, NotificationToken>(
       r#"
-      SELECT *
-      FROM "users"."notification_tokens"
-      WHERE user_id = $1 AND platform = $2 AND token = $3
+      INSERT INTO "users"."notification_tokens" (user_id, platform, token)
+      VALUES ($1, $2, $3)
+      ON CONFLICT (user_id, token)
+      DO UPDATE SET platform = EXCLUDED.platform, last_seen_at = NOW()
+      RETURNING *
       "#,
     )
-    .bind(&payload.user_id)
-    .bind(&payload.platform)
-    .bind(&payload.token)
-    .fetch_optional(&self.db)
+    .bind(payload.user_id)
+    .bind(payload.platform)
+    .bind(payload.token)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|err| AppError::BadRequest(err.to_string()))?;
 
-    // Return existing token if found
-    if let Some(token) = existing_token {
-      return Ok(token);
-    }
-
-    let record = sqlx::query_as::<_, NotificationToken>(
+    // Ghi lại lịch sử thiết bị để kiểm tra gian lận giới thiệu sau khi token đổi chủ
+    sqlx::query(
       r#"
-      SELECT *
-      FROM "users"."notification_tokens"
-      WHERE platform = $1 AND token = $2
+      INSERT INTO "users"."notification_token_history" (token, user_id)
+      VALUES ($1, $2)
+      ON CONFLICT (token, user_id) DO UPDATE SET last_seen_at = NOW()
       "#,
     )
-    .bind(&payload.platform)
-    .bind(&payload.token)
-    .fetch_optional(&self.db)
+    .bind(&token.token)
+    .bind(token.user_id)
+    .execute(&mut *tx)
     .await?;
 
-    // Create new token if not exists
-    let token = sqlx::query_as::<_, NotificationToken>(
+    // Thiết bị đã đăng nhập tài khoản khác thì bỏ bản đăng ký cũ
+    sqlx::query(
       r#"
-      INSERT INTO "users"."notification_tokens" (user_id, platform, token)
-      VALUES ($1, $2, $3)
-      RETURNING *
+      DELETE FROM "users"."notification_tokens"
+      WHERE token = $1 AND id <> $2
       "#,
     )
-    .bind(payload.user_id)
-    .bind(payload.platform)
-    .bind(payload.token)
-    .fetch_one(&self.db)
-    .await
-    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    .bind(&token.token)
+    .bind(token.id)
+    .execute(&mut *tx)
+    .await?;
 
-    if let Some(token_old) = record {
-      let _ = sqlx::query(
-        r#"
-        DELETE FROM "users"."notification_tokens"
-        WHERE id = $4
-        "#,
-      )
-      .bind(token_old.id)
-      .execute(&self.db)
-      .await?;
-    }
+    tx.commit().await?;
 
     Ok(token)
   }
@@ -87,7 +110,7 @@ impl NotificationTokenRepository for SqlxNotiTokenRepository {
     let token = sqlx::query_as::<_, NotificationToken>(
       r#"
         UPDATE "users"."notification_tokens"
-        SET user_id = $1, platform = $2, token = $3
+        SET user_id = $1, platform = $2, token = $3, last_seen_at = NOW()
         WHERE id = $4
         RETURNING *
         "#,
@@ -96,9 +119,10 @@ impl NotificationTokenRepository for SqlxNotiTokenRepository {
     .bind(payload.platform)
     .bind(payload.token)
     .bind(id)
-    .fetch_one(&self.db)
+    .fetch_optional(&self.db)
     .await
-    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    .map_err(|err| AppError::BadRequest(err.to_string()))?
+    .ok_or(AppError::NotFound)?;
     Ok(token)
   }
 
@@ -109,7 +133,7 @@ impl NotificationTokenRepository for SqlxNotiTokenRepository {
     let token = sqlx::query(
       r#"
         DELETE FROM "users"."notification_tokens"
-        WHERE id = $4
+        WHERE id = $1
         "#,
     )
     .bind(id)
@@ -123,6 +147,13 @@ impl NotificationTokenRepository for SqlxNotiTokenRepository {
     Ok(true)
   }
 
+  async fn delete_invalid_tokens(
+    &self,
+    tokens: Vec<String>,
+  ) -> AppResult<u64> {
+    delete_invalid_tokens(&self.db, &tokens).await
+  }
+
   async fn get_token_by_user_id(
     &self,
     user_id: i64,
@@ -0,0 +1,529 @@
+use crate::repositories::{
+  appointment::pay_appointment, deposit::notify_deposit, email::enqueue_deposit_email,
+  notification_outbox::enqueue_notification, webhook::enqueue_webhook_event,
+};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use core_app::{AppResult, errors::AppError};
+use domain::{
+  entities::{
+    appointment::{Appointment, PaymentAppointmentRequest, PaymentTender},
+    deposit::Deposit,
+    notification::CreateNotification,
+    notification_template::NotificationMessage,
+    payment::{
+      BankTransaction, BankTransactionFilter, BankTransferWebhook, CreatePaymentIntentRequest,
+      PaymentIntent,
+    },
+  },
+  repositories::payment_repository::PaymentRepository,
+  services::payment::transfer_mismatch_note,
+};
+use sqlx::{Connection, PgConnection, PgPool};
+use utils::{format_number::format_number, vietqr::normalize_transfer_description};
+
+// Thời gian hiệu lực của mã QR, chuyển khoản sau thời điểm này sẽ được đưa vào danh sách cần kiểm tra
+const INTENT_TTL_MINUTES: i64 = 30;
+
+pub struct SqlxPaymentRepository {
+  pub db: PgPool,
+}
+
+// Báo cho lễ tân giao dịch chuyển khoản không khớp, ghi cùng transaction với kết quả đối soát
+async fn notify_mismatch(
+  conn: &mut PgConnection,
+  transaction: &BankTransaction,
+) -> AppResult<()> {
+  let message = NotificationMessage::new(
+    "PAYMENT_MISMATCH",
+    serde_json::json!({
+      "transaction_id": transaction.provider_transaction_id,
+      "amount": format_number(transaction.amount),
+      "note": transaction.match_note.clone().unwrap_or_default()
+    }),
+  );
+  let data = Some(serde_json::json!({
+    "type": "PAYMENT",
+    "bank_transaction_id": transaction.id,
+    "payment_intent_id": transaction.payment_intent_id,
+    "amount": transaction.amount,
+    "match_status": transaction.match_status
+  }));
+
+  let notification = CreateNotification {
+    user_id: None,
+    title: String::new(),
+    body: String::new(),
+    receiver: "ALLRECEPTIONIST".to_string(),
+    notification_type: "PAYMENT".to_string(),
+    data,
+    appointment_id: None,
+    template: Some(message),
+  };
+
+  enqueue_notification(conn, notification, true).await?;
+  Ok(())
+}
+
+async fn set_match_result(
+  conn: &mut PgConnection,
+  transaction_id: i64,
+  intent_id: Option<i64>,
+  match_status: &str,
+  note: Option<String>,
+) -> AppResult<BankTransaction> {
+  let transaction = sqlx::query_as::<_, BankTransaction>(
+    r#"
+      UPDATE users.bank_transactions
+      SET payment_intent_id = $1, match_status = $2, match_note = $3
+      WHERE id = $4
+      RETURNING *
+    "#,
+  )
+  .bind(intent_id)
+  .bind(match_status)
+  .bind(note)
+  .bind(transaction_id)
+  .fetch_one(&mut *conn)
+  .await?;
+
+  if match_status == "MISMATCH" {
+    if let Some(intent_id) = intent_id {
+      sqlx::query(
+        r#"
+          UPDATE users.payment_intents
+          SET status = 'MISMATCH'
+          WHERE id = $1 AND status = 'PENDING'
+        "#,
+      )
+      .bind(intent_id)
+      .execute(&mut *conn)
+      .await?;
+    }
+  }
+
+  Ok(transaction)
+}
+
+async fn complete_intent(
+  conn: &mut PgConnection,
+  intent_id: i64,
+) -> AppResult<()> {
+  sqlx::query(
+    r#"
+      UPDATE users.payment_intents
+      SET status = 'COMPLETED', paid_at = CURRENT_TIMESTAMP
+      WHERE id = $1
+    "#,
+  )
+  .bind(intent_id)
+  .execute(&mut *conn)
+  .await?;
+
+  Ok(())
+}
+
+// Giao dịch tự động không có người thao tác: ghi nhận cho lễ tân phụ trách lịch hẹn, sau đó là
+// nhân viên đã tạo mã QR, cuối cùng là quản trị viên. Khách hàng tự tạo mã QR không được dùng.
+async fn get_payment_actor(
+  conn: &mut PgConnection,
+  appointment_id: i64,
+  intent_created_by: i64,
+) -> AppResult<i64> {
+  let actor_id = sqlx::query_scalar::<_, i64>(
+    r#"
+      SELECT u.pk_user_id
+      FROM users.tbl_users u
+      LEFT JOIN users.appointments a ON a.id = $1
+      WHERE u.is_active AND u.role IN ('RECEPTIONIST', 'ADMIN')
+      ORDER BY u.pk_user_id = a.receptionist_id DESC NULLS LAST,
+               u.pk_user_id = $2 DESC,
+               u.role = 'ADMIN' DESC,
+               u.pk_user_id
+      LIMIT 1
+    "#,
+  )
+  .bind(appointment_id)
+  .bind(intent_created_by)
+  .fetch_optional(&mut *conn)
+  .await?
+  .ok_or(AppError::BadRequest("Không tìm thấy nhân viên ghi nhận thanh toán".to_string()))?;
+
+  Ok(actor_id)
+}
+
+async fn pay_intent_appointment(
+  conn: &mut PgConnection,
+  intent: &PaymentIntent,
+  transaction: &BankTransaction,
+) -> AppResult<()> {
+  let appointment_id = intent.appointment_id.ok_or(AppError::NotFound)?;
+  let actor_id = get_payment_actor(&mut *conn, appointment_id, intent.created_by).await?;
+
+  let full_name = sqlx::query_scalar::<_, Option<String>>(
+    r#"SELECT full_name FROM users.tbl_users WHERE pk_user_id = $1"#,
+  )
+  .bind(intent.user_id)
+  .fetch_one(&mut *conn)
+  .await?;
+
+  let payment = PaymentAppointmentRequest {
+    status: None,
+    user_balance: 0,
+    full_name: full_name.unwrap_or_default(),
+    payment_method: Some("BANK_TRANSFER".to_string()),
+    tenders: Some(vec![PaymentTender {
+      tender_type: "BANK_TRANSFER".to_string(),
+      amount: transaction.amount,
+      reference: Some(transaction.provider_transaction_id.clone()),
+    }]),
+    tip_amount: None,
+    tip_percent: None,
+    tip_payment_method: None,
+  };
+
+  pay_appointment(&mut *conn, actor_id, appointment_id, &payment).await
+}
+
+#[async_trait]
+impl PaymentRepository for SqlxPaymentRepository {
+  async fn create_intent(
+    &self,
+    request: CreatePaymentIntentRequest,
+    memo: String,
+    created_by: i64,
+  ) -> AppResult<PaymentIntent> {
+    let mut tx = self.db.begin().await?;
+    let expires_at = Utc::now() + Duration::minutes(INTENT_TTL_MINUTES);
+
+    let (user_id, amount, appointment_id, deposit_id) = if request.intent_type == "TOP_UP" {
+      let user_id = request.user_id.unwrap_or_default();
+      let amount = request.amount.unwrap_or_default();
+
+      // Tạo sẵn giao dịch nạp tiền PENDING, webhook khớp thì hoàn tất giao dịch này
+      let deposit = sqlx::query_as::<_, Deposit>(
+        r#"
+          INSERT INTO users.deposits (
+            user_id, amount, status, payment_method, notes, created_by, deposit_type
+          ) VALUES ($1, $2, 'PENDING', 'BANK_TRANSFER', $3, $4, 'DEPOSIT')
+          RETURNING *
+        "#,
+      )
+      .bind(user_id)
+      .bind(amount)
+      .bind(format!("Chuyển khoản VietQR {}", memo))
+      .bind(created_by)
+      .fetch_one(&mut *tx)
+      .await
+      .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+      (user_id, amount, None, Some(deposit.id))
+    } else {
+      let appointment_id = request.appointment_id.unwrap_or_default();
+      let appointment = sqlx::query_as::<_, Appointment>(
+        r#"SELECT * FROM users.appointments WHERE id = $1 FOR UPDATE"#,
+      )
+      .bind(appointment_id)
+      .fetch_optional(&mut *tx)
+      .await?
+      .ok_or(AppError::NotFound)?;
+
+      // Khách hàng chỉ tạo được yêu cầu cho lịch hẹn của mình
+      if request.user_id.is_some_and(|user_id| user_id != appointment.user_id) {
+        return Err(AppError::NotFound);
+      }
+
+      if appointment.status != "COMPLETED" {
+        return Err(AppError::BadRequest(
+          "Lịch hẹn cần được hoàn thành trước khi thanh toán".to_string(),
+        ));
+      }
+
+      if appointment.total_price <= 0 {
+        return Err(AppError::BadRequest("Lịch hẹn không có số tiền cần thanh toán".to_string()));
+      }
+
+      // Chỉ giữ một mã QR còn hiệu lực cho mỗi lịch hẹn
+      sqlx::query(
+        r#"
+          UPDATE users.payment_intents
+          SET status = 'CANCELLED'
+          WHERE appointment_id = $1 AND status = 'PENDING'
+        "#,
+      )
+      .bind(appointment_id)
+      .execute(&mut *tx)
+      .await?;
+
+      (appointment.user_id, appointment.total_price, Some(appointment_id), None)
+    };
+
+    let intent = sqlx::query_as::<_, PaymentIntent>(
+      r#"
+        INSERT INTO users.payment_intents (
+          user_id, intent_type, appointment_id, deposit_id, amount, memo, expires_at, created_by
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+      "#,
+    )
+    .bind(user_id)
+    .bind(&request.intent_type)
+    .bind(appointment_id)
+    .bind(deposit_id)
+    .bind(amount)
+    .bind(&memo)
+    .bind(expires_at)
+    .bind(created_by)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    tx.commit().await?;
+
+    Ok(intent)
+  }
+
+  async fn get_intent(
+    &self,
+    id: i64,
+  ) -> AppResult<Option<PaymentIntent>> {
+    let intent =
+      sqlx::query_as::<_, PaymentIntent>(r#"SELECT * FROM users.payment_intents WHERE id = $1"#)
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?;
+
+    Ok(intent)
+  }
+
+  async fn record_bank_transaction(
+    &self,
+    payload: BankTransferWebhook,
+    raw_payload: serde_json::Value,
+  ) -> AppResult<BankTransaction> {
+    let provider = payload.provider.unwrap_or_else(|| "DEFAULT".to_string()).to_uppercase();
+
+    let inserted = sqlx::query_as::<_, BankTransaction>(
+      r#"
+        INSERT INTO users.bank_transactions (
+          provider, provider_transaction_id, amount, description, account_number,
+          transaction_time, raw_payload
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (provider, provider_transaction_id) DO NOTHING
+        RETURNING *
+      "#,
+    )
+    .bind(&provider)
+    .bind(&payload.transaction_id)
+    .bind(payload.amount)
+    .bind(&payload.description)
+    .bind(payload.account_number)
+    .bind(payload.transaction_time)
+    .bind(raw_payload)
+    .fetch_optional(&self.db)
+    .await?;
+
+    if let Some(transaction) = inserted {
+      return Ok(transaction);
+    }
+
+    let existing = sqlx::query_as::<_, BankTransaction>(
+      r#"
+        SELECT * FROM users.bank_transactions
+        WHERE provider = $1 AND provider_transaction_id = $2
+      "#,
+    )
+    .bind(&provider)
+    .bind(&payload.transaction_id)
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(existing)
+  }
+
+  async fn match_bank_transaction(
+    &self,
+    transaction_id: i64,
+  ) -> AppResult<BankTransaction> {
+    let mut tx = self.db.begin().await?;
+
+    // Khoá giao dịch để hai lần webhook gửi lại đồng thời không cùng đối soát
+    let transaction = sqlx::query_as::<_, BankTransaction>(
+      r#"SELECT * FROM users.bank_transactions WHERE id = $1 FOR UPDATE"#,
+    )
+    .bind(transaction_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if transaction.match_status != "PENDING" {
+      tx.commit().await?;
+      return Ok(transaction);
+    }
+
+    let description = normalize_transfer_description(&transaction.description);
+
+    let intent = sqlx::query_as::<_, PaymentIntent>(
+      r#"
+        SELECT * FROM users.payment_intents
+        WHERE status = 'PENDING' AND $1 LIKE '%' || memo || '%'
+        ORDER BY created_at DESC
+        LIMIT 1
+        FOR UPDATE
+      "#,
+    )
+    .bind(&description)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(intent) = intent else {
+      let transaction = set_match_result(
+        &mut tx,
+        transaction.id,
+        None,
+        "UNMATCHED",
+        Some("Không tìm thấy yêu cầu thanh toán theo nội dung chuyển khoản".to_string()),
+      )
+      .await?;
+      notify_mismatch(&mut tx, &transaction).await?;
+      tx.commit().await?;
+      return Ok(transaction);
+    };
+
+    if let Some(note) = transfer_mismatch_note(transaction.amount, &intent, Utc::now()) {
+      let transaction =
+        set_match_result(&mut tx, transaction.id, Some(intent.id), "MISMATCH", Some(note)).await?;
+      notify_mismatch(&mut tx, &transaction).await?;
+      tx.commit().await?;
+      return Ok(transaction);
+    }
+
+    if intent.intent_type == "APPOINTMENT" {
+      // Thanh toán trong savepoint: lỗi thì chỉ huỷ phần thanh toán và ghi nhận MISMATCH,
+      // thành công thì lịch hẹn, yêu cầu thanh toán và giao dịch được lưu cùng lúc
+      let mut savepoint = tx.begin().await?;
+      if let Err(err) = pay_intent_appointment(&mut savepoint, &intent, &transaction).await {
+        savepoint.rollback().await?;
+        tracing::error!("Failed to pay appointment by bank transfer {}: {:?}", transaction.id, err);
+
+        let note = match intent.appointment_id {
+          Some(appointment_id) => {
+            format!("Không thể thanh toán lịch hẹn #{}: {}", appointment_id, err)
+          },
+          None => "Lịch hẹn không còn tồn tại".to_string(),
+        };
+        let transaction =
+          set_match_result(&mut tx, transaction.id, Some(intent.id), "MISMATCH", Some(note))
+            .await?;
+        notify_mismatch(&mut tx, &transaction).await?;
+        tx.commit().await?;
+        return Ok(transaction);
+      }
+      savepoint.commit().await?;
+
+      complete_intent(&mut tx, intent.id).await?;
+      let transaction =
+        set_match_result(&mut tx, transaction.id, Some(intent.id), "MATCHED", None).await?;
+      tx.commit().await?;
+
+      return Ok(transaction);
+    }
+
+    // TOP_UP: hoàn tất giao dịch nạp tiền và cộng số dư trong cùng transaction
+    let deposit = sqlx::query_as::<_, Deposit>(
+      r#"
+        UPDATE users.deposits
+        SET status = 'COMPLETED',
+            transaction_id = $1,
+            reviewed_at = CURRENT_TIMESTAMP
+        WHERE id = $2 AND status = 'PENDING'
+        RETURNING *
+      "#,
+    )
+    .bind(&transaction.provider_transaction_id)
+    .bind(intent.deposit_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(deposit) = deposit else {
+      let transaction = set_match_result(
+        &mut tx,
+        transaction.id,
+        Some(intent.id),
+        "MISMATCH",
+        Some("Giao dịch nạp tiền không còn ở trạng thái chờ".to_string()),
+      )
+      .await?;
+      notify_mismatch(&mut tx, &transaction).await?;
+      tx.commit().await?;
+      return Ok(transaction);
+    };
+
+    sqlx::query(
+      r#"
+        UPDATE users.tbl_users
+        SET balance = balance + $1
+        WHERE pk_user_id = $2
+      "#,
+    )
+    .bind(deposit.amount)
+    .bind(deposit.user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    complete_intent(&mut tx, intent.id).await?;
+    let transaction =
+      set_match_result(&mut tx, transaction.id, Some(intent.id), "MATCHED", None).await?;
+
+    notify_deposit(
+      &mut tx,
+      deposit.user_id,
+      "CUSTOMER",
+      NotificationMessage::new(
+        "DEPOSIT_COMPLETED",
+        serde_json::json!({ "amount": format_number(deposit.amount) }),
+      ),
+      &deposit,
+    )
+    .await?;
+    enqueue_webhook_event(&mut *tx, "deposit.completed", serde_json::json!(deposit)).await?;
+    enqueue_deposit_email(&mut tx, &deposit).await?;
+
+    tx.commit().await?;
+
+    Ok(transaction)
+  }
+
+  async fn flag_mismatch(
+    &self,
+    transaction_id: i64,
+    intent_id: Option<i64>,
+    note: String,
+  ) -> AppResult<BankTransaction> {
+    let mut tx = self.db.begin().await?;
+    let transaction =
+      set_match_result(&mut tx, transaction_id, intent_id, "MISMATCH", Some(note)).await?;
+    notify_mismatch(&mut tx, &transaction).await?;
+    tx.commit().await?;
+
+    Ok(transaction)
+  }
+
+  async fn get_bank_transactions(
+    &self,
+    filter: BankTransactionFilter,
+  ) -> AppResult<Vec<BankTransaction>> {
+    let transactions = sqlx::query_as::<_, BankTransaction>(
+      r#"
+        SELECT * FROM users.bank_transactions
+        WHERE ($1::text IS NULL OR match_status = $1)
+        ORDER BY created_at DESC
+      "#,
+    )
+    .bind(filter.match_status)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(transactions)
+  }
+}
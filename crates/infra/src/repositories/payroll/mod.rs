@@ -0,0 +1,421 @@
+use async_trait::async_trait;
+use core_app::{AppResult, errors::AppError};
+use domain::{
+  entities::payroll::{
+    Commission, CommissionRule, CreateCommissionRuleRequest, CreatePayrollAdjustmentRequest,
+    PayrollItem, PayrollPeriod, PayrollReport, TechnicianPayroll, UpdateCommissionRuleRequest,
+  },
+  repositories::payroll_repository::PayrollRepository,
+};
+use sqlx::{PgConnection, PgPool};
+
+pub struct SqlxPayrollRepository {
+  pub db: PgPool,
+}
+
+// Kỳ lương còn mở gần nhất tính từ `period` (mặc định là tháng hiện tại theo giờ Việt Nam)
+pub async fn get_open_period<'e>(
+  executor: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+  period: Option<String>,
+) -> AppResult<String> {
+  let period = sqlx::query_scalar::<_, String>(
+    r#"
+    SELECT to_char(m, 'YYYY-MM')
+    FROM generate_series(
+      to_date(COALESCE($1, to_char(NOW() AT TIME ZONE 'Asia/Ho_Chi_Minh', 'YYYY-MM')), 'YYYY-MM')::timestamp,
+      to_date(COALESCE($1, to_char(NOW() AT TIME ZONE 'Asia/Ho_Chi_Minh', 'YYYY-MM')), 'YYYY-MM')::timestamp + INTERVAL '10 years',
+      INTERVAL '1 month'
+    ) AS m
+    WHERE NOT EXISTS (
+      SELECT 1 FROM users.payroll_periods p
+      WHERE p.period = to_char(m, 'YYYY-MM') AND p.status = 'LOCKED'
+    )
+    ORDER BY m
+    LIMIT 1
+    "#,
+  )
+  .bind(period)
+  .fetch_one(executor)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+  Ok(period)
+}
+
+// Sinh hoa hồng cho từng dịch vụ của lịch hẹn khi thanh toán.
+// Quy tắc được chọn theo độ cụ thể: dịch vụ + cấp bậc > dịch vụ > cấp bậc > mặc định.
+pub async fn create_commissions(
+  conn: &mut PgConnection,
+  appointment_id: i64,
+  created_by: i64,
+) -> AppResult<()> {
+  let period = get_open_period(&mut *conn, None).await?;
+
+  sqlx::query(
+    r#"
+    INSERT INTO users.commissions (
+      technician_id, appointment_id, appointment_service_id, rule_id, period,
+      entry_type, service_name, base_amount, commission_amount, created_by
+    )
+    SELECT
+      aps.technician_id,
+      aps.appointment_id,
+      aps.id,
+      r.id,
+      $2,
+      'COMMISSION',
+      aps.service_name,
+      GREATEST(aps.unit_price * aps.quantity - aps.discount, 0),
+      CASE
+        WHEN r.commission_type = 'PERCENTAGE'
+          THEN ROUND(GREATEST(aps.unit_price * aps.quantity - aps.discount, 0) * r.value / 100.0)::BIGINT
+        WHEN r.commission_type = 'FIXED' THEN r.value * aps.quantity
+        ELSE 0
+      END,
+      $3
+    FROM users.appointments_services aps
+    JOIN users.tbl_users t ON t.pk_user_id = aps.technician_id
+    LEFT JOIN LATERAL (
+      SELECT cr.*
+      FROM users.commission_rules cr
+      WHERE cr.is_active = true
+        AND (cr.service_id IS NULL OR cr.service_id = aps.service_id)
+        AND (cr.technician_level IS NULL OR cr.technician_level = t.technician_level)
+      ORDER BY (cr.service_id IS NOT NULL) DESC, (cr.technician_level IS NOT NULL) DESC, cr.id DESC
+      LIMIT 1
+    ) r ON true
+    WHERE aps.appointment_id = $1 AND aps.status != 'CANCELLED'
+    ON CONFLICT (appointment_service_id) WHERE entry_type = 'COMMISSION' DO NOTHING
+    "#,
+  )
+  .bind(appointment_id)
+  .bind(&period)
+  .bind(created_by)
+  .execute(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  // Tiền tip của mỗi kỹ thuật viên ghi vào một dòng hoa hồng của họ (ưu tiên dòng có quy tắc tính tip),
+  // cộng vào hoa hồng nếu quy tắc áp dụng có tính tip (không có quy tắc thì mặc định tính)
+  sqlx::query(
+    r#"
+    UPDATE users.commissions c
+    SET tip_amount = t.amount,
+        commission_amount = c.commission_amount + CASE
+          WHEN COALESCE((SELECT cr.include_tips FROM users.commission_rules cr WHERE cr.id = c.rule_id), true)
+            THEN t.amount
+          ELSE 0
+        END
+    FROM (
+      SELECT technician_id, SUM(amount)::BIGINT AS amount
+      FROM users.appointment_tips
+      WHERE appointment_id = $1
+      GROUP BY technician_id
+    ) t
+    WHERE c.technician_id = t.technician_id
+      AND c.id = (
+        SELECT c2.id
+        FROM users.commissions c2
+        LEFT JOIN users.commission_rules cr ON cr.id = c2.rule_id
+        WHERE c2.appointment_id = $1 AND c2.technician_id = t.technician_id
+          AND c2.entry_type = 'COMMISSION'
+        ORDER BY COALESCE(cr.include_tips, true) DESC, c2.id
+        LIMIT 1
+      )
+      AND c.tip_amount = 0
+    "#,
+  )
+  .bind(appointment_id)
+  .execute(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  // Kỹ thuật viên nhận tip nhưng không có dòng dịch vụ nào thì ghi riêng một dòng tip
+  sqlx::query(
+    r#"
+    INSERT INTO users.commissions (
+      technician_id, appointment_id, period, entry_type, tip_amount, commission_amount, notes, created_by
+    )
+    SELECT t.technician_id, $1, $2, 'COMMISSION', t.amount, t.amount, 'Tiền tip', $3
+    FROM (
+      SELECT technician_id, SUM(amount)::BIGINT AS amount
+      FROM users.appointment_tips
+      WHERE appointment_id = $1
+      GROUP BY technician_id
+    ) t
+    WHERE NOT EXISTS (
+      SELECT 1 FROM users.commissions c
+      WHERE c.appointment_id = $1 AND c.technician_id = t.technician_id AND c.entry_type = 'COMMISSION'
+    )
+    "#,
+  )
+  .bind(appointment_id)
+  .bind(&period)
+  .bind(created_by)
+  .execute(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(())
+}
+
+#[async_trait]
+impl PayrollRepository for SqlxPayrollRepository {
+  async fn get_commission_rules(&self) -> AppResult<Vec<CommissionRule>> {
+    let rules = sqlx::query_as::<_, CommissionRule>(
+      r#"
+      SELECT * FROM users.commission_rules
+      ORDER BY is_active DESC, service_id NULLS LAST, technician_level NULLS LAST, id DESC
+      "#,
+    )
+    .fetch_all(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    Ok(rules)
+  }
+
+  async fn create_commission_rule(
+    &self,
+    payload: CreateCommissionRuleRequest,
+    created_by: i64,
+  ) -> AppResult<CommissionRule> {
+    let rule = sqlx::query_as::<_, CommissionRule>(
+      r#"
+      INSERT INTO users.commission_rules (
+        service_id, technician_level, commission_type, value, include_tips, created_by
+      )
+      VALUES ($1, $2, $3, $4, $5, $6)
+      RETURNING *
+      "#,
+    )
+    .bind(payload.service_id)
+    .bind(payload.technician_level)
+    .bind(payload.commission_type)
+    .bind(payload.value)
+    .bind(payload.include_tips.unwrap_or(true))
+    .bind(created_by)
+    .fetch_one(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    Ok(rule)
+  }
+
+  async fn update_commission_rule(
+    &self,
+    id: i64,
+    payload: UpdateCommissionRuleRequest,
+  ) -> AppResult<CommissionRule> {
+    let rule = sqlx::query_as::<_, CommissionRule>(
+      r#"
+      UPDATE users.commission_rules
+      SET commission_type = COALESCE($1, commission_type),
+          value = COALESCE($2, value),
+          include_tips = COALESCE($3, include_tips),
+          is_active = COALESCE($4, is_active)
+      WHERE id = $5
+      RETURNING *
+      "#,
+    )
+    .bind(payload.commission_type)
+    .bind(payload.value)
+    .bind(payload.include_tips)
+    .bind(payload.is_active)
+    .bind(id)
+    .fetch_optional(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(rule)
+  }
+
+  async fn delete_commission_rule(
+    &self,
+    id: i64,
+  ) -> AppResult<()> {
+    let result = sqlx::query(
+      r#"
+      DELETE FROM users.commission_rules
+      WHERE id = $1
+      "#,
+    )
+    .bind(id)
+    .execute(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    if result.rows_affected() == 0 {
+      return Err(AppError::NotFound);
+    }
+
+    Ok(())
+  }
+
+  async fn get_payroll(
+    &self,
+    period: String,
+    technician_id: Option<i64>,
+  ) -> AppResult<PayrollReport> {
+    let status = sqlx::query_scalar::<_, String>(
+      r#"
+      SELECT status FROM users.payroll_periods
+      WHERE period = $1
+      "#,
+    )
+    .bind(&period)
+    .fetch_optional(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?
+    .unwrap_or_else(|| "OPEN".to_string());
+
+    let items = sqlx::query_as::<_, PayrollItem>(
+      r#"
+      SELECT
+        c.id,
+        c.technician_id,
+        u.full_name AS technician_name,
+        u.technician_level,
+        c.appointment_id,
+        c.appointment_service_id,
+        COALESCE(aps.start_time, a.start_time) AS appointment_start_time,
+        c.rule_id,
+        c.entry_type,
+        c.service_name,
+        c.base_amount,
+        c.tip_amount,
+        c.commission_amount,
+        c.notes,
+        c.created_at
+      FROM users.commissions c
+      JOIN users.tbl_users u ON u.pk_user_id = c.technician_id
+      LEFT JOIN users.appointments a ON a.id = c.appointment_id
+      LEFT JOIN users.appointments_services aps ON aps.id = c.appointment_service_id
+      WHERE c.period = $1 AND ($2::BIGINT IS NULL OR c.technician_id = $2)
+      ORDER BY u.full_name, c.technician_id, c.created_at, c.id
+      "#,
+    )
+    .bind(&period)
+    .bind(technician_id)
+    .fetch_all(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    let mut technicians: Vec<TechnicianPayroll> = Vec::new();
+
+    for item in items {
+      let index = match technicians.iter().position(|t| t.technician_id == item.technician_id) {
+        Some(index) => index,
+        None => {
+          technicians.push(TechnicianPayroll {
+            technician_id: item.technician_id,
+            technician_name: item.technician_name.clone(),
+            technician_level: item.technician_level.clone(),
+            total_base: 0,
+            total_tips: 0,
+            total_commission: 0,
+            total_adjustment: 0,
+            total_payout: 0,
+            items: Vec::new(),
+          });
+          technicians.len() - 1
+        },
+      };
+
+      let technician = &mut technicians[index];
+      if item.entry_type == "ADJUSTMENT" {
+        technician.total_adjustment += item.commission_amount;
+      } else {
+        technician.total_base += item.base_amount;
+        technician.total_tips += item.tip_amount;
+        technician.total_commission += item.commission_amount;
+      }
+      technician.total_payout += item.commission_amount;
+      technician.items.push(item);
+    }
+
+    let total_payout = technicians.iter().map(|t| t.total_payout).sum();
+
+    Ok(PayrollReport { period, status, total_payout, technicians })
+  }
+
+  async fn lock_payroll_period(
+    &self,
+    period: String,
+    locked_by: i64,
+  ) -> AppResult<PayrollPeriod> {
+    let payroll_period = sqlx::query_as::<_, PayrollPeriod>(
+      r#"
+      INSERT INTO users.payroll_periods (period, status, locked_by, locked_at)
+      VALUES ($1, 'LOCKED', $2, NOW())
+      ON CONFLICT (period) DO UPDATE
+      SET status = 'LOCKED', locked_by = EXCLUDED.locked_by, locked_at = NOW()
+      WHERE users.payroll_periods.status != 'LOCKED'
+      RETURNING *
+      "#,
+    )
+    .bind(&period)
+    .bind(locked_by)
+    .fetch_optional(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?
+    .ok_or_else(|| AppError::BadRequest(format!("Kỳ lương {} đã được khoá", period)))?;
+
+    Ok(payroll_period)
+  }
+
+  async fn create_adjustment(
+    &self,
+    payload: CreatePayrollAdjustmentRequest,
+    created_by: i64,
+  ) -> AppResult<Commission> {
+    let is_technician = sqlx::query_scalar::<_, bool>(
+      r#"
+      SELECT EXISTS (
+        SELECT 1 FROM users.tbl_users
+        WHERE pk_user_id = $1 AND role = 'TECHNICIAN'
+      )
+      "#,
+    )
+    .bind(payload.technician_id)
+    .fetch_one(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    if !is_technician {
+      return Err(AppError::BadRequest("Kỹ thuật viên không tồn tại".to_string()));
+    }
+
+    let period = get_open_period(&self.db, payload.period.clone()).await?;
+    if let Some(requested) = payload.period {
+      if requested != period {
+        return Err(AppError::BadRequest(format!(
+          "Kỳ lương {} đã được khoá, vui lòng ghi điều chỉnh vào kỳ {}",
+          requested, period
+        )));
+      }
+    }
+
+    let commission = sqlx::query_as::<_, Commission>(
+      r#"
+      INSERT INTO users.commissions (
+        technician_id, appointment_id, period, entry_type, commission_amount, notes, created_by
+      )
+      VALUES ($1, $2, $3, 'ADJUSTMENT', $4, $5, $6)
+      RETURNING *
+      "#,
+    )
+    .bind(payload.technician_id)
+    .bind(payload.appointment_id)
+    .bind(period)
+    .bind(payload.amount)
+    .bind(payload.notes)
+    .bind(created_by)
+    .fetch_one(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    Ok(commission)
+  }
+}
@@ -1,19 +1,14 @@
-use super::{
-  appointment::send_noti::send_firebase_notification, image::LocalImageService,
-  notification::SqlxNotificationRepository, notification_token::SqlxNotiTokenRepository,
-};
+use super::{image::LocalImageService, notification_outbox::enqueue_notification};
 use async_trait::async_trait;
 use core_app::{AppResult, errors::AppError};
 use domain::{
   entities::{
     notification::CreateNotification,
+    notification_template::NotificationMessage,
     profile::UpdateProfileRequest,
     user::{User, UserWithPassword},
   },
-  repositories::{
-    image_repository::ImageRepository, notification_repository::NotificationRepository,
-    profile_repository::ProfileRepository,
-  },
+  repositories::{image_repository::ImageRepository, profile_repository::ProfileRepository},
 };
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -115,7 +110,8 @@ impl ProfileRepository for SqlxProfileRepository {
     let res = sqlx::query_as::<_, User>(
       r#"
         UPDATE users.tbl_users 
-        SET full_name = $1, email_address = $2, address = $3, date_of_birth = $4
+        SET full_name = $1, email_address = $2, address = $3, date_of_birth = $4,
+          preferred_language = COALESCE($6, preferred_language)
         WHERE pk_user_id = $5
         RETURNING *
     "#,
@@ -125,6 +121,7 @@ impl ProfileRepository for SqlxProfileRepository {
     .bind(data.address)
     .bind(data.date_of_birth)
     .bind(user.pk_user_id)
+    .bind(data.preferred_language)
     .fetch_one(&self.db)
     .await
     .map_err(|err| AppError::BadRequest(err.to_string()))?;
@@ -203,60 +200,41 @@ impl ProfileRepository for SqlxProfileRepository {
     }
 
     // Send notification to receptionists
-    let db = self.db.clone();
     let user_full_name = user.full_name.clone().unwrap_or_default();
     let user_phone = user.phone.clone().unwrap_or_default();
-    let user_avatar = user.avatar.clone();
-    tokio::spawn(async move {
-      // Delete avatar if exists
-      if let Some(avatar) = user_avatar {
+    let notification = CreateNotification {
+      user_id: None,
+      title: String::new(),
+      body: String::new(),
+      receiver: "ALLRECEPTIONIST".to_string(),
+      notification_type: "SYSTEM".to_string(),
+      data: Some(serde_json::json!({
+        "type": "SYSTEM",
+        "action": "ACCOUNT_DELETED",
+        "user_name": user_full_name,
+        "phone_number": user_phone
+      })),
+      appointment_id: None,
+      template: Some(NotificationMessage::new(
+        "ACCOUNT_DELETED",
+        serde_json::json!({ "user_name": user_full_name, "phone": user_phone }),
+      )),
+    };
+
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    if let Err(err) = enqueue_notification(&mut conn, notification, true).await {
+      tracing::error!("Failed to create notification: {:?}", err);
+    }
+
+    // Delete avatar if exists
+    if let Some(avatar) = user.avatar.clone() {
+      tokio::spawn(async move {
         let image_repo = Arc::new(LocalImageService);
         if let Err(err) = image_repo.remove_old_image(avatar.as_str()).await {
           tracing::error!("Failed to delete avatar: {:?}", err);
         }
-      }
-      // Create notification
-      let notification_repo = SqlxNotificationRepository { db: db.clone() };
-      let notification = CreateNotification {
-        user_id: None,
-        title: "Tài khoản đã bị xóa".to_string(),
-        body: format!(
-          "Người dùng {} (SĐT: {}) đã xóa tài khoản của họ",
-          user_full_name, user_phone
-        ),
-        receiver: "ALLRECEPTIONIST".to_string(),
-        notification_type: "SYSTEM".to_string(),
-        data: Some(serde_json::json!({
-          "type": "SYSTEM",
-          "action": "ACCOUNT_DELETED",
-          "user_name": user_full_name,
-          "phone_number": user_phone
-        })),
-        appointment_id: None,
-      };
-
-      if let Err(err) = notification_repo.create(notification).await {
-        tracing::error!("Failed to create notification: {:?}", err);
-      }
-
-      // Send Firebase notification
-      let noti_token_repo = SqlxNotiTokenRepository { db: db.clone() };
-      let _ = send_firebase_notification(
-        &db,
-        std::sync::Arc::new(noti_token_repo),
-        0,
-        "Tài khoản đã bị xóa".to_string(),
-        format!("Người dùng {} (SĐT: {}) đã xóa tài khoản của họ", user_full_name, user_phone),
-        "ALLRECEPTIONIST".to_string(),
-        Some(serde_json::json!({
-          "type": "SYSTEM",
-          "action": "ACCOUNT_DELETED",
-          "user_name": user_full_name,
-          "phone_number": user_phone
-        })),
-      )
-      .await;
-    });
+      });
+    }
 
     Ok(true)
   }
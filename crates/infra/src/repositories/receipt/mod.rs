@@ -0,0 +1,255 @@
+use crate::repositories::appointment::common::loyalty_points;
+use async_trait::async_trait;
+use chrono::{Datelike, FixedOffset, Utc};
+use core_app::{AppResult, errors::AppError};
+use domain::{
+  entities::receipt::{Receipt, ReceiptDocument, ReceiptLine, ReceiptSpaInfo, ReceiptTender},
+  repositories::receipt_repository::ReceiptRepository,
+};
+use sqlx::{FromRow, PgConnection, PgPool};
+
+pub struct SqlxReceiptRepository {
+  pub db: PgPool,
+}
+
+#[derive(FromRow)]
+struct ReceiptAppointment {
+  status: String,
+  user_id: Option<i64>,
+  customer_name: Option<String>,
+  customer_phone: Option<String>,
+  price: i64,
+  surcharge: i64,
+  promotion: i64,
+  total_price: i64,
+}
+
+// Cấp số hoá đơn kế tiếp theo năm (giờ Việt Nam), dạng HD2025-000001.
+// Dòng counter bị khoá tới khi transaction kết thúc nên số luôn liên tục, không trùng
+async fn next_receipt_no(conn: &mut PgConnection) -> AppResult<String> {
+  let utc_plus_7 = FixedOffset::east_opt(7 * 3600).unwrap();
+  let year = Utc::now().with_timezone(&utc_plus_7).year();
+
+  let number: i64 = sqlx::query_scalar(
+    r#"
+    INSERT INTO users.receipt_counters (year, last_number)
+    VALUES ($1, 1)
+    ON CONFLICT (year) DO UPDATE SET last_number = users.receipt_counters.last_number + 1
+    RETURNING last_number
+    "#,
+  )
+  .bind(year)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(format!("HD{}-{:06}", year, number))
+}
+
+// Xuất hoá đơn cho lịch hẹn, gọi trong transaction thanh toán
+pub async fn issue_receipt(
+  conn: &mut PgConnection,
+  appointment_id: i64,
+  created_by: i64,
+) -> AppResult<Receipt> {
+  let appointment = sqlx::query_as::<_, ReceiptAppointment>(
+    r#"
+    SELECT a.status, a.user_id, u.full_name AS customer_name, u.phone AS customer_phone,
+           a.price, a.surcharge, a.promotion, a.total_price
+    FROM users.appointments a
+    LEFT JOIN users.tbl_users u ON u.pk_user_id = a.user_id
+    WHERE a.id = $1
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_optional(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?
+  .ok_or(AppError::NotFound)?;
+
+  if appointment.status != "PAYMENT" {
+    return Err(AppError::BadRequest("Lịch hẹn chưa được thanh toán".to_string()));
+  }
+
+  let cashier_name: Option<String> =
+    sqlx::query_scalar("SELECT full_name FROM users.tbl_users WHERE pk_user_id = $1")
+      .bind(created_by)
+      .fetch_optional(&mut *conn)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?
+      .flatten();
+
+  let lines = sqlx::query_as::<_, ReceiptLine>(
+    r#"
+    SELECT COALESCE(aps.service_name, s.service_name) AS service_name,
+           COALESCE(aps.quantity, 1) AS quantity,
+           aps.unit_price,
+           aps.discount,
+           aps.unit_price * COALESCE(aps.quantity, 1) - aps.discount AS amount
+    FROM users.appointments_services aps
+    JOIN users.service_items s ON s.id = aps.service_id
+    WHERE aps.appointment_id = $1 AND aps.status <> 'CANCELLED'
+    ORDER BY aps.sequence, aps.id
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_all(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let tenders = sqlx::query_as::<_, ReceiptTender>(
+    r#"
+    SELECT tender_type, amount, reference
+    FROM users.appointment_payments
+    WHERE appointment_id = $1
+    ORDER BY id
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_all(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let tip_total: i64 = sqlx::query_scalar(
+    "SELECT COALESCE(SUM(amount), 0)::INT8 FROM users.appointment_tips WHERE appointment_id = $1",
+  )
+  .bind(appointment_id)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let sum_tender = |tender_type: &str| -> i64 {
+    tenders.iter().filter(|tender| tender.tender_type == tender_type).map(|t| t.amount).sum()
+  };
+  let wallet_used = sum_tender("WALLET");
+  let (points_used, points_earned) = loyalty_points(appointment.total_price, sum_tender("POINTS"));
+
+  let receipt_no = next_receipt_no(conn).await?;
+  let document = ReceiptDocument {
+    receipt_no: receipt_no.clone(),
+    issued_at: Utc::now(),
+    appointment_id,
+    spa: ReceiptSpaInfo::default(),
+    customer_id: appointment.user_id,
+    customer_name: appointment.customer_name,
+    customer_phone: appointment.customer_phone,
+    cashier_name,
+    lines,
+    subtotal: appointment.price,
+    surcharge: appointment.surcharge,
+    promotion: appointment.promotion,
+    total: appointment.total_price,
+    tenders,
+    wallet_used,
+    points_used,
+    points_earned,
+    tip_total,
+    share_token: None,
+  };
+
+  let snapshot =
+    serde_json::to_value(&document).map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let receipt = sqlx::query_as::<_, Receipt>(
+    r#"
+    INSERT INTO users.receipts (appointment_id, receipt_no, snapshot, issued_at, created_by)
+    VALUES ($1, $2, $3, $4, $5)
+    RETURNING *
+    "#,
+  )
+  .bind(appointment_id)
+  .bind(&receipt_no)
+  .bind(snapshot)
+  .bind(document.issued_at)
+  .bind(created_by)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(receipt)
+}
+
+fn to_document(receipt: Receipt) -> AppResult<ReceiptDocument> {
+  serde_json::from_value::<ReceiptDocument>(receipt.snapshot)
+    .map_err(|err| AppError::Unhandled(Box::new(err)))
+}
+
+#[async_trait]
+impl ReceiptRepository for SqlxReceiptRepository {
+  async fn get_appointment_customer(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<Option<i64>> {
+    let customer_id =
+      sqlx::query_scalar::<_, Option<i64>>("SELECT user_id FROM users.appointments WHERE id = $1")
+        .bind(appointment_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| AppError::Unhandled(Box::new(err)))?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(customer_id)
+  }
+
+  async fn get_receipt_by_appointment(
+    &self,
+    appointment_id: i64,
+    issued_by: Option<i64>,
+  ) -> AppResult<ReceiptDocument> {
+    let receipt =
+      sqlx::query_as::<_, Receipt>("SELECT * FROM users.receipts WHERE appointment_id = $1")
+        .bind(appointment_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    if let Some(receipt) = receipt {
+      return to_document(receipt);
+    }
+
+    let Some(issued_by) = issued_by else {
+      return Err(AppError::NotFound);
+    };
+
+    // Lịch hẹn thanh toán trước khi có chức năng hoá đơn thì xuất bổ sung
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    // Khoá lịch hẹn để hai yêu cầu đồng thời không xuất hai hoá đơn
+    sqlx::query("SELECT id FROM users.appointments WHERE id = $1 FOR UPDATE")
+      .bind(appointment_id)
+      .execute(&mut *tx)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let existing =
+      sqlx::query_as::<_, Receipt>("SELECT * FROM users.receipts WHERE appointment_id = $1")
+        .bind(appointment_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let receipt = match existing {
+      Some(receipt) => receipt,
+      None => issue_receipt(&mut tx, appointment_id, issued_by).await?,
+    };
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    to_document(receipt)
+  }
+
+  async fn get_receipt_by_no(
+    &self,
+    receipt_no: String,
+  ) -> AppResult<ReceiptDocument> {
+    let receipt =
+      sqlx::query_as::<_, Receipt>("SELECT * FROM users.receipts WHERE receipt_no = $1")
+        .bind(receipt_no)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| AppError::Unhandled(Box::new(err)))?
+        .ok_or(AppError::NotFound)?;
+
+    to_document(receipt)
+  }
+}
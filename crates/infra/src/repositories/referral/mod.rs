@@ -0,0 +1,531 @@
+use crate::repositories::{
+  appointment::common::get_membership_level, notification_outbox::enqueue_notification,
+};
+use async_trait::async_trait;
+use core_app::{AppResult, configs::ReferralConfig, errors::AppError};
+use domain::{
+  entities::{
+    notification::CreateNotification,
+    notification_template::NotificationMessage,
+    referral::{
+      NewReferral, Referral, ReferralCandidate, ReferralFilter, ReferralReportFilter,
+      ReferralReportRow, ReferralSummary,
+    },
+  },
+  repositories::referral_repository::ReferralRepository,
+};
+use sqlx::{PgConnection, PgPool};
+use utils::format_number::format_number;
+
+pub struct SqlxReferralRepository {
+  pub db: PgPool,
+}
+
+const SELECT_REFERRAL: &str = r#"
+  SELECT r.*, ru.full_name AS referrer_name, rd.full_name AS referred_name, rd.phone AS referred_phone
+  FROM users.referrals r
+  LEFT JOIN users.tbl_users ru ON ru.pk_user_id = r.referrer_id
+  LEFT JOIN users.tbl_users rd ON rd.pk_user_id = r.referred_id
+"#;
+
+/// Kiểm tra mã giới thiệu và các quy tắc chống gian lận: không tự giới thiệu (cùng tài khoản
+/// hoặc cùng số điện thoại), chỉ áp dụng cho khách chưa từng thanh toán, mỗi khách một lần,
+/// không dùng thiết bị của người giới thiệu và mỗi thiết bị chỉ được giới thiệu một lần.
+pub async fn check_referral_code(
+  conn: &mut PgConnection,
+  referral_code: &str,
+  candidate: &ReferralCandidate,
+) -> AppResult<i64> {
+  let (referrer_id, referrer_phone) = sqlx::query_as::<_, (i64, Option<String>)>(
+    r#"
+    SELECT pk_user_id, phone FROM users.tbl_users
+    WHERE referral_code = $1 AND role = 'CUSTOMER' AND is_active = true
+    "#,
+  )
+  .bind(referral_code)
+  .fetch_optional(&mut *conn)
+  .await?
+  .ok_or_else(|| AppError::BadRequest("Invalid referral code".to_string()))?;
+
+  if candidate.user_id == Some(referrer_id) || referrer_phone.as_deref() == Some(&candidate.phone) {
+    return Err(AppError::BadRequest("You cannot use your own referral code".to_string()));
+  }
+
+  if let Some(user_id) = candidate.user_id {
+    let (already_referred, has_paid) = sqlx::query_as::<_, (bool, bool)>(
+      r#"
+      SELECT
+        EXISTS (SELECT 1 FROM users.referrals WHERE referred_id = $1),
+        EXISTS (SELECT 1 FROM users.appointments WHERE user_id = $1 AND status = 'PAYMENT')
+      "#,
+    )
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if already_referred {
+      return Err(AppError::BadRequest(
+        "A referral code has already been applied to this customer".to_string(),
+      ));
+    }
+
+    if has_paid {
+      return Err(AppError::BadRequest("Referral codes are only for new customers".to_string()));
+    }
+  }
+
+  if let Some(device_token) = candidate.device_token.as_deref() {
+    let (referrer_device, used_device) = sqlx::query_as::<_, (bool, bool)>(
+      r#"
+      SELECT
+        EXISTS (
+          SELECT 1 FROM users.notification_token_history WHERE user_id = $1 AND token = $2
+        ),
+        EXISTS (SELECT 1 FROM users.referrals WHERE device_token = $2)
+      "#,
+    )
+    .bind(referrer_id)
+    .bind(device_token)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if referrer_device {
+      return Err(AppError::BadRequest(
+        "Referral codes cannot be used on the referrer's device".to_string(),
+      ));
+    }
+
+    if used_device {
+      return Err(AppError::BadRequest(
+        "This device has already been used for a referral".to_string(),
+      ));
+    }
+  }
+
+  Ok(referrer_id)
+}
+
+pub async fn insert_referral(
+  conn: &mut PgConnection,
+  referral: NewReferral,
+) -> AppResult<Referral> {
+  let referral = sqlx::query_as::<_, Referral>(
+    r#"
+    INSERT INTO users.referrals (
+      referrer_id, referred_id, referral_code, source, device_token, created_by
+    )
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT (referred_id) DO NOTHING
+    RETURNING *
+    "#,
+  )
+  .bind(referral.referrer_id)
+  .bind(referral.referred_id)
+  .bind(referral.referral_code)
+  .bind(referral.source)
+  .bind(referral.device_token)
+  .bind(referral.created_by)
+  .fetch_optional(&mut *conn)
+  .await
+  .map_err(|err| AppError::BadRequest(err.to_string()))?
+  .ok_or_else(|| {
+    AppError::BadRequest("A referral code has already been applied to this customer".to_string())
+  })?;
+
+  Ok(referral)
+}
+
+// Kiểm tra và ghi nhận mã giới thiệu cho khách đăng ký bằng số điện thoại
+pub async fn apply_signup_referral(
+  conn: &mut PgConnection,
+  referral_code: String,
+  candidate: ReferralCandidate,
+) -> AppResult<Referral> {
+  let referrer_id = check_referral_code(conn, &referral_code, &candidate).await?;
+  let referred_id = candidate.user_id.ok_or(AppError::NotFound)?;
+
+  insert_referral(
+    conn,
+    NewReferral {
+      referrer_id,
+      referred_id,
+      referral_code,
+      source: "SIGNUP".to_string(),
+      device_token: candidate.device_token,
+      created_by: Some(referred_id),
+    },
+  )
+  .await
+}
+
+// Cộng thưởng qua ví (kèm giao dịch nạp tiền) hoặc điểm tích luỹ (cập nhật hạng thành viên)
+async fn credit_reward(
+  conn: &mut PgConnection,
+  user_id: i64,
+  reward_type: &str,
+  amount: i64,
+  notes: String,
+) -> AppResult<()> {
+  if amount <= 0 {
+    return Ok(());
+  }
+
+  if reward_type == "POINTS" {
+    let points = sqlx::query_scalar::<_, i64>(
+      r#"
+      UPDATE users.tbl_users
+      SET loyalty_points = loyalty_points + $1
+      WHERE pk_user_id = $2
+      RETURNING loyalty_points
+      "#,
+    )
+    .bind(amount)
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    sqlx::query("UPDATE users.tbl_users SET membership_level = $1 WHERE pk_user_id = $2")
+      .bind(get_membership_level(points))
+      .bind(user_id)
+      .execute(&mut *conn)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    return Ok(());
+  }
+
+  sqlx::query(
+    r#"
+    INSERT INTO users.deposits (
+      user_id, amount, status, payment_method, notes, created_by, deposit_type
+    )
+    VALUES ($1, $2, 'COMPLETED', 'REFERRAL', $3, $1, 'DEPOSIT')
+    "#,
+  )
+  .bind(user_id)
+  .bind(amount)
+  .bind(notes)
+  .execute(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  sqlx::query("UPDATE users.tbl_users SET balance = balance + $1 WHERE pk_user_id = $2")
+    .bind(amount)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(())
+}
+
+async fn notify_referral(
+  conn: &mut PgConnection,
+  user_id: i64,
+  message: NotificationMessage,
+  referral: &Referral,
+) -> AppResult<()> {
+  let notification = CreateNotification {
+    user_id: Some(user_id),
+    title: String::new(),
+    body: String::new(),
+    receiver: "CUSTOMER".to_string(),
+    notification_type: "REFERRAL".to_string(),
+    data: Some(serde_json::json!({
+      "type": "REFERRAL",
+      "referral_id": referral.id,
+      "status": referral.status
+    })),
+    appointment_id: referral.appointment_id,
+    template: Some(message),
+  };
+
+  enqueue_notification(conn, notification, true).await?;
+  Ok(())
+}
+
+// Xét thưởng một lượt giới thiệu đang chờ, trả về None nếu đã được xử lý trước đó
+async fn reward_referral(
+  db: &PgPool,
+  config: &ReferralConfig,
+  id: i64,
+) -> AppResult<Option<Referral>> {
+  let mut tx = db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let Some(referral) = sqlx::query_as::<_, Referral>(
+    "SELECT * FROM users.referrals WHERE id = $1 AND status = 'PENDING' FOR UPDATE",
+  )
+  .bind(id)
+  .fetch_optional(&mut *tx)
+  .await?
+  else {
+    return Ok(None);
+  };
+
+  let (appointment_id, shared_device, rewarded_count) =
+    sqlx::query_as::<_, (Option<i64>, bool, i64)>(
+      r#"
+      SELECT
+        (SELECT id FROM users.appointments
+         WHERE user_id = $2 AND status = 'PAYMENT'
+         ORDER BY paid_at NULLS LAST, id
+         LIMIT 1),
+        EXISTS (
+          SELECT 1 FROM users.notification_token_history a
+          JOIN users.notification_token_history b ON a.token = b.token
+          WHERE a.user_id = $1 AND b.user_id = $2
+        ) OR EXISTS (
+          SELECT 1 FROM users.notification_token_history h
+          JOIN users.referrals r ON r.device_token = h.token
+          WHERE r.id = $3 AND h.user_id = $1
+        ),
+        (SELECT COUNT(*) FROM users.referrals WHERE referrer_id = $1 AND status = 'REWARDED')
+      "#,
+    )
+    .bind(referral.referrer_id)
+    .bind(referral.referred_id)
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+  if appointment_id.is_none() {
+    return Ok(None);
+  }
+
+  // Hai tài khoản từng dùng chung thiết bị (kể cả thiết bị lúc đăng ký) thì không thưởng
+  if shared_device {
+    let referral = sqlx::query_as::<_, Referral>(
+      r#"
+      UPDATE users.referrals
+      SET status = 'REJECTED', reject_reason = 'Referrer and referred customer share a device',
+          appointment_id = $2
+      WHERE id = $1
+      RETURNING *
+      "#,
+    )
+    .bind(id)
+    .bind(appointment_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    return Ok(Some(referral));
+  }
+
+  // Người giới thiệu đã đạt giới hạn số lần thưởng thì chỉ thưởng cho khách mới
+  let referrer_reward =
+    if config.max_rewards_per_referrer > 0 && rewarded_count >= config.max_rewards_per_referrer {
+      0
+    } else {
+      config.referrer_reward
+    };
+
+  credit_reward(
+    &mut tx,
+    referral.referrer_id,
+    &config.reward_type,
+    referrer_reward,
+    format!("Thưởng giới thiệu khách hàng #{}", referral.referred_id),
+  )
+  .await?;
+  credit_reward(
+    &mut tx,
+    referral.referred_id,
+    &config.reward_type,
+    config.referred_reward,
+    "Quà chào mừng từ mã giới thiệu".to_string(),
+  )
+  .await?;
+
+  let referral = sqlx::query_as::<_, Referral>(
+    r#"
+    UPDATE users.referrals
+    SET status = 'REWARDED', appointment_id = $2, reward_type = $3,
+        referrer_reward = $4, referred_reward = $5, rewarded_at = NOW()
+    WHERE id = $1
+    RETURNING *
+    "#,
+  )
+  .bind(id)
+  .bind(appointment_id)
+  .bind(&config.reward_type)
+  .bind(referrer_reward)
+  .bind(config.referred_reward)
+  .fetch_one(&mut *tx)
+  .await?;
+
+  // Mẫu thông báo khác nhau theo hình thức thưởng (điểm tích luỹ hoặc tiền vào ví)
+  let reward_suffix = if config.reward_type == "POINTS" { "POINTS" } else { "WALLET" };
+  if referral.referrer_reward > 0 {
+    notify_referral(
+      &mut tx,
+      referral.referrer_id,
+      NotificationMessage::new(
+        &format!("REFERRER_REWARD_{}", reward_suffix),
+        serde_json::json!({ "amount": format_number(referral.referrer_reward) }),
+      ),
+      &referral,
+    )
+    .await?;
+  }
+  if referral.referred_reward > 0 {
+    notify_referral(
+      &mut tx,
+      referral.referred_id,
+      NotificationMessage::new(
+        &format!("REFERRED_REWARD_{}", reward_suffix),
+        serde_json::json!({ "amount": format_number(referral.referred_reward) }),
+      ),
+      &referral,
+    )
+    .await?;
+  }
+
+  tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(Some(referral))
+}
+
+/// Thưởng cho các lượt giới thiệu có lịch hẹn đầu tiên của khách đã chuyển sang PAYMENT.
+/// Chạy định kỳ để bao quát mọi luồng thanh toán lịch hẹn.
+pub async fn process_referral_rewards(
+  db: &PgPool,
+  config: &ReferralConfig,
+) -> AppResult<usize> {
+  let ids = sqlx::query_scalar::<_, i64>(
+    r#"
+    SELECT r.id FROM users.referrals r
+    WHERE r.status = 'PENDING'
+      AND EXISTS (
+        SELECT 1 FROM users.appointments a
+        WHERE a.user_id = r.referred_id AND a.status = 'PAYMENT'
+      )
+    ORDER BY r.id
+    "#,
+  )
+  .fetch_all(db)
+  .await?;
+
+  let mut processed = 0;
+  for id in ids {
+    match reward_referral(db, config, id).await {
+      Ok(Some(_)) => processed += 1,
+      Ok(None) => {},
+      Err(err) => tracing::error!("Failed to reward referral {}: {:?}", id, err),
+    }
+  }
+
+  Ok(processed)
+}
+
+#[async_trait]
+impl ReferralRepository for SqlxReferralRepository {
+  async fn check_referral(
+    &self,
+    referral_code: &str,
+    candidate: &ReferralCandidate,
+  ) -> AppResult<i64> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    check_referral_code(&mut conn, referral_code, candidate).await
+  }
+
+  async fn get_summary(
+    &self,
+    user_id: i64,
+  ) -> AppResult<ReferralSummary> {
+    let referral_code = sqlx::query_scalar::<_, Option<String>>(
+      "SELECT referral_code FROM users.tbl_users WHERE pk_user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let referrals = sqlx::query_as::<_, Referral>(&format!(
+      "{} WHERE r.referrer_id = $1 ORDER BY r.created_at DESC",
+      SELECT_REFERRAL
+    ))
+    .bind(user_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    let referred_by =
+      sqlx::query_as::<_, Referral>(&format!("{} WHERE r.referred_id = $1", SELECT_REFERRAL))
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+    let count = |status: &str| referrals.iter().filter(|r| r.status == status).count() as i64;
+
+    Ok(ReferralSummary {
+      referral_code,
+      total_referrals: referrals.len() as i64,
+      pending: count("PENDING"),
+      rewarded: count("REWARDED"),
+      rejected: count("REJECTED"),
+      total_reward: referrals
+        .iter()
+        .filter(|r| r.status == "REWARDED")
+        .map(|r| r.referrer_reward)
+        .sum(),
+      referred_by,
+      referrals,
+    })
+  }
+
+  async fn get_referrals(
+    &self,
+    filter: ReferralFilter,
+  ) -> AppResult<Vec<Referral>> {
+    let referrals = sqlx::query_as::<_, Referral>(&format!(
+      r#"
+      {}
+      WHERE ($1::int8 IS NULL OR r.referrer_id = $1)
+        AND ($2::text IS NULL OR r.status = $2)
+      ORDER BY r.created_at DESC
+      "#,
+      SELECT_REFERRAL
+    ))
+    .bind(filter.referrer_id)
+    .bind(filter.status)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(referrals)
+  }
+
+  async fn get_report(
+    &self,
+    filter: ReferralReportFilter,
+  ) -> AppResult<Vec<ReferralReportRow>> {
+    let rows = sqlx::query_as::<_, ReferralReportRow>(
+      r#"
+      SELECT
+        r.referrer_id,
+        u.full_name AS referrer_name,
+        u.phone AS referrer_phone,
+        u.referral_code,
+        COUNT(*) AS total_referrals,
+        COUNT(*) FILTER (WHERE r.status = 'PENDING') AS pending,
+        COUNT(*) FILTER (WHERE r.status = 'REWARDED') AS rewarded,
+        COUNT(*) FILTER (WHERE r.status = 'REJECTED') AS rejected,
+        COALESCE(SUM(r.referrer_reward) FILTER (WHERE r.status = 'REWARDED'), 0)::BIGINT AS total_referrer_reward,
+        COALESCE(SUM(r.referred_reward) FILTER (WHERE r.status = 'REWARDED'), 0)::BIGINT AS total_referred_reward
+      FROM users.referrals r
+      JOIN users.tbl_users u ON u.pk_user_id = r.referrer_id
+      WHERE ($1::timestamptz IS NULL OR r.created_at >= $1)
+        AND ($2::timestamptz IS NULL OR r.created_at <= $2)
+      GROUP BY r.referrer_id, u.full_name, u.phone, u.referral_code
+      ORDER BY total_referrals DESC, rewarded DESC
+      "#,
+    )
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(rows)
+  }
+}
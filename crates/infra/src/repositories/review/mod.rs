@@ -0,0 +1,367 @@
+use crate::repositories::notification_outbox::enqueue_notification;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use core_app::{AppResult, configs::ReviewConfig, errors::AppError};
+use domain::{
+  entities::{
+    notification::CreateNotification,
+    notification_template::NotificationMessage,
+    review::{
+      CreateReviewRequest, ModerateReviewRequest, Review, ReviewContext, ReviewDetail,
+      ReviewFilter, ReviewPhoto, TechnicianReview,
+    },
+  },
+  repositories::review_repository::ReviewRepository,
+};
+use sqlx::{PgConnection, PgPool};
+
+pub struct SqlxReviewRepository {
+  pub db: PgPool,
+}
+
+const SELECT_REVIEW: &str = r#"
+  SELECT r.*, c.full_name AS customer_name
+  FROM users.reviews r
+  LEFT JOIN users.tbl_users c ON c.pk_user_id = r.customer_id
+"#;
+
+// Gắn đánh giá kỹ thuật viên và ảnh cho danh sách đánh giá
+async fn attach_details(
+  conn: &mut PgConnection,
+  reviews: Vec<Review>,
+) -> AppResult<Vec<ReviewDetail>> {
+  let ids: Vec<i64> = reviews.iter().map(|review| review.id).collect();
+
+  let technicians = sqlx::query_as::<_, TechnicianReview>(
+    r#"
+    SELECT tr.*, t.full_name AS technician_name
+    FROM users.technician_reviews tr
+    LEFT JOIN users.tbl_users t ON t.pk_user_id = tr.technician_id
+    WHERE tr.review_id = ANY($1)
+    ORDER BY tr.id
+    "#,
+  )
+  .bind(&ids)
+  .fetch_all(&mut *conn)
+  .await?;
+
+  let photos = sqlx::query_as::<_, ReviewPhoto>(
+    "SELECT * FROM users.review_photos WHERE review_id = ANY($1) ORDER BY id",
+  )
+  .bind(&ids)
+  .fetch_all(&mut *conn)
+  .await?;
+
+  Ok(
+    reviews
+      .into_iter()
+      .map(|review| ReviewDetail {
+        technicians: technicians.iter().filter(|t| t.review_id == review.id).cloned().collect(),
+        photos: photos.iter().filter(|p| p.review_id == review.id).cloned().collect(),
+        review,
+      })
+      .collect(),
+  )
+}
+
+async fn get_detail(
+  conn: &mut PgConnection,
+  id: i64,
+) -> AppResult<ReviewDetail> {
+  let review = sqlx::query_as::<_, Review>(&format!("{} WHERE r.id = $1", SELECT_REVIEW))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+  attach_details(conn, vec![review]).await?.pop().ok_or(AppError::NotFound)
+}
+
+// Nhắc khách đánh giá các lịch hẹn đã thanh toán đủ prompt_delay_hours mà chưa được đánh giá.
+// Lịch hẹn được ghi vào review_prompts cùng transaction với outbox nên mỗi lịch hẹn chỉ được nhắc một lần
+pub async fn send_review_prompts(
+  db: &PgPool,
+  config: &ReviewConfig,
+) -> AppResult<usize> {
+  let mut tx = db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  let due = sqlx::query_as::<_, (i64, i64, String)>(
+    r#"
+    WITH due AS (
+      INSERT INTO users.review_prompts (appointment_id)
+      SELECT a.id
+      FROM users.appointments a
+      WHERE a.status = 'PAYMENT'
+        AND a.user_id IS NOT NULL
+        AND a.paid_at <= NOW() - make_interval(hours => $1::int)
+        AND a.paid_at > NOW() - make_interval(days => $2::int)
+        AND NOT EXISTS (SELECT 1 FROM users.reviews r WHERE r.appointment_id = a.id)
+      ON CONFLICT DO NOTHING
+      RETURNING appointment_id
+    )
+    SELECT a.id, a.user_id, a.start_time
+    FROM due
+    JOIN users.appointments a ON a.id = due.appointment_id
+    "#,
+  )
+  .bind(config.prompt_delay_hours as i32)
+  .bind(config.window_days as i32)
+  .fetch_all(&mut *tx)
+  .await?;
+
+  for (appointment_id, user_id, start_time) in due.iter() {
+    let message =
+      NotificationMessage::new("REVIEW_PROMPT", serde_json::json!({ "start_time": start_time }));
+    let data = Some(serde_json::json!({
+      "type": "REVIEW",
+      "appointment_id": appointment_id
+    }));
+
+    let notification = CreateNotification {
+      user_id: Some(*user_id),
+      title: String::new(),
+      body: String::new(),
+      receiver: "CUSTOMER".to_string(),
+      notification_type: "REVIEW".to_string(),
+      data,
+      appointment_id: Some(*appointment_id),
+      template: Some(message),
+    };
+
+    enqueue_notification(&mut tx, notification, true).await?;
+  }
+
+  tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(due.len())
+}
+
+#[async_trait]
+impl ReviewRepository for SqlxReviewRepository {
+  async fn get_context(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<ReviewContext> {
+    let (customer_id, status, finished_at, technician_ids) =
+      sqlx::query_as::<_, (Option<i64>, String, Option<DateTime<Utc>>, Vec<i64>)>(
+        r#"
+        SELECT
+          a.user_id,
+          a.status,
+          -- Lịch hẹn COMPLETED cũ chưa có completed_at thì lấy updated_at làm mốc
+          COALESCE(a.paid_at, a.completed_at, a.updated_at),
+          ARRAY(
+            SELECT aps.technician_id FROM users.appointments_services aps
+            WHERE aps.appointment_id = a.id AND aps.technician_id IS NOT NULL
+            UNION
+            SELECT a.technician_id WHERE a.technician_id IS NOT NULL
+          )
+        FROM users.appointments a
+        WHERE a.id = $1
+        "#,
+      )
+      .bind(appointment_id)
+      .fetch_optional(&self.db)
+      .await?
+      .ok_or(AppError::NotFound)?;
+
+    Ok(ReviewContext { appointment_id, customer_id, status, finished_at, technician_ids })
+  }
+
+  async fn create_review(
+    &self,
+    customer_id: i64,
+    request: CreateReviewRequest,
+  ) -> AppResult<ReviewDetail> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let id = sqlx::query_scalar::<_, i64>(
+      r#"
+      INSERT INTO users.reviews (appointment_id, customer_id, rating, comment)
+      VALUES ($1, $2, $3, $4)
+      ON CONFLICT (appointment_id) DO NOTHING
+      RETURNING id
+      "#,
+    )
+    .bind(request.appointment_id)
+    .bind(customer_id)
+    .bind(request.rating)
+    .bind(request.comment)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| {
+      AppError::BadRequest("This appointment has already been reviewed".to_string())
+    })?;
+
+    for technician in request.technicians {
+      sqlx::query(
+        r#"
+        INSERT INTO users.technician_reviews (review_id, technician_id, rating, comment)
+        VALUES ($1, $2, $3, $4)
+        "#,
+      )
+      .bind(id)
+      .bind(technician.technician_id)
+      .bind(technician.rating)
+      .bind(technician.comment)
+      .execute(&mut *tx)
+      .await
+      .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    }
+
+    let detail = get_detail(&mut tx, id).await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(detail)
+  }
+
+  async fn get_review(
+    &self,
+    id: i64,
+  ) -> AppResult<ReviewDetail> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    get_detail(&mut conn, id).await
+  }
+
+  async fn get_appointment_review(
+    &self,
+    appointment_id: i64,
+  ) -> AppResult<Option<ReviewDetail>> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let review =
+      sqlx::query_as::<_, Review>(&format!("{} WHERE r.appointment_id = $1", SELECT_REVIEW))
+        .bind(appointment_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    match review {
+      Some(review) => Ok(attach_details(&mut conn, vec![review]).await?.pop()),
+      None => Ok(None),
+    }
+  }
+
+  async fn get_reviews(
+    &self,
+    filter: ReviewFilter,
+  ) -> AppResult<Vec<ReviewDetail>> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let reviews = sqlx::query_as::<_, Review>(&format!(
+      r#"
+      {}
+      WHERE ($1::int8 IS NULL OR r.appointment_id = $1)
+        AND ($2::int8 IS NULL OR r.customer_id = $2)
+        AND ($3::int8 IS NULL OR EXISTS (
+          SELECT 1 FROM users.technician_reviews tr
+          WHERE tr.review_id = r.id AND tr.technician_id = $3
+        ))
+        AND ($4::int4 IS NULL OR r.rating = $4)
+        AND ($5 OR NOT r.is_hidden)
+      ORDER BY r.created_at DESC
+      "#,
+      SELECT_REVIEW
+    ))
+    .bind(filter.appointment_id)
+    .bind(filter.customer_id)
+    .bind(filter.technician_id)
+    .bind(filter.rating)
+    .bind(filter.include_hidden.unwrap_or(false))
+    .fetch_all(&mut *conn)
+    .await?;
+
+    attach_details(&mut conn, reviews).await
+  }
+
+  async fn moderate(
+    &self,
+    id: i64,
+    staff_id: i64,
+    request: ModerateReviewRequest,
+  ) -> AppResult<ReviewDetail> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    sqlx::query_scalar::<_, i64>(
+      r#"
+      UPDATE users.reviews
+      SET is_hidden = $2,
+          hidden_reason = $3,
+          hidden_by = CASE WHEN $2 THEN $4 END,
+          hidden_at = CASE WHEN $2 THEN NOW() END
+      WHERE id = $1
+      RETURNING id
+      "#,
+    )
+    .bind(id)
+    .bind(request.is_hidden)
+    .bind(request.reason)
+    .bind(staff_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    get_detail(&mut conn, id).await
+  }
+
+  async fn reply(
+    &self,
+    id: i64,
+    staff_id: i64,
+    reply: String,
+  ) -> AppResult<ReviewDetail> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    sqlx::query_scalar::<_, i64>(
+      r#"
+      UPDATE users.reviews
+      SET reply = $2, replied_by = $3, replied_at = NOW()
+      WHERE id = $1
+      RETURNING id
+      "#,
+    )
+    .bind(id)
+    .bind(reply)
+    .bind(staff_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    get_detail(&mut conn, id).await
+  }
+
+  async fn add_photo(
+    &self,
+    review_id: i64,
+    image: String,
+  ) -> AppResult<ReviewPhoto> {
+    let photo = sqlx::query_as::<_, ReviewPhoto>(
+      "INSERT INTO users.review_photos (review_id, image) VALUES ($1, $2) RETURNING *",
+    )
+    .bind(review_id)
+    .bind(image)
+    .fetch_one(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    Ok(photo)
+  }
+
+  async fn delete_photo(
+    &self,
+    review_id: i64,
+    photo_id: i64,
+  ) -> AppResult<ReviewPhoto> {
+    let photo = sqlx::query_as::<_, ReviewPhoto>(
+      "DELETE FROM users.review_photos WHERE id = $1 AND review_id = $2 RETURNING *",
+    )
+    .bind(photo_id)
+    .bind(review_id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(photo)
+  }
+}
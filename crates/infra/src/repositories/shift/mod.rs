@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use core_app::{AppResult, errors::AppError};
+use domain::{
+  entities::shift::{
+    COUNTABLE_TENDERS, CloseShiftRequest, OpenShiftRequest, ReceptionistVariance, Shift,
+    ShiftFilter, ShiftSummary, ShiftTender, ShiftVarianceReport, TenderVariance,
+  },
+  repositories::shift_repository::ShiftRepository,
+};
+use sqlx::{FromRow, PgConnection, PgPool};
+
+pub struct SqlxShiftRepository {
+  pub db: PgPool,
+}
+
+#[derive(FromRow)]
+struct VarianceTotals {
+  shift_count: i64,
+  total_expected: i64,
+  total_counted: i64,
+  total_variance: i64,
+}
+
+const SELECT_SHIFT: &str = r#"
+  SELECT s.*, u.full_name AS receptionist_name
+  FROM users.shifts s
+  LEFT JOIN users.tbl_users u ON u.pk_user_id = s.receptionist_id
+"#;
+
+// Số tiền hệ thống ghi nhận trong ca theo từng hình thức thanh toán:
+// - thanh toán lịch hẹn do lễ tân thu
+// - nạp ví (+) / chi trả rút ví (-) do lễ tân tạo hoặc duyệt, bỏ qua giao dịch nội bộ của ví
+// - tip tiền mặt
+// Tiền mặt cộng thêm tiền đầu ca
+async fn expected_tenders(
+  conn: &mut PgConnection,
+  shift: &Shift,
+  until: DateTime<Utc>,
+) -> AppResult<Vec<ShiftTender>> {
+  let tenders = sqlx::query_as::<_, ShiftTender>(
+    r#"
+    WITH payments AS (
+      SELECT tender_type, SUM(amount)::INT8 AS amount
+      FROM users.appointment_payments
+      WHERE created_by = $1 AND created_at >= $2 AND created_at < $3
+      GROUP BY tender_type
+    ), deposits AS (
+      SELECT payment_method AS tender_type,
+             SUM(CASE WHEN deposit_type = 'DEPOSIT' THEN amount ELSE -amount END)::INT8 AS amount
+      FROM users.deposits
+      WHERE status = 'COMPLETED'
+        AND deposit_type IN ('DEPOSIT', 'WITHDRAW')
+        AND payment_method <> 'WALLET'
+        AND COALESCE(reviewed_by, created_by) = $1
+        AND COALESCE(reviewed_at, created_at) >= $2
+        AND COALESCE(reviewed_at, created_at) < $3
+      GROUP BY payment_method
+    ), tips AS (
+      SELECT payment_method AS tender_type, SUM(amount)::INT8 AS amount
+      FROM users.appointment_tips
+      WHERE payment_method = 'CASH' AND created_by = $1 AND created_at >= $2 AND created_at < $3
+      GROUP BY payment_method
+    ), tenders AS (
+      SELECT tender_type FROM payments
+      UNION SELECT tender_type FROM deposits
+      UNION SELECT tender_type FROM tips
+      UNION SELECT 'CASH'
+    )
+    SELECT t.tender_type,
+           COALESCE(p.amount, 0) AS payment_amount,
+           COALESCE(d.amount, 0) AS deposit_amount,
+           COALESCE(ti.amount, 0) AS tip_amount,
+           COALESCE(p.amount, 0) + COALESCE(d.amount, 0) + COALESCE(ti.amount, 0)
+             + CASE WHEN t.tender_type = 'CASH' THEN $4 ELSE 0 END AS expected_amount,
+           NULL::INT8 AS counted_amount,
+           NULL::INT8 AS variance
+    FROM tenders t
+    LEFT JOIN payments p ON p.tender_type = t.tender_type
+    LEFT JOIN deposits d ON d.tender_type = t.tender_type
+    LEFT JOIN tips ti ON ti.tender_type = t.tender_type
+    ORDER BY t.tender_type
+    "#,
+  )
+  .bind(shift.receptionist_id)
+  .bind(shift.opened_at)
+  .bind(until)
+  .bind(shift.opening_float)
+  .fetch_all(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(tenders)
+}
+
+async fn appointment_count(
+  conn: &mut PgConnection,
+  shift: &Shift,
+  until: DateTime<Utc>,
+) -> AppResult<i64> {
+  let count: i64 = sqlx::query_scalar(
+    r#"
+    SELECT COUNT(DISTINCT appointment_id)
+    FROM users.appointment_payments
+    WHERE created_by = $1 AND created_at >= $2 AND created_at < $3
+    "#,
+  )
+  .bind(shift.receptionist_id)
+  .bind(shift.opened_at)
+  .bind(until)
+  .fetch_one(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(count)
+}
+
+#[async_trait]
+impl ShiftRepository for SqlxShiftRepository {
+  async fn open_shift(
+    &self,
+    receptionist_id: i64,
+    request: OpenShiftRequest,
+  ) -> AppResult<Shift> {
+    if self.get_open_shift(receptionist_id).await?.is_some() {
+      return Err(AppError::BadRequest("Bạn đang có ca làm việc chưa đóng".to_string()));
+    }
+
+    // Unique index chỉ cho phép một ca OPEN, hai yêu cầu đồng thời thì yêu cầu sau bị từ chối
+    let shift = sqlx::query_as::<_, Shift>(
+      r#"
+      INSERT INTO users.shifts (receptionist_id, opening_float, notes)
+      VALUES ($1, $2, $3)
+      ON CONFLICT DO NOTHING
+      RETURNING *
+      "#,
+    )
+    .bind(receptionist_id)
+    .bind(request.opening_float)
+    .bind(request.notes)
+    .fetch_optional(&self.db)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?
+    .ok_or_else(|| AppError::BadRequest("Bạn đang có ca làm việc chưa đóng".to_string()))?;
+
+    Ok(shift)
+  }
+
+  async fn get_open_shift(
+    &self,
+    receptionist_id: i64,
+  ) -> AppResult<Option<Shift>> {
+    let shift = sqlx::query_as::<_, Shift>(&format!(
+      "{} WHERE s.receptionist_id = $1 AND s.status = 'OPEN'",
+      SELECT_SHIFT
+    ))
+    .bind(receptionist_id)
+    .fetch_optional(&self.db)
+    .await?;
+
+    Ok(shift)
+  }
+
+  async fn get_shift_summary(
+    &self,
+    shift_id: i64,
+  ) -> AppResult<ShiftSummary> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let shift = sqlx::query_as::<_, Shift>(&format!("{} WHERE s.id = $1", SELECT_SHIFT))
+      .bind(shift_id)
+      .fetch_optional(&mut *conn)
+      .await?
+      .ok_or(AppError::NotFound)?;
+
+    let until = shift.closed_at.unwrap_or_else(Utc::now);
+    let appointment_count = appointment_count(&mut conn, &shift, until).await?;
+
+    let tenders = match shift.status.as_str() {
+      "CLOSED" => {
+        sqlx::query_as::<_, ShiftTender>(
+          r#"
+          SELECT tender_type, payment_amount, deposit_amount, tip_amount,
+                 expected_amount, counted_amount, variance
+          FROM users.shift_tenders
+          WHERE shift_id = $1
+          ORDER BY tender_type
+          "#,
+        )
+        .bind(shift.id)
+        .fetch_all(&mut *conn)
+        .await?
+      },
+      _ => expected_tenders(&mut conn, &shift, until).await?,
+    };
+
+    Ok(ShiftSummary { shift, appointment_count, tenders })
+  }
+
+  async fn close_shift(
+    &self,
+    shift_id: i64,
+    closed_by: i64,
+    request: CloseShiftRequest,
+  ) -> AppResult<ShiftSummary> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let shift = sqlx::query_as::<_, Shift>("SELECT * FROM users.shifts WHERE id = $1 FOR UPDATE")
+      .bind(shift_id)
+      .fetch_optional(&mut *tx)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?
+      .ok_or(AppError::NotFound)?;
+
+    if shift.status != "OPEN" {
+      return Err(AppError::BadRequest("Ca làm việc đã được đóng".to_string()));
+    }
+
+    let closed_at = Utc::now();
+    let mut tenders = expected_tenders(&mut tx, &shift, closed_at).await?;
+
+    let counted: HashMap<String, i64> =
+      request.counted.into_iter().map(|counted| (counted.tender_type, counted.amount)).collect();
+
+    // Mọi hình thức kiểm đếm được có phát sinh tiền trong ca đều phải nhập số thực tế
+    // để tổng chênh lệch không bỏ sót (ví, điểm không kiểm đếm)
+    let missing: Vec<&str> = tenders
+      .iter()
+      .filter(|tender| COUNTABLE_TENDERS.contains(&tender.tender_type.as_str()))
+      .filter(|tender| tender.expected_amount != 0 && !counted.contains_key(&tender.tender_type))
+      .map(|tender| tender.tender_type.as_str())
+      .collect();
+    if !missing.is_empty() {
+      return Err(AppError::BadRequest(format!(
+        "Chưa nhập số kiểm đếm cho hình thức: {}",
+        missing.join(", ")
+      )));
+    }
+
+    // Hình thức có kiểm đếm nhưng không phát sinh giao dịch trong ca
+    for tender_type in counted.keys() {
+      if !tenders.iter().any(|tender| &tender.tender_type == tender_type) {
+        tenders.push(ShiftTender {
+          tender_type: tender_type.clone(),
+          payment_amount: 0,
+          deposit_amount: 0,
+          tip_amount: 0,
+          expected_amount: 0,
+          counted_amount: None,
+          variance: None,
+        });
+      }
+    }
+    tenders.sort_by(|a, b| a.tender_type.cmp(&b.tender_type));
+
+    let (mut total_expected, mut total_counted) = (0, 0);
+    for tender in tenders.iter_mut() {
+      if let Some(amount) = counted.get(&tender.tender_type) {
+        tender.counted_amount = Some(*amount);
+        tender.variance = Some(amount - tender.expected_amount);
+        total_expected += tender.expected_amount;
+        total_counted += amount;
+      }
+
+      sqlx::query(
+        r#"
+        INSERT INTO users.shift_tenders (
+          shift_id, tender_type, payment_amount, deposit_amount, tip_amount,
+          expected_amount, counted_amount, variance
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+      )
+      .bind(shift.id)
+      .bind(&tender.tender_type)
+      .bind(tender.payment_amount)
+      .bind(tender.deposit_amount)
+      .bind(tender.tip_amount)
+      .bind(tender.expected_amount)
+      .bind(tender.counted_amount)
+      .bind(tender.variance)
+      .execute(&mut *tx)
+      .await
+      .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+    }
+
+    sqlx::query(
+      r#"
+      UPDATE users.shifts
+      SET status = 'CLOSED', closed_at = $2, closed_by = $3, total_expected = $4,
+          total_counted = $5, total_variance = $6, closing_notes = $7
+      WHERE id = $1
+      "#,
+    )
+    .bind(shift.id)
+    .bind(closed_at)
+    .bind(closed_by)
+    .bind(total_expected)
+    .bind(total_counted)
+    .bind(total_counted - total_expected)
+    .bind(request.notes)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    self.get_shift_summary(shift.id).await
+  }
+
+  async fn get_shifts(
+    &self,
+    filter: ShiftFilter,
+  ) -> AppResult<Vec<Shift>> {
+    let shifts = sqlx::query_as::<_, Shift>(&format!(
+      r#"
+      {}
+      WHERE ($1::int8 IS NULL OR s.receptionist_id = $1)
+        AND ($2::text IS NULL OR s.status = $2)
+        AND ($3::timestamptz IS NULL OR s.opened_at >= $3)
+        AND ($4::timestamptz IS NULL OR s.opened_at < $4)
+      ORDER BY s.opened_at DESC
+      "#,
+      SELECT_SHIFT
+    ))
+    .bind(filter.receptionist_id)
+    .bind(filter.status)
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(shifts)
+  }
+
+  async fn get_variance_report(
+    &self,
+    filter: ShiftFilter,
+  ) -> AppResult<ShiftVarianceReport> {
+    let conditions = r#"
+      s.status = 'CLOSED'
+      AND ($1::int8 IS NULL OR s.receptionist_id = $1)
+      AND ($2::timestamptz IS NULL OR s.opened_at >= $2)
+      AND ($3::timestamptz IS NULL OR s.opened_at < $3)
+    "#;
+
+    let totals = sqlx::query_as::<_, VarianceTotals>(&format!(
+      r#"
+      SELECT COUNT(*) AS shift_count,
+             COALESCE(SUM(s.total_expected), 0)::INT8 AS total_expected,
+             COALESCE(SUM(s.total_counted), 0)::INT8 AS total_counted,
+             COALESCE(SUM(s.total_variance), 0)::INT8 AS total_variance
+      FROM users.shifts s
+      WHERE {}
+      "#,
+      conditions
+    ))
+    .bind(filter.receptionist_id)
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .fetch_one(&self.db)
+    .await?;
+
+    let receptionists = sqlx::query_as::<_, ReceptionistVariance>(&format!(
+      r#"
+      SELECT s.receptionist_id, u.full_name AS receptionist_name,
+             COUNT(*) AS shift_count,
+             COALESCE(SUM(s.total_expected), 0)::INT8 AS total_expected,
+             COALESCE(SUM(s.total_counted), 0)::INT8 AS total_counted,
+             COALESCE(SUM(s.total_variance), 0)::INT8 AS total_variance
+      FROM users.shifts s
+      LEFT JOIN users.tbl_users u ON u.pk_user_id = s.receptionist_id
+      WHERE {}
+      GROUP BY s.receptionist_id, u.full_name
+      ORDER BY total_variance
+      "#,
+      conditions
+    ))
+    .bind(filter.receptionist_id)
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .fetch_all(&self.db)
+    .await?;
+
+    let tenders = sqlx::query_as::<_, TenderVariance>(&format!(
+      r#"
+      SELECT t.tender_type,
+             COALESCE(SUM(t.expected_amount), 0)::INT8 AS total_expected,
+             COALESCE(SUM(t.counted_amount), 0)::INT8 AS total_counted,
+             COALESCE(SUM(t.variance), 0)::INT8 AS total_variance
+      FROM users.shift_tenders t
+      JOIN users.shifts s ON s.id = t.shift_id
+      WHERE t.counted_amount IS NOT NULL AND {}
+      GROUP BY t.tender_type
+      ORDER BY t.tender_type
+      "#,
+      conditions
+    ))
+    .bind(filter.receptionist_id)
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(ShiftVarianceReport {
+      shift_count: totals.shift_count,
+      total_expected: totals.total_expected,
+      total_counted: totals.total_counted,
+      total_variance: totals.total_variance,
+      receptionists,
+      tenders,
+    })
+  }
+}
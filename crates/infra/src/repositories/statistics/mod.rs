@@ -3,7 +3,7 @@ use core_app::AppResult;
 use domain::{
   entities::statistics::{
     AdminStatistics, CustomerStatistics, DailyStatistics, ReceptionistStatistics,
-    ServiceStatistics, TechnicianStatistics, TechnicianStats,
+    ServiceStatistics, TechnicianStatistics, TechnicianStats, TenderStatistics,
   },
   repositories::statistics_repository::StatisticsRepository,
 };
@@ -74,6 +74,31 @@ impl StatisticsRepository for SqlxStatisticsRepository {
     .await?;
 
     println!("Debug - Total Revenue: {}", total_revenue);
+
+    // Tiền tip tính riêng, không cộng vào doanh thu
+    let total_tips: i64 = sqlx::query_scalar(
+      r#"
+      SELECT COALESCE(SUM(amount), 0)::BIGINT
+      FROM users.appointment_tips
+      "#,
+    )
+    .fetch_one(&self.db)
+    .await?;
+
+    // Doanh thu theo từng hình thức thanh toán
+    let tender_statistics: Vec<TenderStatistics> = sqlx::query_as(
+      r#"
+      SELECT
+        tender_type,
+        COUNT(*)::BIGINT as total_count,
+        COALESCE(SUM(amount), 0)::BIGINT as total_amount
+      FROM users.appointment_payments
+      GROUP BY tender_type
+      ORDER BY total_amount DESC
+      "#,
+    )
+    .fetch_all(&self.db)
+    .await?;
     println!("Debug - Total Appointments: {}", total_appointments);
     println!("Debug - Completed Appointments: {}", completed_appointments);
 
@@ -81,14 +106,13 @@ impl StatisticsRepository for SqlxStatisticsRepository {
     let service_statistics: Vec<ServiceStatistics> = sqlx::query_as(
       r#"
       SELECT 
-        s.id as service_id,
-        s.service_name,
+        aps.service_id,
+        aps.service_name,
         COUNT(DISTINCT a.id) as total_count,
-        SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN a.total_price ELSE 0 END)::BIGINT as total_revenue
+        SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN aps.unit_price * aps.quantity - aps.discount ELSE 0 END)::BIGINT as total_revenue
       FROM users.appointments a
       JOIN users.appointments_services aps ON a.id = aps.appointment_id
-      JOIN users.service_items s ON aps.service_id = s.id
-      GROUP BY s.id, s.service_name
+      GROUP BY aps.service_id, aps.service_name
       HAVING COUNT(DISTINCT a.id) > 0
       ORDER BY total_count DESC
       "#,
@@ -104,7 +128,7 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       SELECT 
         TO_CHAR(TO_TIMESTAMP(a.start_time, 'HH24:MI DD/MM/YYYY'), 'YYYY-MM-DD') as date,
         COUNT(DISTINCT a.id)::bigint as total_appointments,
-        SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN a.total_price ELSE 0 END)::bigint as total_revenue,
+        SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN aps.unit_price * aps.quantity - aps.discount ELSE 0 END)::bigint as total_revenue,
         COUNT(DISTINCT a.user_id)::bigint as unique_customers,
         COUNT(DISTINCT aps.technician_id)::bigint as active_technicians
       FROM users.appointments a
@@ -141,7 +165,8 @@ impl StatisticsRepository for SqlxStatisticsRepository {
         t.pk_user_id as technician_id,
         t.full_name as technician_name,
         COUNT(DISTINCT a.id)::bigint as total_appointments,
-        SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN a.total_price ELSE 0 END)::bigint as total_revenue,
+        SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN aps.unit_price * aps.quantity - aps.discount ELSE 0 END)::bigint as total_revenue,
+        COALESCE((SELECT SUM(tp.amount) FROM users.appointment_tips tp WHERE tp.technician_id = t.pk_user_id), 0)::bigint as total_tips,
         COUNT(DISTINCT a.user_id)::bigint as unique_customers,
         ROUND(AVG(EXTRACT(EPOCH FROM (TO_TIMESTAMP(a.end_time, 'HH24:MI DD/MM/YYYY') - TO_TIMESTAMP(a.start_time, 'HH24:MI DD/MM/YYYY')))/3600)::numeric, 2)::bigint as avg_service_time
       FROM users.appointments a
@@ -177,13 +202,11 @@ impl StatisticsRepository for SqlxStatisticsRepository {
     let parent_service_statistics: Vec<(i64, String, i64)> = sqlx::query_as(
       r#"
       SELECT 
-        s.id AS parent_service_id,
-        s.service_name,
+        aps.parent_service_id,
+        aps.parent_service_name,
         COUNT(aps.id) AS total_usage
-      FROM users.services s
-      LEFT JOIN users.service_items si ON si.parent_service_id = s.id
-      LEFT JOIN users.appointments_services aps ON aps.service_id = si.id
-      GROUP BY s.id, s.service_name
+      FROM users.appointments_services aps
+      GROUP BY aps.parent_service_id, aps.parent_service_name
       ORDER BY total_usage DESC
       "#,
     )
@@ -192,6 +215,8 @@ impl StatisticsRepository for SqlxStatisticsRepository {
 
     Ok(AdminStatistics {
       total_revenue,
+      total_tips,
+      tender_statistics,
       total_appointments,
       completed_appointments,
       cancelled_appointments: total_cancelled,
@@ -280,6 +305,23 @@ impl StatisticsRepository for SqlxStatisticsRepository {
     .fetch_one(&self.db)
     .await?;
 
+    let tender_statistics: Vec<TenderStatistics> = sqlx::query_as(
+      r#"
+      SELECT
+        p.tender_type,
+        COUNT(*)::BIGINT as total_count,
+        COALESCE(SUM(p.amount), 0)::BIGINT as total_amount
+      FROM users.appointment_payments p
+      JOIN users.appointments a ON a.id = p.appointment_id
+      WHERE a.receptionist_id = $1
+      GROUP BY p.tender_type
+      ORDER BY total_amount DESC
+      "#,
+    )
+    .bind(user_id)
+    .fetch_all(&self.db)
+    .await?;
+
     let daily_stats: Vec<DailyStatistics> = sqlx::query_as(
       r#"
       SELECT 
@@ -311,15 +353,13 @@ impl StatisticsRepository for SqlxStatisticsRepository {
     let parent_service_statistics: Vec<(i64, String, i64)> = sqlx::query_as(
       r#"
       SELECT 
-        s.id AS parent_service_id,
-        s.service_name,
+        aps.parent_service_id,
+        aps.parent_service_name,
         COUNT(aps.id)::BIGINT AS total_usage
-      FROM users.services s
-      LEFT JOIN users.service_items si ON si.parent_service_id = s.id
-      LEFT JOIN users.appointments_services aps ON aps.service_id = si.id
-      LEFT JOIN users.appointments a ON a.id = aps.appointment_id
+      FROM users.appointments_services aps
+      JOIN users.appointments a ON a.id = aps.appointment_id
       WHERE a.receptionist_id = $1
-      GROUP BY s.id, s.service_name
+      GROUP BY aps.parent_service_id, aps.parent_service_name
       ORDER BY total_usage DESC
       "#,
     )
@@ -334,6 +374,7 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       completed_appointments,
       cancelled_appointments,
       total_revenue,
+      tender_statistics,
       daily_statistics: daily_stats,
       appointment_status_counts,
       parent_service_statistics,
@@ -409,15 +450,14 @@ impl StatisticsRepository for SqlxStatisticsRepository {
     let favorite_services: Vec<ServiceStatistics> = sqlx::query_as(
       r#"
       SELECT 
-          s.id as service_id,
-          s.service_name,
+          aps.service_id,
+          aps.service_name,
           COUNT(*) as total_count,
-          SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN a.total_price ELSE 0 END)::BIGINT as total_revenue
+          SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN aps.unit_price * aps.quantity - aps.discount ELSE 0 END)::BIGINT as total_revenue
       FROM users.appointments a
       JOIN users.appointments_services aps ON a.id = aps.appointment_id
-      JOIN users.service_items s ON aps.service_id = s.id
       WHERE a.user_id = $1
-      GROUP BY s.id, s.service_name
+      GROUP BY aps.service_id, aps.service_name
       ORDER BY total_count DESC
       LIMIT 5
       "#,
@@ -432,10 +472,8 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       SELECT 
           TO_CHAR(TO_TIMESTAMP(a.start_time, 'HH24:MI DD/MM/YYYY'), 'YYYY-MM-DD') as date,
           COUNT(*) as total_appointments,
-          SUM(CASE WHEN status IN ('COMPLETED', 'PAYMENT') THEN a.total_price ELSE 0 END)::BIGINT as total_revenue
+          SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN a.total_price ELSE 0 END)::BIGINT as total_revenue
       FROM users.appointments a
-      JOIN users.appointments_services aps ON a.id = aps.appointment_id
-      JOIN users.service_items s ON aps.service_id = s.id
       WHERE a.user_id = $1
       GROUP BY TO_CHAR(TO_TIMESTAMP(a.start_time, 'HH24:MI DD/MM/YYYY'), 'YYYY-MM-DD')
       ORDER BY date DESC
@@ -466,7 +504,7 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       r#"
       SELECT COUNT(*)
       FROM users.appointments
-      WHERE technician_id = $1
+      WHERE id IN (SELECT appointment_id FROM users.appointments_services WHERE technician_id = $1)
       "#,
     )
     .bind(user_id)
@@ -478,7 +516,7 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       r#"
       SELECT COUNT(*)
       FROM users.appointments
-      WHERE technician_id = $1 
+      WHERE id IN (SELECT appointment_id FROM users.appointments_services WHERE technician_id = $1)
       AND DATE(TO_TIMESTAMP(start_time, 'HH24:MI DD/MM/YYYY')) = CURRENT_DATE
       "#,
     )
@@ -491,7 +529,7 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       r#"
       SELECT COUNT(*)
       FROM users.appointments
-      WHERE technician_id = $1 AND status = 'COMPLETED'
+      WHERE id IN (SELECT appointment_id FROM users.appointments_services WHERE technician_id = $1) AND status = 'COMPLETED'
       "#,
     )
     .bind(user_id)
@@ -503,7 +541,7 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       r#"
       SELECT COUNT(*)
       FROM users.appointments
-      WHERE technician_id = $1 AND status = 'CONFIRMED'
+      WHERE id IN (SELECT appointment_id FROM users.appointments_services WHERE technician_id = $1) AND status = 'CONFIRMED'
       "#,
     )
     .bind(user_id)
@@ -513,9 +551,35 @@ impl StatisticsRepository for SqlxStatisticsRepository {
     // Get total revenue
     let total_revenue: i64 = sqlx::query_scalar(
       r#"
-      SELECT COALESCE(SUM(total_price)::BIGINT, 0)::BIGINT
-      FROM users.appointments
-      WHERE technician_id = $1 AND status IN ('COMPLETED', 'PAYMENT')
+      SELECT COALESCE(SUM(aps.unit_price * aps.quantity - aps.discount)::BIGINT, 0)::BIGINT
+      FROM users.appointments a
+      JOIN users.appointments_services aps ON a.id = aps.appointment_id
+      WHERE aps.technician_id = $1 AND a.status IN ('COMPLETED', 'PAYMENT')
+      "#,
+    )
+    .bind(user_id)
+    .fetch_one(&self.db)
+    .await?;
+
+    // Get total tips
+    let total_tips: i64 = sqlx::query_scalar(
+      r#"
+      SELECT COALESCE(SUM(amount), 0)::BIGINT
+      FROM users.appointment_tips
+      WHERE technician_id = $1
+      "#,
+    )
+    .bind(user_id)
+    .fetch_one(&self.db)
+    .await?;
+
+    // Get rating from visible reviews
+    let (average_rating, review_count): (Option<f64>, i64) = sqlx::query_as(
+      r#"
+      SELECT AVG(tr.rating)::float8, COUNT(*)
+      FROM users.technician_reviews tr
+      JOIN users.reviews r ON r.id = tr.review_id
+      WHERE tr.technician_id = $1 AND NOT r.is_hidden
       "#,
     )
     .bind(user_id)
@@ -527,15 +591,14 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       r#"
       WITH service_stats AS (
       SELECT 
-        s.id as service_id,
-        s.service_name,
+        aps.service_id,
+        aps.service_name,
         COUNT(DISTINCT a.id) as total_count,
-        SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN a.total_price ELSE 0 END)::BIGINT as total_revenue
+        SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN aps.unit_price * aps.quantity - aps.discount ELSE 0 END)::BIGINT as total_revenue
       FROM users.appointments a
       JOIN users.appointments_services aps ON a.id = aps.appointment_id
-      JOIN users.service_items s ON aps.service_id = s.id
-      WHERE a.technician_id = $1
-      GROUP BY s.id, s.service_name
+      WHERE aps.technician_id = $1
+      GROUP BY aps.service_id, aps.service_name
       )
       SELECT 
         service_id,
@@ -555,12 +618,13 @@ impl StatisticsRepository for SqlxStatisticsRepository {
     let daily_statistics: Vec<DailyStatistics> = sqlx::query_as(
       r#"
       SELECT 
-          TO_CHAR(TO_TIMESTAMP(start_time, 'HH24:MI DD/MM/YYYY'), 'YYYY-MM-DD') as date,
-          COUNT(*) as total_appointments,
-          SUM(CASE WHEN status IN ('COMPLETED', 'PAYMENT') THEN total_price ELSE 0 END)::BIGINT as total_revenue
-      FROM users.appointments
-      WHERE technician_id = $1
-      GROUP BY TO_CHAR(TO_TIMESTAMP(start_time, 'HH24:MI DD/MM/YYYY'), 'YYYY-MM-DD')
+          TO_CHAR(TO_TIMESTAMP(a.start_time, 'HH24:MI DD/MM/YYYY'), 'YYYY-MM-DD') as date,
+          COUNT(DISTINCT a.id) as total_appointments,
+          SUM(CASE WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN aps.unit_price * aps.quantity - aps.discount ELSE 0 END)::BIGINT as total_revenue
+      FROM users.appointments a
+      JOIN users.appointments_services aps ON a.id = aps.appointment_id
+      WHERE aps.technician_id = $1
+      GROUP BY TO_CHAR(TO_TIMESTAMP(a.start_time, 'HH24:MI DD/MM/YYYY'), 'YYYY-MM-DD')
       ORDER BY date DESC
       LIMIT 30
       "#,
@@ -574,6 +638,9 @@ impl StatisticsRepository for SqlxStatisticsRepository {
       today_appointments,
       completed_appointments,
       total_revenue,
+      total_tips,
+      average_rating,
+      review_count,
       service_statistics,
       daily_statistics,
       confirmed_appointments,
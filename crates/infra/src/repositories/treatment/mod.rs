@@ -0,0 +1,459 @@
+use async_trait::async_trait;
+use core_app::{AppResult, errors::AppError};
+use domain::{
+  entities::treatment::{
+    CreateTreatmentFieldRequest, CreateTreatmentRecordRequest, TreatmentContext, TreatmentField,
+    TreatmentPhoto, TreatmentRecord, TreatmentRecordDetail, TreatmentRecordFilter,
+    TreatmentRecordHistory, UpdateTreatmentFieldRequest, UpdateTreatmentRecordRequest,
+  },
+  repositories::treatment_repository::TreatmentRepository,
+};
+use sqlx::{PgConnection, PgPool};
+
+pub struct SqlxTreatmentRepository {
+  pub db: PgPool,
+}
+
+const SELECT_RECORD: &str = r#"
+  SELECT r.*, c.full_name AS customer_name, t.full_name AS technician_name, s.service_name
+  FROM users.treatment_records r
+  LEFT JOIN users.tbl_users c ON c.pk_user_id = r.customer_id
+  LEFT JOIN users.tbl_users t ON t.pk_user_id = r.technician_id
+  LEFT JOIN users.services s ON s.id = r.service_id
+"#;
+
+// Lưu nội dung hồ sơ sau mỗi lần thay đổi thành một phiên bản
+async fn insert_history(
+  conn: &mut PgConnection,
+  record_id: i64,
+  changed_by: i64,
+) -> AppResult<()> {
+  sqlx::query(
+    r#"
+    INSERT INTO users.treatment_record_history (record_id, version, snapshot, changed_by)
+    SELECT r.id, r.version, to_jsonb(r) - 'version' - 'created_at' - 'updated_at', $2
+    FROM users.treatment_records r
+    WHERE r.id = $1
+    "#,
+  )
+  .bind(record_id)
+  .bind(changed_by)
+  .execute(&mut *conn)
+  .await
+  .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  Ok(())
+}
+
+async fn get_photos(
+  conn: &mut PgConnection,
+  record_ids: &[i64],
+) -> AppResult<Vec<TreatmentPhoto>> {
+  let photos = sqlx::query_as::<_, TreatmentPhoto>(
+    r#"
+    SELECT * FROM users.treatment_photos
+    WHERE record_id = ANY($1)
+    ORDER BY photo_type DESC, created_at
+    "#,
+  )
+  .bind(record_ids)
+  .fetch_all(&mut *conn)
+  .await?;
+
+  Ok(photos)
+}
+
+async fn get_detail(
+  conn: &mut PgConnection,
+  id: i64,
+) -> AppResult<TreatmentRecordDetail> {
+  let record = sqlx::query_as::<_, TreatmentRecord>(&format!("{} WHERE r.id = $1", SELECT_RECORD))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+  let photos = get_photos(conn, &[id]).await?;
+
+  Ok(TreatmentRecordDetail { record, photos })
+}
+
+#[async_trait]
+impl TreatmentRepository for SqlxTreatmentRepository {
+  async fn get_fields(
+    &self,
+    service_id: Option<i64>,
+    include_inactive: bool,
+  ) -> AppResult<Vec<TreatmentField>> {
+    let fields = sqlx::query_as::<_, TreatmentField>(
+      r#"
+      SELECT * FROM users.treatment_fields
+      WHERE ($1::int8 IS NULL OR service_id IS NULL OR service_id = $1)
+        AND ($2 OR is_active)
+      ORDER BY service_id NULLS FIRST, sort_order, id
+      "#,
+    )
+    .bind(service_id)
+    .bind(include_inactive)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(fields)
+  }
+
+  async fn create_field(
+    &self,
+    request: CreateTreatmentFieldRequest,
+    created_by: i64,
+  ) -> AppResult<TreatmentField> {
+    let exists: bool = sqlx::query_scalar(
+      r#"
+      SELECT EXISTS (
+        SELECT 1 FROM users.treatment_fields
+        WHERE COALESCE(service_id, 0) = COALESCE($1, 0) AND field_key = $2
+      )
+      "#,
+    )
+    .bind(request.service_id)
+    .bind(&request.field_key)
+    .fetch_one(&self.db)
+    .await?;
+
+    if exists {
+      return Err(AppError::BadRequest(format!("Field {} already exists", request.field_key)));
+    }
+
+    let field = sqlx::query_as::<_, TreatmentField>(
+      r#"
+      INSERT INTO users.treatment_fields (
+        service_id, field_key, label, field_type, options, is_required, is_internal,
+        sort_order, created_by
+      )
+      VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+      RETURNING *
+      "#,
+    )
+    .bind(request.service_id)
+    .bind(request.field_key)
+    .bind(request.label)
+    .bind(request.field_type)
+    .bind(request.options.map(|options| serde_json::json!(options)))
+    .bind(request.is_required.unwrap_or(false))
+    .bind(request.is_internal.unwrap_or(false))
+    .bind(request.sort_order.unwrap_or(0))
+    .bind(created_by)
+    .fetch_one(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    Ok(field)
+  }
+
+  async fn update_field(
+    &self,
+    id: i64,
+    request: UpdateTreatmentFieldRequest,
+  ) -> AppResult<TreatmentField> {
+    let field = sqlx::query_as::<_, TreatmentField>(
+      r#"
+      UPDATE users.treatment_fields
+      SET label = COALESCE($1, label),
+          options = COALESCE($2, options),
+          is_required = COALESCE($3, is_required),
+          is_internal = COALESCE($4, is_internal),
+          sort_order = COALESCE($5, sort_order),
+          is_active = COALESCE($6, is_active)
+      WHERE id = $7
+      RETURNING *
+      "#,
+    )
+    .bind(request.label)
+    .bind(request.options.map(|options| serde_json::json!(options)))
+    .bind(request.is_required)
+    .bind(request.is_internal)
+    .bind(request.sort_order)
+    .bind(request.is_active)
+    .bind(id)
+    .fetch_optional(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(field)
+  }
+
+  async fn get_context(
+    &self,
+    appointment_id: Option<i64>,
+    appointment_service_id: Option<i64>,
+  ) -> AppResult<TreatmentContext> {
+    // Nhóm dịch vụ là dịch vụ cha của dịch vụ con trong lịch hẹn
+    let context = match appointment_service_id {
+      Some(appointment_service_id) => {
+        sqlx::query_as::<_, TreatmentContext>(
+          r#"
+          SELECT a.id AS appointment_id, a.user_id AS customer_id,
+                 si.parent_service_id AS service_id
+          FROM users.appointments_services aps
+          JOIN users.appointments a ON a.id = aps.appointment_id
+          JOIN users.service_items si ON si.id = aps.service_id
+          WHERE aps.id = $1 AND ($2::int8 IS NULL OR aps.appointment_id = $2)
+          "#,
+        )
+        .bind(appointment_service_id)
+        .bind(appointment_id)
+        .fetch_optional(&self.db)
+        .await?
+      },
+      None => {
+        sqlx::query_as::<_, TreatmentContext>(
+          r#"
+          SELECT id AS appointment_id, user_id AS customer_id, NULL::int8 AS service_id
+          FROM users.appointments
+          WHERE id = $1
+          "#,
+        )
+        .bind(appointment_id)
+        .fetch_optional(&self.db)
+        .await?
+      },
+    }
+    .ok_or_else(|| {
+      AppError::BadRequest("Appointment or appointment service not found".to_string())
+    })?;
+
+    Ok(context)
+  }
+
+  async fn is_customer(
+    &self,
+    customer_id: i64,
+  ) -> AppResult<bool> {
+    let exists: bool = sqlx::query_scalar(
+      "SELECT EXISTS (SELECT 1 FROM users.tbl_users WHERE pk_user_id = $1 AND role = 'CUSTOMER')",
+    )
+    .bind(customer_id)
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(exists)
+  }
+
+  async fn create_record(
+    &self,
+    customer_id: i64,
+    technician_id: i64,
+    request: CreateTreatmentRecordRequest,
+  ) -> AppResult<TreatmentRecordDetail> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let id: i64 = sqlx::query_scalar(
+      r#"
+      INSERT INTO users.treatment_records (
+        customer_id, appointment_id, appointment_service_id, service_id, technician_id,
+        skin_type, allergies, machine_settings, reactions, notes, internal_notes, fields
+      )
+      VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, COALESCE($12, '{}'::jsonb))
+      RETURNING id
+      "#,
+    )
+    .bind(customer_id)
+    .bind(request.appointment_id)
+    .bind(request.appointment_service_id)
+    .bind(request.service_id)
+    .bind(technician_id)
+    .bind(request.skin_type)
+    .bind(request.allergies)
+    .bind(request.machine_settings)
+    .bind(request.reactions)
+    .bind(request.notes)
+    .bind(request.internal_notes)
+    .bind(request.fields)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    insert_history(&mut tx, id, technician_id).await?;
+    let detail = get_detail(&mut tx, id).await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(detail)
+  }
+
+  async fn get_record(
+    &self,
+    id: i64,
+  ) -> AppResult<TreatmentRecordDetail> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    get_detail(&mut conn, id).await
+  }
+
+  async fn get_records(
+    &self,
+    filter: TreatmentRecordFilter,
+  ) -> AppResult<Vec<TreatmentRecord>> {
+    let records = sqlx::query_as::<_, TreatmentRecord>(&format!(
+      r#"
+      {}
+      WHERE ($1::int8 IS NULL OR r.customer_id = $1)
+        AND ($2::int8 IS NULL OR r.appointment_id = $2)
+        AND ($3::int8 IS NULL OR r.technician_id = $3)
+        AND ($4::int8 IS NULL OR r.service_id = $4)
+      ORDER BY r.created_at DESC
+      "#,
+      SELECT_RECORD
+    ))
+    .bind(filter.customer_id)
+    .bind(filter.appointment_id)
+    .bind(filter.technician_id)
+    .bind(filter.service_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(records)
+  }
+
+  async fn get_customer_records(
+    &self,
+    customer_id: i64,
+  ) -> AppResult<Vec<TreatmentRecordDetail>> {
+    let mut conn = self.db.acquire().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let records = sqlx::query_as::<_, TreatmentRecord>(&format!(
+      "{} WHERE r.customer_id = $1 ORDER BY r.created_at DESC",
+      SELECT_RECORD
+    ))
+    .bind(customer_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let ids: Vec<i64> = records.iter().map(|record| record.id).collect();
+    let mut photos = get_photos(&mut conn, &ids).await?;
+
+    Ok(
+      records
+        .into_iter()
+        .map(|record| {
+          let (own, rest) = photos.drain(..).partition(|photo| photo.record_id == record.id);
+          photos = rest;
+          TreatmentRecordDetail { record, photos: own }
+        })
+        .collect(),
+    )
+  }
+
+  async fn update_record(
+    &self,
+    id: i64,
+    updated_by: i64,
+    request: UpdateTreatmentRecordRequest,
+  ) -> AppResult<TreatmentRecordDetail> {
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    // Chuỗi rỗng để xoá nội dung, bỏ trống (null) để giữ nguyên
+    sqlx::query(
+      r#"
+      UPDATE users.treatment_records
+      SET skin_type = CASE WHEN $2::text IS NULL THEN skin_type ELSE NULLIF(TRIM($2), '') END,
+          allergies = CASE WHEN $3::text IS NULL THEN allergies ELSE NULLIF(TRIM($3), '') END,
+          machine_settings = CASE
+            WHEN $4::text IS NULL THEN machine_settings ELSE NULLIF(TRIM($4), '')
+          END,
+          reactions = CASE WHEN $5::text IS NULL THEN reactions ELSE NULLIF(TRIM($5), '') END,
+          notes = CASE WHEN $6::text IS NULL THEN notes ELSE NULLIF(TRIM($6), '') END,
+          internal_notes = CASE
+            WHEN $7::text IS NULL THEN internal_notes ELSE NULLIF(TRIM($7), '')
+          END,
+          fields = COALESCE($8, fields),
+          version = version + 1,
+          updated_by = $9
+      WHERE id = $1
+      "#,
+    )
+    .bind(id)
+    .bind(request.skin_type)
+    .bind(request.allergies)
+    .bind(request.machine_settings)
+    .bind(request.reactions)
+    .bind(request.notes)
+    .bind(request.internal_notes)
+    .bind(request.fields)
+    .bind(updated_by)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    insert_history(&mut tx, id, updated_by).await?;
+    let detail = get_detail(&mut tx, id).await?;
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(detail)
+  }
+
+  async fn get_record_history(
+    &self,
+    id: i64,
+  ) -> AppResult<Vec<TreatmentRecordHistory>> {
+    let history = sqlx::query_as::<_, TreatmentRecordHistory>(
+      r#"
+      SELECT h.*, u.full_name AS changed_by_name
+      FROM users.treatment_record_history h
+      LEFT JOIN users.tbl_users u ON u.pk_user_id = h.changed_by
+      WHERE h.record_id = $1
+      ORDER BY h.version DESC
+      "#,
+    )
+    .bind(id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(history)
+  }
+
+  async fn add_photo(
+    &self,
+    record_id: i64,
+    photo_type: String,
+    image: String,
+    caption: Option<String>,
+    uploaded_by: i64,
+  ) -> AppResult<TreatmentPhoto> {
+    let photo = sqlx::query_as::<_, TreatmentPhoto>(
+      r#"
+      INSERT INTO users.treatment_photos (record_id, photo_type, image, caption, uploaded_by)
+      VALUES ($1, $2, $3, $4, $5)
+      RETURNING *
+      "#,
+    )
+    .bind(record_id)
+    .bind(photo_type)
+    .bind(image)
+    .bind(caption)
+    .bind(uploaded_by)
+    .fetch_one(&self.db)
+    .await
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    Ok(photo)
+  }
+
+  async fn delete_photo(
+    &self,
+    record_id: i64,
+    photo_id: i64,
+  ) -> AppResult<TreatmentPhoto> {
+    let photo = sqlx::query_as::<_, TreatmentPhoto>(
+      "DELETE FROM users.treatment_photos WHERE id = $1 AND record_id = $2 RETURNING *",
+    )
+    .bind(photo_id)
+    .bind(record_id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(photo)
+  }
+}
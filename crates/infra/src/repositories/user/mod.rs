@@ -1,7 +1,9 @@
 use async_trait::async_trait;
 use core_app::{AppResult, errors::AppError};
 use domain::{
-  entities::user::{PhoneFilterConvert, RequestCreateUser, User, UserWithPassword},
+  entities::user::{
+    PhoneFilterConvert, RequestCreateUser, TechnicianWithRating, User, UserWithPassword,
+  },
   repositories::user_repository::UserRepository,
 };
 
@@ -21,10 +23,19 @@ impl UserRepository for SqlxUserRepository {
   async fn get_all_technician(
     &self,
     _: UserWithPassword,
-  ) -> AppResult<Vec<User>> {
-    let users = sqlx::query_as::<_, User>(
+  ) -> AppResult<Vec<TechnicianWithRating>> {
+    let users = sqlx::query_as::<_, TechnicianWithRating>(
       r#"
-      SELECT * FROM users.tbl_users WHERE role = 'TECHNICIAN' and is_active = true
+      SELECT u.*, rating.average_rating, COALESCE(rating.review_count, 0) AS review_count
+      FROM users.tbl_users u
+      LEFT JOIN (
+        SELECT tr.technician_id, AVG(tr.rating)::float8 AS average_rating, COUNT(*) AS review_count
+        FROM users.technician_reviews tr
+        JOIN users.reviews r ON r.id = tr.review_id
+        WHERE NOT r.is_hidden
+        GROUP BY tr.technician_id
+      ) rating ON rating.technician_id = u.pk_user_id
+      WHERE u.role = 'TECHNICIAN' and u.is_active = true
       "#,
     )
     .fetch_all(&self.db)
@@ -0,0 +1,518 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use core_app::{AppResult, configs::WebhookConfig, errors::AppError};
+use domain::{
+  entities::webhook::{
+    CreateWebhookSubscriptionRequest, UpdateWebhookSubscriptionRequest, WebhookDelivery,
+    WebhookDeliveryFilter, WebhookSubscription,
+  },
+  repositories::webhook_repository::WebhookRepository,
+};
+use reqwest::{
+  Url,
+  dns::{Addrs, Name, Resolve, Resolving},
+  redirect::Policy,
+};
+use sqlx::{PgConnection, PgPool};
+use std::{
+  collections::HashMap,
+  net::{IpAddr, SocketAddr},
+  sync::Arc,
+};
+use utils::helper::{is_public_ip, sign_hmac_sha256};
+
+pub struct SqlxWebhookRepository {
+  pub db: PgPool,
+}
+
+// Lượt gửi đang PROCESSING quá thời gian này coi như worker đã chết giữa chừng
+const STALE_LOCK_MINUTES: i64 = 5;
+// Chỉ lưu phần đầu phản hồi của hệ thống nhận để xem lỗi
+const MAX_RESPONSE_BODY_LENGTH: usize = 2000;
+
+/// Ghi sự kiện cho mọi subscription đang bật có đăng ký loại sự kiện này.
+/// Chạy trong transaction của nghiệp vụ thì sự kiện chỉ được gửi khi thay đổi được commit.
+pub async fn enqueue_webhook_event<'e>(
+  executor: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+  event_type: &str,
+  data: serde_json::Value,
+) -> AppResult<u64> {
+  let result = sqlx::query(
+    r#"
+    WITH event AS (SELECT gen_random_uuid()::text AS id, NOW() AS created_at)
+    INSERT INTO users.webhook_deliveries (subscription_id, event_type, event_id, payload)
+    SELECT s.id, $1, e.id,
+      jsonb_build_object('id', e.id, 'type', $1::text, 'created_at', e.created_at, 'data', $2::jsonb)
+    FROM users.webhook_subscriptions s
+    CROSS JOIN event e
+    WHERE s.is_active AND $1 = ANY(s.event_types)
+    "#,
+  )
+  .bind(event_type)
+  .bind(data)
+  .execute(executor)
+  .await?;
+
+  Ok(result.rows_affected())
+}
+
+/// Sự kiện lịch hẹn kèm ảnh chụp lịch hẹn và dịch vụ tại thời điểm thay đổi,
+/// `extra` được gộp vào data (ví dụ trạng thái cũ).
+pub async fn enqueue_appointment_event(
+  conn: &mut PgConnection,
+  event_type: &str,
+  appointment_id: i64,
+  extra: serde_json::Value,
+) -> AppResult<u64> {
+  let has_subscribers = sqlx::query_scalar::<_, bool>(
+    "SELECT EXISTS (SELECT 1 FROM users.webhook_subscriptions WHERE is_active AND $1 = ANY(event_types))",
+  )
+  .bind(event_type)
+  .fetch_one(&mut *conn)
+  .await?;
+  if !has_subscribers {
+    return Ok(0);
+  }
+
+  let data = sqlx::query_scalar::<_, serde_json::Value>(
+    r#"
+    SELECT jsonb_build_object(
+      'appointment_id', a.id,
+      'user_id', a.user_id,
+      'customer_name', u.full_name,
+      'customer_phone', u.phone,
+      'technician_id', a.technician_id,
+      'receptionist_id', a.receptionist_id,
+      'start_time', a.start_time,
+      'end_time', a.end_time,
+      'status', a.status,
+      'price', a.price,
+      'surcharge', a.surcharge,
+      'promotion', a.promotion,
+      'total_price', a.total_price,
+      'paid_at', a.paid_at,
+      'services', COALESCE((
+        SELECT jsonb_agg(jsonb_build_object(
+          'service_id', aps.service_id,
+          'service_name', aps.service_name,
+          'technician_id', aps.technician_id,
+          'quantity', aps.quantity,
+          'unit_price', aps.unit_price,
+          'discount', aps.discount,
+          'status', aps.status
+        ) ORDER BY aps.sequence, aps.id)
+        FROM users.appointments_services aps
+        WHERE aps.appointment_id = a.id
+      ), '[]'::jsonb)
+    ) || $2::jsonb
+    FROM users.appointments a
+    LEFT JOIN users.tbl_users u ON u.pk_user_id = a.user_id
+    WHERE a.id = $1
+    "#,
+  )
+  .bind(appointment_id)
+  .bind(extra)
+  .fetch_optional(&mut *conn)
+  .await?
+  .ok_or(AppError::NotFound)?;
+
+  enqueue_webhook_event(&mut *conn, event_type, data).await
+}
+
+fn retry_delay(
+  config: &WebhookConfig,
+  attempts: i32,
+) -> Duration {
+  let exponent = (attempts - 1).clamp(0, 20) as u32;
+  let seconds = config.retry_base_seconds.max(1).saturating_mul(1 << exponent);
+
+  Duration::seconds(seconds.min(config.retry_max_seconds.max(1)))
+}
+
+// Chỉ kết nối tới địa chỉ công khai, kiểm tra tại lúc gửi để chặn DNS trỏ lại vào mạng nội bộ
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+  fn resolve(
+    &self,
+    name: Name,
+  ) -> Resolving {
+    Box::pin(async move {
+      let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+        .await?
+        .filter(|address| is_public_ip(address.ip()))
+        .collect();
+      if addresses.is_empty() {
+        return Err(format!("{} does not resolve to a public address", name.as_str()).into());
+      }
+
+      Ok(Box::new(addresses.into_iter()) as Addrs)
+    })
+  }
+}
+
+// Host là IP thì reqwest không qua resolver nên kiểm tra trực tiếp; subscription cũ có thể còn http
+fn check_target(
+  url: &str,
+  require_https: bool,
+) -> Result<(), String> {
+  let url = Url::parse(url).map_err(|err| format!("Invalid URL: {}", err))?;
+  if url.scheme() != "https" && (require_https || url.scheme() != "http") {
+    return Err("URL must use https".to_string());
+  }
+
+  let host = url.host_str().ok_or_else(|| "URL must have a host".to_string())?;
+  let ip = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().ok();
+  if ip.is_some_and(|ip| !is_public_ip(ip)) {
+    return Err("URL must point to a public address".to_string());
+  }
+
+  Ok(())
+}
+
+// Khi đăng ký subscription: mọi địa chỉ host phân giải ra đều phải công khai. Worker vẫn kiểm tra
+// lại lúc gửi qua PublicResolver vì DNS có thể đổi sau khi đăng ký
+async fn check_public_host(url: &str) -> Result<(), String> {
+  let url = Url::parse(url).map_err(|err| format!("Invalid URL: {}", err))?;
+  let host = url.host_str().ok_or_else(|| "URL must have a host".to_string())?;
+  let addresses: Vec<IpAddr> = match host.trim_start_matches('[').trim_end_matches(']').parse() {
+    Ok(ip) => vec![ip],
+    Err(_) => tokio::net::lookup_host((host, url.port_or_known_default().unwrap_or(443)))
+      .await
+      .map_err(|_| "Cannot resolve URL host".to_string())?
+      .map(|address| address.ip())
+      .collect(),
+  };
+
+  if addresses.is_empty() || !addresses.into_iter().all(is_public_ip) {
+    return Err("URL must point to a public address".to_string());
+  }
+
+  Ok(())
+}
+
+struct DeliveryResult {
+  response_status: Option<i32>,
+  response_body: Option<String>,
+  error: Option<String>,
+}
+
+// Gửi payload kèm chữ ký HMAC-SHA256 của "{timestamp}.{body}" để bên nhận xác thực và chống replay
+async fn send_delivery(
+  client: &reqwest::Client,
+  config: &WebhookConfig,
+  subscription: &WebhookSubscription,
+  delivery: &WebhookDelivery,
+) -> DeliveryResult {
+  if let Err(err) = check_target(&subscription.url, config.require_https) {
+    return DeliveryResult { response_status: None, response_body: None, error: Some(err) };
+  }
+
+  let body = delivery.payload.to_string();
+  let timestamp = Utc::now().timestamp().to_string();
+  let signature =
+    sign_hmac_sha256(&subscription.secret, format!("{}.{}", timestamp, body).as_bytes());
+
+  let response = client
+    .post(&subscription.url)
+    .header("Content-Type", "application/json")
+    .header("X-Webhook-Id", delivery.event_id.as_str())
+    .header("X-Webhook-Event", delivery.event_type.as_str())
+    .header("X-Webhook-Delivery", delivery.id.to_string())
+    .header("X-Webhook-Timestamp", timestamp.as_str())
+    .header("X-Webhook-Signature", format!("sha256={}", signature))
+    .body(body)
+    .send()
+    .await;
+
+  match response {
+    Ok(response) => {
+      let status = response.status();
+      let mut response_body = response.text().await.unwrap_or_default();
+      if response_body.len() > MAX_RESPONSE_BODY_LENGTH {
+        let mut end = MAX_RESPONSE_BODY_LENGTH;
+        while !response_body.is_char_boundary(end) {
+          end -= 1;
+        }
+        response_body.truncate(end);
+      }
+
+      DeliveryResult {
+        response_status: Some(status.as_u16() as i32),
+        response_body: Some(response_body),
+        error: (!status.is_success()).then(|| format!("Unexpected response status {}", status)),
+      }
+    },
+    Err(err) => {
+      // Ghi cả nguyên nhân gốc (ví dụ host bị resolver chặn) thay vì chỉ "error sending request"
+      let mut error = err.to_string();
+      let mut source = std::error::Error::source(&err);
+      while let Some(cause) = source {
+        let message = cause.to_string();
+        if !error.contains(&message) {
+          error.push_str(&format!(": {}", message));
+        }
+        source = cause.source();
+      }
+      DeliveryResult { response_status: None, response_body: None, error: Some(error) }
+    },
+  }
+}
+
+/// Lấy một lô webhook đến hạn gửi (SKIP LOCKED để chạy được nhiều instance) và gửi đi.
+/// Trả về số lượt gửi đã xử lý.
+pub async fn dispatch_webhooks(
+  db: &PgPool,
+  config: &WebhookConfig,
+) -> AppResult<usize> {
+  sqlx::query(
+    r#"
+    UPDATE users.webhook_deliveries
+    SET status = 'PENDING', locked_at = NULL
+    WHERE status = 'PROCESSING' AND locked_at < $1
+    "#,
+  )
+  .bind(Utc::now() - Duration::minutes(STALE_LOCK_MINUTES))
+  .execute(db)
+  .await?;
+
+  let batch = sqlx::query_as::<_, WebhookDelivery>(
+    r#"
+    UPDATE users.webhook_deliveries
+    SET status = 'PROCESSING', locked_at = NOW()
+    WHERE id IN (
+      SELECT id FROM users.webhook_deliveries
+      WHERE status = 'PENDING' AND next_attempt_at <= NOW()
+      ORDER BY next_attempt_at, id
+      LIMIT $1
+      FOR UPDATE SKIP LOCKED
+    )
+    RETURNING *
+    "#,
+  )
+  .bind(config.batch_size.max(1))
+  .fetch_all(db)
+  .await?;
+
+  if batch.is_empty() {
+    return Ok(0);
+  }
+
+  let subscription_ids: Vec<i64> = batch.iter().map(|delivery| delivery.subscription_id).collect();
+  let subscriptions: HashMap<i64, WebhookSubscription> = sqlx::query_as::<_, WebhookSubscription>(
+    "SELECT * FROM users.webhook_subscriptions WHERE id = ANY($1)",
+  )
+  .bind(subscription_ids)
+  .fetch_all(db)
+  .await?
+  .into_iter()
+  .map(|subscription| (subscription.id, subscription))
+  .collect();
+
+  // Không theo redirect: bên nhận có thể chuyển hướng sang địa chỉ nội bộ
+  let client = reqwest::Client::builder()
+    .timeout(std::time::Duration::from_secs(config.timeout_seconds.max(1)))
+    .redirect(Policy::none())
+    .dns_resolver(Arc::new(PublicResolver))
+    .build()
+    .map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+  for delivery in &batch {
+    let result = match subscriptions.get(&delivery.subscription_id) {
+      Some(subscription) if subscription.is_active => {
+        send_delivery(&client, config, subscription, delivery).await
+      },
+      _ => DeliveryResult {
+        response_status: None,
+        response_body: None,
+        error: Some("Subscription is disabled".to_string()),
+      },
+    };
+
+    let attempts = delivery.attempts + 1;
+    let (status, next_attempt_at) = match &result.error {
+      None => ("SUCCEEDED", delivery.next_attempt_at),
+      Some(err) if attempts >= config.max_attempts => {
+        tracing::error!("Webhook delivery {} failed: {}", delivery.id, err);
+        ("FAILED", delivery.next_attempt_at)
+      },
+      Some(_) => ("PENDING", Utc::now() + retry_delay(config, attempts)),
+    };
+
+    sqlx::query(
+      r#"
+      UPDATE users.webhook_deliveries
+      SET status = $2, attempts = $3, locked_at = NULL, next_attempt_at = $4,
+          response_status = $5, response_body = $6, last_error = $7,
+          delivered_at = CASE WHEN $2 = 'SUCCEEDED' THEN NOW() ELSE delivered_at END
+      WHERE id = $1
+      "#,
+    )
+    .bind(delivery.id)
+    .bind(status)
+    .bind(attempts)
+    .bind(next_attempt_at)
+    .bind(result.response_status)
+    .bind(result.response_body)
+    .bind(result.error)
+    .execute(db)
+    .await?;
+  }
+
+  Ok(batch.len())
+}
+
+#[async_trait]
+impl WebhookRepository for SqlxWebhookRepository {
+  async fn list(&self) -> AppResult<Vec<WebhookSubscription>> {
+    let subscriptions = sqlx::query_as::<_, WebhookSubscription>(
+      "SELECT * FROM users.webhook_subscriptions ORDER BY id",
+    )
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(subscriptions)
+  }
+
+  async fn get_by_id(
+    &self,
+    id: i64,
+  ) -> AppResult<WebhookSubscription> {
+    let subscription = sqlx::query_as::<_, WebhookSubscription>(
+      "SELECT * FROM users.webhook_subscriptions WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(subscription)
+  }
+
+  async fn create(
+    &self,
+    payload: CreateWebhookSubscriptionRequest,
+    created_by: i64,
+  ) -> AppResult<WebhookSubscription> {
+    let subscription = sqlx::query_as::<_, WebhookSubscription>(
+      r#"
+      INSERT INTO users.webhook_subscriptions (
+        url, secret, event_types, description, is_active, created_by
+      )
+      VALUES ($1, $2, $3, $4, COALESCE($5, TRUE), $6)
+      RETURNING *
+      "#,
+    )
+    .bind(payload.url)
+    .bind(payload.secret)
+    .bind(payload.event_types)
+    .bind(payload.description)
+    .bind(payload.is_active)
+    .bind(created_by)
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(subscription)
+  }
+
+  async fn update(
+    &self,
+    id: i64,
+    payload: UpdateWebhookSubscriptionRequest,
+  ) -> AppResult<WebhookSubscription> {
+    let subscription = sqlx::query_as::<_, WebhookSubscription>(
+      r#"
+      UPDATE users.webhook_subscriptions
+      SET url = COALESCE($2, url),
+          secret = COALESCE($3, secret),
+          event_types = COALESCE($4, event_types),
+          description = COALESCE($5, description),
+          is_active = COALESCE($6, is_active)
+      WHERE id = $1
+      RETURNING *
+      "#,
+    )
+    .bind(id)
+    .bind(payload.url)
+    .bind(payload.secret)
+    .bind(payload.event_types)
+    .bind(payload.description)
+    .bind(payload.is_active)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(subscription)
+  }
+
+  async fn delete(
+    &self,
+    id: i64,
+  ) -> AppResult<()> {
+    let result = sqlx::query("DELETE FROM users.webhook_subscriptions WHERE id = $1")
+      .bind(id)
+      .execute(&self.db)
+      .await?;
+
+    if result.rows_affected() == 0 {
+      return Err(AppError::NotFound);
+    }
+
+    Ok(())
+  }
+
+  async fn list_deliveries(
+    &self,
+    subscription_id: i64,
+    filter: WebhookDeliveryFilter,
+  ) -> AppResult<Vec<WebhookDelivery>> {
+    let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+      r#"
+      SELECT * FROM users.webhook_deliveries
+      WHERE subscription_id = $1
+        AND ($2::text IS NULL OR status = $2)
+        AND ($3::text IS NULL OR event_type = $3)
+      ORDER BY created_at DESC, id DESC
+      LIMIT 200
+      "#,
+    )
+    .bind(subscription_id)
+    .bind(filter.status)
+    .bind(filter.event_type)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(deliveries)
+  }
+
+  // Gửi lại tạo một lượt gửi mới cùng event_id và payload, giữ nguyên nhật ký của lượt cũ
+  async fn redeliver(
+    &self,
+    delivery_id: i64,
+  ) -> AppResult<WebhookDelivery> {
+    let delivery = sqlx::query_as::<_, WebhookDelivery>(
+      r#"
+      INSERT INTO users.webhook_deliveries (
+        subscription_id, event_type, event_id, payload, redelivery_of
+      )
+      SELECT subscription_id, event_type, event_id, payload, id
+      FROM users.webhook_deliveries
+      WHERE id = $1
+      RETURNING *
+      "#,
+    )
+    .bind(delivery_id)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(delivery)
+  }
+
+  async fn check_public_url(
+    &self,
+    url: &str,
+  ) -> AppResult<()> {
+    check_public_host(url).await.map_err(AppError::BadRequest)
+  }
+}
@@ -0,0 +1,407 @@
+use crate::{events::zalo::ZaloService, repositories::notification_preference::is_channel_enabled};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use core_app::{AppResult, configs::ZaloConfig, errors::AppError};
+use domain::{
+  entities::zalo::{
+    UpdateZaloEventTemplateRequest, ZaloEventTemplate, ZaloMessage, ZaloMessageFilter,
+    ZaloWebhookEvent,
+  },
+  repositories::zalo_repository::ZaloRepository,
+  services::zalo::render_params,
+};
+use sqlx::{FromRow, PgConnection, PgPool};
+use utils::format_number::format_number;
+
+pub struct SqlxZaloRepository {
+  pub db: PgPool,
+}
+
+// Tin đang PROCESSING quá thời gian này coi như worker đã chết giữa chừng
+const STALE_LOCK_MINUTES: i64 = 5;
+// Sự kiện Zalo báo người dùng đã nhận tin ZNS
+const DELIVERED_EVENT: &str = "user_received_message";
+
+#[derive(FromRow)]
+struct ZaloAppointment {
+  user_id: Option<i64>,
+  customer_name: Option<String>,
+  phone: Option<String>,
+  start_time: String,
+  status: String,
+  total_price: i64,
+  technician_name: Option<String>,
+  services: Option<String>,
+  receipt_no: Option<String>,
+}
+
+/// Ghi tin ZNS của lịch hẹn vào hàng đợi bằng transaction của nghiệp vụ, tham số được dựng ngay
+/// lúc này để tin phản ánh đúng lịch hẹn tại thời điểm thay đổi.
+/// Bỏ qua khi sự kiện chưa gắn mẫu, khách không có số điện thoại hoặc đã tắt kênh ZALO.
+pub async fn enqueue_zalo_message(
+  conn: &mut PgConnection,
+  appointment_id: i64,
+  event_type: &str,
+) -> AppResult<Option<i64>> {
+  let template = sqlx::query_as::<_, ZaloEventTemplate>(
+    "SELECT * FROM users.zalo_templates WHERE event_type = $1 AND enabled",
+  )
+  .bind(event_type)
+  .fetch_optional(&mut *conn)
+  .await?;
+  let Some(template) = template else {
+    return Ok(None);
+  };
+
+  let appointment = sqlx::query_as::<_, ZaloAppointment>(
+    r#"
+    SELECT a.user_id, u.full_name AS customer_name, u.phone, a.start_time, a.status,
+      a.total_price, t.full_name AS technician_name,
+      (
+        SELECT string_agg(aps.service_name, ', ' ORDER BY aps.sequence, aps.id)
+        FROM users.appointments_services aps
+        WHERE aps.appointment_id = a.id
+      ) AS services,
+      (
+        SELECT r.receipt_no FROM users.receipts r
+        WHERE r.appointment_id = a.id
+        ORDER BY r.issued_at DESC
+        LIMIT 1
+      ) AS receipt_no
+    FROM users.appointments a
+    LEFT JOIN users.tbl_users u ON u.pk_user_id = a.user_id
+    LEFT JOIN users.tbl_users t ON t.pk_user_id = a.technician_id
+    WHERE a.id = $1
+    "#,
+  )
+  .bind(appointment_id)
+  .fetch_optional(&mut *conn)
+  .await?
+  .ok_or(AppError::NotFound)?;
+
+  let (Some(user_id), Some(phone)) = (appointment.user_id, appointment.phone.clone()) else {
+    return Ok(None);
+  };
+
+  let notification_type = if event_type == "PAYMENT_RECEIPT" { "PAYMENT" } else { "APPOINTMENT" };
+  if !is_channel_enabled(conn, user_id, notification_type, "ZALO").await? {
+    return Ok(None);
+  }
+
+  let variables = serde_json::json!({
+    "appointment_id": appointment_id,
+    "customer_name": appointment.customer_name.unwrap_or_default(),
+    "phone": phone,
+    "start_time": appointment.start_time,
+    "status": appointment.status,
+    "services": appointment.services.unwrap_or_default(),
+    "technician_name": appointment.technician_name.unwrap_or_default(),
+    "total_price": format_number(appointment.total_price),
+    "receipt_no": appointment.receipt_no.unwrap_or_default(),
+  });
+
+  // Nhắc lịch chỉ một lần cho mỗi lịch hẹn (unique index), các sự kiện khác luôn được ghi
+  let id = sqlx::query_scalar::<_, i64>(
+    r#"
+    INSERT INTO users.zalo_messages (
+      event_type, appointment_id, user_id, phone, template_id, template_data
+    )
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT DO NOTHING
+    RETURNING id
+    "#,
+  )
+  .bind(event_type)
+  .bind(appointment_id)
+  .bind(user_id)
+  .bind(phone)
+  .bind(template.template_id)
+  .bind(render_params(&template.params, &variables))
+  .fetch_optional(&mut *conn)
+  .await?;
+
+  Ok(id)
+}
+
+/// Đưa tin nhắc lịch vào hàng đợi cho các lịch hẹn đã xác nhận sắp diễn ra trong reminder_hours giờ.
+/// Trả về số tin đã ghi.
+pub async fn enqueue_zalo_reminders(
+  db: &PgPool,
+  config: &ZaloConfig,
+) -> AppResult<usize> {
+  let appointment_ids = sqlx::query_scalar::<_, i64>(
+    r#"
+    SELECT a.id FROM users.appointments a
+    WHERE a.status = 'CONFIRMED'
+      AND TO_TIMESTAMP(a.start_time, 'HH24:MI DD/MM/YYYY')::timestamp AT TIME ZONE 'Asia/Ho_Chi_Minh'
+        BETWEEN NOW() AND NOW() + make_interval(hours => $1)
+      AND NOT EXISTS (
+        SELECT 1 FROM users.zalo_messages m
+        WHERE m.appointment_id = a.id AND m.event_type = 'APPOINTMENT_REMINDER'
+      )
+    "#,
+  )
+  .bind(config.reminder_hours as i32)
+  .fetch_all(db)
+  .await?;
+
+  let mut conn = db.acquire().await?;
+  let mut count = 0;
+  for appointment_id in appointment_ids {
+    if enqueue_zalo_message(&mut conn, appointment_id, "APPOINTMENT_REMINDER").await?.is_some() {
+      count += 1;
+    }
+  }
+
+  Ok(count)
+}
+
+fn retry_delay(
+  config: &ZaloConfig,
+  attempts: i32,
+) -> Duration {
+  let exponent = (attempts - 1).clamp(0, 10) as u32;
+
+  Duration::seconds(config.retry_base_seconds.max(1).saturating_mul(1 << exponent))
+}
+
+/// Lấy một lô tin ZNS đến hạn gửi (SKIP LOCKED để chạy được nhiều instance) và gửi qua Zalo.
+/// Trả về số tin đã xử lý.
+pub async fn dispatch_zalo_messages(
+  db: &PgPool,
+  config: &ZaloConfig,
+) -> AppResult<usize> {
+  sqlx::query(
+    r#"
+    UPDATE users.zalo_messages
+    SET status = 'PENDING', locked_at = NULL
+    WHERE status = 'PROCESSING' AND locked_at < $1
+    "#,
+  )
+  .bind(Utc::now() - Duration::minutes(STALE_LOCK_MINUTES))
+  .execute(db)
+  .await?;
+
+  let batch = sqlx::query_as::<_, ZaloMessage>(
+    r#"
+    UPDATE users.zalo_messages
+    SET status = 'PROCESSING', locked_at = NOW()
+    WHERE id IN (
+      SELECT id FROM users.zalo_messages
+      WHERE status = 'PENDING' AND next_attempt_at <= NOW()
+      ORDER BY next_attempt_at, id
+      LIMIT $1
+      FOR UPDATE SKIP LOCKED
+    )
+    RETURNING *
+    "#,
+  )
+  .bind(config.batch_size.max(1))
+  .fetch_all(db)
+  .await?;
+
+  if batch.is_empty() {
+    return Ok(0);
+  }
+
+  let service = ZaloService::try_new();
+  for message in &batch {
+    // tracking_id là id của tin trong hàng đợi để đối soát callback
+    let result = match (&service, message.template_id.as_deref()) {
+      (Some(service), Some(template_id)) => service
+        .send_template(
+          db,
+          &message.phone,
+          template_id,
+          message.template_data.clone().unwrap_or_default(),
+          &message.id.to_string(),
+        )
+        .await
+        .map_err(|err| err.to_string()),
+      (None, _) => Err("Zalo is not configured".to_string()),
+      (_, None) => Err("Zalo template is not configured".to_string()),
+    };
+
+    let attempts = message.attempts + 1;
+    match result {
+      // Callback có thể về trước khi kịp ghi SENT, khi đó giữ nguyên DELIVERED
+      Ok(msg_id) => {
+        sqlx::query(
+          r#"
+          UPDATE users.zalo_messages
+          SET status = CASE WHEN status = 'DELIVERED' THEN status ELSE 'SENT' END,
+              attempts = $2, msg_id = $3, sent_at = NOW(), locked_at = NULL, last_error = NULL
+          WHERE id = $1
+          "#,
+        )
+        .bind(message.id)
+        .bind(attempts)
+        .bind(msg_id)
+        .execute(db)
+        .await?;
+      },
+      Err(err) if attempts >= config.max_attempts => {
+        tracing::error!("Zalo message {} failed: {}", message.id, err);
+        sqlx::query(
+          r#"
+          UPDATE users.zalo_messages
+          SET status = 'FAILED', attempts = $2, locked_at = NULL, last_error = $3
+          WHERE id = $1
+          "#,
+        )
+        .bind(message.id)
+        .bind(attempts)
+        .bind(err)
+        .execute(db)
+        .await?;
+      },
+      Err(err) => {
+        sqlx::query(
+          r#"
+          UPDATE users.zalo_messages
+          SET status = 'PENDING', attempts = $2, locked_at = NULL, last_error = $3,
+              next_attempt_at = $4
+          WHERE id = $1
+          "#,
+        )
+        .bind(message.id)
+        .bind(attempts)
+        .bind(err)
+        .bind(Utc::now() + retry_delay(config, attempts))
+        .execute(db)
+        .await?;
+      },
+    }
+  }
+
+  Ok(batch.len())
+}
+
+#[async_trait]
+impl ZaloRepository for SqlxZaloRepository {
+  async fn list_templates(&self) -> AppResult<Vec<ZaloEventTemplate>> {
+    let templates =
+      sqlx::query_as::<_, ZaloEventTemplate>("SELECT * FROM users.zalo_templates ORDER BY id")
+        .fetch_all(&self.db)
+        .await?;
+
+    Ok(templates)
+  }
+
+  async fn update_template(
+    &self,
+    event_type: &str,
+    payload: UpdateZaloEventTemplateRequest,
+  ) -> AppResult<ZaloEventTemplate> {
+    let current = sqlx::query_as::<_, ZaloEventTemplate>(
+      "SELECT * FROM users.zalo_templates WHERE event_type = $1",
+    )
+    .bind(event_type)
+    .fetch_optional(&self.db)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let enabled = payload.enabled.unwrap_or(current.enabled);
+    if enabled && payload.template_id.is_none() && current.template_id.is_none() {
+      return Err(AppError::BadRequest("Template ID is required to enable this event".to_string()));
+    }
+
+    let template = sqlx::query_as::<_, ZaloEventTemplate>(
+      r#"
+      UPDATE users.zalo_templates
+      SET template_id = COALESCE($2, template_id),
+          params = COALESCE($3, params),
+          enabled = $4
+      WHERE event_type = $1
+      RETURNING *
+      "#,
+    )
+    .bind(event_type)
+    .bind(payload.template_id)
+    .bind(payload.params)
+    .bind(enabled)
+    .fetch_one(&self.db)
+    .await?;
+
+    Ok(template)
+  }
+
+  async fn list_messages(
+    &self,
+    filter: ZaloMessageFilter,
+  ) -> AppResult<Vec<ZaloMessage>> {
+    let messages = sqlx::query_as::<_, ZaloMessage>(
+      r#"
+      SELECT * FROM users.zalo_messages
+      WHERE ($1::text IS NULL OR status = $1)
+        AND ($2::text IS NULL OR event_type = $2)
+        AND ($3::int8 IS NULL OR appointment_id = $3)
+      ORDER BY created_at DESC
+      LIMIT 200
+      "#,
+    )
+    .bind(filter.status)
+    .bind(filter.event_type)
+    .bind(filter.appointment_id)
+    .fetch_all(&self.db)
+    .await?;
+
+    Ok(messages)
+  }
+
+  async fn record_callback(
+    &self,
+    event: ZaloWebhookEvent,
+    payload: serde_json::Value,
+  ) -> AppResult<()> {
+    let msg_id = event.message.as_ref().and_then(|message| message.msg_id.clone());
+    let tracking_id = event.message.as_ref().and_then(|message| message.tracking_id.clone());
+
+    let mut tx = self.db.begin().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    let message_id = sqlx::query_scalar::<_, i64>(
+      r#"
+      SELECT id FROM users.zalo_messages
+      WHERE msg_id = $1 OR id = $2
+      ORDER BY (msg_id = $1) DESC NULLS LAST
+      LIMIT 1
+      "#,
+    )
+    .bind(&msg_id)
+    .bind(tracking_id.as_deref().and_then(|id| id.parse::<i64>().ok()))
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    sqlx::query(
+      r#"
+      INSERT INTO users.zalo_callbacks (event_name, msg_id, tracking_id, message_id, payload)
+      VALUES ($1, $2, $3, $4, $5)
+      "#,
+    )
+    .bind(&event.event_name)
+    .bind(&msg_id)
+    .bind(tracking_id)
+    .bind(message_id)
+    .bind(payload)
+    .execute(&mut *tx)
+    .await?;
+
+    if event.event_name == DELIVERED_EVENT {
+      sqlx::query(
+        r#"
+        UPDATE users.zalo_messages
+        SET status = 'DELIVERED', delivered_at = NOW(), msg_id = COALESCE(msg_id, $2)
+        WHERE id = $1 AND status IN ('PROCESSING', 'SENT')
+        "#,
+      )
+      .bind(message_id)
+      .bind(msg_id)
+      .execute(&mut *tx)
+      .await?;
+    }
+
+    tx.commit().await.map_err(|err| AppError::Unhandled(Box::new(err)))?;
+
+    Ok(())
+  }
+}
@@ -1,17 +1,151 @@
 pub fn format_number(number: i64) -> String {
   let mut s = number.to_string();
-  let length = s.len();
-  let mut pos: usize = length;
+  let mut pos: usize = s.len();
 
-  // Xử lý số âm (bỏ qua dấu '-' nếu có)
-  if number < 0 {
-    pos -= 1; // Tránh đặt dấu chấm ngay sau dấu âm
-  }
+  // Xử lý số âm: không tính dấu '-' vào nhóm chữ số để tránh đặt dấu chấm ngay sau dấu âm
+  let sign = usize::from(number < 0);
 
   // Chèn dấu chấm từ phải sang trái mỗi 3 chữ số
-  while pos > 3 {
+  while pos > sign + 3 {
     pos -= 3;
     s.insert(pos, '.');
   }
   s
 }
+
+const DIGIT_WORDS: [&str; 10] =
+  ["không", "một", "hai", "ba", "bốn", "năm", "sáu", "bảy", "tám", "chín"];
+
+// Đọc nhóm 3 chữ số; `full` = true khi có nhóm đứng trước nên phải đọc cả "không trăm", "linh"
+fn read_three_digits(
+  number: u64,
+  full: bool,
+) -> String {
+  let hundreds = (number / 100) as usize;
+  let tens = ((number / 10) % 10) as usize;
+  let units = (number % 10) as usize;
+  let mut words: Vec<&str> = Vec::new();
+
+  if hundreds > 0 || full {
+    words.push(DIGIT_WORDS[hundreds]);
+    words.push("trăm");
+  }
+
+  match tens {
+    0 if units > 0 && (hundreds > 0 || full) => words.push("linh"),
+    0 => {},
+    1 => words.push("mười"),
+    _ => {
+      words.push(DIGIT_WORDS[tens]);
+      words.push("mươi");
+    },
+  }
+
+  match units {
+    0 => {},
+    1 if tens >= 2 => words.push("mốt"),
+    5 if tens >= 1 => words.push("lăm"),
+    _ => words.push(DIGIT_WORDS[units]),
+  }
+
+  words.join(" ")
+}
+
+// Đọc số tiền bằng chữ theo cách ghi trên hoá đơn, ví dụ 1.250.000 -> "Một triệu hai trăm năm mươi nghìn đồng"
+pub fn number_to_vietnamese_words(number: i64) -> String {
+  if number == 0 {
+    return "Không đồng".to_string();
+  }
+
+  let scales = ["", "nghìn", "triệu", "tỷ"];
+  let mut groups = Vec::new();
+  let mut rest = number.unsigned_abs();
+  while rest > 0 {
+    groups.push(rest % 1000);
+    rest /= 1000;
+  }
+
+  let mut words: Vec<String> = Vec::new();
+  for (index, group) in groups.iter().enumerate().rev() {
+    if *group == 0 {
+      // Nhóm tỷ vẫn phải đọc để nối với các nhóm lớn hơn (nghìn tỷ, triệu tỷ)
+      if index % 3 == 0 && index > 0 && words.last().is_some_and(|word| word != "tỷ") {
+        words.push("tỷ".to_string());
+      }
+      continue;
+    }
+
+    words.push(read_three_digits(*group, index + 1 < groups.len()));
+    let scale = match index {
+      0 => "",
+      index if index % 3 == 0 => "tỷ",
+      index => scales[index % 3],
+    };
+    if !scale.is_empty() {
+      words.push(scale.to_string());
+    }
+  }
+
+  let mut text = words.join(" ");
+  if number < 0 {
+    text = format!("âm {}", text);
+  }
+
+  let mut chars = text.chars();
+  match chars.next() {
+    Some(first) => format!("{}{} đồng", first.to_uppercase(), chars.as_str()),
+    None => text,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn format_number_groups_thousands() {
+    let cases = [
+      (0, "0"),
+      (999, "999"),
+      (1_000, "1.000"),
+      (1_250_000, "1.250.000"),
+      (-999, "-999"),
+      (-350_000, "-350.000"),
+      (-1_250_000, "-1.250.000"),
+    ];
+
+    for (number, expected) in cases {
+      assert_eq!(format_number(number), expected, "{}", number);
+    }
+  }
+
+  #[test]
+  fn number_to_vietnamese_words_cases() {
+    let cases = [
+      (0, "Không đồng"),
+      (5, "Năm đồng"),
+      (10, "Mười đồng"),
+      (15, "Mười lăm đồng"),
+      (21, "Hai mươi mốt đồng"),
+      (25, "Hai mươi lăm đồng"),
+      (101, "Một trăm linh một đồng"),
+      (105, "Một trăm linh năm đồng"),
+      (115, "Một trăm mười lăm đồng"),
+      (1_001, "Một nghìn không trăm linh một đồng"),
+      (1_021, "Một nghìn không trăm hai mươi mốt đồng"),
+      (15_000, "Mười lăm nghìn đồng"),
+      (100_500, "Một trăm nghìn năm trăm đồng"),
+      (1_250_000, "Một triệu hai trăm năm mươi nghìn đồng"),
+      (1_005_000, "Một triệu không trăm linh năm nghìn đồng"),
+      (1_000_000_000, "Một tỷ đồng"),
+      (1_000_000_000_000, "Một nghìn tỷ đồng"),
+      (2_000_001_000_000, "Hai nghìn tỷ không trăm linh một triệu đồng"),
+      (1_000_000_000_000_000, "Một triệu tỷ đồng"),
+      (-250_000, "Âm hai trăm năm mươi nghìn đồng"),
+    ];
+
+    for (number, expected) in cases {
+      assert_eq!(number_to_vietnamese_words(number), expected, "{}", number);
+    }
+  }
+}
@@ -1,6 +1,10 @@
 use core_app::{AppResult, errors::AppError};
+use hmac::{Hmac, Mac};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
-use rand::random_range;
+use rand::{random, random_range};
+use sha2::Sha256;
+use std::net::IpAddr;
+use unicode_normalization::UnicodeNormalization;
 
 pub fn generate_phone_code() -> String {
   let code: u32 = random_range(100_000..=999_999); // 6 chữ số
@@ -23,3 +27,127 @@ pub fn decode_token<T: for<'de> serde::Deserialize<'de>>(
     .map(|data| data.claims)
     .map_err(|err| AppError::BadRequest(err.to_string()))
 }
+
+// Secret ngẫu nhiên 32 byte dạng hex, dùng ký webhook
+pub fn generate_secret() -> String {
+  hex::encode(random::<[u8; 32]>())
+}
+
+// Chữ ký HMAC-SHA256 dạng hex
+pub fn sign_hmac_sha256(
+  secret: &str,
+  payload: &[u8],
+) -> String {
+  let mut mac =
+    Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+  mac.update(payload);
+  hex::encode(mac.finalize().into_bytes())
+}
+
+// Kiểm tra chữ ký HMAC-SHA256 (hex), so sánh constant-time
+pub fn verify_hmac_sha256(
+  secret: &str,
+  payload: &[u8],
+  signature: &str,
+) -> bool {
+  let Ok(signature) = hex::decode(signature.trim()) else {
+    return false;
+  };
+
+  let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+    return false;
+  };
+  mac.update(payload);
+  mac.verify_slice(&signature).is_ok()
+}
+
+// Kiểm tra chữ ký SHA-256 (hex) của payload, so sánh constant-time
+pub fn verify_sha256(
+  payload: &[u8],
+  signature: &str,
+) -> bool {
+  let Ok(signature) = hex::decode(signature.trim()) else {
+    return false;
+  };
+
+  let digest = <Sha256 as sha2::Digest>::digest(payload);
+  digest.len() == signature.len()
+    && digest.iter().zip(&signature).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+// Địa chỉ định tuyến công khai: loại loopback, mạng nội bộ, link-local (metadata cloud), CGNAT,
+// multicast và các dải dành riêng, dùng để chặn webhook gọi vào hạ tầng nội bộ
+pub fn is_public_ip(ip: IpAddr) -> bool {
+  match ip {
+    IpAddr::V4(ip) => {
+      let [a, b, ..] = ip.octets();
+      !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (b == 18 || b == 19))
+        || a >= 240)
+    },
+    IpAddr::V6(ip) => {
+      if let Some(ip) = ip.to_ipv4_mapped() {
+        return is_public_ip(IpAddr::V4(ip));
+      }
+      let [first, second, ..] = ip.segments();
+      !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && second == 0x0db8))
+    },
+  }
+}
+
+// Bỏ dấu tiếng Việt cho máy in nhiệt / font PDF chỉ hỗ trợ ASCII
+pub fn remove_vietnamese_accents(text: &str) -> String {
+  text
+    .nfd()
+    .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+    .map(|c| match c {
+      'đ' => 'd',
+      'Đ' => 'D',
+      c => c,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rejects_internal_addresses() {
+    for ip in [
+      "127.0.0.1",
+      "10.1.2.3",
+      "172.16.0.1",
+      "192.168.1.10",
+      "169.254.169.254",
+      "100.64.0.1",
+      "0.0.0.0",
+      "255.255.255.255",
+      "::1",
+      "::",
+      "fd00::1",
+      "fe80::1",
+      "::ffff:127.0.0.1",
+      "::ffff:10.0.0.1",
+    ] {
+      assert!(!is_public_ip(ip.parse().unwrap()), "{} should not be public", ip);
+    }
+
+    for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111", "::ffff:8.8.8.8"] {
+      assert!(is_public_ip(ip.parse().unwrap()), "{} should be public", ip);
+    }
+  }
+}
@@ -3,3 +3,4 @@ pub mod format_number;
 pub mod helper;
 pub mod password;
 pub mod pre_process;
+pub mod vietqr;
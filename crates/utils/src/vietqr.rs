@@ -0,0 +1,67 @@
+use rand::random_range;
+
+// Định danh NAPAS cho chuyển khoản nhanh 24/7 tới tài khoản
+const NAPAS_GUID: &str = "A000000727";
+const SERVICE_CODE_ACCOUNT: &str = "QRIBFTTA";
+const MEMO_CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+fn tlv(
+  id: &str,
+  value: &str,
+) -> String {
+  format!("{}{:02}{}", id, value.len(), value)
+}
+
+// CRC-16/CCITT-FALSE theo chuẩn EMVCo (poly 0x1021, init 0xFFFF)
+fn crc16(data: &[u8]) -> u16 {
+  let mut crc: u16 = 0xFFFF;
+  for byte in data {
+    crc ^= (*byte as u16) << 8;
+    for _ in 0..8 {
+      crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+    }
+  }
+  crc
+}
+
+// Tạo chuỗi VietQR (EMVCo) để app ngân hàng quét, đã điền sẵn số tiền và nội dung chuyển khoản
+pub fn build_vietqr_payload(
+  bank_bin: &str,
+  account_number: &str,
+  amount: i64,
+  memo: &str,
+) -> String {
+  let beneficiary = format!("{}{}", tlv("00", bank_bin), tlv("01", account_number));
+  let merchant_account = format!(
+    "{}{}{}",
+    tlv("00", NAPAS_GUID),
+    tlv("01", &beneficiary),
+    tlv("02", SERVICE_CODE_ACCOUNT)
+  );
+
+  let mut payload = String::new();
+  payload.push_str(&tlv("00", "01"));
+  // 12: QR động, dùng một lần cho một giao dịch
+  payload.push_str(&tlv("01", "12"));
+  payload.push_str(&tlv("38", &merchant_account));
+  payload.push_str(&tlv("53", "704"));
+  payload.push_str(&tlv("54", &amount.to_string()));
+  payload.push_str(&tlv("58", "VN"));
+  payload.push_str(&tlv("62", &tlv("08", memo)));
+  payload.push_str("6304");
+
+  let crc = crc16(payload.as_bytes());
+  format!("{}{:04X}", payload, crc)
+}
+
+// Nội dung chuyển khoản dạng SPAxxxxxxxx, chỉ gồm chữ hoa và số để ngân hàng không cắt bỏ
+pub fn generate_transfer_memo() -> String {
+  let code: String =
+    (0..8).map(|_| MEMO_CHARSET[random_range(0..MEMO_CHARSET.len())] as char).collect();
+  format!("SPA{}", code)
+}
+
+// Bỏ dấu cách, ký tự đặc biệt trong nội dung chuyển khoản ngân hàng gửi về để so khớp memo
+pub fn normalize_transfer_description(description: &str) -> String {
+  description.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_uppercase()
+}
@@ -0,0 +1,11 @@
+-- Add down migration script here
+ALTER TABLE "users"."appointments_services"
+DROP CONSTRAINT IF EXISTS appointments_services_quantity_check,
+ALTER COLUMN quantity DROP NOT NULL,
+ALTER COLUMN sequence DROP NOT NULL;
+
+ALTER TABLE "users"."appointments_services"
+DROP COLUMN IF EXISTS service_name,
+DROP COLUMN IF EXISTS service_name_en,
+DROP COLUMN IF EXISTS unit_price,
+DROP COLUMN IF EXISTS discount;
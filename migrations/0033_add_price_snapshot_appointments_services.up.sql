@@ -0,0 +1,22 @@
+-- Add up migration script here
+-- Lưu lại giá, tên dịch vụ tại thời điểm đặt lịch
+ALTER TABLE "users"."appointments_services"
+ADD COLUMN service_name VARCHAR(100),
+ADD COLUMN service_name_en TEXT,
+ADD COLUMN unit_price INT8 NOT NULL DEFAULT 0 CHECK (unit_price >= 0),
+ADD COLUMN discount INT8 NOT NULL DEFAULT 0 CHECK (discount >= 0);
+
+UPDATE "users"."appointments_services" aps
+SET service_name = s.service_name,
+    service_name_en = s.service_name_en,
+    unit_price = s.price
+FROM "users"."service_items" s
+WHERE aps.service_id = s.id;
+
+UPDATE "users"."appointments_services" SET quantity = 1 WHERE quantity IS NULL;
+UPDATE "users"."appointments_services" SET sequence = 1 WHERE sequence IS NULL;
+
+ALTER TABLE "users"."appointments_services"
+ALTER COLUMN quantity SET NOT NULL,
+ALTER COLUMN sequence SET NOT NULL,
+ADD CONSTRAINT appointments_services_quantity_check CHECK (quantity > 0);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS idx_appointments_services_technician_status;
+
+ALTER TABLE "users"."appointments_services"
+DROP COLUMN IF EXISTS start_time,
+DROP COLUMN IF EXISTS end_time,
+DROP COLUMN IF EXISTS status;
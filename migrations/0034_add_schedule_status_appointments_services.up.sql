@@ -0,0 +1,19 @@
+-- Add up migration script here
+-- Mỗi dịch vụ trong lịch hẹn có thời gian và trạng thái riêng
+ALTER TABLE "users"."appointments_services"
+ADD COLUMN start_time VARCHAR(30),
+ADD COLUMN end_time VARCHAR(30),
+ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'IN_PROGRESS', 'COMPLETED', 'CANCELLED'));
+
+UPDATE "users"."appointments_services" aps
+SET start_time = a.start_time,
+    end_time = a.end_time,
+    status = CASE
+      WHEN a.status IN ('COMPLETED', 'PAYMENT') THEN 'COMPLETED'
+      WHEN a.status IN ('IN_PROGRESS', 'CANCELLED') THEN a.status
+      ELSE 'PENDING'
+    END
+FROM "users"."appointments" a
+WHERE aps.appointment_id = a.id;
+
+CREATE INDEX IF NOT EXISTS idx_appointments_services_technician_status ON "users"."appointments_services" (technician_id, status);
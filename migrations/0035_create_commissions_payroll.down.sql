@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."commissions";
+DROP TABLE IF EXISTS "users"."payroll_periods";
+DROP TABLE IF EXISTS "users"."commission_rules";
+
+ALTER TABLE "users"."tbl_users"
+DROP COLUMN IF EXISTS technician_level;
@@ -0,0 +1,69 @@
+-- Add up migration script here
+-- Cấp bậc kỹ thuật viên dùng để áp quy tắc hoa hồng
+ALTER TABLE "users"."tbl_users"
+ADD COLUMN technician_level VARCHAR(50);
+
+-- Quy tắc hoa hồng: service_id / technician_level NULL nghĩa là áp dụng cho tất cả
+CREATE TABLE IF NOT EXISTS "users"."commission_rules" (
+    id BIGSERIAL PRIMARY KEY,
+    service_id BIGINT REFERENCES users.service_items(id) ON DELETE CASCADE,
+    technician_level VARCHAR(50),
+    commission_type VARCHAR(20) NOT NULL CHECK (commission_type IN ('PERCENTAGE', 'FIXED')),
+    value BIGINT NOT NULL CHECK (value >= 0),
+    include_tips BOOLEAN NOT NULL DEFAULT TRUE,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_commission_rules_service_id ON users.commission_rules(service_id);
+
+CREATE TRIGGER update_commission_rule_timestamp
+    BEFORE UPDATE ON "users"."commission_rules"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Kỳ lương theo tháng (YYYY-MM), kỳ đã khoá thì không ghi thêm vào được
+CREATE TABLE IF NOT EXISTS "users"."payroll_periods" (
+    id BIGSERIAL PRIMARY KEY,
+    period VARCHAR(7) NOT NULL UNIQUE,
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN' CHECK (status IN ('OPEN', 'LOCKED')),
+    locked_by BIGINT REFERENCES users.tbl_users(pk_user_id),
+    locked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_payroll_period_timestamp
+    BEFORE UPDATE ON "users"."payroll_periods"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Hoa hồng của kỹ thuật viên theo từng dịch vụ trong lịch hẹn, hoặc bút toán điều chỉnh
+CREATE TABLE IF NOT EXISTS "users"."commissions" (
+    id BIGSERIAL PRIMARY KEY,
+    technician_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    appointment_id BIGINT REFERENCES users.appointments(id) ON DELETE SET NULL,
+    appointment_service_id BIGINT REFERENCES users.appointments_services(id) ON DELETE SET NULL,
+    rule_id BIGINT REFERENCES users.commission_rules(id) ON DELETE SET NULL,
+    period VARCHAR(7) NOT NULL,
+    entry_type VARCHAR(20) NOT NULL DEFAULT 'COMMISSION' CHECK (entry_type IN ('COMMISSION', 'ADJUSTMENT')),
+    service_name VARCHAR(100),
+    base_amount BIGINT NOT NULL DEFAULT 0,
+    tip_amount BIGINT NOT NULL DEFAULT 0,
+    commission_amount BIGINT NOT NULL DEFAULT 0,
+    notes TEXT,
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_commissions_period_technician ON users.commissions(period, technician_id);
+CREATE UNIQUE INDEX idx_commissions_appointment_service ON users.commissions(appointment_service_id)
+    WHERE entry_type = 'COMMISSION';
+
+CREATE TRIGGER update_commission_timestamp
+    BEFORE UPDATE ON "users"."commissions"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
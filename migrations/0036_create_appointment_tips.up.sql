@@ -0,0 +1,20 @@
+-- Add up migration script here
+-- Tiền tip của khách cho kỹ thuật viên, tách riêng khỏi doanh thu dịch vụ
+CREATE TABLE IF NOT EXISTS "users"."appointment_tips" (
+    id BIGSERIAL PRIMARY KEY,
+    appointment_id BIGINT NOT NULL REFERENCES users.appointments(id) ON DELETE CASCADE,
+    technician_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    payment_method VARCHAR(50) NOT NULL DEFAULT 'CASH' CHECK (payment_method IN ('WALLET', 'CASH')),
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_appointment_tips_appointment_id ON users.appointment_tips(appointment_id);
+CREATE INDEX idx_appointment_tips_technician_id ON users.appointment_tips(technician_id);
+
+CREATE TRIGGER update_appointment_tip_timestamp
+    BEFORE UPDATE ON "users"."appointment_tips"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
@@ -0,0 +1,21 @@
+-- Add up migration script here
+-- Mỗi hình thức thanh toán của lịch hẹn được ghi thành một dòng riêng
+CREATE TABLE IF NOT EXISTS "users"."appointment_payments" (
+    id BIGSERIAL PRIMARY KEY,
+    appointment_id BIGINT NOT NULL REFERENCES users.appointments(id) ON DELETE CASCADE,
+    tender_type VARCHAR(30) NOT NULL CHECK (tender_type IN ('WALLET', 'CASH', 'BANK_TRANSFER', 'CARD', 'POINTS', 'PACKAGE_SESSION', 'VOUCHER')),
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    reference VARCHAR(100),
+    deposit_id BIGINT REFERENCES users.deposits(id) ON DELETE SET NULL,
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_appointment_payments_appointment_id ON users.appointment_payments(appointment_id);
+CREATE INDEX idx_appointment_payments_tender_type ON users.appointment_payments(tender_type);
+
+CREATE TRIGGER update_appointment_payment_timestamp
+    BEFORE UPDATE ON "users"."appointment_payments"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
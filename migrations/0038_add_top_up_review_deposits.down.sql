@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE "users"."deposits"
+DROP COLUMN IF EXISTS proof_image,
+DROP COLUMN IF EXISTS rejection_reason,
+DROP COLUMN IF EXISTS reviewed_by,
+DROP COLUMN IF EXISTS reviewed_at;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- Khách hàng tự tạo yêu cầu nạp tiền kèm ảnh chuyển khoản, nhân viên duyệt hoặc từ chối
+ALTER TABLE "users"."deposits"
+ADD COLUMN proof_image TEXT,
+ADD COLUMN rejection_reason TEXT,
+ADD COLUMN reviewed_by BIGINT REFERENCES users.tbl_users(pk_user_id),
+ADD COLUMN reviewed_at TIMESTAMPTZ;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."bank_transactions";
+DROP TABLE IF EXISTS "users"."payment_intents";
@@ -0,0 +1,50 @@
+-- Add up migration script here
+-- Yêu cầu thanh toán chuyển khoản VietQR, mỗi yêu cầu có một nội dung chuyển khoản (memo) riêng
+CREATE TABLE IF NOT EXISTS "users"."payment_intents" (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    intent_type VARCHAR(20) NOT NULL CHECK (intent_type IN ('TOP_UP', 'APPOINTMENT')),
+    appointment_id BIGINT REFERENCES users.appointments(id) ON DELETE SET NULL,
+    deposit_id BIGINT REFERENCES users.deposits(id) ON DELETE SET NULL,
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    memo VARCHAR(50) NOT NULL UNIQUE,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'PROCESSING', 'COMPLETED', 'MISMATCH', 'CANCELLED')),
+    expires_at TIMESTAMPTZ NOT NULL,
+    paid_at TIMESTAMPTZ,
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_payment_intents_status ON users.payment_intents(status);
+CREATE INDEX idx_payment_intents_appointment_id ON users.payment_intents(appointment_id);
+
+CREATE TRIGGER update_payment_intent_timestamp
+    BEFORE UPDATE ON "users"."payment_intents"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Giao dịch chuyển khoản nhận từ webhook ngân hàng, lưu lại toàn bộ để đối soát
+CREATE TABLE IF NOT EXISTS "users"."bank_transactions" (
+    id BIGSERIAL PRIMARY KEY,
+    provider VARCHAR(50) NOT NULL,
+    provider_transaction_id VARCHAR(100) NOT NULL,
+    amount BIGINT NOT NULL,
+    description TEXT NOT NULL,
+    account_number VARCHAR(50),
+    transaction_time TIMESTAMPTZ,
+    payment_intent_id BIGINT REFERENCES users.payment_intents(id) ON DELETE SET NULL,
+    match_status VARCHAR(20) NOT NULL DEFAULT 'UNMATCHED' CHECK (match_status IN ('MATCHED', 'MISMATCH', 'UNMATCHED')),
+    match_note TEXT,
+    raw_payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, provider_transaction_id)
+);
+
+CREATE INDEX idx_bank_transactions_match_status ON users.bank_transactions(match_status);
+
+CREATE TRIGGER update_bank_transaction_timestamp
+    BEFORE UPDATE ON "users"."bank_transactions"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
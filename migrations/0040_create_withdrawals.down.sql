@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS users.idx_deposits_user_type_status;
+
+ALTER TABLE "users"."deposits"
+DROP COLUMN IF EXISTS bank_name,
+DROP COLUMN IF EXISTS bank_account_number,
+DROP COLUMN IF EXISTS bank_account_name;
+
+DROP TABLE IF EXISTS "users"."bank_accounts";
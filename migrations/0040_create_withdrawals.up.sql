@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- Tài khoản ngân hàng khách hàng dùng để nhận tiền rút từ ví
+CREATE TABLE IF NOT EXISTS "users"."bank_accounts" (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL UNIQUE REFERENCES users.tbl_users(pk_user_id) ON DELETE CASCADE,
+    bank_name VARCHAR(100) NOT NULL,
+    account_number VARCHAR(50) NOT NULL,
+    account_name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_bank_account_timestamp
+    BEFORE UPDATE ON "users"."bank_accounts"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Lưu lại tài khoản nhận tại thời điểm yêu cầu rút tiền, mã chuyển khoản trả lại nằm ở transaction_id
+ALTER TABLE "users"."deposits"
+ADD COLUMN bank_name VARCHAR(100),
+ADD COLUMN bank_account_number VARCHAR(50),
+ADD COLUMN bank_account_name VARCHAR(100);
+
+CREATE INDEX idx_deposits_user_type_status ON users.deposits(user_id, deposit_type, status);
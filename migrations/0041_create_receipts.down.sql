@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."receipts";
+DROP TABLE IF EXISTS "users"."receipt_counters";
@@ -0,0 +1,23 @@
+-- Add up migration script here
+-- Bộ đếm số hoá đơn theo năm, cấp số trong cùng transaction thanh toán nên không bị nhảy số
+CREATE TABLE IF NOT EXISTS "users"."receipt_counters" (
+    year INT PRIMARY KEY,
+    last_number BIGINT NOT NULL DEFAULT 0
+);
+
+-- Hoá đơn của lịch hẹn đã thanh toán, snapshot giữ nguyên nội dung tại thời điểm xuất
+CREATE TABLE IF NOT EXISTS "users"."receipts" (
+    id BIGSERIAL PRIMARY KEY,
+    appointment_id BIGINT UNIQUE REFERENCES users.appointments(id) ON DELETE SET NULL,
+    receipt_no VARCHAR(30) NOT NULL UNIQUE,
+    snapshot JSONB NOT NULL,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_receipt_timestamp
+    BEFORE UPDATE ON "users"."receipts"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."invoice_lines";
+DROP TABLE IF EXISTS "users"."invoices";
+DROP TABLE IF EXISTS "users"."invoice_counters";
+DROP FUNCTION IF EXISTS "users".prevent_invoice_line_change();
+DROP FUNCTION IF EXISTS "users".prevent_invoice_change();
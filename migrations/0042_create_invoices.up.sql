@@ -0,0 +1,109 @@
+-- Add up migration script here
+-- Bộ đếm số hoá đơn điện tử theo ký hiệu (ký hiệu chứa năm nên mỗi năm đánh số lại từ 1)
+CREATE TABLE IF NOT EXISTS "users"."invoice_counters" (
+    symbol VARCHAR(10) PRIMARY KEY,
+    last_number BIGINT NOT NULL DEFAULT 0
+);
+
+-- Hoá đơn GTGT cho khách doanh nghiệp, đã xuất thì không được sửa nội dung
+CREATE TABLE IF NOT EXISTS "users"."invoices" (
+    id BIGSERIAL PRIMARY KEY,
+    invoice_type VARCHAR(20) NOT NULL DEFAULT 'ORIGINAL' CHECK (invoice_type IN ('ORIGINAL', 'REPLACEMENT', 'ADJUSTMENT')),
+    original_invoice_id BIGINT REFERENCES users.invoices(id) ON DELETE RESTRICT,
+    template_code VARCHAR(1) NOT NULL DEFAULT '1',
+    symbol VARCHAR(10) NOT NULL,
+    invoice_number BIGINT NOT NULL,
+    issued_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'ISSUED' CHECK (status IN ('ISSUED', 'REPLACED')),
+    buyer_tax_code VARCHAR(20) NOT NULL,
+    buyer_company_name VARCHAR(255) NOT NULL,
+    buyer_address TEXT NOT NULL,
+    buyer_name VARCHAR(150),
+    buyer_email VARCHAR(255),
+    payment_method VARCHAR(20) NOT NULL DEFAULT 'TM/CK',
+    total_before_tax BIGINT NOT NULL,
+    total_vat BIGINT NOT NULL,
+    total_amount BIGINT NOT NULL,
+    amount_in_words TEXT NOT NULL,
+    reason TEXT,
+    xml_content TEXT NOT NULL,
+    submission_status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (submission_status IN ('PENDING', 'SUBMITTED', 'FAILED')),
+    provider VARCHAR(50),
+    provider_reference TEXT,
+    submission_error TEXT,
+    submitted_at TIMESTAMPTZ,
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (symbol, invoice_number)
+);
+
+-- amount, vat_amount, total có thể âm với dòng chiết khấu hoặc hoá đơn điều chỉnh giảm
+CREATE TABLE IF NOT EXISTS "users"."invoice_lines" (
+    id BIGSERIAL PRIMARY KEY,
+    invoice_id BIGINT NOT NULL REFERENCES users.invoices(id) ON DELETE CASCADE,
+    line_no INT NOT NULL,
+    line_type VARCHAR(20) NOT NULL CHECK (line_type IN ('SERVICE', 'SURCHARGE', 'DISCOUNT', 'ADJUSTMENT')),
+    appointment_id BIGINT REFERENCES users.appointments(id) ON DELETE RESTRICT,
+    appointment_service_id BIGINT REFERENCES users.appointments_services(id) ON DELETE RESTRICT,
+    description TEXT NOT NULL,
+    unit VARCHAR(20) NOT NULL DEFAULT 'Lượt',
+    quantity INT NOT NULL,
+    unit_price BIGINT NOT NULL,
+    amount BIGINT NOT NULL,
+    vat_rate INT NOT NULL CHECK (vat_rate IN (0, 5, 8, 10)),
+    vat_amount BIGINT NOT NULL,
+    total BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_invoices_original_invoice_id ON users.invoices(original_invoice_id);
+CREATE INDEX idx_invoices_buyer_tax_code ON users.invoices(buyer_tax_code);
+CREATE INDEX idx_invoice_lines_invoice_id ON users.invoice_lines(invoice_id);
+CREATE INDEX idx_invoice_lines_appointment_id ON users.invoice_lines(appointment_id);
+
+-- Hoá đơn đã xuất chỉ được đổi trạng thái và thông tin gửi nhà cung cấp
+CREATE OR REPLACE FUNCTION "users".prevent_invoice_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        RAISE EXCEPTION 'Issued invoices cannot be deleted';
+    END IF;
+
+    IF (OLD.invoice_type, OLD.original_invoice_id, OLD.template_code, OLD.symbol, OLD.invoice_number,
+        OLD.issued_date, OLD.buyer_tax_code, OLD.buyer_company_name, OLD.buyer_address, OLD.buyer_name,
+        OLD.buyer_email, OLD.payment_method, OLD.total_before_tax, OLD.total_vat, OLD.total_amount,
+        OLD.amount_in_words, OLD.reason, OLD.xml_content, OLD.created_by)
+       IS DISTINCT FROM
+       (NEW.invoice_type, NEW.original_invoice_id, NEW.template_code, NEW.symbol, NEW.invoice_number,
+        NEW.issued_date, NEW.buyer_tax_code, NEW.buyer_company_name, NEW.buyer_address, NEW.buyer_name,
+        NEW.buyer_email, NEW.payment_method, NEW.total_before_tax, NEW.total_vat, NEW.total_amount,
+        NEW.amount_in_words, NEW.reason, NEW.xml_content, NEW.created_by) THEN
+        RAISE EXCEPTION 'Issued invoices cannot be modified, issue a replacement or adjustment invoice';
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION "users".prevent_invoice_line_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'Invoice lines cannot be modified';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_invoice_change
+    BEFORE UPDATE OR DELETE ON "users"."invoices"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".prevent_invoice_change();
+
+CREATE TRIGGER prevent_invoice_line_change
+    BEFORE UPDATE OR DELETE ON "users"."invoice_lines"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".prevent_invoice_line_change();
+
+CREATE TRIGGER update_invoice_timestamp
+    BEFORE UPDATE ON "users"."invoices"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
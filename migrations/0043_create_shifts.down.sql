@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS users.idx_appointment_payments_created_by;
+DROP TABLE IF EXISTS "users"."shift_tenders";
+DROP TABLE IF EXISTS "users"."shifts";
+DROP FUNCTION IF EXISTS "users".prevent_shift_tender_change();
+DROP FUNCTION IF EXISTS "users".prevent_closed_shift_change();
@@ -0,0 +1,76 @@
+-- Add up migration script here
+-- Ca làm việc của lễ tân, mỗi lễ tân chỉ có một ca đang mở
+CREATE TABLE IF NOT EXISTS "users"."shifts" (
+    id BIGSERIAL PRIMARY KEY,
+    receptionist_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN' CHECK (status IN ('OPEN', 'CLOSED')),
+    opening_float BIGINT NOT NULL DEFAULT 0 CHECK (opening_float >= 0),
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    closed_at TIMESTAMPTZ,
+    closed_by BIGINT REFERENCES users.tbl_users(pk_user_id),
+    total_expected BIGINT,
+    total_counted BIGINT,
+    total_variance BIGINT,
+    notes TEXT,
+    closing_notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_shifts_open_receptionist ON users.shifts(receptionist_id) WHERE status = 'OPEN';
+CREATE INDEX idx_shifts_opened_at ON users.shifts(opened_at);
+
+-- Số liệu từng hình thức thanh toán chốt lúc đóng ca
+CREATE TABLE IF NOT EXISTS "users"."shift_tenders" (
+    id BIGSERIAL PRIMARY KEY,
+    shift_id BIGINT NOT NULL REFERENCES users.shifts(id) ON DELETE CASCADE,
+    tender_type VARCHAR(30) NOT NULL,
+    payment_amount BIGINT NOT NULL DEFAULT 0,
+    deposit_amount BIGINT NOT NULL DEFAULT 0,
+    tip_amount BIGINT NOT NULL DEFAULT 0,
+    expected_amount BIGINT NOT NULL,
+    counted_amount BIGINT,
+    variance BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (shift_id, tender_type)
+);
+
+CREATE INDEX idx_appointment_payments_created_by ON users.appointment_payments(created_by, created_at);
+
+CREATE TRIGGER update_shift_timestamp
+    BEFORE UPDATE ON "users"."shifts"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Ca đã đóng thì khoá, không cho sửa hoặc xoá số liệu kiểm két
+CREATE OR REPLACE FUNCTION "users".prevent_closed_shift_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.status = 'CLOSED' THEN
+        RAISE EXCEPTION 'Closed shift % cannot be modified', OLD.id;
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION "users".prevent_shift_tender_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'Shift tenders cannot be modified';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_closed_shift_change
+    BEFORE UPDATE OR DELETE ON "users"."shifts"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".prevent_closed_shift_change();
+
+CREATE TRIGGER prevent_shift_tender_change
+    BEFORE UPDATE OR DELETE ON "users"."shift_tenders"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".prevent_shift_tender_change();
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."treatment_photos";
+DROP TABLE IF EXISTS "users"."treatment_record_history";
+DROP TABLE IF EXISTS "users"."treatment_records";
+DROP TABLE IF EXISTS "users"."treatment_fields";
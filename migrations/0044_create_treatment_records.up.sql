@@ -0,0 +1,77 @@
+-- Add up migration script here
+-- Trường thông tin lâm sàng theo từng nhóm dịch vụ (services), service_id NULL áp dụng cho mọi nhóm
+CREATE TABLE IF NOT EXISTS "users"."treatment_fields" (
+    id BIGSERIAL PRIMARY KEY,
+    service_id BIGINT REFERENCES users.services(id) ON DELETE CASCADE,
+    field_key VARCHAR(50) NOT NULL,
+    label VARCHAR(100) NOT NULL,
+    field_type VARCHAR(20) NOT NULL CHECK (field_type IN ('TEXT', 'NUMBER', 'BOOLEAN', 'SELECT')),
+    options JSONB,
+    is_required BOOLEAN NOT NULL DEFAULT FALSE,
+    is_internal BOOLEAN NOT NULL DEFAULT FALSE,
+    sort_order INT NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_treatment_fields_key ON users.treatment_fields(COALESCE(service_id, 0), field_key);
+
+-- Hồ sơ điều trị của khách, gắn với lịch hẹn / dịch vụ trong lịch hẹn nếu có
+CREATE TABLE IF NOT EXISTS "users"."treatment_records" (
+    id BIGSERIAL PRIMARY KEY,
+    customer_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    appointment_id BIGINT REFERENCES users.appointments(id) ON DELETE SET NULL,
+    appointment_service_id BIGINT REFERENCES users.appointments_services(id) ON DELETE SET NULL,
+    service_id BIGINT REFERENCES users.services(id) ON DELETE SET NULL,
+    technician_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    skin_type VARCHAR(50),
+    allergies TEXT,
+    machine_settings TEXT,
+    reactions TEXT,
+    notes TEXT,
+    internal_notes TEXT,
+    fields JSONB NOT NULL DEFAULT '{}'::jsonb,
+    version INT NOT NULL DEFAULT 1,
+    updated_by BIGINT REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_treatment_records_customer_id ON users.treatment_records(customer_id, created_at);
+CREATE INDEX idx_treatment_records_appointment_id ON users.treatment_records(appointment_id);
+
+-- Lịch sử chỉnh sửa, mỗi phiên bản lưu lại toàn bộ nội dung hồ sơ
+CREATE TABLE IF NOT EXISTS "users"."treatment_record_history" (
+    id BIGSERIAL PRIMARY KEY,
+    record_id BIGINT NOT NULL REFERENCES users.treatment_records(id) ON DELETE CASCADE,
+    version INT NOT NULL,
+    snapshot JSONB NOT NULL,
+    changed_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (record_id, version)
+);
+
+-- Ảnh trước / sau điều trị
+CREATE TABLE IF NOT EXISTS "users"."treatment_photos" (
+    id BIGSERIAL PRIMARY KEY,
+    record_id BIGINT NOT NULL REFERENCES users.treatment_records(id) ON DELETE CASCADE,
+    photo_type VARCHAR(10) NOT NULL CHECK (photo_type IN ('BEFORE', 'AFTER')),
+    image TEXT NOT NULL,
+    caption TEXT,
+    uploaded_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_treatment_photos_record_id ON users.treatment_photos(record_id);
+
+CREATE TRIGGER update_treatment_field_timestamp
+    BEFORE UPDATE ON "users"."treatment_fields"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+CREATE TRIGGER update_treatment_record_timestamp
+    BEFORE UPDATE ON "users"."treatment_records"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."consent_submissions";
+DROP TABLE IF EXISTS "users"."consent_templates";
+DROP FUNCTION IF EXISTS "users".prevent_consent_submission_change();
@@ -0,0 +1,66 @@
+-- Add up migration script here
+-- Mẫu phiếu khai thông tin / cam kết đồng ý, service_id NULL áp dụng cho mọi dịch vụ
+CREATE TABLE IF NOT EXISTS "users"."consent_templates" (
+    id BIGSERIAL PRIMARY KEY,
+    service_id BIGINT REFERENCES users.services(id) ON DELETE CASCADE,
+    title VARCHAR(200) NOT NULL,
+    description TEXT,
+    questions JSONB NOT NULL DEFAULT '[]'::jsonb,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_consent_templates_service_id ON users.consent_templates(service_id);
+
+-- Phiếu khách đã ký: lưu nguyên mẫu, câu trả lời, chữ ký và bản PDF tại thời điểm ký
+CREATE TABLE IF NOT EXISTS "users"."consent_submissions" (
+    id BIGSERIAL PRIMARY KEY,
+    template_id BIGINT NOT NULL REFERENCES users.consent_templates(id),
+    customer_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    appointment_id BIGINT REFERENCES users.appointments(id) ON DELETE SET NULL,
+    template_snapshot JSONB NOT NULL,
+    answers JSONB NOT NULL,
+    signature_image TEXT NOT NULL,
+    signed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ip_address VARCHAR(64),
+    user_agent TEXT,
+    pdf_content BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (template_id, customer_id)
+);
+
+CREATE INDEX idx_consent_submissions_customer_id ON users.consent_submissions(customer_id);
+CREATE INDEX idx_consent_submissions_appointment_id ON users.consent_submissions(appointment_id);
+
+-- Phiếu đã ký không được sửa hoặc xoá; chỉ cho phép bỏ liên kết khi lịch hẹn bị xoá
+CREATE OR REPLACE FUNCTION "users".prevent_consent_submission_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        RAISE EXCEPTION 'Signed consent forms cannot be deleted';
+    END IF;
+
+    IF (OLD.template_id, OLD.customer_id, OLD.template_snapshot, OLD.answers, OLD.signature_image,
+        OLD.signed_at, OLD.ip_address, OLD.user_agent, OLD.pdf_content, OLD.created_at)
+       IS DISTINCT FROM
+       (NEW.template_id, NEW.customer_id, NEW.template_snapshot, NEW.answers, NEW.signature_image,
+        NEW.signed_at, NEW.ip_address, NEW.user_agent, NEW.pdf_content, NEW.created_at)
+       OR (NEW.appointment_id IS NOT NULL AND NEW.appointment_id IS DISTINCT FROM OLD.appointment_id) THEN
+        RAISE EXCEPTION 'Signed consent forms cannot be modified';
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_consent_submission_change
+    BEFORE UPDATE OR DELETE ON "users"."consent_submissions"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".prevent_consent_submission_change();
+
+CREATE TRIGGER update_consent_template_timestamp
+    BEFORE UPDATE ON "users"."consent_templates"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
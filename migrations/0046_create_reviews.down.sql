@@ -0,0 +1,19 @@
+-- Add down migration script here
+DELETE FROM "users"."notifications" WHERE notification_type = 'REVIEW';
+
+ALTER TABLE "users"."notifications"
+DROP CONSTRAINT IF EXISTS notifications_notification_type_check;
+
+ALTER TABLE "users"."notifications"
+ADD CONSTRAINT notifications_notification_type_check
+CHECK (notification_type IN ('APPOINTMENT', 'PROMOTION', 'SURCHARGE', 'PAYMENT', 'SYSTEM', 'DEPOSIT'));
+
+DROP TABLE IF EXISTS "users"."review_prompts";
+DROP TABLE IF EXISTS "users"."review_photos";
+DROP TABLE IF EXISTS "users"."technician_reviews";
+DROP TABLE IF EXISTS "users"."reviews";
+
+DROP TRIGGER IF EXISTS set_appointment_paid_at ON "users"."appointments";
+DROP FUNCTION IF EXISTS "users".set_appointment_paid_at();
+
+ALTER TABLE "users"."appointments" DROP COLUMN IF EXISTS paid_at;
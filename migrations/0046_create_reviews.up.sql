@@ -0,0 +1,80 @@
+-- Add up migration script here
+-- Thời điểm lịch hẹn chuyển sang PAYMENT, dùng cho hạn đánh giá và lịch nhắc đánh giá
+ALTER TABLE "users"."appointments" ADD COLUMN IF NOT EXISTS paid_at TIMESTAMPTZ;
+
+UPDATE users.appointments SET paid_at = updated_at WHERE status = 'PAYMENT' AND paid_at IS NULL;
+
+CREATE OR REPLACE FUNCTION "users".set_appointment_paid_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status = 'PAYMENT' AND (TG_OP = 'INSERT' OR OLD.status IS DISTINCT FROM 'PAYMENT') THEN
+        NEW.paid_at := CURRENT_TIMESTAMP;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_appointment_paid_at
+    BEFORE INSERT OR UPDATE OF status ON "users"."appointments"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".set_appointment_paid_at();
+
+-- Đánh giá tổng thể của khách cho lịch hẹn, mỗi lịch hẹn một đánh giá
+CREATE TABLE IF NOT EXISTS "users"."reviews" (
+    id BIGSERIAL PRIMARY KEY,
+    appointment_id BIGINT NOT NULL UNIQUE REFERENCES users.appointments(id) ON DELETE CASCADE,
+    customer_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    rating INT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    comment TEXT,
+    is_hidden BOOLEAN NOT NULL DEFAULT FALSE,
+    hidden_reason TEXT,
+    hidden_by BIGINT REFERENCES users.tbl_users(pk_user_id),
+    hidden_at TIMESTAMPTZ,
+    reply TEXT,
+    replied_by BIGINT REFERENCES users.tbl_users(pk_user_id),
+    replied_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_reviews_customer_id ON users.reviews(customer_id);
+
+-- Đánh giá từng kỹ thuật viên đã phục vụ trong lịch hẹn
+CREATE TABLE IF NOT EXISTS "users"."technician_reviews" (
+    id BIGSERIAL PRIMARY KEY,
+    review_id BIGINT NOT NULL REFERENCES users.reviews(id) ON DELETE CASCADE,
+    technician_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    rating INT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (review_id, technician_id)
+);
+
+CREATE INDEX idx_technician_reviews_technician_id ON users.technician_reviews(technician_id);
+
+CREATE TABLE IF NOT EXISTS "users"."review_photos" (
+    id BIGSERIAL PRIMARY KEY,
+    review_id BIGINT NOT NULL REFERENCES users.reviews(id) ON DELETE CASCADE,
+    image TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_review_photos_review_id ON users.review_photos(review_id);
+
+-- Lịch hẹn đã gửi thông báo nhắc đánh giá, tránh nhắc lại
+CREATE TABLE IF NOT EXISTS "users"."review_prompts" (
+    appointment_id BIGINT PRIMARY KEY REFERENCES users.appointments(id) ON DELETE CASCADE,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_review_timestamp
+    BEFORE UPDATE ON "users"."reviews"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+ALTER TABLE "users"."notifications"
+DROP CONSTRAINT IF EXISTS notifications_notification_type_check;
+
+ALTER TABLE "users"."notifications"
+ADD CONSTRAINT notifications_notification_type_check
+CHECK (notification_type IN ('APPOINTMENT', 'PROMOTION', 'SURCHARGE', 'PAYMENT', 'SYSTEM', 'DEPOSIT', 'REVIEW'));
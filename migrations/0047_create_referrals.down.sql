@@ -0,0 +1,17 @@
+-- Add down migration script here
+DELETE FROM "users"."notifications" WHERE notification_type = 'REFERRAL';
+
+ALTER TABLE "users"."notifications"
+DROP CONSTRAINT IF EXISTS notifications_notification_type_check;
+
+ALTER TABLE "users"."notifications"
+ADD CONSTRAINT notifications_notification_type_check
+CHECK (notification_type IN ('APPOINTMENT', 'PROMOTION', 'SURCHARGE', 'PAYMENT', 'SYSTEM', 'DEPOSIT', 'REVIEW'));
+
+DROP TABLE IF EXISTS "users"."referrals";
+
+DROP TRIGGER IF EXISTS set_referral_code ON "users"."tbl_users";
+DROP FUNCTION IF EXISTS "users".set_referral_code();
+DROP FUNCTION IF EXISTS "users".generate_referral_code();
+
+ALTER TABLE "users"."tbl_users" DROP COLUMN IF EXISTS referral_code;
@@ -0,0 +1,72 @@
+-- Add up migration script here
+-- Mã giới thiệu riêng của từng khách hàng
+ALTER TABLE "users"."tbl_users" ADD COLUMN IF NOT EXISTS referral_code VARCHAR(20) UNIQUE;
+
+CREATE OR REPLACE FUNCTION "users".generate_referral_code()
+RETURNS TEXT AS $$
+DECLARE
+    new_code TEXT;
+BEGIN
+    LOOP
+        new_code := upper(substr(md5(random()::text || clock_timestamp()::text), 1, 8));
+        EXIT WHEN NOT EXISTS (SELECT 1 FROM users.tbl_users WHERE referral_code = new_code);
+    END LOOP;
+    RETURN new_code;
+END;
+$$ LANGUAGE plpgsql;
+
+UPDATE users.tbl_users
+SET referral_code = "users".generate_referral_code()
+WHERE role = 'CUSTOMER' AND referral_code IS NULL;
+
+CREATE OR REPLACE FUNCTION "users".set_referral_code()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.role = 'CUSTOMER' AND NEW.referral_code IS NULL THEN
+        NEW.referral_code := "users".generate_referral_code();
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_referral_code
+    BEFORE INSERT OR UPDATE OF role ON "users"."tbl_users"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".set_referral_code();
+
+-- Mỗi khách chỉ được giới thiệu một lần; thưởng khi lịch hẹn đầu tiên của khách được thanh toán
+CREATE TABLE IF NOT EXISTS "users"."referrals" (
+    id BIGSERIAL PRIMARY KEY,
+    referrer_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id),
+    referred_id BIGINT NOT NULL UNIQUE REFERENCES users.tbl_users(pk_user_id),
+    referral_code VARCHAR(20) NOT NULL,
+    source VARCHAR(20) NOT NULL CHECK (source IN ('SIGNUP', 'RECEPTION')),
+    device_token TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'REWARDED', 'REJECTED')),
+    reject_reason TEXT,
+    appointment_id BIGINT REFERENCES users.appointments(id) ON DELETE SET NULL,
+    reward_type VARCHAR(10) CHECK (reward_type IN ('WALLET', 'POINTS')),
+    referrer_reward BIGINT NOT NULL DEFAULT 0,
+    referred_reward BIGINT NOT NULL DEFAULT 0,
+    rewarded_at TIMESTAMPTZ,
+    created_by BIGINT REFERENCES users.tbl_users(pk_user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (referrer_id <> referred_id)
+);
+
+CREATE INDEX idx_referrals_referrer_id ON users.referrals(referrer_id);
+CREATE INDEX idx_referrals_status ON users.referrals(status);
+CREATE INDEX idx_referrals_device_token ON users.referrals(device_token);
+
+CREATE TRIGGER update_referral_timestamp
+    BEFORE UPDATE ON "users"."referrals"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+ALTER TABLE "users"."notifications"
+DROP CONSTRAINT IF EXISTS notifications_notification_type_check;
+
+ALTER TABLE "users"."notifications"
+ADD CONSTRAINT notifications_notification_type_check
+CHECK (notification_type IN ('APPOINTMENT', 'PROMOTION', 'SURCHARGE', 'PAYMENT', 'SYSTEM', 'DEPOSIT', 'REVIEW', 'REFERRAL'));
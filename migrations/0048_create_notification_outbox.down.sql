@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."notification_deliveries";
+DROP TABLE IF EXISTS "users"."notification_outbox";
@@ -0,0 +1,53 @@
+-- Add up migration script here
+-- Hàng đợi thông báo đẩy, được ghi cùng transaction với thay đổi nghiệp vụ và gửi bởi worker
+CREATE TABLE IF NOT EXISTS "users"."notification_outbox" (
+    id BIGSERIAL PRIMARY KEY,
+    notification_id BIGINT REFERENCES users.notifications(id) ON DELETE SET NULL,
+    user_id BIGINT REFERENCES users.tbl_users(pk_user_id) ON DELETE CASCADE,
+    receiver VARCHAR(50) NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    notification_type VARCHAR(50) NOT NULL,
+    data JSONB,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING'
+        CHECK (status IN ('PENDING', 'PROCESSING', 'SENT', 'DEAD')),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_at TIMESTAMPTZ,
+    last_error TEXT,
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_outbox_pending
+    ON "users"."notification_outbox"(next_attempt_at) WHERE status = 'PENDING';
+CREATE INDEX IF NOT EXISTS idx_notification_outbox_status ON "users"."notification_outbox"(status);
+
+CREATE TRIGGER update_notification_outbox_timestamp
+    BEFORE UPDATE ON "users"."notification_outbox"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Kết quả gửi theo từng FCM token của một thông báo
+CREATE TABLE IF NOT EXISTS "users"."notification_deliveries" (
+    id BIGSERIAL PRIMARY KEY,
+    outbox_id BIGINT NOT NULL REFERENCES users.notification_outbox(id) ON DELETE CASCADE,
+    token VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING'
+        CHECK (status IN ('PENDING', 'SENT', 'FAILED', 'INVALID')),
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (outbox_id, token)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_deliveries_outbox
+    ON "users"."notification_deliveries"(outbox_id);
+
+CREATE TRIGGER update_notification_deliveries_timestamp
+    BEFORE UPDATE ON "users"."notification_deliveries"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
@@ -0,0 +1,8 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS "users".idx_notification_tokens_last_seen_at;
+DROP INDEX IF EXISTS "users".idx_notification_tokens_token;
+
+ALTER TABLE "users"."notification_tokens"
+    DROP CONSTRAINT IF EXISTS notification_tokens_user_id_token_key;
+
+ALTER TABLE "users"."notification_tokens" DROP COLUMN IF EXISTS last_seen_at;
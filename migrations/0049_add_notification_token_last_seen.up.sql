@@ -0,0 +1,16 @@
+-- Add up migration script here
+ALTER TABLE "users"."notification_tokens"
+    ADD COLUMN IF NOT EXISTS last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+UPDATE "users"."notification_tokens" SET last_seen_at = updated_at;
+
+-- Một thiết bị chỉ thuộc về một người dùng, giữ lại bản đăng ký mới nhất của mỗi token
+DELETE FROM "users"."notification_tokens" a
+USING "users"."notification_tokens" b
+WHERE a.token = b.token AND a.id < b.id;
+
+ALTER TABLE "users"."notification_tokens"
+    ADD CONSTRAINT notification_tokens_user_id_token_key UNIQUE (user_id, token);
+
+CREATE INDEX IF NOT EXISTS idx_notification_tokens_token ON "users"."notification_tokens"(token);
+CREATE INDEX IF NOT EXISTS idx_notification_tokens_last_seen_at ON "users"."notification_tokens"(last_seen_at);
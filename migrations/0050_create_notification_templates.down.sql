@@ -0,0 +1,8 @@
+-- Add down migration script here
+ALTER TABLE "users"."notification_outbox"
+    DROP COLUMN IF EXISTS variables,
+    DROP COLUMN IF EXISTS event_type;
+
+DROP TABLE IF EXISTS "users"."notification_templates";
+
+ALTER TABLE "users"."tbl_users" DROP COLUMN IF EXISTS preferred_language;
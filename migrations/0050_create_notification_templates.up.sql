@@ -0,0 +1,147 @@
+-- Add up migration script here
+ALTER TABLE "users"."tbl_users"
+    ADD COLUMN IF NOT EXISTS preferred_language VARCHAR(5) NOT NULL DEFAULT 'vi'
+    CHECK (preferred_language IN ('vi', 'en', 'ko'));
+
+CREATE TABLE IF NOT EXISTS "users"."notification_templates" (
+    id BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR(100) NOT NULL,
+    locale VARCHAR(5) NOT NULL CHECK (locale IN ('vi', 'en', 'ko')),
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (event_type, locale)
+);
+
+CREATE TRIGGER update_notification_templates_timestamp
+    BEFORE UPDATE ON "users"."notification_templates"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Thông báo theo mẫu được dựng lại theo ngôn ngữ của từng người nhận khi gửi
+ALTER TABLE "users"."notification_outbox"
+    ADD COLUMN IF NOT EXISTS event_type VARCHAR(100),
+    ADD COLUMN IF NOT EXISTS variables JSONB;
+
+INSERT INTO "users"."notification_templates" (event_type, locale, title, body) VALUES
+    ('APPOINTMENT_CREATED', 'vi', 'Lịch hẹn mới', '{user_name} vừa đặt lịch hẹn thành công! Vui lòng vào kiểm tra.'),
+    ('APPOINTMENT_CREATED', 'en', 'New appointment', '{user_name} has just booked an appointment. Please check it.'),
+    ('APPOINTMENT_CREATED', 'ko', '새 예약', '{user_name}님이 예약을 완료했습니다. 확인해 주세요.'),
+
+    ('APPOINTMENT_CREATED_TECHNICIAN', 'vi', 'Phân công lịch hẹn', '{user_name} vừa đặt lịch hẹn thành công! Vui lòng vào kiểm tra. Dịch vụ: {services}'),
+    ('APPOINTMENT_CREATED_TECHNICIAN', 'en', 'Appointment assigned', '{user_name} has just booked an appointment. Please check it. Services: {services}'),
+    ('APPOINTMENT_CREATED_TECHNICIAN', 'ko', '예약 배정', '{user_name}님이 예약을 완료했습니다. 확인해 주세요. 서비스: {services}'),
+
+    ('APPOINTMENT_CANCELLED_BY_CUSTOMER', 'vi', 'Hủy lịch hẹn', '{user_name} đã hủy lịch hẹn. Thời gian: {start_time}'),
+    ('APPOINTMENT_CANCELLED_BY_CUSTOMER', 'en', 'Appointment cancelled', '{user_name} cancelled the appointment. Time: {start_time}'),
+    ('APPOINTMENT_CANCELLED_BY_CUSTOMER', 'ko', '예약 취소', '{user_name}님이 예약을 취소했습니다. 시간: {start_time}'),
+
+    ('APPOINTMENT_UPDATED', 'vi', 'Cập nhật lịch hẹn', 'Lịch hẹn của {user_name} đã được cập nhật. Vui lòng kiểm tra.'),
+    ('APPOINTMENT_UPDATED', 'en', 'Appointment updated', 'The appointment of {user_name} has been updated. Please check it.'),
+    ('APPOINTMENT_UPDATED', 'ko', '예약 변경', '{user_name}님의 예약이 변경되었습니다. 확인해 주세요.'),
+
+    ('APPOINTMENT_CONFIRMED', 'vi', 'Lịch hẹn đã được xác nhận', 'Lịch hẹn của {user_name} đã được xác nhận. Thời gian: {start_time}'),
+    ('APPOINTMENT_CONFIRMED', 'en', 'Appointment confirmed', 'The appointment of {user_name} has been confirmed. Time: {start_time}'),
+    ('APPOINTMENT_CONFIRMED', 'ko', '예약 확정', '{user_name}님의 예약이 확정되었습니다. 시간: {start_time}'),
+
+    ('APPOINTMENT_PAID', 'vi', 'Thanh toán thành công', 'Lịch hẹn của {user_name} đã được thanh toán thành công. Thời gian: {start_time}'),
+    ('APPOINTMENT_PAID', 'en', 'Payment successful', 'The appointment of {user_name} has been paid. Time: {start_time}'),
+    ('APPOINTMENT_PAID', 'ko', '결제 완료', '{user_name}님의 예약 결제가 완료되었습니다. 시간: {start_time}'),
+
+    ('APPOINTMENT_PAID_POINTS', 'vi', 'Thanh toán thành công', 'Lịch hẹn của {user_name} đã được thanh toán thành công. Bạn được nhận thêm vào {points} điểm'),
+    ('APPOINTMENT_PAID_POINTS', 'en', 'Payment successful', 'The appointment of {user_name} has been paid. You earned {points} points'),
+    ('APPOINTMENT_PAID_POINTS', 'ko', '결제 완료', '{user_name}님의 예약 결제가 완료되었습니다. {points} 포인트가 적립되었습니다'),
+
+    ('APPOINTMENT_CANCELLED', 'vi', 'Hủy lịch hẹn', 'Lịch hẹn của {user_name} đã bị hủy. Thời gian: {start_time}'),
+    ('APPOINTMENT_CANCELLED', 'en', 'Appointment cancelled', 'The appointment of {user_name} has been cancelled. Time: {start_time}'),
+    ('APPOINTMENT_CANCELLED', 'ko', '예약 취소', '{user_name}님의 예약이 취소되었습니다. 시간: {start_time}'),
+
+    ('APPOINTMENT_CANCELLED_TECHNICIAN', 'vi', 'Hủy lịch hẹn', 'Lịch hẹn của {user_name} đã bị hủy. Thời gian: {start_time} Dịch vụ: {services}'),
+    ('APPOINTMENT_CANCELLED_TECHNICIAN', 'en', 'Appointment cancelled', 'The appointment of {user_name} has been cancelled. Time: {start_time} Services: {services}'),
+    ('APPOINTMENT_CANCELLED_TECHNICIAN', 'ko', '예약 취소', '{user_name}님의 예약이 취소되었습니다. 시간: {start_time} 서비스: {services}'),
+
+    ('APPOINTMENT_ASSIGNED', 'vi', 'Phân công lịch hẹn', 'Bạn đã được phân công cho lịch hẹn của {user_name}. Thời gian: {start_time}. Dịch vụ: {services}'),
+    ('APPOINTMENT_ASSIGNED', 'en', 'Appointment assigned', 'You have been assigned to the appointment of {user_name}. Time: {start_time}. Services: {services}'),
+    ('APPOINTMENT_ASSIGNED', 'ko', '예약 배정', '{user_name}님의 예약에 배정되었습니다. 시간: {start_time}. 서비스: {services}'),
+
+    ('APPOINTMENT_UNASSIGNED', 'vi', 'Hủy phân công lịch hẹn', 'Lịch hẹn của {user_name} đã được phân công cho kỹ thuật viên khác. Thời gian: {start_time}'),
+    ('APPOINTMENT_UNASSIGNED', 'en', 'Appointment reassigned', 'The appointment of {user_name} has been reassigned to another technician. Time: {start_time}'),
+    ('APPOINTMENT_UNASSIGNED', 'ko', '예약 배정 취소', '{user_name}님의 예약이 다른 관리사에게 배정되었습니다. 시간: {start_time}'),
+
+    ('APPOINTMENT_COMPLETED', 'vi', 'Hoàn thành lịch hẹn', 'Lịch hẹn của {user_name} đã được hoàn thành. Vui lòng thanh toán với lễ tân'),
+    ('APPOINTMENT_COMPLETED', 'en', 'Appointment completed', 'The appointment of {user_name} has been completed. Please pay at the reception'),
+    ('APPOINTMENT_COMPLETED', 'ko', '예약 완료', '{user_name}님의 예약이 완료되었습니다. 리셉션에서 결제해 주세요'),
+
+    ('APPOINTMENT_IN_PROGRESS', 'vi', 'Bắt đầu thực hiện dịch vụ', 'Kỹ thuật viên đã bắt đầu thực hiện dịch vụ cho lịch hẹn của {user_name}. Thời gian: {start_time}'),
+    ('APPOINTMENT_IN_PROGRESS', 'en', 'Service started', 'The technician has started the service for the appointment of {user_name}. Time: {start_time}'),
+    ('APPOINTMENT_IN_PROGRESS', 'ko', '서비스 시작', '관리사가 {user_name}님의 예약 서비스를 시작했습니다. 시간: {start_time}'),
+
+    ('SERVICE_ASSIGNED', 'vi', 'Phân công dịch vụ', 'Bạn đã được phân công dịch vụ {service_name} cho lịch hẹn của {user_name}. Thời gian: {start_time}'),
+    ('SERVICE_ASSIGNED', 'en', 'Service assigned', 'You have been assigned the service {service_name} for the appointment of {user_name}. Time: {start_time}'),
+    ('SERVICE_ASSIGNED', 'ko', '서비스 배정', '{user_name}님의 예약 서비스 {service_name}에 배정되었습니다. 시간: {start_time}'),
+
+    ('SERVICE_UNASSIGNED', 'vi', 'Hủy phân công dịch vụ', 'Dịch vụ {service_name} của {user_name} đã được phân công cho kỹ thuật viên khác. Thời gian: {start_time}'),
+    ('SERVICE_UNASSIGNED', 'en', 'Service reassigned', 'The service {service_name} of {user_name} has been reassigned to another technician. Time: {start_time}'),
+    ('SERVICE_UNASSIGNED', 'ko', '서비스 배정 취소', '{user_name}님의 서비스 {service_name}이(가) 다른 관리사에게 배정되었습니다. 시간: {start_time}'),
+
+    ('SERVICE_STATUS_UPDATED', 'vi', 'Cập nhật dịch vụ', 'Dịch vụ {service_name} của {user_name} chuyển sang trạng thái {status}'),
+    ('SERVICE_STATUS_UPDATED', 'en', 'Service updated', 'The service {service_name} of {user_name} changed to {status}'),
+    ('SERVICE_STATUS_UPDATED', 'ko', '서비스 상태 변경', '{user_name}님의 서비스 {service_name} 상태가 {status}(으)로 변경되었습니다'),
+
+    ('TIP_RECEIVED', 'vi', 'Bạn nhận được tiền tip', 'Khách hàng {user_name} đã tip cho bạn {amount} VND'),
+    ('TIP_RECEIVED', 'en', 'You received a tip', 'Customer {user_name} tipped you {amount} VND'),
+    ('TIP_RECEIVED', 'ko', '팁을 받았습니다', '{user_name} 고객님이 {amount} VND 팁을 주셨습니다'),
+
+    ('DEPOSIT_COMPLETED', 'vi', 'Nạp tiền thành công', 'Bạn đã nạp thành công {amount}đ vào tài khoản'),
+    ('DEPOSIT_COMPLETED', 'en', 'Top-up successful', '{amount} VND has been added to your account'),
+    ('DEPOSIT_COMPLETED', 'ko', '충전 완료', '{amount} VND가 계정에 충전되었습니다'),
+
+    ('DEPOSIT_REQUESTED', 'vi', 'Yêu cầu nạp tiền mới', 'Khách hàng yêu cầu nạp {amount}đ, vui lòng kiểm tra chứng từ chuyển khoản'),
+    ('DEPOSIT_REQUESTED', 'en', 'New top-up request', 'A customer requested a top-up of {amount} VND, please check the transfer receipt'),
+    ('DEPOSIT_REQUESTED', 'ko', '새 충전 요청', '고객이 {amount} VND 충전을 요청했습니다. 이체 증빙을 확인해 주세요'),
+
+    ('DEPOSIT_REJECTED', 'vi', 'Yêu cầu nạp tiền bị từ chối', 'Yêu cầu nạp {amount}đ đã bị từ chối. Lý do: {reason}'),
+    ('DEPOSIT_REJECTED', 'en', 'Top-up request rejected', 'Your top-up request of {amount} VND was rejected. Reason: {reason}'),
+    ('DEPOSIT_REJECTED', 'ko', '충전 요청 거절', '{amount} VND 충전 요청이 거절되었습니다. 사유: {reason}'),
+
+    ('WITHDRAWAL_REQUESTED', 'vi', 'Yêu cầu rút tiền mới', 'Khách hàng yêu cầu rút {amount}đ về tài khoản ngân hàng'),
+    ('WITHDRAWAL_REQUESTED', 'en', 'New withdrawal request', 'A customer requested a withdrawal of {amount} VND to a bank account'),
+    ('WITHDRAWAL_REQUESTED', 'ko', '새 출금 요청', '고객이 은행 계좌로 {amount} VND 출금을 요청했습니다'),
+
+    ('WITHDRAWAL_COMPLETED', 'vi', 'Rút tiền thành công', '{amount}đ đã được chuyển về tài khoản {bank_name} {bank_account_number}. Mã giao dịch: {transaction_id}'),
+    ('WITHDRAWAL_COMPLETED', 'en', 'Withdrawal successful', '{amount} VND has been transferred to {bank_name} {bank_account_number}. Transaction ID: {transaction_id}'),
+    ('WITHDRAWAL_COMPLETED', 'ko', '출금 완료', '{amount} VND가 {bank_name} {bank_account_number} 계좌로 이체되었습니다. 거래 번호: {transaction_id}'),
+
+    ('WITHDRAWAL_REJECTED', 'vi', 'Yêu cầu rút tiền bị từ chối', 'Yêu cầu rút {amount}đ đã bị từ chối, số tiền đã được hoàn lại vào ví. Lý do: {reason}'),
+    ('WITHDRAWAL_REJECTED', 'en', 'Withdrawal request rejected', 'Your withdrawal request of {amount} VND was rejected and the amount was refunded to your wallet. Reason: {reason}'),
+    ('WITHDRAWAL_REJECTED', 'ko', '출금 요청 거절', '{amount} VND 출금 요청이 거절되어 지갑으로 환불되었습니다. 사유: {reason}'),
+
+    ('PAYMENT_MISMATCH', 'vi', 'Giao dịch chuyển khoản cần kiểm tra', 'Giao dịch {transaction_id} ({amount}đ): {note}'),
+    ('PAYMENT_MISMATCH', 'en', 'Bank transfer needs review', 'Transaction {transaction_id} ({amount} VND): {note}'),
+    ('PAYMENT_MISMATCH', 'ko', '확인이 필요한 계좌 이체', '거래 {transaction_id} ({amount} VND): {note}'),
+
+    ('REFERRER_REWARD_POINTS', 'vi', 'Thưởng giới thiệu', 'Bạn nhận được {amount} điểm tích luỹ nhờ giới thiệu khách hàng mới. Cảm ơn bạn!'),
+    ('REFERRER_REWARD_POINTS', 'en', 'Referral reward', 'You received {amount} loyalty points for referring a new customer. Thank you!'),
+    ('REFERRER_REWARD_POINTS', 'ko', '추천 보상', '신규 고객 추천으로 {amount} 포인트를 받았습니다. 감사합니다!'),
+
+    ('REFERRER_REWARD_WALLET', 'vi', 'Thưởng giới thiệu', 'Bạn nhận được {amount}đ vào ví nhờ giới thiệu khách hàng mới. Cảm ơn bạn!'),
+    ('REFERRER_REWARD_WALLET', 'en', 'Referral reward', 'You received {amount} VND in your wallet for referring a new customer. Thank you!'),
+    ('REFERRER_REWARD_WALLET', 'ko', '추천 보상', '신규 고객 추천으로 지갑에 {amount} VND를 받았습니다. 감사합니다!'),
+
+    ('REFERRED_REWARD_POINTS', 'vi', 'Quà chào mừng', 'Bạn nhận được {amount} điểm tích luỹ từ mã giới thiệu sau lần sử dụng dịch vụ đầu tiên.'),
+    ('REFERRED_REWARD_POINTS', 'en', 'Welcome gift', 'You received {amount} loyalty points from your referral code after your first visit.'),
+    ('REFERRED_REWARD_POINTS', 'ko', '웰컴 선물', '첫 서비스 이용 후 추천 코드로 {amount} 포인트를 받았습니다.'),
+
+    ('REFERRED_REWARD_WALLET', 'vi', 'Quà chào mừng', 'Bạn nhận được {amount}đ vào ví từ mã giới thiệu sau lần sử dụng dịch vụ đầu tiên.'),
+    ('REFERRED_REWARD_WALLET', 'en', 'Welcome gift', 'You received {amount} VND in your wallet from your referral code after your first visit.'),
+    ('REFERRED_REWARD_WALLET', 'ko', '웰컴 선물', '첫 서비스 이용 후 추천 코드로 지갑에 {amount} VND를 받았습니다.'),
+
+    ('REVIEW_PROMPT', 'vi', 'Đánh giá dịch vụ', 'Cảm ơn bạn đã sử dụng dịch vụ lúc {start_time}. Hãy dành chút thời gian đánh giá lịch hẹn và kỹ thuật viên nhé!'),
+    ('REVIEW_PROMPT', 'en', 'Rate your visit', 'Thank you for visiting us at {start_time}. Please take a moment to review your appointment and technician!'),
+    ('REVIEW_PROMPT', 'ko', '서비스 평가', '{start_time}에 서비스를 이용해 주셔서 감사합니다. 예약과 관리사를 평가해 주세요!'),
+
+    ('ACCOUNT_DELETED', 'vi', 'Tài khoản đã bị xóa', 'Người dùng {user_name} (SĐT: {phone}) đã xóa tài khoản của họ'),
+    ('ACCOUNT_DELETED', 'en', 'Account deleted', 'User {user_name} (phone: {phone}) deleted their account'),
+    ('ACCOUNT_DELETED', 'ko', '계정 삭제', '사용자 {user_name} (전화번호: {phone})님이 계정을 삭제했습니다')
+ON CONFLICT (event_type, locale) DO NOTHING;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."notification_quiet_hours";
+DROP TABLE IF EXISTS "users"."notification_preferences";
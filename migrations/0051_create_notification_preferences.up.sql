@@ -0,0 +1,32 @@
+-- Add up migration script here
+-- Chỉ lưu các lựa chọn khác mặc định, không có dòng nghĩa là kênh đang bật
+CREATE TABLE IF NOT EXISTS "users"."notification_preferences" (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES "users"."tbl_users"(pk_user_id) ON DELETE CASCADE,
+    notification_type VARCHAR(50) NOT NULL,
+    channel VARCHAR(20) NOT NULL CHECK (channel IN ('PUSH', 'IN_APP', 'ZALO', 'SMS', 'EMAIL')),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, notification_type, channel)
+);
+
+CREATE TABLE IF NOT EXISTS "users"."notification_quiet_hours" (
+    user_id BIGINT PRIMARY KEY REFERENCES "users"."tbl_users"(pk_user_id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    start_time TIME NOT NULL DEFAULT '22:00',
+    end_time TIME NOT NULL DEFAULT '07:00',
+    timezone VARCHAR(64) NOT NULL DEFAULT 'Asia/Ho_Chi_Minh',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_notification_preferences_timestamp
+    BEFORE UPDATE ON "users"."notification_preferences"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+CREATE TRIGGER update_notification_quiet_hours_timestamp
+    BEFORE UPDATE ON "users"."notification_quiet_hours"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."campaign_recipients";
+DROP TABLE IF EXISTS "users"."campaigns";
@@ -0,0 +1,44 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS "users"."campaigns" (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'DRAFT'
+        CHECK (status IN ('DRAFT', 'SCHEDULED', 'SENDING', 'COMPLETED', 'CANCELLED')),
+    audience JSONB NOT NULL DEFAULT '{}',
+    messages JSONB NOT NULL DEFAULT '[]',
+    deep_link VARCHAR(500),
+    scheduled_at TIMESTAMP WITH TIME ZONE,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_by BIGINT REFERENCES "users"."tbl_users"(pk_user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Danh sách người nhận được chốt khi chiến dịch bắt đầu gửi
+CREATE TABLE IF NOT EXISTS "users"."campaign_recipients" (
+    id BIGSERIAL PRIMARY KEY,
+    campaign_id BIGINT NOT NULL REFERENCES "users"."campaigns"(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES "users"."tbl_users"(pk_user_id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'QUEUED')),
+    outbox_id BIGINT REFERENCES "users"."notification_outbox"(id) ON DELETE SET NULL,
+    queued_at TIMESTAMP WITH TIME ZONE,
+    opened_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (campaign_id, user_id)
+);
+
+CREATE INDEX idx_campaigns_status ON "users"."campaigns"(status, scheduled_at);
+CREATE INDEX idx_campaign_recipients_pending ON "users"."campaign_recipients"(campaign_id)
+    WHERE status = 'PENDING';
+
+CREATE TRIGGER update_campaigns_timestamp
+    BEFORE UPDATE ON "users"."campaigns"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+CREATE TRIGGER update_campaign_recipients_timestamp
+    BEFORE UPDATE ON "users"."campaign_recipients"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
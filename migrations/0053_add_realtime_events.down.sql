@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS notify_appointment_status ON "users"."appointments";
+DROP FUNCTION IF EXISTS "users".notify_appointment_status();
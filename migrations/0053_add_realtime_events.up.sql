@@ -0,0 +1,33 @@
+-- Add up migration script here
+-- Phát sự kiện realtime khi lịch hẹn được tạo hoặc đổi trạng thái, payload khớp RealtimeEvent::AppointmentStatus
+CREATE OR REPLACE FUNCTION "users".notify_appointment_status()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' AND OLD.status IS NOT DISTINCT FROM NEW.status THEN
+        RETURN NEW;
+    END IF;
+
+    PERFORM pg_notify('app_events', json_build_object(
+        'type', 'APPOINTMENT_STATUS',
+        'appointment_id', NEW.id,
+        'user_id', NEW.user_id,
+        'technician_ids', (
+            SELECT COALESCE(json_agg(DISTINCT t.technician_id), '[]'::json)
+            FROM (
+                SELECT NEW.technician_id AS technician_id
+                UNION
+                SELECT technician_id FROM "users"."appointments_services" WHERE appointment_id = NEW.id
+            ) t
+            WHERE t.technician_id IS NOT NULL
+        ),
+        'status', NEW.status
+    )::text);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_appointment_status
+    AFTER INSERT OR UPDATE OF status ON "users"."appointments"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".notify_appointment_status();
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS "users".idx_notifications_created_at;
+DROP TABLE IF EXISTS "users"."notifications_archive";
@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- Thông báo in-app quá hạn lưu giữ được chuyển sang đây, giữ nguyên cột để có thể tra cứu lại
+CREATE TABLE IF NOT EXISTS "users"."notifications_archive" (
+    LIKE "users"."notifications",
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_notifications_archive_user_id ON "users"."notifications_archive"(user_id);
+CREATE INDEX idx_notifications_created_at ON "users"."notifications"(created_at);
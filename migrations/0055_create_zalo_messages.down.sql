@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."zalo_callbacks";
+DROP TABLE IF EXISTS "users"."zalo_messages";
+DROP TABLE IF EXISTS "users"."zalo_templates";
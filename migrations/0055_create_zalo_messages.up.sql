@@ -0,0 +1,74 @@
+-- Add up migration script here
+-- Mẫu ZNS theo sự kiện, params là {tên tham số ZNS: nội dung}, nội dung có thể chứa biến dạng {ten_bien}
+CREATE TABLE IF NOT EXISTS "users"."zalo_templates" (
+    id BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR(50) NOT NULL UNIQUE,
+    template_id VARCHAR(50),
+    params JSONB NOT NULL DEFAULT '{}',
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    CONSTRAINT zalo_templates_enabled_check CHECK (NOT enabled OR template_id IS NOT NULL),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_zalo_templates_timestamp
+    BEFORE UPDATE ON "users"."zalo_templates"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+INSERT INTO "users"."zalo_templates" (event_type, params) VALUES
+    ('OTP', '{"otp": "{otp}"}'),
+    ('APPOINTMENT_CONFIRMED', '{"customer_name": "{customer_name}", "booking_code": "{appointment_id}", "booking_time": "{start_time}", "services": "{services}"}'),
+    ('APPOINTMENT_REMINDER', '{"customer_name": "{customer_name}", "booking_code": "{appointment_id}", "booking_time": "{start_time}", "services": "{services}"}'),
+    ('APPOINTMENT_RESCHEDULED', '{"customer_name": "{customer_name}", "booking_code": "{appointment_id}", "booking_time": "{start_time}", "services": "{services}"}'),
+    ('APPOINTMENT_CANCELLED', '{"customer_name": "{customer_name}", "booking_code": "{appointment_id}", "booking_time": "{start_time}"}'),
+    ('PAYMENT_RECEIPT', '{"customer_name": "{customer_name}", "booking_code": "{appointment_id}", "total_price": "{total_price}", "receipt_number": "{receipt_no}"}')
+ON CONFLICT (event_type) DO NOTHING;
+
+-- Hàng đợi tin ZNS, được ghi cùng transaction nghiệp vụ và gửi bởi worker
+CREATE TABLE IF NOT EXISTS "users"."zalo_messages" (
+    id BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR(50) NOT NULL,
+    appointment_id BIGINT REFERENCES "users"."appointments"(id) ON DELETE SET NULL,
+    user_id BIGINT REFERENCES "users"."tbl_users"(pk_user_id) ON DELETE SET NULL,
+    phone VARCHAR(15) NOT NULL,
+    template_id VARCHAR(50),
+    template_data JSONB,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING'
+        CHECK (status IN ('PENDING', 'PROCESSING', 'SENT', 'DELIVERED', 'FAILED')),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_at TIMESTAMP WITH TIME ZONE,
+    last_error TEXT,
+    msg_id VARCHAR(100),
+    sent_at TIMESTAMP WITH TIME ZONE,
+    delivered_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_zalo_messages_pending ON "users"."zalo_messages"(next_attempt_at)
+    WHERE status IN ('PENDING', 'PROCESSING');
+CREATE INDEX idx_zalo_messages_appointment_id ON "users"."zalo_messages"(appointment_id);
+CREATE INDEX idx_zalo_messages_msg_id ON "users"."zalo_messages"(msg_id);
+-- Mỗi lịch hẹn chỉ nhắc một lần
+CREATE UNIQUE INDEX idx_zalo_messages_reminder ON "users"."zalo_messages"(appointment_id, event_type)
+    WHERE event_type = 'APPOINTMENT_REMINDER';
+
+CREATE TRIGGER update_zalo_messages_timestamp
+    BEFORE UPDATE ON "users"."zalo_messages"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Callback trạng thái gửi từ Zalo, lưu nguyên payload để đối soát
+CREATE TABLE IF NOT EXISTS "users"."zalo_callbacks" (
+    id BIGSERIAL PRIMARY KEY,
+    event_name VARCHAR(100) NOT NULL,
+    msg_id VARCHAR(100),
+    tracking_id VARCHAR(100),
+    message_id BIGINT REFERENCES "users"."zalo_messages"(id) ON DELETE SET NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_zalo_callbacks_message_id ON "users"."zalo_callbacks"(message_id);
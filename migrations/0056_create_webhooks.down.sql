@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS enqueue_user_created_webhook ON "users"."tbl_users";
+DROP FUNCTION IF EXISTS "users".enqueue_user_created_webhook();
+DROP TABLE IF EXISTS "users"."webhook_deliveries";
+DROP TABLE IF EXISTS "users"."webhook_subscriptions";
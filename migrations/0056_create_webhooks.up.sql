@@ -0,0 +1,83 @@
+-- Add up migration script here
+-- Đăng ký nhận webhook của hệ thống bên ngoài (CRM, kế toán...)
+CREATE TABLE IF NOT EXISTS "users"."webhook_subscriptions" (
+    id BIGSERIAL PRIMARY KEY,
+    url VARCHAR(500) NOT NULL,
+    secret VARCHAR(255) NOT NULL,
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+    description TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by BIGINT REFERENCES "users"."tbl_users"(pk_user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_webhook_subscriptions_timestamp
+    BEFORE UPDATE ON "users"."webhook_subscriptions"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Mỗi sự kiện tạo một lượt gửi cho từng subscription, đồng thời là nhật ký gửi
+CREATE TABLE IF NOT EXISTS "users"."webhook_deliveries" (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id BIGINT NOT NULL REFERENCES "users"."webhook_subscriptions"(id) ON DELETE CASCADE,
+    event_type VARCHAR(50) NOT NULL,
+    event_id VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING'
+        CHECK (status IN ('PENDING', 'PROCESSING', 'SUCCEEDED', 'FAILED')),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_at TIMESTAMP WITH TIME ZONE,
+    response_status INT,
+    response_body TEXT,
+    last_error TEXT,
+    delivered_at TIMESTAMP WITH TIME ZONE,
+    redelivery_of BIGINT REFERENCES "users"."webhook_deliveries"(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_webhook_deliveries_pending ON "users"."webhook_deliveries"(next_attempt_at)
+    WHERE status IN ('PENDING', 'PROCESSING');
+CREATE INDEX idx_webhook_deliveries_subscription_id
+    ON "users"."webhook_deliveries"(subscription_id, created_at DESC);
+
+CREATE TRIGGER update_webhook_deliveries_timestamp
+    BEFORE UPDATE ON "users"."webhook_deliveries"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+-- Người dùng được tạo từ nhiều luồng (đăng ký, admin, import) nên phát user.created bằng trigger,
+-- không gửi các trường nhạy cảm như mật khẩu
+CREATE OR REPLACE FUNCTION "users".enqueue_user_created_webhook()
+RETURNS TRIGGER AS $$
+DECLARE
+    event_id TEXT := gen_random_uuid()::text;
+BEGIN
+    INSERT INTO "users"."webhook_deliveries" (subscription_id, event_type, event_id, payload)
+    SELECT s.id, 'user.created', event_id, jsonb_build_object(
+        'id', event_id,
+        'type', 'user.created',
+        'created_at', NOW(),
+        'data', jsonb_build_object(
+            'user_id', NEW.pk_user_id,
+            'full_name', NEW.full_name,
+            'phone', NEW.phone,
+            'email_address', NEW.email_address,
+            'role', NEW.role,
+            'membership_level', NEW.membership_level,
+            'created_at', NEW.created_at
+        )
+    )
+    FROM "users"."webhook_subscriptions" s
+    WHERE s.is_active AND 'user.created' = ANY(s.event_types);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER enqueue_user_created_webhook
+    AFTER INSERT ON "users"."tbl_users"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".enqueue_user_created_webhook();
@@ -0,0 +1,8 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "users"."email_messages";
+DROP TABLE IF EXISTS "users"."email_templates";
+DROP TABLE IF EXISTS "users"."email_codes";
+DROP TRIGGER IF EXISTS reset_email_verification ON "users"."tbl_users";
+DROP FUNCTION IF EXISTS "users".reset_email_verification();
+DROP INDEX IF EXISTS "users".idx_tbl_users_verified_email;
+ALTER TABLE "users"."tbl_users" DROP COLUMN IF EXISTS is_email_verified;
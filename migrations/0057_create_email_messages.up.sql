@@ -0,0 +1,114 @@
+-- Add up migration script here
+-- Email chỉ dùng để gửi thông báo/đặt lại mật khẩu sau khi đã xác minh, mỗi địa chỉ chỉ xác minh cho một tài khoản
+ALTER TABLE "users"."tbl_users"
+    ADD COLUMN IF NOT EXISTS is_email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tbl_users_verified_email
+    ON "users"."tbl_users"(LOWER(email_address))
+    WHERE is_email_verified;
+
+-- Đổi địa chỉ email thì phải xác minh lại
+CREATE OR REPLACE FUNCTION "users".reset_email_verification()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.email_address IS DISTINCT FROM OLD.email_address THEN
+        NEW.is_email_verified = FALSE;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reset_email_verification
+    BEFORE UPDATE OF email_address ON "users"."tbl_users"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".reset_email_verification();
+
+-- Mã xác minh email và mã đặt lại mật khẩu qua email
+CREATE TABLE IF NOT EXISTS "users"."email_codes" (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES "users"."tbl_users"(pk_user_id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    code VARCHAR(6) NOT NULL,
+    purpose VARCHAR(20) NOT NULL CHECK (purpose IN ('VERIFY_EMAIL', 'RESET_PASSWORD')),
+    attempts INT NOT NULL DEFAULT 0,
+    used_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT email_codes_valid_expires CHECK (expires_at > created_at)
+);
+
+CREATE INDEX idx_email_codes_user_id ON "users"."email_codes"(user_id, purpose, created_at DESC);
+
+-- Mẫu email theo sự kiện và ngôn ngữ, nội dung là HTML được lồng vào khung chung khi gửi,
+-- biến dạng {ten_bien} được escape trước khi thay vào
+CREATE TABLE IF NOT EXISTS "users"."email_templates" (
+    id BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR(50) NOT NULL,
+    locale VARCHAR(5) NOT NULL CHECK (locale IN ('vi', 'en', 'ko')),
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (event_type, locale)
+);
+
+CREATE TRIGGER update_email_templates_timestamp
+    BEFORE UPDATE ON "users"."email_templates"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
+
+INSERT INTO "users"."email_templates" (event_type, locale, subject, body) VALUES
+    ('EMAIL_VERIFICATION', 'vi', 'Mã xác minh email của bạn',
+     '<p>Xin chào {customer_name},</p><p>Mã xác minh email của bạn là:</p><p style="font-size:24px;font-weight:bold;letter-spacing:4px">{code}</p><p>Mã có hiệu lực trong {ttl_minutes} phút. Nếu bạn không yêu cầu, vui lòng bỏ qua email này.</p>'),
+    ('EMAIL_VERIFICATION', 'en', 'Your email verification code',
+     '<p>Hello {customer_name},</p><p>Your email verification code is:</p><p style="font-size:24px;font-weight:bold;letter-spacing:4px">{code}</p><p>The code expires in {ttl_minutes} minutes. If you did not request it, please ignore this email.</p>'),
+
+    ('PASSWORD_RESET', 'vi', 'Đặt lại mật khẩu',
+     '<p>Xin chào {customer_name},</p><p>Mã đặt lại mật khẩu của bạn là:</p><p style="font-size:24px;font-weight:bold;letter-spacing:4px">{code}</p><p>Mã có hiệu lực trong {ttl_minutes} phút. Nếu bạn không yêu cầu đặt lại mật khẩu, vui lòng bỏ qua email này.</p>'),
+    ('PASSWORD_RESET', 'en', 'Reset your password',
+     '<p>Hello {customer_name},</p><p>Your password reset code is:</p><p style="font-size:24px;font-weight:bold;letter-spacing:4px">{code}</p><p>The code expires in {ttl_minutes} minutes. If you did not request a password reset, please ignore this email.</p>'),
+
+    ('APPOINTMENT_CONFIRMED', 'vi', 'Xác nhận lịch hẹn #{appointment_id}',
+     '<p>Xin chào {customer_name},</p><p>Lịch hẹn của bạn đã được xác nhận.</p><table cellpadding="4"><tr><td>Mã lịch hẹn</td><td><b>#{appointment_id}</b></td></tr><tr><td>Thời gian</td><td><b>{start_time}</b></td></tr><tr><td>Dịch vụ</td><td>{services}</td></tr><tr><td>Kỹ thuật viên</td><td>{technician_name}</td></tr><tr><td>Tạm tính</td><td>{total_price} VND</td></tr></table><p>Lịch hẹn được đính kèm dạng file .ics để bạn thêm vào lịch.</p>'),
+    ('APPOINTMENT_CONFIRMED', 'en', 'Appointment #{appointment_id} confirmed',
+     '<p>Hello {customer_name},</p><p>Your appointment has been confirmed.</p><table cellpadding="4"><tr><td>Booking code</td><td><b>#{appointment_id}</b></td></tr><tr><td>Time</td><td><b>{start_time}</b></td></tr><tr><td>Services</td><td>{services}</td></tr><tr><td>Technician</td><td>{technician_name}</td></tr><tr><td>Estimated total</td><td>{total_price} VND</td></tr></table><p>The appointment is attached as an .ics file so you can add it to your calendar.</p>'),
+
+    ('DEPOSIT_RECEIPT', 'vi', 'Biên nhận nạp tiền #{deposit_id}',
+     '<p>Xin chào {customer_name},</p><p>Tài khoản của bạn đã được nạp tiền thành công.</p><table cellpadding="4"><tr><td>Mã giao dịch</td><td><b>#{deposit_id}</b></td></tr><tr><td>Số tiền</td><td><b>{amount} VND</b></td></tr><tr><td>Phương thức</td><td>{payment_method}</td></tr><tr><td>Số dư hiện tại</td><td>{balance} VND</td></tr></table>'),
+    ('DEPOSIT_RECEIPT', 'en', 'Top-up receipt #{deposit_id}',
+     '<p>Hello {customer_name},</p><p>Your account has been topped up successfully.</p><table cellpadding="4"><tr><td>Transaction</td><td><b>#{deposit_id}</b></td></tr><tr><td>Amount</td><td><b>{amount} VND</b></td></tr><tr><td>Payment method</td><td>{payment_method}</td></tr><tr><td>Current balance</td><td>{balance} VND</td></tr></table>'),
+
+    ('PAYMENT_RECEIPT', 'vi', 'Biên nhận thanh toán lịch hẹn #{appointment_id}',
+     '<p>Xin chào {customer_name},</p><p>Cảm ơn bạn đã sử dụng dịch vụ. Lịch hẹn của bạn đã được thanh toán.</p><table cellpadding="4"><tr><td>Số biên nhận</td><td><b>{receipt_no}</b></td></tr><tr><td>Mã lịch hẹn</td><td>#{appointment_id}</td></tr><tr><td>Thời gian</td><td>{start_time}</td></tr><tr><td>Dịch vụ</td><td>{services}</td></tr><tr><td>Tổng thanh toán</td><td><b>{total_price} VND</b></td></tr></table>'),
+    ('PAYMENT_RECEIPT', 'en', 'Payment receipt for appointment #{appointment_id}',
+     '<p>Hello {customer_name},</p><p>Thank you for visiting us. Your appointment has been paid.</p><table cellpadding="4"><tr><td>Receipt number</td><td><b>{receipt_no}</b></td></tr><tr><td>Booking code</td><td>#{appointment_id}</td></tr><tr><td>Time</td><td>{start_time}</td></tr><tr><td>Services</td><td>{services}</td></tr><tr><td>Total paid</td><td><b>{total_price} VND</b></td></tr></table>')
+ON CONFLICT (event_type, locale) DO NOTHING;
+
+-- Hàng đợi email, được ghi cùng transaction nghiệp vụ; tiêu đề/nội dung dựng theo mẫu khi gửi
+CREATE TABLE IF NOT EXISTS "users"."email_messages" (
+    id BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR(50) NOT NULL,
+    user_id BIGINT REFERENCES "users"."tbl_users"(pk_user_id) ON DELETE SET NULL,
+    to_address VARCHAR(255) NOT NULL,
+    locale VARCHAR(5) NOT NULL DEFAULT 'vi',
+    variables JSONB NOT NULL DEFAULT '{}',
+    subject TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING'
+        CHECK (status IN ('PENDING', 'PROCESSING', 'SENT', 'FAILED')),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_at TIMESTAMP WITH TIME ZONE,
+    last_error TEXT,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_email_messages_pending ON "users"."email_messages"(next_attempt_at)
+    WHERE status IN ('PENDING', 'PROCESSING');
+CREATE INDEX idx_email_messages_user_id ON "users"."email_messages"(user_id, created_at DESC);
+
+CREATE TRIGGER update_email_messages_timestamp
+    BEFORE UPDATE ON "users"."email_messages"
+    FOR EACH ROW
+    EXECUTE FUNCTION "users".update_timestamp();
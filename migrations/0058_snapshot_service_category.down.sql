@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE "users"."appointments_services"
+DROP CONSTRAINT IF EXISTS appointments_services_discount_limit_check,
+DROP COLUMN IF EXISTS service_name_ko,
+DROP COLUMN IF EXISTS parent_service_id,
+DROP COLUMN IF EXISTS parent_service_name;
@@ -0,0 +1,18 @@
+-- Add up migration script here
+-- Lưu thêm tên tiếng Hàn và nhóm dịch vụ tại thời điểm đặt lịch để báo cáo không đổi theo danh mục hiện tại
+ALTER TABLE "users"."appointments_services"
+ADD COLUMN service_name_ko TEXT,
+ADD COLUMN parent_service_id BIGINT,
+ADD COLUMN parent_service_name VARCHAR(100);
+
+UPDATE "users"."appointments_services" aps
+SET service_name_ko = si.service_name_ko,
+    parent_service_id = s.id,
+    parent_service_name = s.service_name
+FROM "users"."service_items" si
+JOIN "users"."services" s ON s.id = si.parent_service_id
+WHERE aps.service_id = si.id;
+
+-- Chiết khấu của một dòng không vượt quá thành tiền của dòng đó
+ALTER TABLE "users"."appointments_services"
+ADD CONSTRAINT appointments_services_discount_limit_check CHECK (discount <= unit_price * quantity);
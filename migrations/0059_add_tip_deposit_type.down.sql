@@ -0,0 +1,11 @@
+-- Add down migration script here
+ALTER TABLE users.deposits
+DROP CONSTRAINT IF EXISTS check_deposit_type;
+
+UPDATE users.deposits SET deposit_type = 'WITHDRAW' WHERE deposit_type = 'TIP';
+
+ALTER TABLE users.deposits
+ADD CONSTRAINT check_deposit_type
+CHECK (deposit_type IN ('DEPOSIT', 'PAYMENT', 'WITHDRAW'));
+
+COMMENT ON COLUMN users.deposits.deposit_type IS 'Type of deposit: DEPOSIT for adding money, PAYMENT for service payment, WITHDRAW for withdrawing money';
@@ -0,0 +1,16 @@
+-- Add up migration script here
+-- Tiền tip trả từ ví có loại riêng để không bị tính vào hạn mức rút tiền
+ALTER TABLE users.deposits
+DROP CONSTRAINT IF EXISTS check_deposit_type;
+
+UPDATE users.deposits
+SET deposit_type = 'TIP'
+WHERE deposit_type = 'WITHDRAW'
+  AND payment_method = 'WALLET'
+  AND notes LIKE 'Tiền tip lịch hẹn #%';
+
+ALTER TABLE users.deposits
+ADD CONSTRAINT check_deposit_type
+CHECK (deposit_type IN ('DEPOSIT', 'PAYMENT', 'WITHDRAW', 'TIP'));
+
+COMMENT ON COLUMN users.deposits.deposit_type IS 'Type of deposit: DEPOSIT for adding money, PAYMENT for service payment, WITHDRAW for withdrawing money, TIP for wallet tips';
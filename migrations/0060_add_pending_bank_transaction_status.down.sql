@@ -0,0 +1,11 @@
+-- Add down migration script here
+UPDATE users.bank_transactions SET match_status = 'UNMATCHED' WHERE match_status = 'PENDING';
+
+ALTER TABLE users.bank_transactions ALTER COLUMN match_status SET DEFAULT 'UNMATCHED';
+
+ALTER TABLE users.bank_transactions
+DROP CONSTRAINT IF EXISTS bank_transactions_match_status_check;
+
+ALTER TABLE users.bank_transactions
+ADD CONSTRAINT bank_transactions_match_status_check
+CHECK (match_status IN ('MATCHED', 'MISMATCH', 'UNMATCHED'));
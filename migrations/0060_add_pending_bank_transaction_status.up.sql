@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- Giao dịch mới ghi nhận ở trạng thái PENDING cho đến khi đối soát xong, webhook gửi lại
+-- giao dịch còn PENDING (tiến trình chết giữa chừng) sẽ được đối soát lại
+ALTER TABLE users.bank_transactions
+DROP CONSTRAINT IF EXISTS bank_transactions_match_status_check;
+
+ALTER TABLE users.bank_transactions
+ADD CONSTRAINT bank_transactions_match_status_check
+CHECK (match_status IN ('PENDING', 'MATCHED', 'MISMATCH', 'UNMATCHED'));
+
+ALTER TABLE users.bank_transactions ALTER COLUMN match_status SET DEFAULT 'PENDING';
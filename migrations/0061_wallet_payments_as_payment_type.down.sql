@@ -0,0 +1,6 @@
+-- Add down migration script here
+UPDATE users.deposits
+SET deposit_type = 'WITHDRAW'
+WHERE deposit_type = 'PAYMENT'
+  AND payment_method = 'WALLET'
+  AND notes LIKE 'Thanh toán lịch hẹn #%';
@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- Thanh toán lịch hẹn bằng ví trước đây ghi là WITHDRAW, chuyển sang PAYMENT để WITHDRAW
+-- chỉ còn là giao dịch rút tiền (hạn mức rút tiền và bộ lọc danh sách không bị lẫn)
+UPDATE users.deposits
+SET deposit_type = 'PAYMENT'
+WHERE deposit_type = 'WITHDRAW'
+  AND payment_method = 'WALLET'
+  AND notes LIKE 'Thanh toán lịch hẹn #%';
@@ -0,0 +1,19 @@
+-- Add up migration script here
+-- Lịch sử thiết bị - người dùng: notification_tokens chỉ giữ chủ sở hữu hiện tại của mỗi token,
+-- bảng này giữ mọi tài khoản từng đăng ký trên thiết bị để kiểm tra gian lận giới thiệu
+CREATE TABLE IF NOT EXISTS "users"."notification_token_history" (
+    token TEXT NOT NULL,
+    user_id BIGINT NOT NULL REFERENCES users.tbl_users(pk_user_id) ON DELETE CASCADE,
+    first_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (token, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_token_history_user_id ON "users"."notification_token_history"(user_id);
+
+INSERT INTO "users"."notification_token_history" (token, user_id, first_seen_at, last_seen_at)
+SELECT token, user_id, MIN(created_at), MAX(last_seen_at)
+FROM "users"."notification_tokens"
+GROUP BY token, user_id
+ON CONFLICT DO NOTHING;
+
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE "users"."appointment_payments" DROP CONSTRAINT IF EXISTS appointment_payments_tender_type_check;
+ALTER TABLE "users"."appointment_payments" ADD CONSTRAINT appointment_payments_tender_type_check
+    CHECK (tender_type IN ('WALLET', 'CASH', 'BANK_TRANSFER', 'CARD', 'POINTS', 'PACKAGE_SESSION', 'VOUCHER'));
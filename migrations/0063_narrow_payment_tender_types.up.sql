@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- VOUCHER và PACKAGE_SESSION chưa có nguồn dữ liệu để đối chiếu mã / số dư nên không được nhận,
+-- thu hẹp ràng buộc cho khớp với TENDER_TYPES
+ALTER TABLE "users"."appointment_payments" DROP CONSTRAINT IF EXISTS appointment_payments_tender_type_check;
+ALTER TABLE "users"."appointment_payments" ADD CONSTRAINT appointment_payments_tender_type_check
+    CHECK (tender_type IN ('WALLET', 'CASH', 'BANK_TRANSFER', 'CARD', 'POINTS'));